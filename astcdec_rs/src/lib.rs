@@ -0,0 +1,1405 @@
+#![no_std]
+//! A safe, no_std, pure Rust decoder for ASTC compressed blocks.
+
+// Used information sources:
+// https://registry.khronos.org/DataFormat/specs/1.3/dataformat.1.3.html#ASTC
+// https://github.com/ARM-software/astc-encoder/blob/main/Docs/FormatOverview.md
+//
+// All blocks store 128 bits as a little endian integer regardless of the block footprint.
+// Only 2D block footprints are supported.
+// Invalid blocks decode to the error color magenta as recommended by the specification.
+
+/// The largest supported number of pixels in a block for the 12x12 footprint.
+const MAX_TEXELS: usize = 144;
+/// The largest number of weights in a block including both planes for dual plane blocks.
+const MAX_WEIGHTS: usize = 64;
+/// The largest number of color endpoint values in a block.
+const MAX_COLOR_VALUES: usize = 18;
+
+/// Decode 16 bytes from `compressed_block` to RGBA8
+/// with `destination_pitch` many bytes per output row.
+///
+/// The block covers `block_width` x `block_height` pixels.
+/// Set `srgb` for sRGB formats to match the rounding of the sRGB decode mode.
+/// Blocks using HDR endpoints decode to magenta.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 6x5 pixel block.
+/// let compressed_block = [0u8; 16];
+/// let mut decompressed_block = [0u8; 6 * 5 * 4];
+/// astcdec_rs::astc_ldr(&compressed_block, &mut decompressed_block, 6 * 4, 6, 5, false);
+/// ```
+pub fn astc_ldr(
+    compressed_block: &[u8],
+    decompressed_block: &mut [u8],
+    destination_pitch: usize,
+    block_width: usize,
+    block_height: usize,
+    srgb: bool,
+) {
+    let texels = decode_texels(compressed_block, block_width, block_height, srgb, false);
+
+    for y in 0..block_height {
+        for x in 0..block_width {
+            let pixel = match &texels {
+                // LDR blocks only contain UNORM16 values.
+                Some(texels) => texels[y * block_width + x].values.map(|v| (v >> 8) as u8),
+                None => [255, 0, 255, 255],
+            };
+            let index = y * destination_pitch + x * 4;
+            decompressed_block[index..index + 4].copy_from_slice(&pixel);
+        }
+    }
+}
+
+/// Decode 16 bytes from `compressed_block` to RGBAF32
+/// with `destination_pitch` many floats per output row.
+///
+/// The block covers `block_width` x `block_height` pixels.
+/// Channels using LDR endpoints are normalized to the range `0.0` to `1.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 8x8 pixel block.
+/// let compressed_block = [0u8; 16];
+/// let mut decompressed_block = [0f32; 8 * 8 * 4];
+/// astcdec_rs::astc_hdr(&compressed_block, &mut decompressed_block, 8 * 4, 8, 8);
+/// ```
+pub fn astc_hdr(
+    compressed_block: &[u8],
+    decompressed_block: &mut [f32],
+    destination_pitch: usize,
+    block_width: usize,
+    block_height: usize,
+) {
+    let texels = decode_texels(compressed_block, block_width, block_height, false, true);
+
+    for y in 0..block_height {
+        for x in 0..block_width {
+            let pixel = match &texels {
+                Some(texels) => {
+                    let texel = texels[y * block_width + x];
+                    [0, 1, 2, 3].map(|c| {
+                        if texel.is_half[c] {
+                            f32_from_f16(texel.values[c])
+                        } else {
+                            texel.values[c] as f32 / 65535.0
+                        }
+                    })
+                }
+                None => [1.0, 0.0, 1.0, 1.0],
+            };
+            let index = y * destination_pitch + x * 4;
+            decompressed_block[index..index + 4].copy_from_slice(&pixel);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Texel {
+    values: [u16; 4],
+    // Channels from HDR endpoints store half precision floats instead of UNORM16.
+    is_half: [bool; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Bits,
+    Trits,
+    Quints,
+}
+
+// The integer sequence encoding for each range of values from 2 to 256 values.
+const RANGES: [(Encoding, u32); 21] = [
+    (Encoding::Bits, 1),
+    (Encoding::Trits, 0),
+    (Encoding::Bits, 2),
+    (Encoding::Quints, 0),
+    (Encoding::Trits, 1),
+    (Encoding::Bits, 3),
+    (Encoding::Quints, 1),
+    (Encoding::Trits, 2),
+    (Encoding::Bits, 4),
+    (Encoding::Quints, 2),
+    (Encoding::Trits, 3),
+    (Encoding::Bits, 5),
+    (Encoding::Quints, 3),
+    (Encoding::Trits, 4),
+    (Encoding::Bits, 6),
+    (Encoding::Quints, 4),
+    (Encoding::Trits, 5),
+    (Encoding::Bits, 7),
+    (Encoding::Quints, 5),
+    (Encoding::Trits, 6),
+    (Encoding::Bits, 8),
+];
+
+// Color endpoints need at least 6 values per channel.
+const MIN_COLOR_RANGE: usize = 4;
+
+fn read_bits(bits: u128, offset: usize, count: usize) -> u32 {
+    if offset >= 128 {
+        0
+    } else {
+        ((bits >> offset) & ((1u128 << count) - 1)) as u32
+    }
+}
+
+fn low_bits(bits: u128, count: usize) -> u128 {
+    if count >= 128 {
+        bits
+    } else {
+        bits & ((1u128 << count) - 1)
+    }
+}
+
+struct BlockMode {
+    grid_width: usize,
+    grid_height: usize,
+    dual_plane: bool,
+    weight_range: usize,
+}
+
+impl BlockMode {
+    fn decode(mode: u32) -> Option<Self> {
+        let bit = |i: u32| (mode >> i) & 1;
+
+        let a = ((mode >> 5) & 3) as usize;
+        let mut high_precision = bit(9) != 0;
+        let mut dual_plane = bit(10) != 0;
+
+        let (grid_width, grid_height, range) = if mode & 3 != 0 {
+            let range = bit(4) | ((mode & 3) << 1);
+            let b = ((mode >> 7) & 3) as usize;
+            let (width, height) = match (mode >> 2) & 3 {
+                0 => (b + 4, a + 2),
+                1 => (b + 8, a + 2),
+                2 => (a + 2, b + 8),
+                _ => {
+                    let b = b & 1;
+                    if bit(8) != 0 {
+                        (b + 2, a + 2)
+                    } else {
+                        (a + 2, b + 6)
+                    }
+                }
+            };
+            (width, height, range)
+        } else {
+            let range = bit(4) | (((mode >> 2) & 3) << 1);
+            if (mode >> 2) & 3 == 0 {
+                return None;
+            }
+            let b = ((mode >> 9) & 3) as usize;
+            let (width, height) = match (mode >> 7) & 3 {
+                0 => (12, a + 2),
+                1 => (a + 2, 12),
+                2 => {
+                    // These bits are used for the grid size instead.
+                    high_precision = false;
+                    dual_plane = false;
+                    (a + 6, b + 6)
+                }
+                _ => match a {
+                    0 => (6, 10),
+                    1 => (10, 6),
+                    _ => return None,
+                },
+            };
+            (width, height, range)
+        };
+
+        let weight_range = (range - 2) as usize + if high_precision { 6 } else { 0 };
+
+        Some(Self {
+            grid_width,
+            grid_height,
+            dual_plane,
+            weight_range,
+        })
+    }
+
+    fn weight_count(&self) -> usize {
+        self.grid_width * self.grid_height * if self.dual_plane { 2 } else { 1 }
+    }
+}
+
+fn decode_texels(
+    compressed_block: &[u8],
+    block_width: usize,
+    block_height: usize,
+    srgb: bool,
+    hdr: bool,
+) -> Option<[Texel; MAX_TEXELS]> {
+    let bits = u128::from_le_bytes(compressed_block[..16].try_into().unwrap());
+
+    if !(4..=12).contains(&block_width) || !(4..=12).contains(&block_height) {
+        return None;
+    }
+    let texel_count = block_width * block_height;
+
+    let mut texels = [Texel::default(); MAX_TEXELS];
+
+    let block_mode = read_bits(bits, 0, 11);
+    if block_mode & 0x1FF == 0x1FC {
+        let texel = void_extent(bits, hdr)?;
+        texels[..texel_count].fill(texel);
+        return Some(texels);
+    }
+
+    let mode = BlockMode::decode(block_mode)?;
+    if mode.grid_width > block_width || mode.grid_height > block_height {
+        return None;
+    }
+
+    let weight_count = mode.weight_count();
+    let weight_bits = ise_bit_count(weight_count, mode.weight_range);
+    if weight_count > MAX_WEIGHTS || !(24..=96).contains(&weight_bits) {
+        return None;
+    }
+
+    let partition_count = read_bits(bits, 11, 2) as usize + 1;
+    if partition_count == 4 && mode.dual_plane {
+        return None;
+    }
+
+    // The weights are stored starting from the most significant bit.
+    // Additional fields are stored immediately below the weights.
+    let mut below_weights = 128 - weight_bits;
+
+    let mut endpoint_modes = [0u32; 4];
+    let (partition_index, color_start) = if partition_count == 1 {
+        endpoint_modes[0] = read_bits(bits, 13, 4);
+        (0, 17)
+    } else {
+        let encoded = read_bits(bits, 23, 6);
+        let selector = encoded & 3;
+        if selector == 0 {
+            // All partitions share the same endpoint mode.
+            endpoint_modes = [encoded >> 2; 4];
+        } else {
+            let high_bit_count = 3 * partition_count - 4;
+            below_weights -= high_bit_count;
+            let encoded = encoded | (read_bits(bits, below_weights, high_bit_count) << 6);
+
+            // Each partition selects the base class or the next class.
+            let base_class = selector - 1;
+            for (i, endpoint_mode) in endpoint_modes.iter_mut().take(partition_count).enumerate() {
+                let class = base_class + ((encoded >> (2 + i)) & 1);
+                let mode = (encoded >> (2 + partition_count + 2 * i)) & 3;
+                *endpoint_mode = (class << 2) | mode;
+            }
+        }
+        (read_bits(bits, 13, 10), 29)
+    };
+
+    let plane2_component = if mode.dual_plane {
+        below_weights -= 2;
+        Some(read_bits(bits, below_weights, 2) as usize)
+    } else {
+        None
+    };
+
+    let color_value_count: usize = endpoint_modes[..partition_count]
+        .iter()
+        .map(|m| ((m >> 2) as usize + 1) * 2)
+        .sum();
+    if color_value_count > MAX_COLOR_VALUES {
+        return None;
+    }
+
+    // Colors use the largest range that fits in the remaining bits.
+    let color_bit_count = below_weights.checked_sub(color_start)?;
+    let color_range = (0..RANGES.len())
+        .rev()
+        .find(|r| ise_bit_count(color_value_count, *r) <= color_bit_count)?;
+    if color_range < MIN_COLOR_RANGE {
+        return None;
+    }
+
+    let mut color_values = [0u8; MAX_COLOR_VALUES];
+    let color_values = &mut color_values[..color_value_count];
+    decode_ise(
+        low_bits(
+            bits >> color_start,
+            ise_bit_count(color_value_count, color_range),
+        ),
+        color_range,
+        color_values,
+    );
+    for value in color_values.iter_mut() {
+        *value = unquantize_color(*value, color_range);
+    }
+
+    let mut endpoints = [Endpoints::default(); 4];
+    let mut values = &color_values[..];
+    for (endpoint, mode) in endpoints
+        .iter_mut()
+        .zip(endpoint_modes)
+        .take(partition_count)
+    {
+        let count = ((mode >> 2) as usize + 1) * 2;
+        *endpoint = decode_endpoints(mode, &values[..count], srgb);
+        values = &values[count..];
+
+        if !hdr && endpoint.is_hdr.contains(&true) {
+            return None;
+        }
+    }
+
+    // Weights are stored in reverse bit order.
+    let mut weights = [0u8; MAX_WEIGHTS];
+    let weights = &mut weights[..weight_count];
+    decode_ise(
+        low_bits(bits.reverse_bits(), weight_bits),
+        mode.weight_range,
+        weights,
+    );
+    for weight in weights.iter_mut() {
+        *weight = unquantize_weight(*weight, mode.weight_range);
+    }
+
+    let plane_count = if mode.dual_plane { 2 } else { 1 };
+    let small_block = texel_count < 31;
+
+    for y in 0..block_height {
+        for x in 0..block_width {
+            let partition = if partition_count > 1 {
+                select_partition(partition_index, x, y, partition_count, small_block)
+            } else {
+                0
+            };
+            let endpoint = &endpoints[partition];
+
+            let plane_weights = [0, 1].map(|plane| {
+                infill_weight(
+                    weights,
+                    plane_count,
+                    plane,
+                    (mode.grid_width, mode.grid_height),
+                    (block_width, block_height),
+                    (x, y),
+                )
+            });
+
+            let texel = &mut texels[y * block_width + x];
+            for c in 0..4 {
+                let weight = if plane2_component == Some(c) {
+                    plane_weights[1]
+                } else {
+                    plane_weights[0]
+                };
+                let value =
+                    (endpoint.e0[c] as u32 * (64 - weight) + endpoint.e1[c] as u32 * weight + 32)
+                        >> 6;
+
+                texel.values[c] = if endpoint.is_hdr[c] {
+                    f16_from_lns(value)
+                } else {
+                    value as u16
+                };
+                texel.is_half[c] = endpoint.is_hdr[c];
+            }
+        }
+    }
+
+    Some(texels)
+}
+
+fn void_extent(bits: u128, hdr: bool) -> Option<Texel> {
+    // 2D blocks set both reserved bits.
+    if read_bits(bits, 10, 2) != 3 {
+        return None;
+    }
+
+    let is_hdr = read_bits(bits, 9, 1) != 0;
+    if is_hdr && !hdr {
+        return None;
+    }
+
+    // The extent coordinates are only an optimization hint and can be ignored.
+    // The constant color is UNORM16 for LDR and half precision float for HDR.
+    let values = [0, 1, 2, 3].map(|i| read_bits(bits, 64 + 16 * i, 16) as u16);
+    Some(Texel {
+        values,
+        is_half: [is_hdr; 4],
+    })
+}
+
+fn ise_bit_count(count: usize, range: usize) -> usize {
+    let (encoding, bits) = RANGES[range];
+    let bits = bits as usize;
+    match encoding {
+        Encoding::Bits => count * bits,
+        Encoding::Trits => count * bits + (8 * count).div_ceil(5),
+        Encoding::Quints => count * bits + (7 * count).div_ceil(3),
+    }
+}
+
+struct BitReader {
+    bits: u128,
+    offset: usize,
+}
+
+impl BitReader {
+    fn read(&mut self, count: u32) -> u32 {
+        let value = read_bits(self.bits, self.offset, count as usize);
+        self.offset += count as usize;
+        value
+    }
+}
+
+// Decode the integer sequence in `bits` to values in the given range.
+// Trits and quints are stored in the upper bits of each value.
+fn decode_ise(bits: u128, range: usize, values: &mut [u8]) {
+    let (encoding, bit_count) = RANGES[range];
+    let mut reader = BitReader { bits, offset: 0 };
+
+    match encoding {
+        Encoding::Bits => {
+            for value in values {
+                *value = reader.read(bit_count) as u8;
+            }
+        }
+        Encoding::Trits => {
+            // Each group of 5 values interleaves 8 bits of packed trits.
+            // Missing bits for incomplete groups are zero.
+            for chunk in values.chunks_mut(5) {
+                let mut m = [0; 5];
+                let mut t = 0;
+                m[0] = reader.read(bit_count);
+                t |= reader.read(2);
+                m[1] = reader.read(bit_count);
+                t |= reader.read(2) << 2;
+                m[2] = reader.read(bit_count);
+                t |= reader.read(1) << 4;
+                m[3] = reader.read(bit_count);
+                t |= reader.read(2) << 5;
+                m[4] = reader.read(bit_count);
+                t |= reader.read(1) << 7;
+
+                let trits = decode_trits(t);
+                for (i, value) in chunk.iter_mut().enumerate() {
+                    *value = ((trits[i] << bit_count) | m[i]) as u8;
+                }
+            }
+        }
+        Encoding::Quints => {
+            // Each group of 3 values interleaves 7 bits of packed quints.
+            for chunk in values.chunks_mut(3) {
+                let mut m = [0; 3];
+                let mut q = 0;
+                m[0] = reader.read(bit_count);
+                q |= reader.read(3);
+                m[1] = reader.read(bit_count);
+                q |= reader.read(2) << 3;
+                m[2] = reader.read(bit_count);
+                q |= reader.read(2) << 5;
+
+                let quints = decode_quints(q);
+                for (i, value) in chunk.iter_mut().enumerate() {
+                    *value = ((quints[i] << bit_count) | m[i]) as u8;
+                }
+            }
+        }
+    }
+}
+
+fn decode_trits(t: u32) -> [u32; 5] {
+    let bit = |x: u32, i: u32| (x >> i) & 1;
+
+    let (c, t3, t4) = if (t >> 2) & 7 == 7 {
+        (((t >> 5) & 7) << 2 | (t & 3), 2, 2)
+    } else if (t >> 5) & 3 == 3 {
+        (t & 0x1F, bit(t, 7), 2)
+    } else {
+        (t & 0x1F, (t >> 5) & 3, bit(t, 7))
+    };
+
+    let (t0, t1, t2) = if c & 3 == 3 {
+        let t0 = (bit(c, 3) << 1) | (bit(c, 2) & !bit(c, 3) & 1);
+        (t0, bit(c, 4), 2)
+    } else if (c >> 2) & 3 == 3 {
+        (c & 3, 2, 2)
+    } else {
+        let t0 = (bit(c, 1) << 1) | (bit(c, 0) & !bit(c, 1) & 1);
+        (t0, (c >> 2) & 3, bit(c, 4))
+    };
+
+    [t0, t1, t2, t3, t4]
+}
+
+fn decode_quints(q: u32) -> [u32; 3] {
+    let bit = |x: u32, i: u32| (x >> i) & 1;
+
+    if (q >> 1) & 3 == 3 && (q >> 5) & 3 == 0 {
+        let not_q0 = !bit(q, 0) & 1;
+        let q2 = (bit(q, 0) << 2) | ((bit(q, 4) & not_q0) << 1) | (bit(q, 3) & not_q0);
+        [4, 4, q2]
+    } else {
+        let (c, q2) = if (q >> 1) & 3 == 3 {
+            let c = (((q >> 3) & 3) << 3) | ((!(q >> 5) & 3) << 1) | bit(q, 0);
+            (c, 4)
+        } else {
+            (q & 0x1F, (q >> 5) & 3)
+        };
+
+        if c & 7 == 5 {
+            [(c >> 3) & 3, 4, q2]
+        } else {
+            [c & 7, (c >> 3) & 3, q2]
+        }
+    }
+}
+
+// Repeat the low `bits` of `value` to fill `target` bits.
+fn replicate_bits(value: u32, bits: u32, target: u32) -> u32 {
+    let mut result = 0;
+    let mut shift = target as i32 - bits as i32;
+    while shift > -(bits as i32) {
+        if shift >= 0 {
+            result |= value << shift;
+        } else {
+            result |= value >> -shift;
+        }
+        shift -= bits as i32;
+    }
+    result & ((1 << target) - 1)
+}
+
+// Unquantize color endpoint values to the range 0 to 255.
+fn unquantize_color(value: u8, range: usize) -> u8 {
+    let (encoding, bit_count) = RANGES[range];
+    let value = value as u32;
+    let m = value & ((1 << bit_count) - 1);
+    let d = value >> bit_count;
+
+    let (b, c) = match (encoding, bit_count) {
+        (Encoding::Bits, _) => return replicate_bits(value, bit_count, 8) as u8,
+        (Encoding::Trits, 1) => (0, 204),
+        (Encoding::Trits, 2) => ((m >> 1) * 0x116, 93),
+        (Encoding::Trits, 3) => {
+            let x = (m >> 1) & 3;
+            ((x << 7) | (x << 2) | x, 44)
+        }
+        (Encoding::Trits, 4) => {
+            let x = (m >> 1) & 7;
+            ((x << 6) | x, 22)
+        }
+        (Encoding::Trits, 5) => {
+            let x = (m >> 1) & 0xF;
+            ((x << 5) | (x >> 2), 11)
+        }
+        (Encoding::Trits, _) => {
+            let x = (m >> 1) & 0x1F;
+            ((x << 4) | (x >> 4), 5)
+        }
+        (Encoding::Quints, 1) => (0, 113),
+        (Encoding::Quints, 2) => ((m >> 1) * 0x10C, 54),
+        (Encoding::Quints, 3) => {
+            let x = (m >> 1) & 3;
+            ((x << 7) | (x << 1) | (x >> 1), 26)
+        }
+        (Encoding::Quints, 4) => {
+            let x = (m >> 1) & 7;
+            ((x << 6) | (x >> 1), 13)
+        }
+        (Encoding::Quints, _) => {
+            let x = (m >> 1) & 0xF;
+            ((x << 5) | (x >> 3), 6)
+        }
+    };
+
+    let a = if m & 1 != 0 { 0x1FF } else { 0 };
+    let t = (d * c + b) ^ a;
+    ((a & 0x80) | (t >> 2)) as u8
+}
+
+// Unquantize weights to the range 0 to 64.
+fn unquantize_weight(value: u8, range: usize) -> u8 {
+    let (encoding, bit_count) = RANGES[range];
+    let value = value as u32;
+    let m = value & ((1 << bit_count) - 1);
+    let d = value >> bit_count;
+
+    let t = match (encoding, bit_count) {
+        (Encoding::Bits, _) => replicate_bits(value, bit_count, 6),
+        // These ranges already use the full range 0 to 64.
+        (Encoding::Trits, 0) => return d as u8 * 32,
+        (Encoding::Quints, 0) => return d as u8 * 16,
+        _ => {
+            let (b, c) = match (encoding, bit_count) {
+                (Encoding::Trits, 1) => (0, 50),
+                (Encoding::Trits, 2) => ((m >> 1) * 0x45, 23),
+                (Encoding::Trits, _) => {
+                    let x = (m >> 1) & 3;
+                    ((x << 5) | x, 11)
+                }
+                (Encoding::Quints, 1) => (0, 28),
+                _ => ((m >> 1) * 0x42, 13),
+            };
+            let a = if m & 1 != 0 { 0x7F } else { 0 };
+            let t = (d * c + b) ^ a;
+            (a & 0x20) | (t >> 2)
+        }
+    };
+
+    // Map 0 to 63 to 0 to 64.
+    if t > 32 {
+        t as u8 + 1
+    } else {
+        t as u8
+    }
+}
+
+// Bilinearly interpolate the weight grid to the block footprint.
+fn infill_weight(
+    weights: &[u8],
+    plane_count: usize,
+    plane: usize,
+    grid_dimensions: (usize, usize),
+    block_dimensions: (usize, usize),
+    texel: (usize, usize),
+) -> u32 {
+    let (grid_width, grid_height) = grid_dimensions;
+    let (block_width, block_height) = block_dimensions;
+    let (x, y) = texel;
+
+    let ds = (1024 + block_width / 2) / (block_width - 1);
+    let dt = (1024 + block_height / 2) / (block_height - 1);
+
+    let gs = (ds * x * (grid_width - 1) + 32) >> 6;
+    let gt = (dt * y * (grid_height - 1) + 32) >> 6;
+    let (js, fs) = (gs >> 4, gs & 0xF);
+    let (jt, ft) = (gt >> 4, gt & 0xF);
+
+    let w11 = (fs * ft + 8) >> 4;
+    let w10 = ft - w11;
+    let w01 = fs - w11;
+    // fs + ft can exceed 16, so add w11 first to avoid underflow.
+    let w00 = 16 + w11 - fs - ft;
+
+    // Neighbors past the edge of the grid always have a factor of zero.
+    let weight = |i: usize| {
+        weights
+            .get(i * plane_count + plane)
+            .copied()
+            .unwrap_or_default() as usize
+    };
+    let v0 = js + jt * grid_width;
+
+    ((weight(v0) * w00
+        + weight(v0 + 1) * w01
+        + weight(v0 + grid_width) * w10
+        + weight(v0 + grid_width + 1) * w11
+        + 8)
+        >> 4) as u32
+}
+
+fn hash52(p: u32) -> u32 {
+    let mut p = p;
+    p ^= p >> 15;
+    p = p.wrapping_sub(p << 17);
+    p = p.wrapping_add(p << 7);
+    p = p.wrapping_add(p << 4);
+    p ^= p >> 5;
+    p = p.wrapping_add(p << 16);
+    p ^= p >> 7;
+    p ^= p >> 3;
+    p ^= p << 6;
+    p ^= p >> 17;
+    p
+}
+
+fn select_partition(
+    seed: u32,
+    x: usize,
+    y: usize,
+    partition_count: usize,
+    small_block: bool,
+) -> usize {
+    let (x, y) = if small_block {
+        (x as u32 * 2, y as u32 * 2)
+    } else {
+        (x as u32, y as u32)
+    };
+
+    let seed = seed + (partition_count as u32 - 1) * 1024;
+    let rnum = hash52(seed);
+
+    let mut seeds = [
+        rnum & 0xF,
+        (rnum >> 4) & 0xF,
+        (rnum >> 8) & 0xF,
+        (rnum >> 12) & 0xF,
+        (rnum >> 16) & 0xF,
+        (rnum >> 20) & 0xF,
+        (rnum >> 24) & 0xF,
+        (rnum >> 28) & 0xF,
+        (rnum >> 18) & 0xF,
+        (rnum >> 22) & 0xF,
+        (rnum >> 26) & 0xF,
+        rnum.rotate_left(2) & 0xF,
+    ];
+    for s in &mut seeds {
+        *s *= *s;
+    }
+
+    let (sh1, sh2) = if seed & 1 != 0 {
+        (
+            if seed & 2 != 0 { 4 } else { 5 },
+            if partition_count == 3 { 6 } else { 5 },
+        )
+    } else {
+        (
+            if partition_count == 3 { 6 } else { 5 },
+            if seed & 2 != 0 { 4 } else { 5 },
+        )
+    };
+    let sh3 = if seed & 0x10 != 0 { sh1 } else { sh2 };
+
+    for (i, s) in seeds.iter_mut().enumerate() {
+        *s >>= match i {
+            0..=7 if i % 2 == 0 => sh1,
+            0..=7 => sh2,
+            _ => sh3,
+        };
+    }
+
+    // Only 2D blocks are supported, so the z coordinate terms are always zero.
+    let a = (seeds[0] * x + seeds[1] * y + (rnum >> 14)) & 0x3F;
+    let b = (seeds[2] * x + seeds[3] * y + (rnum >> 10)) & 0x3F;
+    let c = if partition_count >= 3 {
+        (seeds[4] * x + seeds[5] * y + (rnum >> 6)) & 0x3F
+    } else {
+        0
+    };
+    let d = if partition_count >= 4 {
+        (seeds[6] * x + seeds[7] * y + (rnum >> 2)) & 0x3F
+    } else {
+        0
+    };
+
+    if a >= b && a >= c && a >= d {
+        0
+    } else if b >= c && b >= d {
+        1
+    } else if c >= d {
+        2
+    } else {
+        3
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Endpoints {
+    // Expanded to 16 bits for interpolation.
+    e0: [u16; 4],
+    e1: [u16; 4],
+    // HDR channels are stored in a logarithmic encoding.
+    is_hdr: [bool; 4],
+}
+
+impl Endpoints {
+    fn ldr(e0: [i32; 4], e1: [i32; 4], srgb: bool) -> Self {
+        Self {
+            e0: e0.map(|v| expand_ldr(v, srgb)),
+            e1: e1.map(|v| expand_ldr(v, srgb)),
+            is_hdr: [false; 4],
+        }
+    }
+
+    fn hdr(e0: [i32; 4], e1: [i32; 4], alpha_is_hdr: bool) -> Self {
+        // HDR endpoints use 12 bits.
+        Self {
+            e0: e0.map(|v| (v << 4) as u16),
+            e1: e1.map(|v| (v << 4) as u16),
+            is_hdr: [true, true, true, alpha_is_hdr],
+        }
+    }
+}
+
+fn expand_ldr(value: i32, srgb: bool) -> u16 {
+    let value = value.clamp(0, 255) as u16;
+    if srgb {
+        (value << 8) | 0x80
+    } else {
+        value * 257
+    }
+}
+
+// Transfer the most significant bit from a to b and sign extend the remaining bits of a.
+fn bit_transfer_signed(a: i32, b: i32) -> (i32, i32) {
+    let b = (b >> 1) | (a & 0x80);
+    let a = (a >> 1) & 0x3F;
+    let a = if a & 0x20 != 0 { a - 0x40 } else { a };
+    (a, b)
+}
+
+fn blue_contract(r: i32, g: i32, b: i32, a: i32) -> [i32; 4] {
+    [(r + b) >> 1, (g + b) >> 1, b, a]
+}
+
+fn decode_endpoints(mode: u32, values: &[u8], srgb: bool) -> Endpoints {
+    let mut v = [0i32; 8];
+    for (v, value) in v.iter_mut().zip(values) {
+        *v = *value as i32;
+    }
+
+    match mode {
+        // LDR luminance, direct
+        0 => Endpoints::ldr([v[0], v[0], v[0], 255], [v[1], v[1], v[1], 255], srgb),
+        // LDR luminance, base+offset
+        1 => {
+            let l0 = (v[0] >> 2) | (v[1] & 0xC0);
+            let l1 = (l0 + (v[1] & 0x3F)).min(255);
+            Endpoints::ldr([l0, l0, l0, 255], [l1, l1, l1, 255], srgb)
+        }
+        // HDR luminance, large range
+        2 => {
+            let (y0, y1) = if v[1] >= v[0] {
+                (v[0] << 4, v[1] << 4)
+            } else {
+                ((v[1] << 4) + 8, (v[0] << 4) - 8)
+            };
+            Endpoints::hdr([y0, y0, y0, 0x780], [y1, y1, y1, 0x780], true)
+        }
+        // HDR luminance, small range
+        3 => {
+            let (y0, d) = if v[0] & 0x80 != 0 {
+                (
+                    ((v[1] & 0xE0) << 4) | ((v[0] & 0x7F) << 2),
+                    (v[1] & 0x1F) << 2,
+                )
+            } else {
+                (
+                    ((v[1] & 0xF0) << 4) | ((v[0] & 0x7F) << 1),
+                    (v[1] & 0x0F) << 1,
+                )
+            };
+            let y1 = (y0 + d).min(0xFFF);
+            Endpoints::hdr([y0, y0, y0, 0x780], [y1, y1, y1, 0x780], true)
+        }
+        // LDR luminance+alpha, direct
+        4 => Endpoints::ldr([v[0], v[0], v[0], v[2]], [v[1], v[1], v[1], v[3]], srgb),
+        // LDR luminance+alpha, base+offset
+        5 => {
+            let (b0, a0) = bit_transfer_signed(v[1], v[0]);
+            let (b2, a2) = bit_transfer_signed(v[3], v[2]);
+            let l1 = a0 + b0;
+            Endpoints::ldr([a0, a0, a0, a2], [l1, l1, l1, a2 + b2], srgb)
+        }
+        // LDR RGB, base+scale
+        6 => Endpoints::ldr(
+            [
+                (v[0] * v[3]) >> 8,
+                (v[1] * v[3]) >> 8,
+                (v[2] * v[3]) >> 8,
+                255,
+            ],
+            [v[0], v[1], v[2], 255],
+            srgb,
+        ),
+        // HDR RGB, base+scale
+        7 => {
+            let (e0, e1) = hdr_rgb_base_scale(&v);
+            Endpoints::hdr(e0, e1, true)
+        }
+        // LDR RGB, direct
+        8 => {
+            if v[1] + v[3] + v[5] >= v[0] + v[2] + v[4] {
+                Endpoints::ldr([v[0], v[2], v[4], 255], [v[1], v[3], v[5], 255], srgb)
+            } else {
+                Endpoints::ldr(
+                    blue_contract(v[1], v[3], v[5], 255),
+                    blue_contract(v[0], v[2], v[4], 255),
+                    srgb,
+                )
+            }
+        }
+        // LDR RGB, base+offset
+        9 => {
+            let (b0, a0) = bit_transfer_signed(v[1], v[0]);
+            let (b1, a1) = bit_transfer_signed(v[3], v[2]);
+            let (b2, a2) = bit_transfer_signed(v[5], v[4]);
+            if b0 + b1 + b2 >= 0 {
+                Endpoints::ldr([a0, a1, a2, 255], [a0 + b0, a1 + b1, a2 + b2, 255], srgb)
+            } else {
+                Endpoints::ldr(
+                    blue_contract(a0 + b0, a1 + b1, a2 + b2, 255),
+                    blue_contract(a0, a1, a2, 255),
+                    srgb,
+                )
+            }
+        }
+        // LDR RGB, base+scale plus two A
+        10 => Endpoints::ldr(
+            [
+                (v[0] * v[3]) >> 8,
+                (v[1] * v[3]) >> 8,
+                (v[2] * v[3]) >> 8,
+                v[4],
+            ],
+            [v[0], v[1], v[2], v[5]],
+            srgb,
+        ),
+        // HDR RGB, direct
+        11 => {
+            let (e0, e1) = hdr_rgb_direct(&v);
+            Endpoints::hdr(e0, e1, true)
+        }
+        // LDR RGBA, direct
+        12 => {
+            if v[1] + v[3] + v[5] >= v[0] + v[2] + v[4] {
+                Endpoints::ldr([v[0], v[2], v[4], v[6]], [v[1], v[3], v[5], v[7]], srgb)
+            } else {
+                Endpoints::ldr(
+                    blue_contract(v[1], v[3], v[5], v[7]),
+                    blue_contract(v[0], v[2], v[4], v[6]),
+                    srgb,
+                )
+            }
+        }
+        // LDR RGBA, base+offset
+        13 => {
+            let (b0, a0) = bit_transfer_signed(v[1], v[0]);
+            let (b1, a1) = bit_transfer_signed(v[3], v[2]);
+            let (b2, a2) = bit_transfer_signed(v[5], v[4]);
+            let (b3, a3) = bit_transfer_signed(v[7], v[6]);
+            if b0 + b1 + b2 >= 0 {
+                Endpoints::ldr([a0, a1, a2, a3], [a0 + b0, a1 + b1, a2 + b2, a3 + b3], srgb)
+            } else {
+                Endpoints::ldr(
+                    blue_contract(a0 + b0, a1 + b1, a2 + b2, a3 + b3),
+                    blue_contract(a0, a1, a2, a3),
+                    srgb,
+                )
+            }
+        }
+        // HDR RGB, direct + LDR alpha
+        14 => {
+            let (e0, e1) = hdr_rgb_direct(&v);
+            let mut endpoints = Endpoints::hdr(e0, e1, false);
+            endpoints.e0[3] = expand_ldr(v[6], false);
+            endpoints.e1[3] = expand_ldr(v[7], false);
+            endpoints
+        }
+        // HDR RGB, direct + HDR alpha
+        _ => {
+            let (mut e0, mut e1) = hdr_rgb_direct(&v);
+            (e0[3], e1[3]) = hdr_alpha(v[6], v[7]);
+            Endpoints::hdr(e0, e1, true)
+        }
+    }
+}
+
+fn hdr_rgb_base_scale(v: &[i32; 8]) -> ([i32; 4], [i32; 4]) {
+    let mode_value =
+        ((v[0] & 0xC0) >> 6) | (((v[1] & 0x80) >> 7) << 2) | (((v[2] & 0x80) >> 7) << 3);
+
+    let (major_component, mode) = if mode_value & 0xC != 0xC {
+        (mode_value >> 2, mode_value & 3)
+    } else if mode_value != 0xF {
+        (mode_value & 3, 4)
+    } else {
+        (0, 5)
+    };
+
+    let mut red = v[0] & 0x3F;
+    let mut green = v[1] & 0x1F;
+    let mut blue = v[2] & 0x1F;
+    let mut scale = v[3] & 0x1F;
+
+    let bit0 = (v[1] >> 6) & 1;
+    let bit1 = (v[1] >> 5) & 1;
+    let bit2 = (v[2] >> 6) & 1;
+    let bit3 = (v[2] >> 5) & 1;
+    let bit4 = (v[3] >> 7) & 1;
+    let bit5 = (v[3] >> 6) & 1;
+    let bit6 = (v[3] >> 5) & 1;
+
+    // The placement of the remaining bits depends on the mode.
+    let mode_mask = 1 << mode;
+    if mode_mask & 0x30 != 0 {
+        green |= bit0 << 6;
+        blue |= bit2 << 6;
+    }
+    if mode_mask & 0x3A != 0 {
+        green |= bit1 << 5;
+        blue |= bit3 << 5;
+    }
+    if mode_mask & 0x3D != 0 {
+        scale |= bit6 << 5;
+    }
+    if mode_mask & 0x2D != 0 {
+        scale |= bit5 << 6;
+    }
+    if mode_mask & 0x04 != 0 {
+        scale |= bit4 << 7;
+    }
+    if mode_mask & 0x3B != 0 {
+        red |= bit4 << 6;
+    }
+    if mode_mask & 0x04 != 0 {
+        red |= bit3 << 6;
+    }
+    if mode_mask & 0x10 != 0 {
+        red |= bit5 << 7;
+    }
+    if mode_mask & 0x0F != 0 {
+        red |= bit2 << 7;
+    }
+    if mode_mask & 0x05 != 0 {
+        red |= bit1 << 8;
+        red |= bit0 << 9;
+    }
+    if mode_mask & 0x0A != 0 {
+        red |= bit0 << 8;
+    }
+    if mode_mask & 0x02 != 0 {
+        red |= bit6 << 9;
+        red |= bit5 << 10;
+    }
+    if mode_mask & 0x01 != 0 {
+        red |= bit3 << 10;
+    }
+
+    // Expand to 12 bits.
+    let shift = [1, 1, 2, 3, 4, 5][mode as usize];
+    red <<= shift;
+    green <<= shift;
+    blue <<= shift;
+    scale <<= shift;
+
+    // Green and blue are differences from red except for mode 5.
+    if mode != 5 {
+        green = red - green;
+        blue = red - blue;
+    }
+
+    match major_component {
+        1 => core::mem::swap(&mut red, &mut green),
+        2 => core::mem::swap(&mut red, &mut blue),
+        _ => (),
+    }
+
+    let e0 = [red - scale, green - scale, blue - scale, 0x780].map(|c| c.clamp(0, 0xFFF));
+    let e1 = [red, green, blue, 0x780].map(|c| c.clamp(0, 0xFFF));
+    (e0, e1)
+}
+
+fn hdr_rgb_direct(v: &[i32; 8]) -> ([i32; 4], [i32; 4]) {
+    let mode = ((v[1] & 0x80) >> 7) | (((v[2] & 0x80) >> 7) << 1) | (((v[3] & 0x80) >> 7) << 2);
+    let major_component = ((v[4] & 0x80) >> 7) | (((v[5] & 0x80) >> 7) << 1);
+
+    if major_component == 3 {
+        return (
+            [v[0] << 4, v[2] << 4, (v[4] & 0x7F) << 5, 0x780],
+            [v[1] << 4, v[3] << 4, (v[5] & 0x7F) << 5, 0x780],
+        );
+    }
+
+    let mut a = v[0] | ((v[1] & 0x40) << 2);
+    let mut b0 = v[2] & 0x3F;
+    let mut b1 = v[3] & 0x3F;
+    let mut c = v[1] & 0x3F;
+    let mut d0 = v[4] & 0x7F;
+    let mut d1 = v[5] & 0x7F;
+
+    let d_bits = [7, 6, 7, 6, 5, 6, 5, 6][mode as usize];
+
+    let bit0 = (v[2] >> 6) & 1;
+    let bit1 = (v[3] >> 6) & 1;
+    let bit2 = (v[4] >> 6) & 1;
+    let bit3 = (v[5] >> 6) & 1;
+    let bit4 = (v[4] >> 5) & 1;
+    let bit5 = (v[5] >> 5) & 1;
+
+    // The placement of the remaining bits depends on the mode.
+    let mode_mask = 1 << mode;
+    if mode_mask & 0xA4 != 0 {
+        a |= bit0 << 9;
+    }
+    if mode_mask & 0x08 != 0 {
+        a |= bit2 << 9;
+    }
+    if mode_mask & 0x50 != 0 {
+        a |= bit4 << 9;
+        a |= bit5 << 10;
+    }
+    if mode_mask & 0xA0 != 0 {
+        a |= bit1 << 10;
+    }
+    if mode_mask & 0xC0 != 0 {
+        a |= bit2 << 11;
+    }
+    if mode_mask & 0x04 != 0 {
+        c |= bit1 << 6;
+    }
+    if mode_mask & 0xE8 != 0 {
+        c |= bit3 << 6;
+    }
+    if mode_mask & 0x20 != 0 {
+        c |= bit2 << 7;
+    }
+    if mode_mask & 0x5B != 0 {
+        b0 |= bit0 << 6;
+        b1 |= bit1 << 6;
+    }
+    if mode_mask & 0x12 != 0 {
+        b0 |= bit2 << 7;
+        b1 |= bit3 << 7;
+    }
+    if mode_mask & 0xAF != 0 {
+        d0 |= bit4 << 5;
+        d1 |= bit5 << 5;
+    }
+    if mode_mask & 0x05 != 0 {
+        d0 |= bit2 << 6;
+        d1 |= bit3 << 6;
+    }
+
+    // Sign extend the differences.
+    let sign_shift = 32 - d_bits;
+    d0 = (d0 << sign_shift) >> sign_shift;
+    d1 = (d1 << sign_shift) >> sign_shift;
+
+    // Expand to 12 bits.
+    let shift = (mode >> 1) ^ 3;
+    a <<= shift;
+    b0 <<= shift;
+    b1 <<= shift;
+    c <<= shift;
+    d0 <<= shift;
+    d1 <<= shift;
+
+    let mut e0 = [a - c, a - b0 - c - d0, a - b1 - c - d1, 0x780].map(|x| x.clamp(0, 0xFFF));
+    let mut e1 = [a, a - b0, a - b1, 0x780].map(|x| x.clamp(0, 0xFFF));
+
+    match major_component {
+        1 => {
+            e0.swap(0, 1);
+            e1.swap(0, 1);
+        }
+        2 => {
+            e0.swap(0, 2);
+            e1.swap(0, 2);
+        }
+        _ => (),
+    }
+
+    (e0, e1)
+}
+
+fn hdr_alpha(v6: i32, v7: i32) -> (i32, i32) {
+    let mode = ((v6 >> 7) & 1) | ((v7 >> 6) & 2);
+    let mut v6 = v6 & 0x7F;
+    let mut v7 = v7 & 0x7F;
+
+    if mode == 3 {
+        (v6 << 5, v7 << 5)
+    } else {
+        v6 |= (v7 << (mode + 1)) & 0x780;
+        v7 &= 0x3F >> mode;
+        v7 ^= 0x20 >> mode;
+        v7 -= 0x20 >> mode;
+        v6 <<= 4 - mode;
+        v7 <<= 4 - mode;
+        v7 += v6;
+        (v6, v7.clamp(0, 0xFFF))
+    }
+}
+
+// Convert the interpolated logarithmic value to a half precision float.
+fn f16_from_lns(value: u32) -> u16 {
+    let exponent = value >> 11;
+    let mantissa = value & 0x7FF;
+    let mantissa = if mantissa < 512 {
+        3 * mantissa
+    } else if mantissa >= 1536 {
+        5 * mantissa - 2048
+    } else {
+        4 * mantissa - 512
+    };
+    // Avoid producing infinity or NaN.
+    (((exponent << 10) + (mantissa >> 3)) as u16).min(0x7BFF)
+}
+
+fn f32_from_f16(value: u16) -> f32 {
+    let sign = ((value & 0x8000) as u32) << 16;
+    let exponent = ((value >> 10) & 0x1F) as u32;
+    let mantissa = (value & 0x3FF) as u32;
+
+    let bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            // Subnormal half values are normal single precision values.
+            let f = mantissa as f32 / (1 << 24) as f32;
+            return if sign != 0 { -f } else { f };
+        }
+        (31, 0) => sign | 0x7F80_0000,
+        (31, _) => sign | 0x7FC0_0000,
+        _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_trits_all_values() {
+        // Every combination of 5 trits has at least one encoding.
+        let mut found = [false; 243];
+        for t in 0..256 {
+            let trits = decode_trits(t);
+            assert!(trits.iter().all(|t| *t < 3));
+            let index = trits.iter().rev().fold(0, |acc, t| acc * 3 + *t as usize);
+            found[index] = true;
+        }
+        assert!(found.iter().all(|f| *f));
+    }
+
+    #[test]
+    fn decode_quints_all_values() {
+        // Every combination of 3 quints has at least one encoding.
+        let mut found = [false; 125];
+        for q in 0..128 {
+            let quints = decode_quints(q);
+            assert!(quints.iter().all(|q| *q < 5));
+            let index = quints.iter().rev().fold(0, |acc, q| acc * 5 + *q as usize);
+            found[index] = true;
+        }
+        assert!(found.iter().all(|f| *f));
+    }
+
+    // The largest value sets only the lowest bit since the lowest bit inverts the other bits.
+    fn max_value(range: usize) -> u8 {
+        match RANGES[range] {
+            (Encoding::Bits, bits) => ((1u32 << bits) - 1) as u8,
+            (Encoding::Trits, 0) => 2,
+            (Encoding::Quints, 0) => 4,
+            _ => 1,
+        }
+    }
+
+    #[test]
+    fn unquantize_weight_endpoints() {
+        for range in 0..12 {
+            assert_eq!(0, unquantize_weight(0, range));
+            assert_eq!(64, unquantize_weight(max_value(range), range));
+        }
+    }
+
+    #[test]
+    fn unquantize_color_endpoints() {
+        for range in MIN_COLOR_RANGE..RANGES.len() {
+            assert_eq!(0, unquantize_color(0, range));
+            assert_eq!(255, unquantize_color(max_value(range), range));
+        }
+    }
+
+    fn decode_luminance_8x8(compressed_block: [u8; 16]) -> [[u8; 8]; 8] {
+        let mut rgba = [0u8; 8 * 8 * 4];
+        astc_ldr(&compressed_block, &mut rgba, 8 * 4, 8, 8, false);
+
+        let mut luminance = [[0u8; 8]; 8];
+        for (i, pixel) in rgba.chunks_exact(4).enumerate() {
+            assert_eq!([pixel[0], pixel[0], pixel[0], 255], pixel);
+            luminance[i / 8][i % 8] = pixel[0];
+        }
+        luminance
+    }
+
+    #[test]
+    fn decode_8x8_reduced_weight_grid() {
+        // A 4x4 grid of 2-bit weights with luminance endpoints 0 and 255.
+        // Bilinear infill uses all four neighbors for most texels.
+        let compressed_block = [
+            0x42, 0x00, 0x00, 0xFE, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4E, 0xB1,
+            0xD8, 0x27,
+        ];
+        assert_eq!(
+            [
+                [0, 36, 72, 112, 143, 183, 219, 255],
+                [112, 116, 124, 128, 128, 131, 139, 143],
+                [223, 195, 171, 143, 112, 84, 60, 32],
+                [203, 195, 191, 147, 108, 64, 60, 52],
+                [135, 179, 223, 171, 84, 32, 76, 120],
+                [96, 147, 203, 175, 80, 52, 108, 159],
+                [131, 124, 112, 128, 128, 143, 131, 124],
+                [171, 96, 20, 80, 175, 235, 159, 84],
+            ],
+            decode_luminance_8x8(compressed_block)
+        );
+    }
+
+    #[test]
+    fn decode_8x8_two_partitions() {
+        // Partition seed 37 with luminance endpoints 16 to 96 and 160 to 240
+        // and the same weight grid as the reduced weight grid test.
+        let compressed_block = [
+            0x42, 0xA8, 0x04, 0x00, 0x02, 0x0C, 0x14, 0x1E, 0x00, 0x00, 0x00, 0x00, 0x4E, 0xB1,
+            0xD8, 0x27,
+        ];
+        assert_eq!(
+            [
+                [160, 171, 183, 195, 205, 73, 85, 96],
+                [195, 197, 199, 200, 200, 57, 59, 61],
+                [230, 222, 214, 205, 195, 42, 34, 26],
+                [80, 222, 220, 207, 194, 36, 34, 32],
+                [58, 217, 230, 214, 186, 26, 39, 53],
+                [46, 207, 224, 215, 41, 32, 49, 66],
+                [57, 199, 195, 200, 56, 61, 57, 54],
+                [70, 46, 166, 185, 71, 90, 66, 42],
+            ],
+            decode_luminance_8x8(compressed_block)
+        );
+    }
+
+    #[test]
+    fn decode_4x4_dual_plane() {
+        // RGBA endpoints (10, 20, 30, 40) and (200, 150, 100, 250) with 1-bit weights.
+        // Alpha uses the second plane of weights.
+        let compressed_block = [
+            0x41, 0x84, 0x15, 0x90, 0x29, 0x2C, 0x3D, 0xC8, 0x50, 0xF4, 0x01, 0xC0, 0xA1, 0x4E,
+            0x9C, 0x72,
+        ];
+        let mut rgba = [0u8; 4 * 4 * 4];
+        astc_ldr(&compressed_block, &mut rgba, 4 * 4, 4, 4, false);
+
+        let e0 = [10, 20, 30];
+        let e1 = [200, 150, 100];
+        let expected: [([u8; 3], u8); 16] = [
+            (e0, 250),
+            (e1, 250),
+            (e0, 40),
+            (e1, 40),
+            (e1, 40),
+            (e0, 250),
+            (e1, 250),
+            (e0, 40),
+            (e0, 250),
+            (e0, 40),
+            (e1, 250),
+            (e1, 40),
+            (e1, 40),
+            (e1, 40),
+            (e0, 40),
+            (e0, 250),
+        ];
+        for (pixel, (rgb, alpha)) in rgba.chunks_exact(4).zip(expected) {
+            assert_eq!([rgb[0], rgb[1], rgb[2], alpha], pixel);
+        }
+    }
+
+    #[test]
+    fn f16_from_lns_one() {
+        assert_eq!(0x3C00, f16_from_lns(0x780 << 4));
+        assert_eq!(1.0, f32_from_f16(0x3C00));
+    }
+}
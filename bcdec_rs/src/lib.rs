@@ -126,6 +126,54 @@ pub fn bc5(compressed_block: &[u8], decompressed_block: &mut [u8], destination_p
     );
 }
 
+/// Decode 8 bytes from `compressed_block` to signed R8
+/// with `destination_pitch` many bytes per output row.
+///
+/// The values `-128` and `-127` both represent `-1.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 8];
+/// let mut decompressed_block = [0i8; 4 * 4];
+/// bcdec_rs::bc4_signed(&compressed_block, &mut decompressed_block, 4);
+/// ```
+pub fn bc4_signed(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i8],
+    destination_pitch: usize,
+) {
+    smooth_alpha_block_signed(compressed_block, decompressed_block, destination_pitch, 1);
+}
+
+/// Decode 16 bytes from `compressed_block` to signed RG8
+/// with `destination_pitch` many bytes per output row.
+///
+/// The values `-128` and `-127` both represent `-1.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 16];
+/// let mut decompressed_block = [0i8; 4 * 4 * 2];
+/// bcdec_rs::bc5_signed(&compressed_block, &mut decompressed_block, 4 * 2);
+/// ```
+pub fn bc5_signed(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i8],
+    destination_pitch: usize,
+) {
+    smooth_alpha_block_signed(compressed_block, decompressed_block, destination_pitch, 2);
+    smooth_alpha_block_signed(
+        &compressed_block[8..],
+        &mut decompressed_block[1..],
+        destination_pitch,
+        2,
+    );
+}
+
 /// Decode 16 bytes from `compressed_block` to RGBFloat16
 /// with `destination_pitch` many half floats per output row.
 ///
@@ -1262,6 +1310,60 @@ fn smooth_alpha_block(
     }
 }
 
+// Signed variant of smooth_alpha_block used for BC4 and BC5 SNORM.
+fn smooth_alpha_block_signed(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i8],
+    destination_pitch: usize,
+    pixel_size: usize,
+) {
+    let mut alpha = [0i32; 8];
+
+    let alpha0 = compressed_block[0] as i8 as i32;
+    let alpha1 = compressed_block[1] as i8 as i32;
+
+    // -128 and -127 both map to -1.0.
+    alpha[0] = alpha0.max(-127);
+    alpha[1] = alpha1.max(-127);
+
+    if alpha0 > alpha1 {
+        // 6 interpolated alpha values.
+        alpha[2] = div_round_i32(6 * alpha[0] + alpha[1], 7); // 6/7*alpha_0 + 1/7*alpha_1
+        alpha[3] = div_round_i32(5 * alpha[0] + 2 * alpha[1], 7); // 5/7*alpha_0 + 2/7*alpha_1
+        alpha[4] = div_round_i32(4 * alpha[0] + 3 * alpha[1], 7); // 4/7*alpha_0 + 3/7*alpha_1
+        alpha[5] = div_round_i32(3 * alpha[0] + 4 * alpha[1], 7); // 3/7*alpha_0 + 4/7*alpha_1
+        alpha[6] = div_round_i32(2 * alpha[0] + 5 * alpha[1], 7); // 2/7*alpha_0 + 5/7*alpha_1
+        alpha[7] = div_round_i32(alpha[0] + 6 * alpha[1], 7); // 1/7*alpha_0 + 6/7*alpha_1
+    } else {
+        // 4 interpolated alpha values.
+        alpha[2] = div_round_i32(4 * alpha[0] + alpha[1], 5); // 4/5*alpha_0 + 1/5*alpha_1
+        alpha[3] = div_round_i32(3 * alpha[0] + 2 * alpha[1], 5); // 3/5*alpha_0 + 2/5*alpha_1
+        alpha[4] = div_round_i32(2 * alpha[0] + 3 * alpha[1], 5); // 2/5*alpha_0 + 3/5*alpha_1
+        alpha[5] = div_round_i32(alpha[0] + 4 * alpha[1], 5); // 1/5*alpha_0 + 4/5*alpha_1
+        alpha[6] = -127;
+        alpha[7] = 127;
+    }
+
+    let block = u64::from_le_bytes(compressed_block[..8].try_into().unwrap());
+    let mut indices = block >> 16;
+    for i in 0..4 {
+        for j in 0..4 {
+            let index = i * destination_pitch + j * pixel_size;
+            decompressed_block[index] = alpha[(indices & 0x07) as usize] as i8;
+            indices >>= 3;
+        }
+    }
+}
+
+// Round to nearest with ties away from zero.
+fn div_round_i32(n: i32, d: i32) -> i32 {
+    if n >= 0 {
+        (n + d / 2) / d
+    } else {
+        -((-n + d / 2) / d)
+    }
+}
+
 struct Bitstream {
     low: u64,
     high: u64,
@@ -0,0 +1,518 @@
+#![no_std]
+//! A safe, no_std, pure Rust decoder for ETC1, ETC2, and EAC compressed blocks.
+
+// Used information sources:
+// https://registry.khronos.org/DataFormat/specs/1.3/dataformat.1.3.html#ETC1
+// https://registry.khronos.org/DataFormat/specs/1.3/dataformat.1.3.html#ETC2
+// https://registry.khronos.org/OpenGL/specs/es/3.0/es_spec_3.0.pdf (Appendix C)
+//
+// All blocks store 4x4 pixels as 64-bit big endian integers.
+// Pixel indices are stored in column-major order unlike BCN formats.
+
+/// Decode 8 bytes from `compressed_block` to RGBA8
+/// with `destination_pitch` many bytes per output row.
+///
+/// ETC1 is a subset of ETC2 RGB, so this is equivalent to [etc2_rgb].
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 8];
+/// let mut decompressed_block = [0u8; 4 * 4 * 4];
+/// etcdec_rs::etc1(&compressed_block, &mut decompressed_block, 4 * 4);
+/// ```
+pub fn etc1(compressed_block: &[u8], decompressed_block: &mut [u8], destination_pitch: usize) {
+    etc2_rgb(compressed_block, decompressed_block, destination_pitch)
+}
+
+/// Decode 8 bytes from `compressed_block` to RGBA8
+/// with `destination_pitch` many bytes per output row.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 8];
+/// let mut decompressed_block = [0u8; 4 * 4 * 4];
+/// etcdec_rs::etc2_rgb(&compressed_block, &mut decompressed_block, 4 * 4);
+/// ```
+pub fn etc2_rgb(compressed_block: &[u8], decompressed_block: &mut [u8], destination_pitch: usize) {
+    color_block(
+        compressed_block,
+        decompressed_block,
+        destination_pitch,
+        false,
+    );
+}
+
+/// Decode 8 bytes from `compressed_block` to RGBA8 with punchthrough alpha
+/// with `destination_pitch` many bytes per output row.
+///
+/// Transparent pixels are decoded as black with an alpha of `0`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 8];
+/// let mut decompressed_block = [0u8; 4 * 4 * 4];
+/// etcdec_rs::etc2_rgba1(&compressed_block, &mut decompressed_block, 4 * 4);
+/// ```
+pub fn etc2_rgba1(
+    compressed_block: &[u8],
+    decompressed_block: &mut [u8],
+    destination_pitch: usize,
+) {
+    color_block(
+        compressed_block,
+        decompressed_block,
+        destination_pitch,
+        true,
+    );
+}
+
+/// Decode 16 bytes from `compressed_block` to RGBA8
+/// with `destination_pitch` many bytes per output row.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 16];
+/// let mut decompressed_block = [0u8; 4 * 4 * 4];
+/// etcdec_rs::etc2_rgba8(&compressed_block, &mut decompressed_block, 4 * 4);
+/// ```
+pub fn etc2_rgba8(
+    compressed_block: &[u8],
+    decompressed_block: &mut [u8],
+    destination_pitch: usize,
+) {
+    // The alpha block comes before the color block.
+    color_block(
+        &compressed_block[8..],
+        decompressed_block,
+        destination_pitch,
+        false,
+    );
+    alpha_block(
+        compressed_block,
+        &mut decompressed_block[3..],
+        destination_pitch,
+        4,
+    );
+}
+
+/// Decode 8 bytes from `compressed_block` to R16
+/// with `destination_pitch` many values per output row.
+///
+/// The decoded values use the 11-bit range `0` to `2047`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 8];
+/// let mut decompressed_block = [0u16; 4 * 4];
+/// etcdec_rs::eac_r11(&compressed_block, &mut decompressed_block, 4);
+/// ```
+pub fn eac_r11(compressed_block: &[u8], decompressed_block: &mut [u16], destination_pitch: usize) {
+    r11_block(compressed_block, decompressed_block, destination_pitch, 1);
+}
+
+/// Decode 16 bytes from `compressed_block` to RG16
+/// with `destination_pitch` many values per output row.
+///
+/// The decoded values use the 11-bit range `0` to `2047`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 16];
+/// let mut decompressed_block = [0u16; 4 * 4 * 2];
+/// etcdec_rs::eac_rg11(&compressed_block, &mut decompressed_block, 4 * 2);
+/// ```
+pub fn eac_rg11(compressed_block: &[u8], decompressed_block: &mut [u16], destination_pitch: usize) {
+    r11_block(compressed_block, decompressed_block, destination_pitch, 2);
+    r11_block(
+        &compressed_block[8..],
+        &mut decompressed_block[1..],
+        destination_pitch,
+        2,
+    );
+}
+
+/// Decode 8 bytes from `compressed_block` to signed R16
+/// with `destination_pitch` many values per output row.
+///
+/// The decoded values use the range `-1023` to `1023`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 8];
+/// let mut decompressed_block = [0i16; 4 * 4];
+/// etcdec_rs::eac_r11_signed(&compressed_block, &mut decompressed_block, 4);
+/// ```
+pub fn eac_r11_signed(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i16],
+    destination_pitch: usize,
+) {
+    r11_block_signed(compressed_block, decompressed_block, destination_pitch, 1);
+}
+
+/// Decode 16 bytes from `compressed_block` to signed RG16
+/// with `destination_pitch` many values per output row.
+///
+/// The decoded values use the range `-1023` to `1023`.
+///
+/// # Examples
+///
+/// ```rust
+/// // Decode a single 4x4 pixel block.
+/// let compressed_block = [0u8; 16];
+/// let mut decompressed_block = [0i16; 4 * 4 * 2];
+/// etcdec_rs::eac_rg11_signed(&compressed_block, &mut decompressed_block, 4 * 2);
+/// ```
+pub fn eac_rg11_signed(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i16],
+    destination_pitch: usize,
+) {
+    r11_block_signed(compressed_block, decompressed_block, destination_pitch, 2);
+    r11_block_signed(
+        &compressed_block[8..],
+        &mut decompressed_block[1..],
+        destination_pitch,
+        2,
+    );
+}
+
+static MODIFIER_TABLE: [[i32; 2]; 8] = [
+    [2, 8],
+    [5, 17],
+    [9, 29],
+    [13, 42],
+    [18, 60],
+    [24, 80],
+    [33, 106],
+    [47, 183],
+];
+
+static DISTANCE_TABLE: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+static ALPHA_MODIFIER_TABLE: [[i32; 8]; 16] = [
+    [-3, -6, -9, -15, 2, 5, 8, 14],
+    [-3, -7, -10, -13, 2, 6, 9, 12],
+    [-2, -5, -8, -13, 1, 4, 7, 12],
+    [-2, -4, -6, -13, 1, 3, 5, 12],
+    [-3, -6, -8, -12, 2, 5, 7, 11],
+    [-3, -7, -9, -11, 2, 6, 8, 10],
+    [-4, -7, -8, -11, 3, 6, 7, 10],
+    [-3, -5, -8, -11, 2, 4, 7, 10],
+    [-2, -6, -8, -10, 1, 5, 7, 9],
+    [-2, -5, -8, -10, 1, 4, 7, 9],
+    [-2, -4, -8, -10, 1, 3, 7, 9],
+    [-2, -5, -7, -10, 1, 4, 6, 9],
+    [-3, -4, -7, -10, 2, 3, 6, 9],
+    [-1, -2, -3, -10, 0, 1, 2, 9],
+    [-4, -6, -8, -9, 3, 5, 7, 8],
+    [-3, -5, -7, -9, 2, 4, 6, 8],
+];
+
+fn read_block(compressed_block: &[u8]) -> u64 {
+    u64::from_be_bytes(compressed_block[..8].try_into().unwrap())
+}
+
+fn bits(block: u64, offset: u32, count: u32) -> i32 {
+    ((block >> offset) & ((1 << count) - 1)) as i32
+}
+
+fn extend_4(x: i32) -> i32 {
+    (x << 4) | x
+}
+
+fn extend_5(x: i32) -> i32 {
+    (x << 3) | (x >> 2)
+}
+
+fn extend_6(x: i32) -> i32 {
+    (x << 2) | (x >> 4)
+}
+
+fn extend_7(x: i32) -> i32 {
+    (x << 1) | (x >> 6)
+}
+
+fn clamp_rgb(rgb: [i32; 3]) -> [u8; 4] {
+    let [r, g, b] = rgb.map(|c| c.clamp(0, 255) as u8);
+    [r, g, b, 255]
+}
+
+fn add_rgb(rgb: [i32; 3], value: i32) -> [i32; 3] {
+    rgb.map(|c| c + value)
+}
+
+// The 2-bit index for the pixel at column x and row y.
+fn pixel_index(block: u64, x: usize, y: usize) -> usize {
+    let i = x * 4 + y;
+    let msb = (block >> (16 + i)) & 1;
+    let lsb = (block >> i) & 1;
+    ((msb << 1) | lsb) as usize
+}
+
+fn color_block(
+    compressed_block: &[u8],
+    decompressed_block: &mut [u8],
+    destination_pitch: usize,
+    punchthrough: bool,
+) {
+    let block = read_block(compressed_block);
+
+    // Punchthrough alpha replaces the differential bit with an opaque bit.
+    let flag = bits(block, 33, 1) != 0;
+    let (differential, opaque) = if punchthrough {
+        (true, flag)
+    } else {
+        (flag, true)
+    };
+
+    let mut pixels = [[[0u8; 4]; 4]; 4];
+    if differential {
+        let r = bits(block, 59, 5);
+        let g = bits(block, 51, 5);
+        let b = bits(block, 43, 5);
+        // The 3-bit deltas are two's complement signed integers.
+        let dr = (bits(block, 56, 3) << 29) >> 29;
+        let dg = (bits(block, 48, 3) << 29) >> 29;
+        let db = (bits(block, 40, 3) << 29) >> 29;
+
+        // Out of range values select the additional ETC2 modes.
+        if !(0..32).contains(&(r + dr)) {
+            t_mode(block, opaque, &mut pixels);
+        } else if !(0..32).contains(&(g + dg)) {
+            h_mode(block, opaque, &mut pixels);
+        } else if !(0..32).contains(&(b + db)) {
+            planar_mode(block, &mut pixels);
+        } else {
+            let base_colors = [
+                [r, g, b].map(extend_5),
+                [r + dr, g + dg, b + db].map(extend_5),
+            ];
+            subblock_mode(block, base_colors, opaque, &mut pixels);
+        }
+    } else {
+        let base_colors = [
+            [bits(block, 60, 4), bits(block, 52, 4), bits(block, 44, 4)].map(extend_4),
+            [bits(block, 56, 4), bits(block, 48, 4), bits(block, 40, 4)].map(extend_4),
+        ];
+        subblock_mode(block, base_colors, opaque, &mut pixels);
+    }
+
+    for (y, row) in pixels.iter().enumerate() {
+        for (x, pixel) in row.iter().enumerate() {
+            let index = y * destination_pitch + x * 4;
+            decompressed_block[index..index + 4].copy_from_slice(pixel);
+        }
+    }
+}
+
+fn subblock_mode(
+    block: u64,
+    base_colors: [[i32; 3]; 2],
+    opaque: bool,
+    pixels: &mut [[[u8; 4]; 4]; 4],
+) {
+    let tables = [bits(block, 37, 3) as usize, bits(block, 34, 3) as usize];
+    let flip = bits(block, 32, 1) != 0;
+
+    for (y, row) in pixels.iter_mut().enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            // The block is split into two 2x4 or 4x2 subblocks.
+            let subblock = if flip { y / 2 } else { x / 2 };
+            let [a, b] = MODIFIER_TABLE[tables[subblock]];
+
+            let index = pixel_index(block, x, y);
+            *pixel = match (index, opaque) {
+                // Punchthrough alpha uses the index for -a for transparent pixels.
+                (2, false) => [0u8; 4],
+                (0, false) => clamp_rgb(base_colors[subblock]),
+                (0, true) => clamp_rgb(add_rgb(base_colors[subblock], a)),
+                (1, _) => clamp_rgb(add_rgb(base_colors[subblock], b)),
+                (2, true) => clamp_rgb(add_rgb(base_colors[subblock], -a)),
+                _ => clamp_rgb(add_rgb(base_colors[subblock], -b)),
+            };
+        }
+    }
+}
+
+fn t_mode(block: u64, opaque: bool, pixels: &mut [[[u8; 4]; 4]; 4]) {
+    let r1 = (bits(block, 59, 2) << 2) | bits(block, 56, 2);
+    let color1 = [r1, bits(block, 52, 4), bits(block, 48, 4)].map(extend_4);
+    let color2 = [bits(block, 44, 4), bits(block, 40, 4), bits(block, 36, 4)].map(extend_4);
+    let distance = DISTANCE_TABLE[((bits(block, 34, 2) << 1) | bits(block, 32, 1)) as usize];
+
+    let paint_colors = [
+        clamp_rgb(color1),
+        clamp_rgb(add_rgb(color2, distance)),
+        clamp_rgb(color2),
+        clamp_rgb(add_rgb(color2, -distance)),
+    ];
+    paint_mode(block, paint_colors, opaque, pixels);
+}
+
+fn h_mode(block: u64, opaque: bool, pixels: &mut [[[u8; 4]; 4]; 4]) {
+    let r1 = bits(block, 59, 4);
+    let g1 = (bits(block, 56, 3) << 1) | bits(block, 52, 1);
+    let b1 = (bits(block, 51, 1) << 3) | bits(block, 47, 3);
+    let r2 = bits(block, 43, 4);
+    let g2 = bits(block, 39, 4);
+    let b2 = bits(block, 35, 4);
+
+    // The least significant bit of the distance index depends on the color ordering.
+    let ordering = ((r1 << 8) | (g1 << 4) | b1) >= ((r2 << 8) | (g2 << 4) | b2);
+    let index = (bits(block, 34, 1) << 2) | (bits(block, 32, 1) << 1) | ordering as i32;
+    let distance = DISTANCE_TABLE[index as usize];
+
+    let color1 = [r1, g1, b1].map(extend_4);
+    let color2 = [r2, g2, b2].map(extend_4);
+    let paint_colors = [
+        clamp_rgb(add_rgb(color1, distance)),
+        clamp_rgb(add_rgb(color1, -distance)),
+        clamp_rgb(add_rgb(color2, distance)),
+        clamp_rgb(add_rgb(color2, -distance)),
+    ];
+    paint_mode(block, paint_colors, opaque, pixels);
+}
+
+fn paint_mode(
+    block: u64,
+    paint_colors: [[u8; 4]; 4],
+    opaque: bool,
+    pixels: &mut [[[u8; 4]; 4]; 4],
+) {
+    for (y, row) in pixels.iter_mut().enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let index = pixel_index(block, x, y);
+            *pixel = if index == 2 && !opaque {
+                [0u8; 4]
+            } else {
+                paint_colors[index]
+            };
+        }
+    }
+}
+
+fn planar_mode(block: u64, pixels: &mut [[[u8; 4]; 4]; 4]) {
+    let origin = [
+        extend_6(bits(block, 57, 6)),
+        extend_7((bits(block, 56, 1) << 6) | bits(block, 49, 6)),
+        extend_6((bits(block, 48, 1) << 5) | (bits(block, 43, 2) << 3) | bits(block, 39, 3)),
+    ];
+    let horizontal = [
+        extend_6((bits(block, 34, 5) << 1) | bits(block, 32, 1)),
+        extend_7(bits(block, 25, 7)),
+        extend_6(bits(block, 19, 6)),
+    ];
+    let vertical = [
+        extend_6(bits(block, 13, 6)),
+        extend_7(bits(block, 6, 7)),
+        extend_6(bits(block, 0, 6)),
+    ];
+
+    // Planar mode is always opaque.
+    for (y, row) in pixels.iter_mut().enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let (x, y) = (x as i32, y as i32);
+            let rgb: [i32; 3] = core::array::from_fn(|i| {
+                (x * (horizontal[i] - origin[i])
+                    + y * (vertical[i] - origin[i])
+                    + 4 * origin[i]
+                    + 2)
+                    >> 2
+            });
+            *pixel = clamp_rgb(rgb);
+        }
+    }
+}
+
+// The 3-bit index for the pixel at column x and row y.
+fn alpha_index(block: u64, x: usize, y: usize) -> usize {
+    let i = x * 4 + y;
+    ((block >> (45 - 3 * i)) & 0x7) as usize
+}
+
+fn alpha_block(
+    compressed_block: &[u8],
+    decompressed_block: &mut [u8],
+    destination_pitch: usize,
+    pixel_size: usize,
+) {
+    let block = read_block(compressed_block);
+    let base = bits(block, 56, 8);
+    let multiplier = bits(block, 52, 4);
+    let modifiers = ALPHA_MODIFIER_TABLE[bits(block, 48, 4) as usize];
+
+    for y in 0..4 {
+        for x in 0..4 {
+            let alpha = base + modifiers[alpha_index(block, x, y)] * multiplier;
+            decompressed_block[y * destination_pitch + x * pixel_size] = alpha.clamp(0, 255) as u8;
+        }
+    }
+}
+
+fn r11_block(
+    compressed_block: &[u8],
+    decompressed_block: &mut [u16],
+    destination_pitch: usize,
+    pixel_size: usize,
+) {
+    let block = read_block(compressed_block);
+    let base = bits(block, 56, 8) * 8 + 4;
+    let multiplier = bits(block, 52, 4);
+    let modifiers = ALPHA_MODIFIER_TABLE[bits(block, 48, 4) as usize];
+
+    for y in 0..4 {
+        for x in 0..4 {
+            let modifier = modifiers[alpha_index(block, x, y)];
+            // A multiplier of 0 uses a multiplier of 1/8 instead.
+            let value = if multiplier == 0 {
+                base + modifier
+            } else {
+                base + modifier * multiplier * 8
+            };
+            decompressed_block[y * destination_pitch + x * pixel_size] =
+                value.clamp(0, 2047) as u16;
+        }
+    }
+}
+
+fn r11_block_signed(
+    compressed_block: &[u8],
+    decompressed_block: &mut [i16],
+    destination_pitch: usize,
+    pixel_size: usize,
+) {
+    let block = read_block(compressed_block);
+    // -128 and -127 both map to -1.0.
+    let base = (bits(block, 56, 8) as u8 as i8).max(-127) as i32 * 8;
+    let multiplier = bits(block, 52, 4);
+    let modifiers = ALPHA_MODIFIER_TABLE[bits(block, 48, 4) as usize];
+
+    for y in 0..4 {
+        for x in 0..4 {
+            let modifier = modifiers[alpha_index(block, x, y)];
+            // A multiplier of 0 uses a multiplier of 1/8 instead.
+            let value = if multiplier == 0 {
+                base + modifier
+            } else {
+                base + modifier * multiplier * 8
+            };
+            decompressed_block[y * destination_pitch + x * pixel_size] =
+                value.clamp(-1023, 1023) as i16;
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use image_dds::{image_from_dds, ImageFormat, Surface};
+use image_dds::{image_from_dds, AlphaMode, ImageFormat, Surface};
 
 fn criterion_benchmark(c: &mut Criterion) {
     // Overestimate the surface size to avoid errors.
@@ -10,6 +10,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         layers: 1,
         mipmaps: 1,
         image_format: ImageFormat::BC7RgbaUnorm,
+        alpha_mode: AlphaMode::Straight,
         data: vec![0u8; 512 * 512 * 2],
     };
     let dds = surface.to_dds().unwrap();
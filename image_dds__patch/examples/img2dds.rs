@@ -23,6 +23,18 @@ fn main() {
                 .to_dds()
                 .unwrap()
         }
+        Some("cube") => {
+            // Assume a vertically stacked image with 6 square faces.
+            image_dds::SurfaceRgba8::from_image_layers(&image, 6)
+                .encode(
+                    format,
+                    image_dds::Quality::Fast,
+                    image_dds::Mipmaps::GeneratedAutomatic,
+                )
+                .unwrap()
+                .to_dds_cubemap()
+                .unwrap()
+        }
         Some("depth") => {
             // Assume a square image.
             image_dds::SurfaceRgba8::from_image_depth(&image, image.height() / image.width())
@@ -14,6 +14,7 @@ fuzz_target!(|input: (image_dds::ImageFormat, &[u8])| {
         layers: 1,
         mipmaps: 1,
         image_format,
+        alpha_mode: image_dds::AlphaMode::Straight,
         data,
     };
 
@@ -0,0 +1,254 @@
+// ASTC formats use different block footprints, so blocks are decoded separately from BCN formats.
+use crate::{div_round_up, error::SurfaceError, mip_size, ImageFormat};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+const CHANNELS: usize = 4;
+// All ASTC block footprints use 128 bits.
+const BLOCK_SIZE_IN_BYTES: usize = 16;
+// The largest 2D block footprint is 12x12 pixels.
+const MAX_BLOCK_ELEMENTS: usize = 12 * 12 * CHANNELS;
+
+/// Decompress the bytes in `data` to the uncompressed RGBA8 format.
+pub fn rgba8_from_astc(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[u8],
+) -> Result<Vec<u8>, SurfaceError> {
+    if is_hdr(image_format) {
+        // Truncate to clamp to 0 to 255 like BC6H.
+        let rgba = rgbaf32_from_astc(width, height, image_format, data)?;
+        return Ok(rgba.into_iter().map(|x| (x * 255.0) as u8).collect());
+    }
+
+    let (block_width, block_height, _) = image_format.block_dimensions();
+    let srgb = image_format.is_srgb();
+    rgba_from_astc(
+        width,
+        height,
+        image_format,
+        data,
+        |block, decompressed, pitch| {
+            astcdec_rs::astc_ldr(
+                block,
+                decompressed,
+                pitch,
+                block_width as usize,
+                block_height as usize,
+                srgb,
+            )
+        },
+    )
+}
+
+/// Decompress the bytes in `data` to the uncompressed RGBAF32 format using the HDR profile.
+pub fn rgbaf32_from_astc(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[u8],
+) -> Result<Vec<f32>, SurfaceError> {
+    let (block_width, block_height, _) = image_format.block_dimensions();
+    rgba_from_astc(
+        width,
+        height,
+        image_format,
+        data,
+        |block, decompressed, pitch| {
+            astcdec_rs::astc_hdr(
+                block,
+                decompressed,
+                pitch,
+                block_width as usize,
+                block_height as usize,
+            )
+        },
+    )
+}
+
+fn rgba_from_astc<T, F>(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[u8],
+    decompress_block: F,
+) -> Result<Vec<T>, SurfaceError>
+where
+    T: Copy + Default + Send,
+    F: Fn(&[u8], &mut [T], usize) + Sync,
+{
+    let (block_width, block_height, _) = image_format.block_dimensions();
+    let (block_width, block_height) = (block_width as usize, block_height as usize);
+
+    // Validate surface dimensions to check for potential overflow.
+    let expected_size = mip_size(
+        width as usize,
+        height as usize,
+        1,
+        block_width,
+        block_height,
+        1,
+        BLOCK_SIZE_IN_BYTES,
+    )
+    .ok_or(SurfaceError::PixelCountWouldOverflow {
+        width,
+        height,
+        depth: 1,
+    })?;
+
+    if data.len() < expected_size {
+        return Err(SurfaceError::NotEnoughData {
+            expected: expected_size,
+            actual: data.len(),
+        });
+    }
+
+    let mut rgba = vec![T::default(); width as usize * height as usize * CHANNELS];
+    if rgba.is_empty() {
+        return Ok(rgba);
+    }
+
+    // Blocks are in row-major order like BCN formats.
+    // Each row of blocks updates up to block_height rows of the RGBA surface.
+    let width = width as usize;
+    let blocks_per_row = div_round_up(width, block_width);
+    let decode_row = |(row, rgba_rows): (usize, &mut [T])| {
+        let rows = rgba_rows.len() / (width * CHANNELS);
+        let mut decompressed = [T::default(); MAX_BLOCK_ELEMENTS];
+        let pitch = block_width * CHANNELS;
+
+        for (i, x) in (0..width).step_by(block_width).enumerate() {
+            let block_start = (row * blocks_per_row + i) * BLOCK_SIZE_IN_BYTES;
+            let block = &data[block_start..block_start + BLOCK_SIZE_IN_BYTES];
+            decompress_block(block, &mut decompressed, pitch);
+
+            // The edges won't always have full blocks.
+            let elements_per_row = CHANNELS * block_width.min(width - x);
+            for (y, block_row) in decompressed.chunks_exact(pitch).take(rows).enumerate() {
+                let start = (y * width + x) * CHANNELS;
+                rgba_rows[start..start + elements_per_row]
+                    .copy_from_slice(&block_row[..elements_per_row]);
+            }
+        }
+    };
+
+    let row_size = width * block_height * CHANNELS;
+    #[cfg(feature = "rayon")]
+    rgba.par_chunks_mut(row_size)
+        .enumerate()
+        .for_each(decode_row);
+    #[cfg(not(feature = "rayon"))]
+    rgba.chunks_mut(row_size).enumerate().for_each(decode_row);
+
+    Ok(rgba)
+}
+
+fn is_hdr(image_format: ImageFormat) -> bool {
+    matches!(
+        image_format,
+        ImageFormat::Astc4x4RgbaFloat
+            | ImageFormat::Astc5x4RgbaFloat
+            | ImageFormat::Astc5x5RgbaFloat
+            | ImageFormat::Astc6x5RgbaFloat
+            | ImageFormat::Astc6x6RgbaFloat
+            | ImageFormat::Astc8x5RgbaFloat
+            | ImageFormat::Astc8x6RgbaFloat
+            | ImageFormat::Astc8x8RgbaFloat
+            | ImageFormat::Astc10x5RgbaFloat
+            | ImageFormat::Astc10x6RgbaFloat
+            | ImageFormat::Astc10x8RgbaFloat
+            | ImageFormat::Astc10x10RgbaFloat
+            | ImageFormat::Astc12x10RgbaFloat
+            | ImageFormat::Astc12x12RgbaFloat
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 4x4 weight grid with LDR RGB endpoints black and white.
+    // Each row uses the weights 0, 21, 43, and 64.
+    const LDR_GRADIENT: [u8; 16] = [66, 0, 1, 254, 1, 254, 1, 254, 1, 0, 0, 0, 39, 39, 39, 39];
+
+    // A 4x4 weight grid with HDR luminance endpoints 1.0 and 2.0.
+    // Each row alternates between the weights 0 and 64.
+    const HDR_LUMINANCE: [u8; 16] = [66, 64, 240, 0, 1, 0, 0, 0, 0, 0, 0, 0, 51, 51, 51, 51];
+
+    // Constant color blocks with an LDR color of 0x8000, 0x4000, 0xFFFF, 0xFFFF
+    // and an HDR color of 1.0, 2.0, 0.5, 1.0.
+    const LDR_VOID_EXTENT: [u8; 16] = [
+        252, 253, 255, 255, 255, 255, 255, 255, 0, 128, 0, 64, 255, 255, 255, 255,
+    ];
+    const HDR_VOID_EXTENT: [u8; 16] = [
+        252, 255, 255, 255, 255, 255, 255, 255, 0, 60, 0, 64, 0, 56, 0, 60,
+    ];
+
+    #[test]
+    fn decode_ldr_4x4() {
+        let rgba = rgba8_from_astc(4, 4, ImageFormat::Astc4x4RgbaUnorm, &LDR_GRADIENT).unwrap();
+        let row = [
+            0u8, 0, 0, 255, 84, 84, 84, 255, 171, 171, 171, 255, 255, 255, 255, 255,
+        ];
+        assert_eq!(row.repeat(4), rgba);
+    }
+
+    #[test]
+    fn decode_ldr_srgb_4x4() {
+        let rgba = rgba8_from_astc(4, 4, ImageFormat::Astc4x4RgbaUnormSrgb, &LDR_GRADIENT).unwrap();
+        let row = [
+            0u8, 0, 0, 255, 84, 84, 84, 255, 171, 171, 171, 255, 255, 255, 255, 255,
+        ];
+        assert_eq!(row.repeat(4), rgba);
+    }
+
+    #[test]
+    fn decode_hdr_4x4() {
+        let rgba = rgbaf32_from_astc(4, 4, ImageFormat::Astc4x4RgbaFloat, &HDR_LUMINANCE).unwrap();
+        let row = [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 1.0];
+        assert_eq!(row.repeat(8), rgba);
+    }
+
+    #[test]
+    fn decode_hdr_4x4_rgba8() {
+        let rgba = rgba8_from_astc(4, 4, ImageFormat::Astc4x4RgbaFloat, &HDR_LUMINANCE).unwrap();
+        assert_eq!(vec![255u8; 4 * 4 * 4], rgba);
+    }
+
+    #[test]
+    fn decode_hdr_endpoints_ldr_format() {
+        // The LDR profile does not support HDR endpoints.
+        let rgba = rgba8_from_astc(4, 4, ImageFormat::Astc4x4RgbaUnorm, &HDR_LUMINANCE).unwrap();
+        assert_eq!([255u8, 0, 255, 255].repeat(16), rgba);
+    }
+
+    #[test]
+    fn decode_void_extent_12x10_partial_blocks() {
+        // A 13x7 surface uses 2 blocks with most of the second block unused.
+        let data = [LDR_VOID_EXTENT, LDR_VOID_EXTENT].concat();
+        let rgba = rgba8_from_astc(13, 7, ImageFormat::Astc12x10RgbaUnorm, &data).unwrap();
+        assert_eq!([128u8, 64, 255, 255].repeat(13 * 7), rgba);
+    }
+
+    #[test]
+    fn decode_void_extent_hdr_5x4() {
+        let rgba =
+            rgbaf32_from_astc(5, 4, ImageFormat::Astc5x4RgbaFloat, &HDR_VOID_EXTENT).unwrap();
+        assert_eq!([1.0, 2.0, 0.5, 1.0].repeat(5 * 4), rgba);
+    }
+
+    #[test]
+    fn decode_not_enough_data() {
+        // A 7x7 surface needs 2x2 blocks for the 6x6 footprint.
+        let result = rgba8_from_astc(7, 7, ImageFormat::Astc6x6RgbaUnorm, &[0u8; 16 * 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 64,
+                actual: 48
+            })
+        ));
+    }
+}
@@ -1,14 +1,14 @@
 mod decode;
-#[cfg(feature = "encode")]
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
 mod encode;
 
-pub use decode::rgba_from_bcn;
-#[cfg(feature = "encode")]
+pub use decode::{rgba_from_bcn, unorm8_from_snorm, BcnDecode};
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
 pub use encode::bcn_from_rgba;
 
 // All BCN formats use 4x4 pixel blocks.
-const BLOCK_WIDTH: usize = 4;
-const BLOCK_HEIGHT: usize = 4;
+pub const BLOCK_WIDTH: usize = 4;
+pub const BLOCK_HEIGHT: usize = 4;
 const CHANNELS: usize = 4;
 const ELEMENTS_PER_BLOCK: usize = BLOCK_WIDTH * BLOCK_HEIGHT * CHANNELS;
 
@@ -16,6 +16,9 @@ pub struct Bc1;
 pub struct Bc2;
 pub struct Bc3;
 pub struct Bc4;
+pub struct Bc4Signed;
 pub struct Bc5;
+pub struct Bc5Signed;
 pub struct Bc6;
+pub struct Bc6Signed;
 pub struct Bc7;
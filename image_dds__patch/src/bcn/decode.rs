@@ -1,8 +1,14 @@
 use bytemuck::Pod;
 
-use crate::{error::SurfaceError, mip_size};
+use crate::{div_round_up, error::SurfaceError, mip_size};
 
-use super::{Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS};
+use super::{
+    Bc1, Bc2, Bc3, Bc4, Bc4Signed, Bc5, Bc5Signed, Bc6, Bc6Signed, Bc7, BLOCK_HEIGHT, BLOCK_WIDTH,
+    CHANNELS,
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 pub trait BcnDecode<Pixel> {
     type CompressedBlock;
@@ -112,6 +118,47 @@ impl BcnDecode<[u8; 4]> for Bc4 {
     }
 }
 
+impl BcnDecode<[f32; 4]> for Bc4Signed {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        // BC4 stores grayscale data, so each decompressed pixel is 1 byte.
+        let mut decompressed_r = [[0i8; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        bcdec_rs::bc4_signed(
+            block,
+            bytemuck::cast_slice_mut(&mut decompressed_r),
+            BLOCK_WIDTH,
+        );
+
+        // Pad to RGBA with alpha set to white.
+        let mut decompressed = [[[0.0; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+        for y in 0..BLOCK_HEIGHT {
+            for x in 0..BLOCK_WIDTH {
+                let r = snorm8_to_f32(decompressed_r[y][x]);
+                decompressed[y][x] = [r, r, r, 1.0];
+            }
+        }
+
+        decompressed
+    }
+}
+
+impl BcnDecode<[u8; 4]> for Bc4Signed {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            Bc4Signed::decompress_block(block);
+        decompressed.map(|row| {
+            row.map(|[r, _, _, _]| {
+                let r = unorm8_from_snorm(r);
+                [r, r, r, 255u8]
+            })
+        })
+    }
+}
+
 impl BcnDecode<[u8; 4]> for Bc5 {
     type CompressedBlock = [u8; 16];
 
@@ -139,30 +186,26 @@ impl BcnDecode<[u8; 4]> for Bc5 {
     }
 }
 
-impl BcnDecode<[f32; 4]> for Bc6 {
+impl BcnDecode<[f32; 4]> for Bc5Signed {
     type CompressedBlock = [u8; 16];
 
     fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
-        // BC6H uses half precision floating point data.
-        // Convert to single precision since f32 is better supported on CPUs.
-        let mut decompressed_rgb = [[[0f32; 3]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+        // BC5 stores RG data, so each decompressed pixel is 2 bytes.
+        let mut decompressed_rg = [[[0i8; 2]; BLOCK_WIDTH]; BLOCK_HEIGHT];
 
-        // Cast the pointer to a less strictly aligned type.
-        // The pitch is in terms of floats rather than bytes.
-        bcdec_rs::bc6h_float(
+        bcdec_rs::bc5_signed(
             block,
-            bytemuck::cast_slice_mut(&mut decompressed_rgb),
-            BLOCK_WIDTH * 3,
-            // TODO: signed vs unsigned?
-            false,
+            bytemuck::cast_slice_mut(&mut decompressed_rg),
+            BLOCK_WIDTH * 2,
         );
 
         // Pad to RGBA with alpha set to white.
         let mut decompressed = [[[0.0; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
         for y in 0..BLOCK_HEIGHT {
-            for x in 0..BLOCK_HEIGHT {
-                let [r, g, b] = decompressed_rgb[y][x];
-                decompressed[y][x] = [r, g, b, 1.0];
+            for x in 0..BLOCK_WIDTH {
+                // It's convention to zero the blue channel when decompressing BC5.
+                let [r, g] = decompressed_rg[y][x].map(snorm8_to_f32);
+                decompressed[y][x] = [r, g, 0.0, 1.0];
             }
         }
 
@@ -170,6 +213,28 @@ impl BcnDecode<[f32; 4]> for Bc6 {
     }
 }
 
+impl BcnDecode<[u8; 4]> for Bc5Signed {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            Bc5Signed::decompress_block(block);
+
+        // The blue channel is zero after remapping to be consistent with unsigned BC5.
+        decompressed.map(|row| {
+            row.map(|[r, g, _, _]| [unorm8_from_snorm(r), unorm8_from_snorm(g), 0u8, 255u8])
+        })
+    }
+}
+
+impl BcnDecode<[f32; 4]> for Bc6 {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        decompress_bc6_block(block, false)
+    }
+}
+
 impl BcnDecode<[u8; 4]> for Bc6 {
     type CompressedBlock = [u8; 16];
 
@@ -182,6 +247,57 @@ impl BcnDecode<[u8; 4]> for Bc6 {
     }
 }
 
+impl BcnDecode<[f32; 4]> for Bc6Signed {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        decompress_bc6_block(block, true)
+    }
+}
+
+impl BcnDecode<[u8; 4]> for Bc6Signed {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            Bc6Signed::decompress_block(block);
+
+        // Truncate to clamp to 0 to 255.
+        // Negative values are not representable and become 0.
+        let float_to_u8 = |x: f32| (x * 255.0) as u8;
+        decompressed.map(|row| row.map(|pixel| pixel.map(float_to_u8)))
+    }
+}
+
+fn decompress_bc6_block(
+    block: &[u8; 16],
+    is_signed: bool,
+) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+    // BC6H uses half precision floating point data.
+    // Convert to single precision since f32 is better supported on CPUs.
+    let mut decompressed_rgb = [[[0f32; 3]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+    // Cast the pointer to a less strictly aligned type.
+    // The pitch is in terms of floats rather than bytes.
+    bcdec_rs::bc6h_float(
+        block,
+        bytemuck::cast_slice_mut(&mut decompressed_rgb),
+        BLOCK_WIDTH * 3,
+        is_signed,
+    );
+
+    // Pad to RGBA with alpha set to white.
+    let mut decompressed = [[[0.0; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+    for y in 0..BLOCK_HEIGHT {
+        for x in 0..BLOCK_HEIGHT {
+            let [r, g, b] = decompressed_rgb[y][x];
+            decompressed[y][x] = [r, g, b, 1.0];
+        }
+    }
+
+    decompressed
+}
+
 impl BcnDecode<[u8; 4]> for Bc7 {
     type CompressedBlock = [u8; 16];
 
@@ -201,7 +317,7 @@ impl BcnDecode<[u8; 4]> for Bc7 {
 /// Decompress the bytes in `data` to the uncompressed RGBA8 format.
 pub fn rgba_from_bcn<F, T>(width: u32, height: u32, data: &[u8]) -> Result<Vec<T>, SurfaceError>
 where
-    T: Copy + Default + Pod,
+    T: Copy + Default + Pod + Send,
     F: BcnDecode<[T; 4]>,
     F::CompressedBlock: ReadBlock,
 {
@@ -232,35 +348,48 @@ where
     }
 
     let mut rgba = vec![T::default(); width as usize * height as usize * CHANNELS];
+    if rgba.is_empty() {
+        return Ok(rgba);
+    }
 
     // BCN formats lay out blocks in row-major order.
-    // TODO: calculate x and y using division and mod?
-    let mut block_start = 0;
-    for y in (0..height).step_by(BLOCK_HEIGHT) {
-        for x in (0..width).step_by(BLOCK_WIDTH) {
+    // Each row of blocks updates up to 4 rows of the RGBA surface.
+    let width = width as usize;
+    let blocks_per_row = div_round_up(width, BLOCK_WIDTH);
+    let decode_row = |(row, rgba_rows): (usize, &mut [T])| {
+        let rows = rgba_rows.len() / (width * CHANNELS);
+        for (i, x) in (0..width).step_by(BLOCK_WIDTH).enumerate() {
             // Use a special type to enforce alignment.
+            let block_start = (row * blocks_per_row + i) * F::CompressedBlock::SIZE_IN_BYTES;
             let block = F::CompressedBlock::read_block(data, block_start);
             // TODO: Add rgba8 and rgbaf32 variants for decompress block.
             let decompressed_block = F::decompress_block(&block);
 
-            // TODO: This can be generic over the pixel type to also support float.
-            // Each block is 4x4, so we need to update multiple rows.
-            put_rgba_block(
-                &mut rgba,
-                decompressed_block,
-                x as usize,
-                y as usize,
-                width as usize,
-                height as usize,
-            );
-
-            block_start += F::CompressedBlock::SIZE_IN_BYTES;
+            put_rgba_block(rgba_rows, decompressed_block, x, 0, width, rows);
         }
-    }
+    };
+
+    let row_size = width * BLOCK_HEIGHT * CHANNELS;
+    #[cfg(feature = "rayon")]
+    rgba.par_chunks_mut(row_size)
+        .enumerate()
+        .for_each(decode_row);
+    #[cfg(not(feature = "rayon"))]
+    rgba.chunks_mut(row_size).enumerate().for_each(decode_row);
 
     Ok(rgba)
 }
 
+// Both -128 and -127 map to -1.0 for SNORM formats.
+fn snorm8_to_f32(x: i8) -> f32 {
+    (x as f32 / 127.0).max(-1.0)
+}
+
+// Remap the SNORM range [-1.0, 1.0] to [0, 255] with 0.0 mapping to 128.
+pub fn unorm8_from_snorm(x: f32) -> u8 {
+    ((x + 1.0) * 0.5 * 255.0).round() as u8
+}
+
 fn put_rgba_block<T: Pod>(
     surface: &mut [T],
     pixels: [[[T; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT],
@@ -289,7 +418,46 @@ fn put_rgba_block<T: Pod>(
 mod tests {
     use super::*;
 
-    // TODO: Add decoding tests?
+    #[test]
+    fn snorm8_to_f32_range() {
+        assert_eq!(-1.0, snorm8_to_f32(-128));
+        assert_eq!(-1.0, snorm8_to_f32(-127));
+        assert_eq!(0.0, snorm8_to_f32(0));
+        assert_eq!(1.0, snorm8_to_f32(127));
+    }
+
+    #[test]
+    fn unorm8_from_snorm_range() {
+        assert_eq!(0, unorm8_from_snorm(-1.0));
+        assert_eq!(128, unorm8_from_snorm(0.0));
+        assert_eq!(255, unorm8_from_snorm(1.0));
+    }
+
+    #[test]
+    fn decode_bc4_signed_endpoints() {
+        // 8 value mode with endpoints -127 and 127.
+        // Indices 0 and 1 select the endpoints.
+        let block = [127u8, 0x81, 0b001000, 0, 0, 0, 0, 0];
+        let pixels: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc4Signed::decompress_block(&block);
+        assert_eq!([1.0, 1.0, 1.0, 1.0], pixels[0][0]);
+        assert_eq!([-1.0, -1.0, -1.0, 1.0], pixels[0][1]);
+        assert_eq!([1.0, 1.0, 1.0, 1.0], pixels[0][2]);
+
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc4Signed::decompress_block(&block);
+        assert_eq!([255, 255, 255, 255], pixels[0][0]);
+        assert_eq!([0, 0, 0, 255], pixels[0][1]);
+    }
+
+    #[test]
+    fn decode_bc5_signed_zero() {
+        // Both endpoints are zero for the red and green channels.
+        let block = [0u8; 16];
+        let pixels: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc5Signed::decompress_block(&block);
+        assert_eq!([[[0.0, 0.0, 0.0, 1.0]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Bc5Signed::decompress_block(&block);
+        assert_eq!([[[128, 128, 0, 255]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+    }
 
     #[test]
     fn put_rgba_block_4x4() {
@@ -1,33 +1,16 @@
-use crate::{mip_size, ImageFormat, Quality, SurfaceError};
-use half::f16;
+use crate::{mip_size, Quality, SurfaceError};
 
-use super::{
-    Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS, ELEMENTS_PER_BLOCK,
-};
+use super::{BLOCK_HEIGHT, BLOCK_WIDTH, ELEMENTS_PER_BLOCK};
 
-// Quality modes are optimized for a balance of speed and quality.
-impl From<Quality> for intel_tex_2::bc6h::EncodeSettings {
-    fn from(value: Quality) -> Self {
-        // TODO: Test quality settings and speed for bc6h.
-        match value {
-            Quality::Fast => intel_tex_2::bc6h::very_fast_settings(),
-            Quality::Normal => intel_tex_2::bc6h::basic_settings(),
-            Quality::Slow => intel_tex_2::bc6h::slow_settings(),
-        }
-    }
-}
+#[cfg(feature = "rayon")]
+use super::CHANNELS;
 
-impl From<Quality> for intel_tex_2::bc7::EncodeSettings {
-    fn from(value: Quality) -> Self {
-        // bc7 has almost imperceptible errors even at ultra_fast
-        // 4k rgba ultra fast (2s), very fast (7s), fast (12s)
-        match value {
-            Quality::Fast => intel_tex_2::bc7::alpha_ultra_fast_settings(),
-            Quality::Normal => intel_tex_2::bc7::alpha_very_fast_settings(),
-            Quality::Slow => intel_tex_2::bc7::alpha_fast_settings(),
-        }
-    }
-}
+// The ISPC kernels from intel_tex_2 take priority when both backends are enabled.
+// BC2 always uses the Rust encoder since intel_tex_2 does not support it.
+#[cfg(feature = "encode")]
+mod ispc;
+#[cfg_attr(feature = "encode", allow(dead_code))]
+mod rs;
 
 pub trait BcnEncode<T> {
     // TODO: How to handle depth with intel-tex-rs-2?
@@ -39,173 +22,6 @@ pub trait BcnEncode<T> {
     ) -> Result<Vec<u8>, SurfaceError>;
 }
 
-impl BcnEncode<u8> for Bc1 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[u8],
-        _: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // RGBA with 4 bytes per pixel.
-        let surface = intel_tex_2::RgbaSurface {
-            width,
-            height,
-            stride: width * CHANNELS as u32,
-            data: rgba8_data,
-        };
-
-        Ok(intel_tex_2::bc1::compress_blocks(&surface))
-    }
-}
-
-impl BcnEncode<u8> for Bc2 {
-    fn compress_surface(
-        _width: u32,
-        _height: u32,
-        _rgba8_data: &[u8],
-        _quality: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // TODO: Find an implementation that supports this?
-        Err(SurfaceError::UnsupportedEncodeFormat {
-            format: ImageFormat::BC2RgbaUnorm,
-        })
-    }
-}
-
-impl BcnEncode<u8> for Bc3 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[u8],
-        _: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // RGBA with 4 bytes per pixel.
-        let surface = intel_tex_2::RgbaSurface {
-            width,
-            height,
-            stride: width * CHANNELS as u32,
-            data: rgba8_data,
-        };
-
-        Ok(intel_tex_2::bc3::compress_blocks(&surface))
-    }
-}
-
-impl BcnEncode<u8> for Bc4 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[u8],
-        _: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // R8 with 4 bytes per pixel.
-        let r8_data: Vec<_> = rgba8_data.chunks_exact(4).map(|p| p[0]).collect();
-        let surface = intel_tex_2::RSurface {
-            width,
-            height,
-            stride: width,
-            data: &r8_data,
-        };
-
-        Ok(intel_tex_2::bc4::compress_blocks(&surface))
-    }
-}
-
-impl BcnEncode<u8> for Bc5 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[u8],
-        _: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // RG8 with 2 bytes per pixel.
-        let rg8_data: Vec<_> = rgba8_data
-            .chunks_exact(4)
-            .flat_map(|p| [p[0], p[1]])
-            .collect();
-        let surface = intel_tex_2::RgSurface {
-            width,
-            height,
-            stride: width * 2,
-            data: &rg8_data,
-        };
-
-        Ok(intel_tex_2::bc5::compress_blocks(&surface))
-    }
-}
-
-impl BcnEncode<f32> for Bc6 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[f32],
-        quality: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // The BC6H encoder expects the data to be in half precision floating point.
-        // This differs from the other formats that expect [u8; 4] for each pixel.
-        let f16_data: Vec<f16> = rgba8_data.iter().copied().map(f16::from_f32).collect();
-
-        let surface = intel_tex_2::RgbaSurface {
-            width,
-            height,
-            stride: width * 4 * std::mem::size_of::<f16>() as u32,
-            data: bytemuck::cast_slice(&f16_data),
-        };
-
-        Ok(intel_tex_2::bc6h::compress_blocks(
-            &quality.into(),
-            &surface,
-        ))
-    }
-}
-
-impl BcnEncode<u8> for Bc6 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[u8],
-        quality: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // The BC6H encoder expects the data to be in half precision floating point.
-        // This differs from the other formats that expect [u8; 4] for each pixel.
-        let f16_data: Vec<f16> = rgba8_data
-            .iter()
-            .map(|v| f16::from_f32(*v as f32 / 255.0))
-            .collect();
-
-        let surface = intel_tex_2::RgbaSurface {
-            width,
-            height,
-            stride: width * 4 * std::mem::size_of::<f16>() as u32,
-            data: bytemuck::cast_slice(&f16_data),
-        };
-
-        Ok(intel_tex_2::bc6h::compress_blocks(
-            &quality.into(),
-            &surface,
-        ))
-    }
-}
-
-impl BcnEncode<u8> for Bc7 {
-    fn compress_surface(
-        width: u32,
-        height: u32,
-        rgba8_data: &[u8],
-        quality: Quality,
-    ) -> Result<Vec<u8>, SurfaceError> {
-        // RGBA with 4 bytes per pixel.
-        let surface = intel_tex_2::RgbaSurface {
-            width,
-            height,
-            stride: width * CHANNELS as u32,
-            data: rgba8_data,
-        };
-
-        Ok(intel_tex_2::bc7::compress_blocks(&quality.into(), &surface))
-    }
-}
-
 pub fn bcn_from_rgba<F, T>(
     width: u32,
     height: u32,
@@ -214,6 +30,7 @@ pub fn bcn_from_rgba<F, T>(
 ) -> Result<Vec<u8>, SurfaceError>
 where
     F: BcnEncode<T>,
+    T: Sync,
 {
     // Surface dimensions are not validated yet and may cause overflow.
     let expected_size = mip_size(
@@ -239,7 +56,49 @@ where
         });
     }
 
-    F::compress_surface(width, height, data, quality)
+    #[cfg(feature = "rayon")]
+    {
+        compress_bands::<F, T>(width, height, data, quality)
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        F::compress_surface(width, height, data, quality)
+    }
+}
+
+// Blocks are independent, so each band of block rows can be compressed separately.
+// The compressed blocks for each band are contiguous and in the same order as the serial path.
+#[cfg(feature = "rayon")]
+fn compress_bands<F, T>(
+    width: u32,
+    height: u32,
+    data: &[T],
+    quality: Quality,
+) -> Result<Vec<u8>, SurfaceError>
+where
+    F: BcnEncode<T>,
+    T: Sync,
+{
+    use rayon::prelude::*;
+
+    const BAND_HEIGHT: u32 = BLOCK_HEIGHT as u32 * 16;
+
+    if height <= BAND_HEIGHT {
+        return F::compress_surface(width, height, data, quality);
+    }
+
+    let band_size = width as usize * BAND_HEIGHT as usize * CHANNELS;
+    let bands: Vec<_> = (0..height).step_by(BAND_HEIGHT as usize).collect();
+    Ok(bands
+        .into_par_iter()
+        .map(|y| {
+            let band_height = BAND_HEIGHT.min(height - y);
+            let start = (y / BAND_HEIGHT) as usize * band_size;
+            let end = start + width as usize * band_height as usize * CHANNELS;
+            F::compress_surface(width, band_height, &data[start..end], quality)
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .concat())
 }
 
 // TODO: Rework these tests.
@@ -248,12 +107,14 @@ where
 mod tests {
     use super::*;
 
+    use crate::bcn::{Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7};
+
     // TODO: Create tests for data length since we can't know what the compressed blocks should be?
     // TODO: Test edge cases and type conversions?
     // TODO: Add tests for validating the input length.
     // TODO: Will compression fail for certain pixel values (test with fuzz tests?)
     fn check_compress_bcn<T: BcnEncode<u8>>(rgba: &[u8], quality: Quality) {
-        bcn_from_rgba::<T, u8>(4, 4, &rgba, quality).unwrap();
+        bcn_from_rgba::<T, u8>(4, 4, rgba, quality).unwrap();
     }
 
     #[test]
@@ -265,9 +126,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn bc2_compress() {
-        // TODO: BC2 compression should return an error.
         let rgba = vec![64u8; ELEMENTS_PER_BLOCK];
         check_compress_bcn::<Bc2>(&rgba, Quality::Fast);
         check_compress_bcn::<Bc2>(&rgba, Quality::Normal);
@@ -313,4 +172,16 @@ mod tests {
         check_compress_bcn::<Bc7>(&rgba, Quality::Normal);
         check_compress_bcn::<Bc7>(&rgba, Quality::Slow);
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn compress_bands_matches_serial() {
+        // Use a partial band at the end.
+        let (width, height) = (8, 160);
+        let rgba: Vec<_> = (0..width * height * 4).map(|i| (i % 251) as u8).collect();
+        assert_eq!(
+            Bc7::compress_surface(width, height, &rgba, Quality::Fast).unwrap(),
+            compress_bands::<Bc7, u8>(width, height, &rgba, Quality::Fast).unwrap()
+        );
+    }
 }
@@ -0,0 +1,182 @@
+use crate::{Quality, SurfaceError};
+use half::f16;
+
+use super::BcnEncode;
+use crate::bcn::{Bc1, Bc3, Bc4, Bc5, Bc6, Bc7, CHANNELS};
+
+// Quality modes are optimized for a balance of speed and quality.
+impl From<Quality> for intel_tex_2::bc6h::EncodeSettings {
+    fn from(value: Quality) -> Self {
+        // TODO: Test quality settings and speed for bc6h.
+        match value {
+            Quality::Fast => intel_tex_2::bc6h::very_fast_settings(),
+            Quality::Normal => intel_tex_2::bc6h::basic_settings(),
+            Quality::Slow => intel_tex_2::bc6h::slow_settings(),
+        }
+    }
+}
+
+impl From<Quality> for intel_tex_2::bc7::EncodeSettings {
+    fn from(value: Quality) -> Self {
+        // bc7 has almost imperceptible errors even at ultra_fast
+        // 4k rgba ultra fast (2s), very fast (7s), fast (12s)
+        match value {
+            Quality::Fast => intel_tex_2::bc7::alpha_ultra_fast_settings(),
+            Quality::Normal => intel_tex_2::bc7::alpha_very_fast_settings(),
+            Quality::Slow => intel_tex_2::bc7::alpha_fast_settings(),
+        }
+    }
+}
+
+impl BcnEncode<u8> for Bc1 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // RGBA with 4 bytes per pixel.
+        let surface = intel_tex_2::RgbaSurface {
+            width,
+            height,
+            stride: width * CHANNELS as u32,
+            data: rgba8_data,
+        };
+
+        Ok(intel_tex_2::bc1::compress_blocks(&surface))
+    }
+}
+
+impl BcnEncode<u8> for Bc3 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // RGBA with 4 bytes per pixel.
+        let surface = intel_tex_2::RgbaSurface {
+            width,
+            height,
+            stride: width * CHANNELS as u32,
+            data: rgba8_data,
+        };
+
+        Ok(intel_tex_2::bc3::compress_blocks(&surface))
+    }
+}
+
+impl BcnEncode<u8> for Bc4 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // R8 with 4 bytes per pixel.
+        let r8_data: Vec<_> = rgba8_data.chunks_exact(4).map(|p| p[0]).collect();
+        let surface = intel_tex_2::RSurface {
+            width,
+            height,
+            stride: width,
+            data: &r8_data,
+        };
+
+        Ok(intel_tex_2::bc4::compress_blocks(&surface))
+    }
+}
+
+impl BcnEncode<u8> for Bc5 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // RG8 with 2 bytes per pixel.
+        let rg8_data: Vec<_> = rgba8_data
+            .chunks_exact(4)
+            .flat_map(|p| [p[0], p[1]])
+            .collect();
+        let surface = intel_tex_2::RgSurface {
+            width,
+            height,
+            stride: width * 2,
+            data: &rg8_data,
+        };
+
+        Ok(intel_tex_2::bc5::compress_blocks(&surface))
+    }
+}
+
+impl BcnEncode<f32> for Bc6 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[f32],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // The BC6H encoder expects the data to be in half precision floating point.
+        // This differs from the other formats that expect [u8; 4] for each pixel.
+        let f16_data: Vec<f16> = rgba8_data.iter().copied().map(f16::from_f32).collect();
+
+        let surface = intel_tex_2::RgbaSurface {
+            width,
+            height,
+            stride: width * 4 * std::mem::size_of::<f16>() as u32,
+            data: bytemuck::cast_slice(&f16_data),
+        };
+
+        Ok(intel_tex_2::bc6h::compress_blocks(
+            &quality.into(),
+            &surface,
+        ))
+    }
+}
+
+impl BcnEncode<u8> for Bc6 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // The BC6H encoder expects the data to be in half precision floating point.
+        // This differs from the other formats that expect [u8; 4] for each pixel.
+        let f16_data: Vec<f16> = rgba8_data
+            .iter()
+            .map(|v| f16::from_f32(*v as f32 / 255.0))
+            .collect();
+
+        let surface = intel_tex_2::RgbaSurface {
+            width,
+            height,
+            stride: width * 4 * std::mem::size_of::<f16>() as u32,
+            data: bytemuck::cast_slice(&f16_data),
+        };
+
+        Ok(intel_tex_2::bc6h::compress_blocks(
+            &quality.into(),
+            &surface,
+        ))
+    }
+}
+
+impl BcnEncode<u8> for Bc7 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // RGBA with 4 bytes per pixel.
+        let surface = intel_tex_2::RgbaSurface {
+            width,
+            height,
+            stride: width * CHANNELS as u32,
+            data: rgba8_data,
+        };
+
+        Ok(intel_tex_2::bc7::compress_blocks(&quality.into(), &surface))
+    }
+}
@@ -0,0 +1,1101 @@
+// A safe, pure Rust block encoder for targets without the ISPC kernels.
+// The block layouts match the decoding in bcdec_rs.
+// https://learn.microsoft.com/en-us/windows/win32/direct3d10/d3d10-graphics-programming-guide-resources-block-compression
+// https://learn.microsoft.com/en-us/windows/win32/direct3d11/bc7-format-mode-reference
+use std::ops::RangeInclusive;
+
+use half::f16;
+
+use super::BcnEncode;
+use crate::bcn::{Bc2, Bc4Signed, Bc5Signed, Bc6Signed, BLOCK_HEIGHT, BLOCK_WIDTH, CHANNELS};
+use crate::{rgba::floyd_steinberg, Quality, SurfaceError};
+
+#[cfg(not(feature = "encode"))]
+use crate::bcn::{Bc1, Bc3, Bc4, Bc5, Bc6, Bc7};
+
+const PIXELS_PER_BLOCK: usize = BLOCK_WIDTH * BLOCK_HEIGHT;
+
+// The largest finite half float as a bit pattern.
+const F16_MAX_BITS: i32 = 0x7BFF;
+
+type RgbaBlock = [[u8; 4]; PIXELS_PER_BLOCK];
+type RgbaFloatBlock = [[f32; 4]; PIXELS_PER_BLOCK];
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<u8> for Bc1 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba8_data, |pixels| {
+            bc1_block(pixels, quality)
+        }))
+    }
+}
+
+impl BcnEncode<u8> for Bc2 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba8_data, |pixels| {
+            bc2_block(pixels, quality)
+        }))
+    }
+}
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<u8> for Bc3 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba8_data, |pixels| {
+            bc3_block(pixels, quality)
+        }))
+    }
+}
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<u8> for Bc4 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba8_data, bc4_block))
+    }
+}
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<u8> for Bc5 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba8_data, bc5_block))
+    }
+}
+
+impl BcnEncode<f32> for Bc4Signed {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba_data: &[f32],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba_data, bc4_signed_block))
+    }
+}
+
+impl BcnEncode<f32> for Bc5Signed {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba_data: &[f32],
+        _: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba_data, bc5_signed_block))
+    }
+}
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<f32> for Bc6 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba_data: &[f32],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba_data, |pixels| {
+            bc6h_block(pixels, false, quality)
+        }))
+    }
+}
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<u8> for Bc6 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        let rgba_data: Vec<_> = rgba8_data.iter().map(|v| *v as f32 / 255.0).collect();
+        Bc6::compress_surface(width, height, &rgba_data, quality)
+    }
+}
+
+impl BcnEncode<f32> for Bc6Signed {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba_data: &[f32],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba_data, |pixels| {
+            bc6h_block(pixels, true, quality)
+        }))
+    }
+}
+
+#[cfg(not(feature = "encode"))]
+impl BcnEncode<u8> for Bc7 {
+    fn compress_surface(
+        width: u32,
+        height: u32,
+        rgba8_data: &[u8],
+        quality: Quality,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        Ok(compress_blocks(width, height, rgba8_data, |pixels| {
+            bc7_block(pixels, quality)
+        }))
+    }
+}
+
+/// Compress each 4x4 block of `rgba_data` in row-major order.
+pub fn compress_blocks<T, const N: usize, F>(
+    width: u32,
+    height: u32,
+    rgba_data: &[T],
+    compress_block: F,
+) -> Vec<u8>
+where
+    T: Copy + Default,
+    F: Fn(&[[T; 4]; PIXELS_PER_BLOCK]) -> [u8; N],
+{
+    let width = width as usize;
+    let height = height as usize;
+
+    let mut compressed = Vec::new();
+    for y in (0..height).step_by(BLOCK_HEIGHT) {
+        for x in (0..width).step_by(BLOCK_WIDTH) {
+            let pixels = read_block(rgba_data, width, height, x, y);
+            compressed.extend_from_slice(&compress_block(&pixels));
+        }
+    }
+    compressed
+}
+
+fn read_block<T: Copy + Default>(
+    rgba_data: &[T],
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> [[T; 4]; PIXELS_PER_BLOCK] {
+    let mut pixels = [[T::default(); 4]; PIXELS_PER_BLOCK];
+    for row in 0..BLOCK_HEIGHT {
+        for col in 0..BLOCK_WIDTH {
+            // Repeat the edge pixels for partial blocks.
+            let pixel_x = (x + col).min(width - 1);
+            let pixel_y = (y + row).min(height - 1);
+            let index = (pixel_y * width + pixel_x) * CHANNELS;
+            pixels[row * BLOCK_WIDTH + col].copy_from_slice(&rgba_data[index..index + CHANNELS]);
+        }
+    }
+    pixels
+}
+
+fn refinement_iterations(quality: Quality) -> usize {
+    match quality {
+        Quality::Fast => 0,
+        Quality::Normal => 1,
+        Quality::Slow => 2,
+    }
+}
+
+pub fn bc1_block(pixels: &RgbaBlock, quality: Quality) -> [u8; 8] {
+    // Alpha is ignored, so all blocks use the opaque 4 color mode.
+    color_block(pixels, quality)
+}
+
+pub fn bc2_block(pixels: &RgbaBlock, quality: Quality) -> [u8; 16] {
+    // Dithering preserves the average alpha at the cost of some noise.
+    let alpha = if quality == Quality::Slow {
+        dithered_alpha4(pixels)
+    } else {
+        pixels.map(|p| ((p[3] as u32 * 15 + 127) / 255) as u8)
+    };
+
+    let mut block = [0u8; 16];
+    for (row, values) in alpha.chunks_exact(BLOCK_WIDTH).enumerate() {
+        let bits = values
+            .iter()
+            .enumerate()
+            .fold(0u16, |bits, (i, a)| bits | (*a as u16) << (i * 4));
+        block[row * 2..row * 2 + 2].copy_from_slice(&bits.to_le_bytes());
+    }
+    block[8..].copy_from_slice(&color_block(pixels, quality));
+    block
+}
+
+fn dithered_alpha4(pixels: &RgbaBlock) -> [u8; PIXELS_PER_BLOCK] {
+    // Floyd-Steinberg error diffusion within the block.
+    let mut values = pixels.map(|p| [p[3] as f32 * 15.0 / 255.0]);
+    floyd_steinberg(&mut values, BLOCK_WIDTH, [15.0]);
+    values.map(|[a]| a as u8)
+}
+
+pub fn bc3_block(pixels: &RgbaBlock, quality: Quality) -> [u8; 16] {
+    let alpha = pixels.map(|p| p[3]);
+
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&alpha_block(&alpha));
+    block[8..].copy_from_slice(&color_block(pixels, quality));
+    block
+}
+
+pub fn bc4_block(pixels: &RgbaBlock) -> [u8; 8] {
+    alpha_block(&pixels.map(|p| p[0]))
+}
+
+pub fn bc5_block(pixels: &RgbaBlock) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&alpha_block(&pixels.map(|p| p[0])));
+    block[8..].copy_from_slice(&alpha_block(&pixels.map(|p| p[1])));
+    block
+}
+
+pub fn bc4_signed_block(pixels: &RgbaFloatBlock) -> [u8; 8] {
+    alpha_block_signed(&pixels.map(|p| snorm8(p[0])))
+}
+
+pub fn bc5_signed_block(pixels: &RgbaFloatBlock) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..8].copy_from_slice(&alpha_block_signed(&pixels.map(|p| snorm8(p[0]))));
+    block[8..].copy_from_slice(&alpha_block_signed(&pixels.map(|p| snorm8(p[1]))));
+    block
+}
+
+fn snorm8(value: f32) -> i8 {
+    // Avoid -128 since it decodes to the same value as -127.
+    (value.clamp(-1.0, 1.0) * 127.0).round() as i8
+}
+
+/// Encode the RGB channels of `pixels` to a BC1 color block.
+pub fn color_block(pixels: &RgbaBlock, quality: Quality) -> [u8; 8] {
+    let colors = pixels.map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]);
+
+    let (low, high) = principal_endpoints(&colors, 0.0..=255.0);
+    let (mut block, mut indices, mut error) =
+        color_block_from_endpoints(pixels, rgb565(high), rgb565(low));
+
+    for _ in 0..refinement_iterations(quality) {
+        // Fractions of the way from color_0 to color_1 for each index.
+        let weights = indices.map(|i| [0.0, 1.0, 1.0 / 3.0, 2.0 / 3.0][i as usize]);
+        let Some((c0, c1)) = least_squares_endpoints(&colors, &weights, 0.0..=255.0) else {
+            break;
+        };
+
+        let (new_block, new_indices, new_error) =
+            color_block_from_endpoints(pixels, rgb565(c0), rgb565(c1));
+        if new_error >= error {
+            break;
+        }
+        block = new_block;
+        indices = new_indices;
+        error = new_error;
+    }
+
+    block
+}
+
+fn color_block_from_endpoints(
+    pixels: &RgbaBlock,
+    c0: u16,
+    c1: u16,
+) -> ([u8; 8], [u8; PIXELS_PER_BLOCK], u32) {
+    // The decoder only uses the 4 color mode if color_0 > color_1.
+    let (c0, c1) = if c0 < c1 { (c1, c0) } else { (c0, c1) };
+
+    let rgb0 = rgb888(c0);
+    let rgb1 = rgb888(c1);
+    let palette = [
+        rgb0,
+        rgb1,
+        [0, 1, 2].map(|i| (2 * rgb0[i] + rgb1[i] + 1) / 3),
+        [0, 1, 2].map(|i| (rgb0[i] + 2 * rgb1[i] + 1) / 3),
+    ];
+
+    let mut indices = [0u8; PIXELS_PER_BLOCK];
+    let mut error = 0;
+    for (index, pixel) in indices.iter_mut().zip(pixels) {
+        let (i, e) = nearest_index(&palette, &[pixel[0], pixel[1], pixel[2]].map(u32::from));
+        *index = i as u8;
+        error += e;
+    }
+
+    let bits = indices
+        .iter()
+        .enumerate()
+        .fold(0u32, |bits, (i, index)| bits | (*index as u32) << (i * 2));
+
+    let mut block = [0u8; 8];
+    block[0..2].copy_from_slice(&c0.to_le_bytes());
+    block[2..4].copy_from_slice(&c1.to_le_bytes());
+    block[4..8].copy_from_slice(&bits.to_le_bytes());
+    (block, indices, error)
+}
+
+fn rgb565(rgb: [f32; 3]) -> u16 {
+    let r = (rgb[0] * 31.0 / 255.0).round().clamp(0.0, 31.0) as u16;
+    let g = (rgb[1] * 63.0 / 255.0).round().clamp(0.0, 63.0) as u16;
+    let b = (rgb[2] * 31.0 / 255.0).round().clamp(0.0, 31.0) as u16;
+    (r << 11) | (g << 5) | b
+}
+
+fn rgb888(c: u16) -> [u32; 3] {
+    // Expand 565 to 888 with the same rounding as the decoder.
+    let r = (((c >> 11) & 0x1F) as u32 * 527 + 23) >> 6;
+    let g = (((c >> 5) & 0x3F) as u32 * 259 + 33) >> 6;
+    let b = ((c & 0x1F) as u32 * 527 + 23) >> 6;
+    [r, g, b]
+}
+
+/// Encode `values` to a BC4 block, which also stores BC3 alpha and each BC5 channel.
+pub fn alpha_block(values: &[u8; PIXELS_PER_BLOCK]) -> [u8; 8] {
+    let min = *values.iter().min().unwrap();
+    let max = *values.iter().max().unwrap();
+
+    // The 8 value mode interpolates between the extremes.
+    let (block8, error8) = alpha_block_from_endpoints(values, max, min);
+    if error8 == 0 {
+        return block8;
+    }
+
+    // The 6 value mode can represent 0 and 255 exactly.
+    let interior = values.iter().copied().filter(|v| *v != 0 && *v != 255);
+    let low = interior.clone().min().unwrap_or(0);
+    let high = interior.max().unwrap_or(0);
+    let (block6, error6) = alpha_block_from_endpoints(values, low, high);
+
+    if error6 < error8 {
+        block6
+    } else {
+        block8
+    }
+}
+
+fn alpha_block_from_endpoints(values: &[u8; PIXELS_PER_BLOCK], a0: u8, a1: u8) -> ([u8; 8], u32) {
+    let palette = alpha_palette(a0 as u32, a1 as u32);
+
+    let mut bits = 0u64;
+    let mut error = 0;
+    for (i, value) in values.iter().enumerate() {
+        let (index, e) = nearest_index(&palette, &[*value as u32]);
+        bits |= (index as u64) << (i * 3);
+        error += e;
+    }
+
+    let mut block = [0u8; 8];
+    block[0] = a0;
+    block[1] = a1;
+    block[2..8].copy_from_slice(&bits.to_le_bytes()[..6]);
+    (block, error)
+}
+
+fn alpha_palette(a0: u32, a1: u32) -> [[u32; 1]; 8] {
+    if a0 > a1 {
+        [
+            [a0],
+            [a1],
+            [(6 * a0 + a1 + 1) / 7],
+            [(5 * a0 + 2 * a1 + 1) / 7],
+            [(4 * a0 + 3 * a1 + 1) / 7],
+            [(3 * a0 + 4 * a1 + 1) / 7],
+            [(2 * a0 + 5 * a1 + 1) / 7],
+            [(a0 + 6 * a1 + 1) / 7],
+        ]
+    } else {
+        [
+            [a0],
+            [a1],
+            [(4 * a0 + a1 + 1) / 5],
+            [(3 * a0 + 2 * a1 + 1) / 5],
+            [(2 * a0 + 3 * a1 + 1) / 5],
+            [(a0 + 4 * a1 + 1) / 5],
+            [0],
+            [255],
+        ]
+    }
+}
+
+/// Encode `values` to a signed BC4 block, which also stores each signed BC5 channel.
+pub fn alpha_block_signed(values: &[i8; PIXELS_PER_BLOCK]) -> [u8; 8] {
+    let min = *values.iter().min().unwrap();
+    let max = *values.iter().max().unwrap();
+
+    // The 8 value mode interpolates between the extremes.
+    let (block8, error8) = alpha_block_signed_from_endpoints(values, max, min);
+    if error8 == 0 {
+        return block8;
+    }
+
+    // The 6 value mode can represent -1.0 and 1.0 exactly.
+    let interior = values.iter().copied().filter(|v| *v != -127 && *v != 127);
+    let low = interior.clone().min().unwrap_or(0);
+    let high = interior.max().unwrap_or(0);
+    let (block6, error6) = alpha_block_signed_from_endpoints(values, low, high);
+
+    if error6 < error8 {
+        block6
+    } else {
+        block8
+    }
+}
+
+fn alpha_block_signed_from_endpoints(
+    values: &[i8; PIXELS_PER_BLOCK],
+    a0: i8,
+    a1: i8,
+) -> ([u8; 8], u32) {
+    let palette = alpha_palette_signed(a0 as i32, a1 as i32);
+
+    let mut bits = 0u64;
+    let mut error = 0;
+    for (i, value) in values.iter().enumerate() {
+        let (index, e) = palette
+            .iter()
+            .map(|p| p.abs_diff(*value as i32).pow(2))
+            .enumerate()
+            .min_by_key(|(_, e)| *e)
+            .unwrap();
+        bits |= (index as u64) << (i * 3);
+        error += e;
+    }
+
+    let mut block = [0u8; 8];
+    block[0] = a0 as u8;
+    block[1] = a1 as u8;
+    block[2..8].copy_from_slice(&bits.to_le_bytes()[..6]);
+    (block, error)
+}
+
+fn alpha_palette_signed(a0: i32, a1: i32) -> [i32; 8] {
+    // Round to nearest with ties away from zero to match decoding.
+    let div_round = |n: i32, d: i32| n.signum() * ((n.abs() + d / 2) / d);
+    if a0 > a1 {
+        [
+            a0,
+            a1,
+            div_round(6 * a0 + a1, 7),
+            div_round(5 * a0 + 2 * a1, 7),
+            div_round(4 * a0 + 3 * a1, 7),
+            div_round(3 * a0 + 4 * a1, 7),
+            div_round(2 * a0 + 5 * a1, 7),
+            div_round(a0 + 6 * a1, 7),
+        ]
+    } else {
+        [
+            a0,
+            a1,
+            div_round(4 * a0 + a1, 5),
+            div_round(3 * a0 + 2 * a1, 5),
+            div_round(2 * a0 + 3 * a1, 5),
+            div_round(a0 + 4 * a1, 5),
+            -127,
+            127,
+        ]
+    }
+}
+
+const BC7_WEIGHTS4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Encode `pixels` to a BC7 block using mode 6.
+pub fn bc7_block(pixels: &RgbaBlock, quality: Quality) -> [u8; 16] {
+    // Mode 6 has a single subset with 7.7.7.7 endpoints, unique p-bits, and 4 bit indices.
+    // This handles both opaque and transparent blocks reasonably well.
+    let colors = pixels.map(|p| p.map(|c| c as f32));
+
+    let (low, high) = principal_endpoints(&colors, 0.0..=255.0);
+    let (mut block, mut indices, mut error) =
+        bc7_mode6_from_endpoints(pixels, quantize_mode6(low), quantize_mode6(high));
+
+    for _ in 0..refinement_iterations(quality) {
+        let weights = indices.map(|i| BC7_WEIGHTS4[i as usize] as f32 / 64.0);
+        let Some((e0, e1)) = least_squares_endpoints(&colors, &weights, 0.0..=255.0) else {
+            break;
+        };
+
+        let (new_block, new_indices, new_error) =
+            bc7_mode6_from_endpoints(pixels, quantize_mode6(e0), quantize_mode6(e1));
+        if new_error >= error {
+            break;
+        }
+        block = new_block;
+        indices = new_indices;
+        error = new_error;
+    }
+
+    block
+}
+
+fn quantize_mode6(endpoint: [f32; 4]) -> ([u32; 4], u32) {
+    // Pick the p-bit that best represents the 8 bit endpoint.
+    let quantize = |p: u32| {
+        let values = endpoint.map(|e| ((e - p as f32) / 2.0).round().clamp(0.0, 127.0) as u32);
+        let error: f32 = values
+            .iter()
+            .zip(endpoint)
+            .map(|(v, e)| (((v << 1) | p) as f32 - e).powi(2))
+            .sum();
+        (values, error)
+    };
+
+    let (values0, error0) = quantize(0);
+    let (values1, error1) = quantize(1);
+    if error0 <= error1 {
+        (values0, 0)
+    } else {
+        (values1, 1)
+    }
+}
+
+fn bc7_mode6_from_endpoints(
+    pixels: &RgbaBlock,
+    endpoint0: ([u32; 4], u32),
+    endpoint1: ([u32; 4], u32),
+) -> ([u8; 16], [u8; PIXELS_PER_BLOCK], u32) {
+    let (mut endpoint0, mut endpoint1) = (endpoint0, endpoint1);
+
+    let palette = |e0: ([u32; 4], u32), e1: ([u32; 4], u32)| {
+        let rgba0 = e0.0.map(|v| (v << 1) | e0.1);
+        let rgba1 = e1.0.map(|v| (v << 1) | e1.1);
+        BC7_WEIGHTS4.map(|w| [0, 1, 2, 3].map(|c| (rgba0[c] * (64 - w) + rgba1[c] * w + 32) >> 6))
+    };
+
+    let colors = palette(endpoint0, endpoint1);
+    let mut indices = [0u8; PIXELS_PER_BLOCK];
+    let mut error = 0;
+    for (index, pixel) in indices.iter_mut().zip(pixels) {
+        let (i, e) = nearest_index(&colors, &pixel.map(u32::from));
+        *index = i as u8;
+        error += e;
+    }
+
+    // The anchor index has an implicit leading 0 bit.
+    if indices[0] >= 8 {
+        std::mem::swap(&mut endpoint0, &mut endpoint1);
+        for index in &mut indices {
+            *index = 15 - *index;
+        }
+    }
+
+    let mut writer = BitWriter::default();
+    writer.write(1 << 6, 7);
+    for c in 0..4 {
+        writer.write(endpoint0.0[c], 7);
+        writer.write(endpoint1.0[c], 7);
+    }
+    writer.write(endpoint0.1, 1);
+    writer.write(endpoint1.1, 1);
+    writer.write(indices[0] as u32, 3);
+    for index in &indices[1..] {
+        writer.write(*index as u32, 4);
+    }
+
+    (writer.bits.to_le_bytes(), indices, error)
+}
+
+/// Encode the RGB channels of `pixels` to a BC6H block using mode 11.
+pub fn bc6h_block(pixels: &RgbaFloatBlock, is_signed: bool, quality: Quality) -> [u8; 16] {
+    // Mode 11 has a single region with 10.10.10 endpoints and 4 bit indices.
+    // Fitting the half float bit patterns approximates a logarithmic error metric.
+    let colors = pixels.map(|p| [p[0], p[1], p[2]].map(|c| f16_bits(c, is_signed)));
+    let colors_f32 = colors.map(|c| c.map(|v| v as f32));
+    let range = if is_signed {
+        -F16_MAX_BITS as f32..=F16_MAX_BITS as f32
+    } else {
+        0.0..=F16_MAX_BITS as f32
+    };
+    let quantize = |endpoint: [f32; 3]| endpoint.map(|e| bc6h_quantize(e, is_signed));
+
+    let (low, high) = principal_endpoints(&colors_f32, range.clone());
+    let (mut block, mut indices, mut error) =
+        bc6h_mode11_from_endpoints(&colors, quantize(low), quantize(high), is_signed);
+
+    for _ in 0..refinement_iterations(quality) {
+        let weights = indices.map(|i| BC7_WEIGHTS4[i as usize] as f32 / 64.0);
+        let Some((e0, e1)) = least_squares_endpoints(&colors_f32, &weights, range.clone()) else {
+            break;
+        };
+
+        let (new_block, new_indices, new_error) =
+            bc6h_mode11_from_endpoints(&colors, quantize(e0), quantize(e1), is_signed);
+        if new_error >= error {
+            break;
+        }
+        block = new_block;
+        indices = new_indices;
+        error = new_error;
+    }
+
+    block
+}
+
+/// Convert `value` to a half float with the sign applied to the magnitude bits.
+fn f16_bits(value: f32, is_signed: bool) -> i32 {
+    if value.is_nan() || (!is_signed && value < 0.0) {
+        return 0;
+    }
+
+    // Infinity is clamped to the largest finite value.
+    let bits = f16::from_f32(value).to_bits() as i32;
+    let magnitude = (bits & 0x7FFF).min(F16_MAX_BITS);
+    if bits & 0x8000 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+fn bc6h_quantize(value: f32, is_signed: bool) -> i32 {
+    // Estimate the inverse of unquantizing and then check the neighbors for the closest value.
+    let (estimate, min, max) = if is_signed {
+        ((value / 62.0).round() as i32, -511, 511)
+    } else {
+        ((value / 31.0).round() as i32, 0, 1023)
+    };
+    let target = value.round() as i32;
+    (estimate - 1..=estimate + 1)
+        .map(|q| q.clamp(min, max))
+        .min_by_key(|q| {
+            let decoded = bc6h_finish_unquantize(bc6h_unquantize(*q, is_signed), is_signed);
+            decoded.abs_diff(target)
+        })
+        .unwrap()
+}
+
+fn bc6h_unquantize(value: i32, is_signed: bool) -> i32 {
+    // Mode 11 uses 10 bit endpoints.
+    if is_signed {
+        let unquantized = match value.abs() {
+            0 => 0,
+            511.. => 0x7FFF,
+            magnitude => ((magnitude << 15) + 0x4000) >> 9,
+        };
+        value.signum() * unquantized
+    } else {
+        match value {
+            0 => 0,
+            1023 => 0xFFFF,
+            _ => ((value << 16) + 0x8000) >> 10,
+        }
+    }
+}
+
+fn bc6h_finish_unquantize(value: i32, is_signed: bool) -> i32 {
+    if is_signed {
+        value.signum() * ((value.abs() * 31) >> 5)
+    } else {
+        (value * 31) >> 6
+    }
+}
+
+fn bc6h_mode11_from_endpoints(
+    colors: &[[i32; 3]; PIXELS_PER_BLOCK],
+    endpoint0: [i32; 3],
+    endpoint1: [i32; 3],
+    is_signed: bool,
+) -> ([u8; 16], [u8; PIXELS_PER_BLOCK], u64) {
+    let (mut endpoint0, mut endpoint1) = (endpoint0, endpoint1);
+
+    let rgb0 = endpoint0.map(|e| bc6h_unquantize(e, is_signed));
+    let rgb1 = endpoint1.map(|e| bc6h_unquantize(e, is_signed));
+    let palette = BC7_WEIGHTS4.map(|w| {
+        let w = w as i32;
+        [0, 1, 2].map(|c| {
+            bc6h_finish_unquantize((rgb0[c] * (64 - w) + rgb1[c] * w + 32) >> 6, is_signed)
+        })
+    });
+
+    let mut indices = [0u8; PIXELS_PER_BLOCK];
+    let mut error = 0;
+    for (index, color) in indices.iter_mut().zip(colors) {
+        let (i, e) = palette
+            .iter()
+            .map(|p| {
+                p.iter()
+                    .zip(color)
+                    .map(|(a, b)| (a.abs_diff(*b) as u64).pow(2))
+                    .sum::<u64>()
+            })
+            .enumerate()
+            .min_by_key(|(_, e)| *e)
+            .unwrap();
+        *index = i as u8;
+        error += e;
+    }
+
+    // The anchor index has an implicit leading 0 bit.
+    if indices[0] >= 8 {
+        std::mem::swap(&mut endpoint0, &mut endpoint1);
+        for index in &mut indices {
+            *index = 15 - *index;
+        }
+    }
+
+    // Signed endpoints are stored in two's complement.
+    let mut writer = BitWriter::default();
+    writer.write(0b00011, 5);
+    for e in endpoint0.iter().chain(&endpoint1) {
+        writer.write(*e as u32, 10);
+    }
+    writer.write(indices[0] as u32, 3);
+    for index in &indices[1..] {
+        writer.write(*index as u32, 4);
+    }
+
+    (writer.bits.to_le_bytes(), indices, error)
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bits: u128,
+    offset: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= (value as u128 & ((1 << count) - 1)) << self.offset;
+        self.offset += count;
+    }
+}
+
+/// Find the palette entry closest to `value` and its squared error.
+fn nearest_index<const N: usize>(palette: &[[u32; N]], value: &[u32; N]) -> (usize, u32) {
+    palette
+        .iter()
+        .map(|color| {
+            color
+                .iter()
+                .zip(value)
+                .map(|(a, b)| a.abs_diff(*b).pow(2))
+                .sum::<u32>()
+        })
+        .enumerate()
+        .min_by_key(|(_, error)| *error)
+        .unwrap()
+}
+
+/// Find endpoints along the line that best fits `colors`.
+fn principal_endpoints<const N: usize>(
+    colors: &[[f32; N]; PIXELS_PER_BLOCK],
+    range: RangeInclusive<f32>,
+) -> ([f32; N], [f32; N]) {
+    let mut mean = [0.0; N];
+    for color in colors {
+        for c in 0..N {
+            mean[c] += color[c] / PIXELS_PER_BLOCK as f32;
+        }
+    }
+
+    let mut covariance = [[0.0; N]; N];
+    for color in colors {
+        for i in 0..N {
+            for j in 0..N {
+                covariance[i][j] += (color[i] - mean[i]) * (color[j] - mean[j]);
+            }
+        }
+    }
+
+    // Power iteration converges quickly to the dominant eigenvector.
+    // Start from the channel with the most variance since a fixed diagonal axis
+    // may be orthogonal to the colors like for opposing channels.
+    let channel = (0..N)
+        .max_by(|a, b| covariance[*a][*a].total_cmp(&covariance[*b][*b]))
+        .unwrap();
+    let mut axis = [0.0; N];
+    axis[channel] = 1.0;
+    for _ in 0..8 {
+        let mut next = [0.0; N];
+        for (i, row) in covariance.iter().enumerate() {
+            next[i] = row.iter().zip(axis).map(|(c, a)| c * a).sum();
+        }
+
+        let length = next.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if length < 1e-6 {
+            // All colors are identical.
+            break;
+        }
+        axis = next.map(|x| x / length);
+    }
+
+    let (mut min_t, mut max_t) = (f32::MAX, f32::MIN);
+    for color in colors {
+        let t: f32 = (0..N).map(|c| (color[c] - mean[c]) * axis[c]).sum();
+        min_t = min_t.min(t);
+        max_t = max_t.max(t);
+    }
+
+    let endpoint = |t: f32| {
+        let mut endpoint = mean;
+        for c in 0..N {
+            endpoint[c] = (mean[c] + axis[c] * t).clamp(*range.start(), *range.end());
+        }
+        endpoint
+    };
+    (endpoint(min_t), endpoint(max_t))
+}
+
+/// Solve for the endpoints that minimize the squared error for the interpolation `weights`.
+fn least_squares_endpoints<const N: usize>(
+    colors: &[[f32; N]; PIXELS_PER_BLOCK],
+    weights: &[f32; PIXELS_PER_BLOCK],
+    range: RangeInclusive<f32>,
+) -> Option<([f32; N], [f32; N])> {
+    let mut aa = 0.0;
+    let mut ab = 0.0;
+    let mut bb = 0.0;
+    let mut ax = [0.0; N];
+    let mut bx = [0.0; N];
+    for (color, t) in colors.iter().zip(weights) {
+        let a = 1.0 - t;
+        let b = *t;
+        aa += a * a;
+        ab += a * b;
+        bb += b * b;
+        for c in 0..N {
+            ax[c] += a * color[c];
+            bx[c] += b * color[c];
+        }
+    }
+
+    let determinant = aa * bb - ab * ab;
+    if determinant.abs() < 1e-6 {
+        return None;
+    }
+
+    let mut e0 = [0.0; N];
+    let mut e1 = [0.0; N];
+    for c in 0..N {
+        e0[c] = ((bb * ax[c] - ab * bx[c]) / determinant).clamp(*range.start(), *range.end());
+        e1[c] = ((aa * bx[c] - ab * ax[c]) / determinant).clamp(*range.start(), *range.end());
+    }
+    Some((e0, e1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient_block() -> RgbaBlock {
+        std::array::from_fn(|i| {
+            let v = (i * 4) as u8;
+            [v, 255 - v, v / 2, 255 - v / 3]
+        })
+    }
+
+    fn max_difference(expected: &RgbaBlock, actual: &[u8], channels: usize) -> u8 {
+        expected
+            .iter()
+            .zip(actual.chunks_exact(channels))
+            .flat_map(|(e, a)| e.iter().zip(a).map(|(e, a)| e.abs_diff(*a)))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn bc1_block_solid_color() {
+        let pixels = [[255u8, 0, 0, 255]; 16];
+        let block = bc1_block(&pixels, Quality::Fast);
+
+        let mut decoded = [0u8; 16 * 4];
+        bcdec_rs::bc1(&block, &mut decoded, 4 * 4);
+        assert_eq!(0, max_difference(&pixels, &decoded, 4));
+    }
+
+    #[test]
+    fn bc1_block_gradient() {
+        let pixels = gradient_block().map(|[r, g, b, _]| [r, g, b, 255]);
+        for quality in [Quality::Fast, Quality::Normal, Quality::Slow] {
+            let block = bc1_block(&pixels, quality);
+
+            let mut decoded = [0u8; 16 * 4];
+            bcdec_rs::bc1(&block, &mut decoded, 4 * 4);
+            assert!(max_difference(&pixels, &decoded, 4) <= 12);
+        }
+    }
+
+    #[test]
+    fn bc2_block_rounded_alpha() {
+        // Multiples of 17 are represented exactly with 4 bits.
+        let pixels: RgbaBlock = std::array::from_fn(|i| [0, 0, 0, (i * 17) as u8]);
+        let block = bc2_block(&pixels, Quality::Normal);
+
+        let mut decoded = [0u8; 16 * 4];
+        bcdec_rs::bc2(&block, &mut decoded, 4 * 4);
+        assert_eq!(0, max_difference(&pixels, &decoded, 4));
+    }
+
+    #[test]
+    fn bc2_block_dithered_alpha() {
+        // Dithering should preserve the average alpha of the block.
+        let pixels = [[255u8, 255, 255, 128]; 16];
+        let block = bc2_block(&pixels, Quality::Slow);
+
+        let mut decoded = [0u8; 16 * 4];
+        bcdec_rs::bc2(&block, &mut decoded, 4 * 4);
+        let sum: u32 = decoded.chunks_exact(4).map(|p| p[3] as u32).sum();
+        assert!((sum / 16).abs_diff(128) <= 2);
+        assert!(decoded.chunks_exact(4).any(|p| p[3] != decoded[3]));
+    }
+
+    #[test]
+    fn bc3_block_gradient() {
+        let pixels = gradient_block();
+        let block = bc3_block(&pixels, Quality::Normal);
+
+        let mut decoded = [0u8; 16 * 4];
+        bcdec_rs::bc3(&block, &mut decoded, 4 * 4);
+        assert!(max_difference(&pixels, &decoded, 4) <= 12);
+    }
+
+    #[test]
+    fn bc4_block_exact_values() {
+        // The 6 value mode represents 0 and 255 exactly.
+        let pixels: RgbaBlock = std::array::from_fn(|i| match i % 3 {
+            0 => [0, 0, 0, 255],
+            1 => [255, 0, 0, 255],
+            _ => [128, 0, 0, 255],
+        });
+        let block = bc4_block(&pixels);
+
+        let mut decoded = [0u8; 16];
+        bcdec_rs::bc4(&block, &mut decoded, 4);
+        assert_eq!(pixels.map(|p| p[0]), decoded);
+    }
+
+    #[test]
+    fn bc5_block_gradient() {
+        let pixels = gradient_block();
+        let block = bc5_block(&pixels);
+
+        let mut decoded = [0u8; 16 * 2];
+        bcdec_rs::bc5(&block, &mut decoded, 4 * 2);
+        let expected = pixels.map(|[r, g, _, _]| [r, g, 0, 0]);
+        for (e, a) in expected.iter().zip(decoded.chunks_exact(2)) {
+            assert!(e[0].abs_diff(a[0]) <= 5);
+            assert!(e[1].abs_diff(a[1]) <= 5);
+        }
+    }
+
+    #[test]
+    fn bc7_block_gradient() {
+        let pixels = gradient_block();
+        for quality in [Quality::Fast, Quality::Normal, Quality::Slow] {
+            let block = bc7_block(&pixels, quality);
+
+            let mut decoded = [0u8; 16 * 4];
+            bcdec_rs::bc7(&block, &mut decoded, 4 * 4);
+            assert!(max_difference(&pixels, &decoded, 4) <= 4);
+        }
+    }
+
+    #[test]
+    fn bc7_block_solid_color() {
+        let pixels = [[10u8, 20, 30, 40]; 16];
+        let block = bc7_block(&pixels, Quality::Fast);
+
+        let mut decoded = [0u8; 16 * 4];
+        bcdec_rs::bc7(&block, &mut decoded, 4 * 4);
+        assert!(max_difference(&pixels, &decoded, 4) <= 1);
+    }
+
+    #[test]
+    fn compress_blocks_partial_blocks() {
+        // Partial blocks still produce a full compressed block.
+        let rgba = vec![128u8; 5 * 3 * 4];
+        let compressed = compress_blocks(5, 3, &rgba, bc4_block);
+        assert_eq!(2 * 8, compressed.len());
+    }
+
+    #[test]
+    fn bc4_signed_block_gradient() {
+        let pixels: RgbaFloatBlock = std::array::from_fn(|i| {
+            let v = i as f32 / 60.0 - 0.125;
+            [v, v, v, 1.0]
+        });
+        let block = bc4_signed_block(&pixels);
+
+        let mut decoded = [0i8; 16];
+        bcdec_rs::bc4_signed(&block, &mut decoded, 4);
+        for (p, d) in pixels.iter().zip(decoded) {
+            assert!((p[0] - d as f32 / 127.0).abs() <= 0.025);
+        }
+    }
+
+    #[test]
+    fn bc5_signed_block_exact_values() {
+        // The 6 value mode represents -1.0 and 1.0 exactly.
+        let pixels: RgbaFloatBlock = std::array::from_fn(|i| match i % 3 {
+            0 => [-1.0, 1.0, 0.0, 1.0],
+            1 => [1.0, -1.0, 0.0, 1.0],
+            _ => [0.0, 0.0, 0.0, 1.0],
+        });
+        let block = bc5_signed_block(&pixels);
+
+        let mut decoded = [0i8; 16 * 2];
+        bcdec_rs::bc5_signed(&block, &mut decoded, 4 * 2);
+        for (p, d) in pixels.iter().zip(decoded.chunks_exact(2)) {
+            assert_eq!(p[0], d[0] as f32 / 127.0);
+            assert_eq!(p[1], d[1] as f32 / 127.0);
+        }
+    }
+
+    fn check_bc6h_block(pixels: &RgbaFloatBlock, is_signed: bool) {
+        for quality in [Quality::Fast, Quality::Normal, Quality::Slow] {
+            let block = bc6h_block(pixels, is_signed, quality);
+
+            let mut decoded = [0.0f32; 16 * 3];
+            bcdec_rs::bc6h_float(&block, &mut decoded, 4 * 3, is_signed);
+            for (p, d) in pixels.iter().zip(decoded.chunks_exact(3)) {
+                for c in 0..3 {
+                    // Allow a small relative error since values are stored logarithmically.
+                    assert!((p[c] - d[c]).abs() <= 0.02 * p[c].abs());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bc6h_block_unsigned_hdr() {
+        let pixels: RgbaFloatBlock = std::array::from_fn(|i| {
+            let v = i as f32 / 16.0;
+            [1.0 + v, 2.0 + v * 2.0, 0.5 - v * 0.25, 1.0]
+        });
+        check_bc6h_block(&pixels, false);
+    }
+
+    #[test]
+    fn bc6h_block_signed_hdr() {
+        let pixels: RgbaFloatBlock = std::array::from_fn(|i| {
+            let v = i as f32 / 16.0;
+            [-1.0 - v, 2.0 + v * 2.0, -0.5 + v * 0.25, 1.0]
+        });
+        check_bc6h_block(&pixels, true);
+    }
+
+    #[test]
+    fn bc6h_block_unsigned_clamps_negative() {
+        let block = bc6h_block(&[[-2.0, 0.0, 1.0, 1.0]; 16], false, Quality::Fast);
+
+        let mut decoded = [0.0f32; 16 * 3];
+        bcdec_rs::bc6h_float(&block, &mut decoded, 4 * 3, false);
+        for d in decoded.chunks_exact(3) {
+            assert_eq!(0.0, d[0]);
+            assert_eq!(0.0, d[1]);
+            assert!((d[2] - 1.0).abs() < 0.01);
+        }
+    }
+}
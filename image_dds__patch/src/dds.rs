@@ -1,11 +1,13 @@
 use std::ops::Range;
 
-use ddsfile::{D3DFormat, Dds, DxgiFormat, FourCC};
+use ddsfile::{Caps2, D3DFormat, Dds, DxgiFormat, FourCC, PixelFormat, PixelFormatFlags};
+use num_traits::FromPrimitive;
 use thiserror::Error;
 
 use crate::{
-    CreateImageError, ImageFormat, Mipmaps, Quality, Surface, SurfaceError, SurfaceRgba32Float,
-    SurfaceRgba8,
+    dxgi::{dxgi_format, image_format_from_dxgi, image_format_from_masks},
+    AlphaMode, CreateImageError, ImageFormat, Mipmaps, Quality, Surface, SurfaceError,
+    SurfaceRgba32Float, SurfaceRgba8, TypelessInterpretation,
 };
 
 /// Errors that can occur when converting to DDS.
@@ -16,9 +18,12 @@ pub enum CreateDdsError {
 
     #[error("error compressing surface: {0}")]
     CompressSurface(#[from] SurfaceError),
+
+    #[error("image format {0:?} has no equivalent DXGI format")]
+    UnsupportedImageFormat(ImageFormat),
 }
 
-#[cfg(feature = "encode")]
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
 #[cfg(feature = "image")]
 /// Encode `image` to a 2D DDS file with the given `format`.
 ///
@@ -35,7 +40,7 @@ pub fn dds_from_image(
         .to_dds()
 }
 
-#[cfg(feature = "encode")]
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
 #[cfg(feature = "image")]
 /// Encode `image` to a 2D DDS file with the given `format`.
 ///
@@ -55,22 +60,71 @@ pub fn dds_from_imagef32(
 #[cfg(feature = "image")]
 /// Decode the given mip level from `dds` to an RGBA8 image.
 /// Array layers are arranged vertically from top to bottom.
+///
+/// Premultiplied alpha like `DXT2` and `DXT4` is converted to straight alpha.
 pub fn image_from_dds(dds: &Dds, mipmap: u32) -> Result<image::RgbaImage, CreateImageError> {
     let layers = array_layer_count(dds);
-    SurfaceRgba8::decode_layers_mipmaps_dds(dds, 0..layers, mipmap..mipmap + 1)?.into_image()
+    let mut surface = SurfaceRgba8::decode_layers_mipmaps_dds(dds, 0..layers, mipmap..mipmap + 1)?;
+    surface.unpremultiply_alpha();
+    surface.into_image()
 }
 
 #[cfg(feature = "image")]
 /// Decode the given mip level from `dds` to an RGBAF32 image.
 /// Array layers are arranged vertically from top to bottom.
+///
+/// Depth formats store depth in the red channel and stencil in the green channel.
+/// Use [SurfaceRgba32Float::linearize_depth] on the decoded surface to visualize depth.
+///
+/// Premultiplied alpha like `DXT2` and `DXT4` is converted to straight alpha.
 pub fn imagef32_from_dds(dds: &Dds, mipmap: u32) -> Result<image::Rgba32FImage, CreateImageError> {
     let layers = array_layer_count(dds);
-    SurfaceRgba32Float::decode_layers_mipmaps_dds(dds, 0..layers, mipmap..mipmap + 1)?.into_image()
+    let mut surface =
+        SurfaceRgba32Float::decode_layers_mipmaps_dds(dds, 0..layers, mipmap..mipmap + 1)?;
+    surface.unpremultiply_alpha();
+    surface.into_image()
 }
 
 impl<T: AsRef<[u8]>> Surface<T> {
     /// Create a DDS file with the same image data and format.
     pub fn to_dds(&self) -> Result<crate::ddsfile::Dds, CreateDdsError> {
+        self.create_dds(false)
+    }
+
+    /// Create a cube map DDS file with the same image data and format.
+    ///
+    /// See [layers](#structfield.layers) for the order of the cube map faces.
+    pub fn to_dds_cubemap(&self) -> Result<crate::ddsfile::Dds, CreateDdsError> {
+        self.validate_cube_map()?;
+        self.create_dds(true)
+    }
+
+    fn create_dds(&self, is_cubemap: bool) -> Result<crate::ddsfile::Dds, CreateDdsError> {
+        // Formats without a DXGI format use a legacy header without array layers.
+        if let Some(format) = legacy_d3d_format(self.image_format) {
+            if self.layers == if is_cubemap { 6 } else { 1 } {
+                let mut dds = Dds::new_d3d(ddsfile::NewD3dParams {
+                    height: self.height,
+                    width: self.width,
+                    depth: (self.depth > 1).then_some(self.depth),
+                    format,
+                    mipmap_levels: (self.mipmaps > 1).then_some(self.mipmaps),
+                    caps2: is_cubemap.then_some(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES),
+                })?;
+                // ddsfile writes UYVY as a 16-bit RGB format without the FOURCC.
+                if format == D3DFormat::UYVY {
+                    dds.header.spf = PixelFormat {
+                        flags: PixelFormatFlags::FOURCC,
+                        fourcc: Some(FourCC(FourCC::UYVY)),
+                        ..Default::default()
+                    };
+                }
+                dds.data = self.data.as_ref().to_vec();
+                return Ok(dds);
+            }
+        }
+
+        // ddsfile divides the layers by 6 for cube maps.
         let mut dds = Dds::new_dxgi(ddsfile::NewDxgiParams {
             height: self.height,
             width: self.width,
@@ -79,17 +133,24 @@ impl<T: AsRef<[u8]>> Surface<T> {
             } else {
                 None
             },
-            format: self.image_format.into(),
+            format: self.image_format.try_into()?,
             mipmap_levels: (self.mipmaps > 1).then_some(self.mipmaps),
             array_layers: (self.layers > 1).then_some(self.layers),
-            caps2: None,
-            is_cubemap: false,
+            // Set the legacy cube map flags for DX9 readers.
+            caps2: is_cubemap.then_some(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES),
+            is_cubemap,
             resource_dimension: if self.depth > 1 {
                 ddsfile::D3D10ResourceDimension::Texture3D
             } else {
                 ddsfile::D3D10ResourceDimension::Texture2D
             },
-            alpha_mode: ddsfile::AlphaMode::Straight, // TODO: Does this matter?
+            alpha_mode: match self.alpha_mode {
+                AlphaMode::Unknown => ddsfile::AlphaMode::Unknown,
+                AlphaMode::Straight => ddsfile::AlphaMode::Straight,
+                AlphaMode::Premultiplied => ddsfile::AlphaMode::PreMultiplied,
+                AlphaMode::Opaque => ddsfile::AlphaMode::Opaque,
+                AlphaMode::Custom => ddsfile::AlphaMode::Custom,
+            },
         })?;
 
         dds.data = self.data.as_ref().to_vec();
@@ -101,12 +162,22 @@ impl<T: AsRef<[u8]>> Surface<T> {
 impl<'a> Surface<&'a [u8]> {
     /// Create a view over the data in `dds` without any copies.
     pub fn from_dds(dds: &'a crate::ddsfile::Dds) -> Result<Self, SurfaceError> {
+        Self::from_dds_with_typeless(dds, TypelessInterpretation::default())
+    }
+
+    /// Create a view over the data in `dds` without any copies
+    /// using `typeless` for typeless DXGI formats like [DxgiFormat::BC7_Typeless].
+    pub fn from_dds_with_typeless(
+        dds: &'a crate::ddsfile::Dds,
+        typeless: TypelessInterpretation,
+    ) -> Result<Self, SurfaceError> {
         let width = dds.get_width();
         let height = dds.get_height();
         let depth = dds.get_depth();
         let layers = array_layer_count(dds);
         let mipmaps = dds.get_num_mipmap_levels();
-        let image_format = dds_image_format(dds).map_err(SurfaceError::UnsupportedDdsFormat)?;
+        let image_format = dds_image_format_with_typeless(dds, typeless)
+            .map_err(SurfaceError::UnsupportedDdsFormat)?;
 
         Ok(Surface {
             width,
@@ -115,12 +186,13 @@ impl<'a> Surface<&'a [u8]> {
             layers,
             mipmaps,
             image_format,
+            alpha_mode: dds_alpha_mode(dds),
             data: &dds.data,
         })
     }
 }
 
-#[cfg(feature = "encode")]
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
 impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
     /// Encode a `width` x `height` x `depth` RGBA8 surface to a DDS file with the given `format`.
     ///
@@ -186,43 +258,58 @@ pub struct DdsFormatInfo {
 }
 
 /// Returns the format of `dds` or `None` if the format is unrecognized.
+///
+/// Typeless DXGI formats use the default [TypelessInterpretation].
 pub fn dds_image_format(dds: &Dds) -> Result<ImageFormat, DdsFormatInfo> {
+    dds_image_format_with_typeless(dds, TypelessInterpretation::default())
+}
+
+/// Returns the format of `dds` using `typeless` for typeless DXGI formats
+/// or `None` if the format is unrecognized.
+pub fn dds_image_format_with_typeless(
+    dds: &Dds,
+    typeless: TypelessInterpretation,
+) -> Result<ImageFormat, DdsFormatInfo> {
     // The format can be DXGI, D3D, or specified in the FOURCC.
     let dxgi = dds.get_dxgi_format();
     let d3d = dds.get_d3d_format();
     let fourcc = dds.header.spf.fourcc.clone();
 
     d3d.and_then(image_format_from_d3d)
-        .or_else(|| dxgi.and_then(image_format_from_dxgi))
+        .or_else(|| dxgi.and_then(|f| image_format_from_dxgi(f as u32, typeless)))
         .or_else(|| fourcc.clone().and_then(image_format_from_fourcc))
+        .or_else(|| {
+            let pf = &dds.header.spf;
+            let mask = |m: Option<u32>| m.unwrap_or_default();
+            image_format_from_masks(
+                pf.flags.bits(),
+                mask(pf.rgb_bit_count),
+                mask(pf.r_bit_mask),
+                mask(pf.g_bit_mask),
+                mask(pf.b_bit_mask),
+                mask(pf.a_bit_mask),
+            )
+        })
         .ok_or(DdsFormatInfo { dxgi, d3d, fourcc })
 }
 
-fn image_format_from_dxgi(format: DxgiFormat) -> Option<ImageFormat> {
-    match format {
-        DxgiFormat::R8_UNorm => Some(ImageFormat::R8Unorm),
-        DxgiFormat::R8G8B8A8_UNorm => Some(ImageFormat::Rgba8Unorm),
-        DxgiFormat::R8G8B8A8_UNorm_sRGB => Some(ImageFormat::Rgba8UnormSrgb),
-        DxgiFormat::R16G16B16A16_Float => Some(ImageFormat::Rgba16Float),
-        DxgiFormat::R32G32B32A32_Float => Some(ImageFormat::Rgba32Float),
-        DxgiFormat::B8G8R8A8_UNorm => Some(ImageFormat::Bgra8Unorm),
-        DxgiFormat::B8G8R8A8_UNorm_sRGB => Some(ImageFormat::Bgra8UnormSrgb),
-        DxgiFormat::BC1_UNorm => Some(ImageFormat::BC1RgbaUnorm),
-        DxgiFormat::BC1_UNorm_sRGB => Some(ImageFormat::BC1RgbaUnormSrgb),
-        DxgiFormat::BC2_UNorm => Some(ImageFormat::BC2RgbaUnorm),
-        DxgiFormat::BC2_UNorm_sRGB => Some(ImageFormat::BC2RgbaUnormSrgb),
-        DxgiFormat::BC3_UNorm => Some(ImageFormat::BC3RgbaUnorm),
-        DxgiFormat::BC3_UNorm_sRGB => Some(ImageFormat::BC3RgbaUnormSrgb),
-        DxgiFormat::BC4_UNorm => Some(ImageFormat::BC4RUnorm),
-        DxgiFormat::BC4_SNorm => Some(ImageFormat::BC4RSnorm),
-        DxgiFormat::BC5_UNorm => Some(ImageFormat::BC5RgUnorm),
-        DxgiFormat::BC5_SNorm => Some(ImageFormat::BC5RgSnorm),
-        DxgiFormat::BC6H_SF16 => Some(ImageFormat::BC6hRgbSfloat),
-        DxgiFormat::BC6H_UF16 => Some(ImageFormat::BC6hRgbUfloat),
-        DxgiFormat::BC7_UNorm => Some(ImageFormat::BC7RgbaUnorm),
-        DxgiFormat::BC7_UNorm_sRGB => Some(ImageFormat::BC7RgbaUnormSrgb),
-        DxgiFormat::B4G4R4A4_UNorm => Some(ImageFormat::Bgra4Unorm),
-        _ => None,
+/// Returns the alpha mode from the DX10 header of `dds`.
+///
+/// Files without a DX10 header use [AlphaMode::Premultiplied] for `DXT2` and `DXT4`
+/// and [AlphaMode::Unknown] otherwise.
+pub fn dds_alpha_mode(dds: &Dds) -> AlphaMode {
+    match &dds.header10 {
+        Some(header10) => match header10.alpha_mode {
+            ddsfile::AlphaMode::Unknown => AlphaMode::Unknown,
+            ddsfile::AlphaMode::Straight => AlphaMode::Straight,
+            ddsfile::AlphaMode::PreMultiplied => AlphaMode::Premultiplied,
+            ddsfile::AlphaMode::Opaque => AlphaMode::Opaque,
+            ddsfile::AlphaMode::Custom => AlphaMode::Custom,
+        },
+        None => match &dds.header.spf.fourcc {
+            Some(FourCC(FourCC::DXT2 | FourCC::DXT4)) => AlphaMode::Premultiplied,
+            _ => AlphaMode::Unknown,
+        },
     }
 }
 
@@ -236,6 +323,30 @@ fn image_format_from_d3d(format: D3DFormat) -> Option<ImageFormat> {
         // BGRA is also ARGB depending on how we look at the bytes.
         D3DFormat::A4R4G4B4 => Some(ImageFormat::Bgra4Unorm),
         D3DFormat::A8R8G8B8 => Some(ImageFormat::Bgra8Unorm),
+        D3DFormat::G16R16 => Some(ImageFormat::Rg16Unorm),
+        D3DFormat::A16B16G16R16 => Some(ImageFormat::Rgba16Unorm),
+        D3DFormat::Q16W16V16U16 => Some(ImageFormat::Rgba16Snorm),
+        D3DFormat::R16F => Some(ImageFormat::R16Float),
+        D3DFormat::G16R16F => Some(ImageFormat::Rg16Float),
+        D3DFormat::A16B16G16R16F => Some(ImageFormat::Rgba16Float),
+        D3DFormat::R32F => Some(ImageFormat::R32Float),
+        D3DFormat::G32R32F => Some(ImageFormat::Rg32Float),
+        D3DFormat::A32B32G32R32F => Some(ImageFormat::Rgba32Float),
+        // D3DX writes DXGI_FORMAT_R10G10B10A2_UNORM with the masks for A2R10G10B10.
+        // Treat both channel orders as RGBA like DirectXTex.
+        D3DFormat::A2B10G10R10 | D3DFormat::A2R10G10B10 => Some(ImageFormat::Rgb10a2Unorm),
+        D3DFormat::R5G6B5 => Some(ImageFormat::B5g6r5Unorm),
+        D3DFormat::A1R5G5B5 => Some(ImageFormat::Bgr5a1Unorm),
+        D3DFormat::X1R5G5B5 => Some(ImageFormat::Bgr5x1Unorm),
+        D3DFormat::R8G8B8 => Some(ImageFormat::Bgr8Unorm),
+        D3DFormat::X8R8G8B8 => Some(ImageFormat::Bgrx8Unorm),
+        // Luminance decodes to grayscale like single channel formats.
+        D3DFormat::L8 => Some(ImageFormat::R8Unorm),
+        D3DFormat::L16 => Some(ImageFormat::R16Unorm),
+        D3DFormat::A8L8 => Some(ImageFormat::La8Unorm),
+        D3DFormat::A8 => Some(ImageFormat::A8Unorm),
+        D3DFormat::YUY2 => Some(ImageFormat::Yuy2),
+        D3DFormat::UYVY => Some(ImageFormat::Uyvy),
         _ => None,
     }
 }
@@ -258,31 +369,247 @@ fn image_format_from_fourcc(fourcc: FourCC) -> Option<ImageFormat> {
     }
 }
 
-impl From<ImageFormat> for DxgiFormat {
-    fn from(value: ImageFormat) -> Self {
-        match value {
-            ImageFormat::BC1RgbaUnorm => Self::BC1_UNorm,
-            ImageFormat::BC1RgbaUnormSrgb => Self::BC1_UNorm_sRGB,
-            ImageFormat::BC2RgbaUnorm => Self::BC2_UNorm,
-            ImageFormat::BC2RgbaUnormSrgb => Self::BC2_UNorm_sRGB,
-            ImageFormat::BC3RgbaUnorm => Self::BC3_UNorm,
-            ImageFormat::BC3RgbaUnormSrgb => Self::BC3_UNorm_sRGB,
-            ImageFormat::BC4RUnorm => Self::BC4_UNorm,
-            ImageFormat::BC4RSnorm => Self::BC4_SNorm,
-            ImageFormat::BC5RgUnorm => Self::BC5_UNorm,
-            ImageFormat::BC5RgSnorm => Self::BC5_SNorm,
-            ImageFormat::BC6hRgbUfloat => Self::BC6H_UF16,
-            ImageFormat::BC6hRgbSfloat => Self::BC6H_SF16,
-            ImageFormat::BC7RgbaUnorm => Self::BC7_UNorm,
-            ImageFormat::BC7RgbaUnormSrgb => Self::BC7_UNorm_sRGB,
-            ImageFormat::R8Unorm => Self::R8_UNorm,
-            ImageFormat::Rgba8Unorm => Self::R8G8B8A8_UNorm,
-            ImageFormat::Rgba8UnormSrgb => Self::R8G8B8A8_UNorm_sRGB,
-            ImageFormat::Rgba16Float => Self::R16G16B16A16_Float,
-            ImageFormat::Rgba32Float => Self::R32G32B32A32_Float,
-            ImageFormat::Bgra8Unorm => Self::B8G8R8A8_UNorm,
-            ImageFormat::Bgra8UnormSrgb => Self::B8G8R8A8_UNorm_sRGB,
-            ImageFormat::Bgra4Unorm => Self::B4G4R4A4_UNorm,
+fn legacy_d3d_format(format: ImageFormat) -> Option<D3DFormat> {
+    match format {
+        ImageFormat::Bgr5x1Unorm => Some(D3DFormat::X1R5G5B5),
+        ImageFormat::Bgr8Unorm => Some(D3DFormat::R8G8B8),
+        ImageFormat::La8Unorm => Some(D3DFormat::A8L8),
+        ImageFormat::Uyvy => Some(D3DFormat::UYVY),
+        _ => None,
+    }
+}
+
+impl TryFrom<ImageFormat> for DxgiFormat {
+    type Error = CreateDdsError;
+
+    fn try_from(value: ImageFormat) -> Result<Self, Self::Error> {
+        dxgi_format(value)
+            .and_then(DxgiFormat::from_u32)
+            .ok_or(CreateDdsError::UnsupportedImageFormat(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_to_dds_cubemap_round_trip() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 12,
+            mipmaps: 3,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 16 * 3 * 12],
+        };
+        let dds = surface.to_dds_cubemap().unwrap();
+
+        let header10 = dds.header10.as_ref().unwrap();
+        assert_eq!(ddsfile::MiscFlag::TEXTURECUBE, header10.misc_flag);
+        assert_eq!(2, header10.array_size);
+        assert!(dds
+            .header
+            .caps2
+            .contains(Caps2::CUBEMAP | Caps2::CUBEMAP_ALLFACES));
+
+        let new_surface = Surface::from_dds(&dds).unwrap();
+        assert_eq!(12, new_surface.layers);
+        assert_eq!(3, new_surface.mipmaps);
+        assert_eq!(&surface.data, new_surface.data);
+    }
+
+    #[test]
+    fn surface_to_dds_legacy_format_round_trip() {
+        // Formats without a DXGI format use the legacy header.
+        for image_format in [
+            ImageFormat::Bgr5x1Unorm,
+            ImageFormat::Bgr8Unorm,
+            ImageFormat::La8Unorm,
+            ImageFormat::Uyvy,
+        ] {
+            let surface = Surface {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format,
+                alpha_mode: AlphaMode::Straight,
+                data: vec![0u8; 16 * image_format.block_size_in_bytes()],
+            };
+            let dds = surface.to_dds().unwrap();
+            assert!(dds.header10.is_none());
+
+            let mut bytes = Vec::new();
+            dds.write(&mut bytes).unwrap();
+            let dds = Dds::read(bytes.as_slice()).unwrap();
+
+            let new_surface = Surface::from_dds(&dds).unwrap();
+            assert_eq!(image_format, new_surface.image_format);
+            assert_eq!(&surface.data, new_surface.data);
         }
     }
+
+    #[test]
+    fn dds_image_format_depth_typeless() {
+        let dds = Dds::new_dxgi(ddsfile::NewDxgiParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: DxgiFormat::R32G8X24_Typeless,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: ddsfile::D3D10ResourceDimension::Texture2D,
+            alpha_mode: ddsfile::AlphaMode::Unknown,
+        })
+        .unwrap();
+        assert_eq!(
+            ImageFormat::Depth32FloatStencil8,
+            dds_image_format(&dds).unwrap()
+        );
+    }
+
+    #[test]
+    fn dds_image_format_bc1_typeless() {
+        let dds = Dds::new_dxgi(ddsfile::NewDxgiParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: DxgiFormat::BC1_Typeless,
+            mipmap_levels: None,
+            array_layers: None,
+            caps2: None,
+            is_cubemap: false,
+            resource_dimension: ddsfile::D3D10ResourceDimension::Texture2D,
+            alpha_mode: ddsfile::AlphaMode::Unknown,
+        })
+        .unwrap();
+        assert_eq!(ImageFormat::BC1RgbaUnorm, dds_image_format(&dds).unwrap());
+        assert_eq!(
+            ImageFormat::BC1RgbaUnormSrgb,
+            dds_image_format_with_typeless(&dds, TypelessInterpretation::UnormSrgb).unwrap()
+        );
+
+        let surface =
+            Surface::from_dds_with_typeless(&dds, TypelessInterpretation::UnormSrgb).unwrap();
+        assert_eq!(ImageFormat::BC1RgbaUnormSrgb, surface.image_format);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn imagef32_from_dds_depth24_stencil8() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Depth24UnormStencil8,
+            alpha_mode: AlphaMode::Straight,
+            data: (0xFFFFFFu32 | (255 << 24)).to_le_bytes().to_vec(),
+        };
+        let dds = surface.to_dds().unwrap();
+
+        let image = imagef32_from_dds(&dds, 0).unwrap();
+        assert_eq!(&[1.0, 1.0, 0.0, 1.0], image.as_raw().as_slice());
+    }
+
+    #[test]
+    fn surface_to_dds_premultiplied() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC3RgbaUnorm,
+            alpha_mode: AlphaMode::Premultiplied,
+            data: vec![0u8; 16],
+        };
+        let dds = surface.to_dds().unwrap();
+        assert_eq!(
+            ddsfile::AlphaMode::PreMultiplied,
+            dds.header10.as_ref().unwrap().alpha_mode
+        );
+        assert_eq!(
+            AlphaMode::Premultiplied,
+            Surface::from_dds(&dds).unwrap().alpha_mode
+        );
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn image_from_dds_dxt2_unpremultiply() {
+        let mut dds = Dds::new_d3d(ddsfile::NewD3dParams {
+            height: 4,
+            width: 4,
+            depth: None,
+            format: D3DFormat::DXT2,
+            mipmap_levels: None,
+            caps2: None,
+        })
+        .unwrap();
+        assert_eq!(AlphaMode::Premultiplied, dds_alpha_mode(&dds));
+
+        // Explicit alpha of 136 and a single gray color with RGB less than alpha.
+        let mut block = vec![0x88; 8];
+        block.extend_from_slice(&[0x10, 0x84, 0x10, 0x84, 0, 0, 0, 0]);
+        dds.data = block;
+
+        let surface = SurfaceRgba8::decode_dds(&dds).unwrap();
+        assert_eq!(AlphaMode::Premultiplied, surface.alpha_mode);
+        assert_eq!([132, 130, 132, 136], surface.data[..4]);
+
+        let image = image_from_dds(&dds, 0).unwrap();
+        assert_eq!([248, 244, 248, 136], image.get_pixel(0, 0).0);
+    }
+
+    #[test]
+    fn surface_to_dds_not_cubemap() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 6,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 16 * 6],
+        };
+        let dds = surface.to_dds().unwrap();
+
+        let header10 = dds.header10.as_ref().unwrap();
+        assert!(header10.misc_flag.is_empty());
+        assert_eq!(6, header10.array_size);
+        assert!(dds.header.caps2.is_empty());
+    }
+
+    #[test]
+    fn surface_to_dds_cubemap_invalid_layers() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 4,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 16 * 4],
+        };
+        let result = surface.to_dds_cubemap();
+        assert!(matches!(
+            result,
+            Err(CreateDdsError::CompressSurface(
+                SurfaceError::InvalidCubeMap {
+                    layers: 4,
+                    depth: 1
+                }
+            ))
+        ));
+    }
 }
@@ -0,0 +1,1238 @@
+// A native DDS reader and writer that does not depend on ddsfile.
+// https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dds-header
+use std::io::{Read, Write};
+
+use thiserror::Error;
+
+use crate::{
+    div_round_up,
+    dxgi::{
+        dxgi_format, image_format_from_dxgi, image_format_from_masks, DDPF_ALPHAPIXELS,
+        DDPF_LUMINANCE, DDPF_RGB,
+    },
+    mip_dimension, mip_size, round_up, AlphaMode, ImageFormat, Surface, SurfaceError,
+    TypelessInterpretation,
+};
+
+const MAGIC: u32 = u32::from_le_bytes(*b"DDS ");
+const HEADER_SIZE: u32 = 124;
+const PIXEL_FORMAT_SIZE: u32 = 32;
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x20000;
+const DDSD_LINEARSIZE: u32 = 0x80000;
+const DDSD_DEPTH: u32 = 0x800000;
+
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x400000;
+
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0xFC00;
+const DDSCAPS2_VOLUME: u32 = 0x200000;
+
+const DDPF_FOURCC: u32 = 0x4;
+
+const D3D10_RESOURCE_DIMENSION_TEXTURE2D: u32 = 3;
+const D3D10_RESOURCE_DIMENSION_TEXTURE3D: u32 = 4;
+const D3D10_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+const DDS_MISC_FLAGS2_ALPHA_MODE_MASK: u32 = 0x7;
+const DDS_ALPHA_MODE_UNKNOWN: u32 = 0;
+const DDS_ALPHA_MODE_STRAIGHT: u32 = 1;
+const DDS_ALPHA_MODE_PREMULTIPLIED: u32 = 2;
+const DDS_ALPHA_MODE_OPAQUE: u32 = 3;
+const DDS_ALPHA_MODE_CUSTOM: u32 = 4;
+
+const DX10: u32 = u32::from_le_bytes(*b"DX10");
+const DXT1: u32 = u32::from_le_bytes(*b"DXT1");
+const DXT2: u32 = u32::from_le_bytes(*b"DXT2");
+const DXT3: u32 = u32::from_le_bytes(*b"DXT3");
+const DXT4: u32 = u32::from_le_bytes(*b"DXT4");
+const DXT5: u32 = u32::from_le_bytes(*b"DXT5");
+const ATI1: u32 = u32::from_le_bytes(*b"ATI1");
+const BC4U: u32 = u32::from_le_bytes(*b"BC4U");
+const BC4S: u32 = u32::from_le_bytes(*b"BC4S");
+const ATI2: u32 = u32::from_le_bytes(*b"ATI2");
+const BC5U: u32 = u32::from_le_bytes(*b"BC5U");
+const BC5S: u32 = u32::from_le_bytes(*b"BC5S");
+const YUY2: u32 = u32::from_le_bytes(*b"YUY2");
+const UYVY: u32 = u32::from_le_bytes(*b"UYVY");
+// D3DFORMAT values stored directly in the FOURCC.
+const D3DFMT_A16B16G16R16: u32 = 36;
+const D3DFMT_Q16W16V16U16: u32 = 110;
+const D3DFMT_R16F: u32 = 111;
+const D3DFMT_G16R16F: u32 = 112;
+const D3DFMT_A16B16G16R16F: u32 = 113;
+const D3DFMT_R32F: u32 = 114;
+const D3DFMT_G32R32F: u32 = 115;
+const D3DFMT_A32B32G32R32F: u32 = 116;
+
+/// Errors that can occur while reading or writing DDS files.
+#[derive(Debug, Error)]
+pub enum DdsError {
+    #[error("error reading or writing DDS: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid DDS magic {0:#x}")]
+    InvalidMagic(u32),
+
+    #[error("invalid DDS header size {0}")]
+    InvalidHeaderSize(u32),
+
+    #[error("DDS format with DXGI format {dxgi_format:?} and pixel format {pixel_format:?} is not supported")]
+    UnsupportedFormat {
+        dxgi_format: Option<u32>,
+        pixel_format: DdsPixelFormat,
+    },
+
+    #[error("image format {0:?} has no equivalent DXGI format")]
+    UnsupportedImageFormat(ImageFormat),
+
+    #[error("error reading surface: {0}")]
+    Surface(#[from] SurfaceError),
+}
+
+/// The header for a DDS file including the optional DX10 header.
+///
+/// Reserved fields are always written as `0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DdsHeader {
+    pub flags: u32,
+    pub height: u32,
+    pub width: u32,
+    pub pitch_or_linear_size: u32,
+    pub depth: u32,
+    pub mipmap_count: u32,
+    pub pixel_format: DdsPixelFormat,
+    pub caps: u32,
+    pub caps2: u32,
+    pub caps3: u32,
+    pub caps4: u32,
+    /// The additional header used when [DdsPixelFormat::fourcc] is `"DX10"`.
+    pub dx10: Option<DdsHeaderDx10>,
+}
+
+/// The pixel format for the legacy DDS header.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct DdsPixelFormat {
+    pub flags: u32,
+    pub fourcc: u32,
+    pub rgb_bit_count: u32,
+    pub r_bit_mask: u32,
+    pub g_bit_mask: u32,
+    pub b_bit_mask: u32,
+    pub a_bit_mask: u32,
+}
+
+/// The extended DDS header for DXGI formats and texture arrays.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DdsHeaderDx10 {
+    pub dxgi_format: u32,
+    pub resource_dimension: u32,
+    pub misc_flag: u32,
+    pub array_size: u32,
+    pub misc_flags2: u32,
+}
+
+impl DdsHeader {
+    /// Read the magic and headers from the start of a DDS file.
+    /// The `reader` is left at the start of the surface data.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, DdsError> {
+        let magic = read_u32(reader)?;
+        if magic != MAGIC {
+            return Err(DdsError::InvalidMagic(magic));
+        }
+
+        let size = read_u32(reader)?;
+        if size != HEADER_SIZE {
+            return Err(DdsError::InvalidHeaderSize(size));
+        }
+
+        let flags = read_u32(reader)?;
+        let height = read_u32(reader)?;
+        let width = read_u32(reader)?;
+        let pitch_or_linear_size = read_u32(reader)?;
+        let depth = read_u32(reader)?;
+        let mipmap_count = read_u32(reader)?;
+        let mut reserved1 = [0u8; 11 * 4];
+        reader.read_exact(&mut reserved1)?;
+
+        // Some writers don't set the pixel format size correctly, so ignore it.
+        let _pixel_format_size = read_u32(reader)?;
+        let pixel_format = DdsPixelFormat {
+            flags: read_u32(reader)?,
+            fourcc: read_u32(reader)?,
+            rgb_bit_count: read_u32(reader)?,
+            r_bit_mask: read_u32(reader)?,
+            g_bit_mask: read_u32(reader)?,
+            b_bit_mask: read_u32(reader)?,
+            a_bit_mask: read_u32(reader)?,
+        };
+
+        let caps = read_u32(reader)?;
+        let caps2 = read_u32(reader)?;
+        let caps3 = read_u32(reader)?;
+        let caps4 = read_u32(reader)?;
+        let _reserved2 = read_u32(reader)?;
+
+        let dx10 = if pixel_format.flags & DDPF_FOURCC != 0 && pixel_format.fourcc == DX10 {
+            Some(DdsHeaderDx10 {
+                dxgi_format: read_u32(reader)?,
+                resource_dimension: read_u32(reader)?,
+                misc_flag: read_u32(reader)?,
+                array_size: read_u32(reader)?,
+                misc_flags2: read_u32(reader)?,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            flags,
+            height,
+            width,
+            pitch_or_linear_size,
+            depth,
+            mipmap_count,
+            pixel_format,
+            caps,
+            caps2,
+            caps3,
+            caps4,
+            dx10,
+        })
+    }
+
+    /// Write the magic and headers for the start of a DDS file.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), DdsError> {
+        let mut bytes = Vec::with_capacity(4 + HEADER_SIZE as usize + 20);
+        bytes.extend_from_slice(&MAGIC.to_le_bytes());
+
+        let pf = &self.pixel_format;
+        for value in [
+            HEADER_SIZE,
+            self.flags,
+            self.height,
+            self.width,
+            self.pitch_or_linear_size,
+            self.depth,
+            self.mipmap_count,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0u8; 11 * 4]);
+
+        for value in [
+            PIXEL_FORMAT_SIZE,
+            pf.flags,
+            pf.fourcc,
+            pf.rgb_bit_count,
+            pf.r_bit_mask,
+            pf.g_bit_mask,
+            pf.b_bit_mask,
+            pf.a_bit_mask,
+            self.caps,
+            self.caps2,
+            self.caps3,
+            self.caps4,
+            0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        if let Some(dx10) = &self.dx10 {
+            for value in [
+                dx10.dxgi_format,
+                dx10.resource_dimension,
+                dx10.misc_flag,
+                dx10.array_size,
+                dx10.misc_flags2,
+            ] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Returns the format of the surface data or `None` if the format is unrecognized.
+    ///
+    /// Typeless DXGI formats use the default [TypelessInterpretation].
+    pub fn image_format(&self) -> Option<ImageFormat> {
+        self.image_format_with_typeless(TypelessInterpretation::default())
+    }
+
+    /// Returns the format of the surface data using `typeless` for typeless DXGI formats
+    /// or `None` if the format is unrecognized.
+    pub fn image_format_with_typeless(
+        &self,
+        typeless: TypelessInterpretation,
+    ) -> Option<ImageFormat> {
+        // The format can be DXGI, specified in the FOURCC, or described by the channel masks.
+        match &self.dx10 {
+            Some(dx10) => image_format_from_dxgi(dx10.dxgi_format, typeless),
+            None if self.pixel_format.flags & DDPF_FOURCC != 0 => {
+                image_format_from_fourcc(self.pixel_format.fourcc)
+            }
+            None => image_format_from_masks(
+                self.pixel_format.flags,
+                self.pixel_format.rgb_bit_count,
+                self.pixel_format.r_bit_mask,
+                self.pixel_format.g_bit_mask,
+                self.pixel_format.b_bit_mask,
+                self.pixel_format.a_bit_mask,
+            ),
+        }
+    }
+
+    /// Returns the alpha mode from the DX10 header.
+    ///
+    /// Files without a DX10 header use [AlphaMode::Premultiplied] for `DXT2` and `DXT4`
+    /// and [AlphaMode::Unknown] otherwise.
+    pub fn alpha_mode(&self) -> AlphaMode {
+        match &self.dx10 {
+            Some(dx10) => match dx10.misc_flags2 & DDS_MISC_FLAGS2_ALPHA_MODE_MASK {
+                DDS_ALPHA_MODE_STRAIGHT => AlphaMode::Straight,
+                DDS_ALPHA_MODE_PREMULTIPLIED => AlphaMode::Premultiplied,
+                DDS_ALPHA_MODE_OPAQUE => AlphaMode::Opaque,
+                DDS_ALPHA_MODE_CUSTOM => AlphaMode::Custom,
+                _ => AlphaMode::Unknown,
+            },
+            None if self.pixel_format.flags & DDPF_FOURCC != 0
+                && matches!(self.pixel_format.fourcc, DXT2 | DXT4) =>
+            {
+                AlphaMode::Premultiplied
+            }
+            None => AlphaMode::Unknown,
+        }
+    }
+
+    fn is_cubemap(&self) -> bool {
+        match &self.dx10 {
+            Some(dx10) => dx10.misc_flag & D3D10_RESOURCE_MISC_TEXTURECUBE != 0,
+            None => self.caps2 & DDSCAPS2_CUBEMAP != 0,
+        }
+    }
+
+    fn layers(&self) -> u32 {
+        // Array layers for DDS are calculated differently for cube maps.
+        let array_size = self.dx10.map(|dx10| dx10.array_size.max(1)).unwrap_or(1);
+        if self.is_cubemap() {
+            array_size * 6
+        } else {
+            array_size
+        }
+    }
+
+    fn surface_header(&self, typeless: TypelessInterpretation) -> Result<Surface<()>, DdsError> {
+        let image_format =
+            self.image_format_with_typeless(typeless)
+                .ok_or(DdsError::UnsupportedFormat {
+                    dxgi_format: self.dx10.map(|dx10| dx10.dxgi_format),
+                    pixel_format: self.pixel_format,
+                })?;
+
+        let is_volume = self.flags & DDSD_DEPTH != 0 || self.caps2 & DDSCAPS2_VOLUME != 0;
+
+        Ok(Surface {
+            width: self.width,
+            height: self.height,
+            depth: if is_volume { self.depth.max(1) } else { 1 },
+            layers: self.layers(),
+            mipmaps: self.mipmap_count.max(1),
+            image_format,
+            alpha_mode: self.alpha_mode(),
+            data: (),
+        })
+    }
+
+    fn from_surface<T>(surface: &Surface<T>, is_cubemap: bool) -> Result<Self, DdsError> {
+        // Formats without a DXGI format use a legacy header without array layers.
+        let dxgi_format = dxgi_format(surface.image_format);
+        let pixel_format = match dxgi_format {
+            Some(_) => DdsPixelFormat {
+                flags: DDPF_FOURCC,
+                fourcc: DX10,
+                ..Default::default()
+            },
+            None => legacy_pixel_format(surface.image_format)
+                .filter(|_| surface.layers == if is_cubemap { 6 } else { 1 })
+                .ok_or(DdsError::UnsupportedImageFormat(surface.image_format))?,
+        };
+
+        let (block_width, block_height, _) = surface.image_format.block_dimensions();
+        let block_size_in_bytes = surface.image_format.block_size_in_bytes();
+        let is_yuv = matches!(
+            surface.image_format,
+            ImageFormat::Yuy2 | ImageFormat::Uyvy | ImageFormat::Nv12 | ImageFormat::P010
+        );
+        let is_compressed = (block_width, block_height) != (1, 1) && !is_yuv;
+
+        let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT;
+        let mut caps = DDSCAPS_TEXTURE;
+        let mut caps2 = 0;
+
+        // Compressed formats use the size of the base level instead of the size of a row.
+        let pitch_or_linear_size = if is_compressed {
+            flags |= DDSD_LINEARSIZE;
+            mip_size(
+                surface.width as usize,
+                surface.height as usize,
+                1,
+                block_width as usize,
+                block_height as usize,
+                1,
+                block_size_in_bytes,
+            )
+            .unwrap_or_default() as u32
+        } else {
+            flags |= DDSD_PITCH;
+            // Planar YUV formats use the pitch of the Y plane like DirectXTex.
+            match surface.image_format {
+                ImageFormat::Nv12 => round_up(surface.width as usize, 2) as u32,
+                ImageFormat::P010 => round_up(surface.width as usize, 2) as u32 * 2,
+                _ => {
+                    (div_round_up(surface.width as usize, block_width as usize)
+                        * block_size_in_bytes) as u32
+                }
+            }
+        };
+
+        if surface.mipmaps > 1 {
+            flags |= DDSD_MIPMAPCOUNT;
+            caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+        }
+        if surface.depth > 1 {
+            flags |= DDSD_DEPTH;
+            caps |= DDSCAPS_COMPLEX;
+            caps2 |= DDSCAPS2_VOLUME;
+        }
+        if surface.layers > 1 {
+            caps |= DDSCAPS_COMPLEX;
+        }
+        // Set the legacy cube map flags for DX9 readers.
+        if is_cubemap {
+            caps2 |= DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALLFACES;
+        }
+
+        Ok(Self {
+            flags,
+            height: surface.height,
+            width: surface.width,
+            pitch_or_linear_size,
+            depth: if surface.depth > 1 { surface.depth } else { 0 },
+            mipmap_count: surface.mipmaps,
+            pixel_format,
+            caps,
+            caps2,
+            caps3: 0,
+            caps4: 0,
+            dx10: dxgi_format.map(|dxgi_format| DdsHeaderDx10 {
+                dxgi_format,
+                resource_dimension: if surface.depth > 1 {
+                    D3D10_RESOURCE_DIMENSION_TEXTURE3D
+                } else {
+                    D3D10_RESOURCE_DIMENSION_TEXTURE2D
+                },
+                misc_flag: if is_cubemap {
+                    D3D10_RESOURCE_MISC_TEXTURECUBE
+                } else {
+                    0
+                },
+                // Array layers for DDS are calculated differently for cube maps.
+                array_size: if is_cubemap {
+                    surface.layers / 6
+                } else {
+                    surface.layers
+                },
+                misc_flags2: match surface.alpha_mode {
+                    AlphaMode::Unknown => DDS_ALPHA_MODE_UNKNOWN,
+                    AlphaMode::Straight => DDS_ALPHA_MODE_STRAIGHT,
+                    AlphaMode::Premultiplied => DDS_ALPHA_MODE_PREMULTIPLIED,
+                    AlphaMode::Opaque => DDS_ALPHA_MODE_OPAQUE,
+                    AlphaMode::Custom => DDS_ALPHA_MODE_CUSTOM,
+                },
+            }),
+        })
+    }
+}
+
+impl<'a> Surface<&'a [u8]> {
+    /// Create a view over the surface data in the DDS file `bytes` without any copies.
+    pub fn from_dds_bytes(bytes: &'a [u8]) -> Result<Self, DdsError> {
+        Self::from_dds_bytes_with_typeless(bytes, TypelessInterpretation::default())
+    }
+
+    /// Create a view over the surface data in the DDS file `bytes` without any copies
+    /// using `typeless` for typeless DXGI formats like `DXGI_FORMAT_BC7_TYPELESS`.
+    pub fn from_dds_bytes_with_typeless(
+        bytes: &'a [u8],
+        typeless: TypelessInterpretation,
+    ) -> Result<Self, DdsError> {
+        let mut reader = bytes;
+        let header = DdsHeader::read(&mut reader)?;
+        let surface = header.surface_header(typeless)?;
+
+        let size = surface_size(&surface)?;
+        let data = reader.get(..size).ok_or(SurfaceError::NotEnoughData {
+            expected: size,
+            actual: reader.len(),
+        })?;
+
+        Ok(surface.with_data(data))
+    }
+}
+
+impl Surface<Vec<u8>> {
+    /// Read a DDS file from `reader` without reading any data after the surface data.
+    pub fn read_dds<R: Read>(reader: &mut R) -> Result<Self, DdsError> {
+        Self::read_dds_with_typeless(reader, TypelessInterpretation::default())
+    }
+
+    /// Read a DDS file from `reader` without reading any data after the surface data
+    /// using `typeless` for typeless DXGI formats like `DXGI_FORMAT_BC7_TYPELESS`.
+    pub fn read_dds_with_typeless<R: Read>(
+        reader: &mut R,
+        typeless: TypelessInterpretation,
+    ) -> Result<Self, DdsError> {
+        let header = DdsHeader::read(reader)?;
+        let surface = header.surface_header(typeless)?;
+
+        // Avoid allocating the full size up front in case the header is invalid.
+        let expected = surface_size(&surface)?;
+        let mut data = Vec::new();
+        reader.take(expected as u64).read_to_end(&mut data)?;
+        if data.len() < expected {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+        }
+
+        Ok(surface.with_data(data))
+    }
+}
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Write a DDS file with the same image data and format to `writer`.
+    pub fn write_dds<W: Write>(&self, writer: &mut W) -> Result<(), DdsError> {
+        self.validate()?;
+
+        DdsHeader::from_surface(self, false)?.write(writer)?;
+        writer.write_all(self.data.as_ref())?;
+        Ok(())
+    }
+
+    /// Write a cube map DDS file with the same image data and format to `writer`.
+    ///
+    /// See [layers](#structfield.layers) for the order of the cube map faces.
+    pub fn write_dds_cubemap<W: Write>(&self, writer: &mut W) -> Result<(), DdsError> {
+        self.validate_cube_map()?;
+        self.validate()?;
+
+        DdsHeader::from_surface(self, true)?.write(writer)?;
+        writer.write_all(self.data.as_ref())?;
+        Ok(())
+    }
+}
+
+impl Surface<()> {
+    fn with_data<T>(self, data: T) -> Surface<T> {
+        Surface {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            image_format: self.image_format,
+            alpha_mode: self.alpha_mode,
+            data,
+        }
+    }
+}
+
+// The total size in bytes for all layers and mipmaps.
+fn surface_size<T>(surface: &Surface<T>) -> Result<usize, SurfaceError> {
+    let (block_width, block_height, block_depth) = surface.image_format.block_dimensions();
+    let block_size_in_bytes = surface.image_format.block_size_in_bytes();
+
+    let overflow = || SurfaceError::PixelCountWouldOverflow {
+        width: surface.width,
+        height: surface.height,
+        depth: surface.depth,
+    };
+
+    let mut layer_size = 0usize;
+    for mipmap in 0..surface.mipmaps {
+        let size = mip_size(
+            mip_dimension(surface.width, mipmap) as usize,
+            mip_dimension(surface.height, mipmap) as usize,
+            mip_dimension(surface.depth, mipmap) as usize,
+            block_width as usize,
+            block_height as usize,
+            block_depth as usize,
+            block_size_in_bytes,
+        )
+        .ok_or_else(overflow)?;
+        layer_size = layer_size.checked_add(size).ok_or_else(overflow)?;
+    }
+
+    layer_size
+        .checked_mul(surface.layers as usize)
+        .ok_or_else(overflow)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn image_format_from_fourcc(fourcc: u32) -> Option<ImageFormat> {
+    match fourcc {
+        DXT1 => Some(ImageFormat::BC1RgbaUnorm),
+        DXT2 | DXT3 => Some(ImageFormat::BC2RgbaUnorm),
+        DXT4 | DXT5 => Some(ImageFormat::BC3RgbaUnorm),
+        ATI1 | BC4U => Some(ImageFormat::BC4RUnorm),
+        BC4S => Some(ImageFormat::BC4RSnorm),
+        ATI2 | BC5U => Some(ImageFormat::BC5RgUnorm),
+        BC5S => Some(ImageFormat::BC5RgSnorm),
+        D3DFMT_A16B16G16R16 => Some(ImageFormat::Rgba16Unorm),
+        D3DFMT_Q16W16V16U16 => Some(ImageFormat::Rgba16Snorm),
+        D3DFMT_R16F => Some(ImageFormat::R16Float),
+        D3DFMT_G16R16F => Some(ImageFormat::Rg16Float),
+        D3DFMT_A16B16G16R16F => Some(ImageFormat::Rgba16Float),
+        D3DFMT_R32F => Some(ImageFormat::R32Float),
+        D3DFMT_G32R32F => Some(ImageFormat::Rg32Float),
+        D3DFMT_A32B32G32R32F => Some(ImageFormat::Rgba32Float),
+        YUY2 => Some(ImageFormat::Yuy2),
+        UYVY => Some(ImageFormat::Uyvy),
+        _ => None,
+    }
+}
+
+fn legacy_pixel_format(format: ImageFormat) -> Option<DdsPixelFormat> {
+    let rgb = |rgb_bit_count, r_bit_mask, g_bit_mask, b_bit_mask| DdsPixelFormat {
+        flags: DDPF_RGB,
+        fourcc: 0,
+        rgb_bit_count,
+        r_bit_mask,
+        g_bit_mask,
+        b_bit_mask,
+        a_bit_mask: 0,
+    };
+    match format {
+        ImageFormat::Rgb8Unorm => Some(rgb(24, 0xff, 0xff00, 0xff0000)),
+        ImageFormat::Bgr8Unorm => Some(rgb(24, 0xff0000, 0xff00, 0xff)),
+        ImageFormat::Bgr5x1Unorm => Some(rgb(16, 0x7c00, 0x3e0, 0x1f)),
+        ImageFormat::La8Unorm => Some(DdsPixelFormat {
+            flags: DDPF_LUMINANCE | DDPF_ALPHAPIXELS,
+            fourcc: 0,
+            rgb_bit_count: 16,
+            r_bit_mask: 0xff,
+            g_bit_mask: 0,
+            b_bit_mask: 0,
+            a_bit_mask: 0xff00,
+        }),
+        // UYVY has no DXGI format and is only identified by the FOURCC.
+        ImageFormat::Uyvy => Some(DdsPixelFormat {
+            flags: DDPF_FOURCC,
+            fourcc: UYVY,
+            ..Default::default()
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_header(pixel_format: DdsPixelFormat, caps2: u32) -> DdsHeader {
+        DdsHeader {
+            flags: DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT,
+            height: 4,
+            width: 4,
+            pitch_or_linear_size: 0,
+            depth: 0,
+            mipmap_count: 0,
+            pixel_format,
+            caps: DDSCAPS_TEXTURE,
+            caps2,
+            caps3: 0,
+            caps4: 0,
+            dx10: None,
+        }
+    }
+
+    #[test]
+    fn write_read_dds_bc7_mipmaps() {
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 2,
+            mipmaps: 4,
+            image_format: ImageFormat::BC7RgbaUnormSrgb,
+            alpha_mode: AlphaMode::Straight,
+            data: (0..(4 + 1 + 1 + 1) * 16 * 2)
+                .map(|i| i as u8)
+                .collect::<Vec<_>>(),
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+        assert_eq!(4 + 124 + 20 + surface.data.len(), bytes.len());
+
+        let new_surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(surface.width, new_surface.width);
+        assert_eq!(surface.height, new_surface.height);
+        assert_eq!(surface.depth, new_surface.depth);
+        assert_eq!(surface.layers, new_surface.layers);
+        assert_eq!(surface.mipmaps, new_surface.mipmaps);
+        assert_eq!(surface.image_format, new_surface.image_format);
+        assert_eq!(&surface.data, new_surface.data);
+
+        let new_surface = Surface::read_dds(&mut bytes.as_slice()).unwrap();
+        assert_eq!(surface, new_surface);
+    }
+
+    #[test]
+    fn write_read_dds_volume() {
+        let surface = Surface {
+            width: 4,
+            height: 2,
+            depth: 3,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![1u8; (4 * 2 * 3 + 2) * 4],
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+
+        let header = DdsHeader::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(16, header.pitch_or_linear_size);
+        assert_ne!(0, header.caps2 & DDSCAPS2_VOLUME);
+        assert_eq!(
+            D3D10_RESOURCE_DIMENSION_TEXTURE3D,
+            header.dx10.unwrap().resource_dimension
+        );
+
+        assert_eq!(surface, Surface::read_dds(&mut bytes.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn write_read_dds_cubemap() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 12,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 8 * 12],
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds_cubemap(&mut bytes).unwrap();
+
+        let header = DdsHeader::read(&mut bytes.as_slice()).unwrap();
+        let dx10 = header.dx10.unwrap();
+        assert_eq!(D3D10_RESOURCE_MISC_TEXTURECUBE, dx10.misc_flag);
+        assert_eq!(2, dx10.array_size);
+        assert_eq!(DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALLFACES, header.caps2);
+
+        assert_eq!(12, Surface::from_dds_bytes(&bytes).unwrap().layers);
+    }
+
+    #[test]
+    fn write_dds_not_enough_data() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 15],
+        };
+        let mut bytes = Vec::new();
+        assert!(matches!(
+            surface.write_dds(&mut bytes),
+            Err(DdsError::Surface(SurfaceError::NotEnoughData { .. }))
+        ));
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn write_dds_cubemap_invalid_layers() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 5,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 8 * 5],
+        };
+        assert!(matches!(
+            surface.write_dds_cubemap(&mut Vec::new()),
+            Err(DdsError::Surface(SurfaceError::InvalidCubeMap {
+                layers: 5,
+                depth: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn read_dds_legacy_fourcc() {
+        let header = legacy_header(
+            DdsPixelFormat {
+                flags: DDPF_FOURCC,
+                fourcc: DXT5,
+                ..Default::default()
+            },
+            0,
+        );
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+        assert_eq!(4 + 124, bytes.len());
+        bytes.extend_from_slice(&[0u8; 16]);
+
+        let surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::BC3RgbaUnorm, surface.image_format);
+        assert_eq!(
+            (4, 4, 1, 1, 1),
+            (
+                surface.width,
+                surface.height,
+                surface.depth,
+                surface.layers,
+                surface.mipmaps
+            )
+        );
+    }
+
+    #[test]
+    fn read_dds_legacy_fourcc_premultiplied() {
+        for (fourcc, image_format) in [
+            (DXT2, ImageFormat::BC2RgbaUnorm),
+            (DXT4, ImageFormat::BC3RgbaUnorm),
+        ] {
+            let header = legacy_header(
+                DdsPixelFormat {
+                    flags: DDPF_FOURCC,
+                    fourcc,
+                    ..Default::default()
+                },
+                0,
+            );
+            let mut bytes = Vec::new();
+            header.write(&mut bytes).unwrap();
+            bytes.extend_from_slice(&[0u8; 16]);
+
+            let surface = Surface::from_dds_bytes(&bytes).unwrap();
+            assert_eq!(image_format, surface.image_format);
+            assert_eq!(AlphaMode::Premultiplied, surface.alpha_mode);
+        }
+    }
+
+    #[test]
+    fn write_read_dds_alpha_mode() {
+        for alpha_mode in [
+            AlphaMode::Unknown,
+            AlphaMode::Straight,
+            AlphaMode::Premultiplied,
+            AlphaMode::Opaque,
+            AlphaMode::Custom,
+        ] {
+            let surface = Surface {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format: ImageFormat::BC7RgbaUnorm,
+                alpha_mode,
+                data: vec![0u8; 16],
+            };
+
+            let mut bytes = Vec::new();
+            surface.write_dds(&mut bytes).unwrap();
+
+            let new_surface = Surface::from_dds_bytes(&bytes).unwrap();
+            assert_eq!(alpha_mode, new_surface.alpha_mode);
+        }
+    }
+
+    #[test]
+    fn read_dds_legacy_fourcc_d3d_format() {
+        let header = legacy_header(
+            DdsPixelFormat {
+                flags: DDPF_FOURCC,
+                fourcc: D3DFMT_G16R16F,
+                ..Default::default()
+            },
+            0,
+        );
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+        bytes.extend_from_slice(&[0u8; 4 * 4 * 4]);
+
+        let surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::Rg16Float, surface.image_format);
+    }
+
+    #[test]
+    fn read_dds_legacy_masks_rg16() {
+        let header = legacy_header(
+            DdsPixelFormat {
+                flags: DDPF_RGB,
+                fourcc: 0,
+                rgb_bit_count: 32,
+                r_bit_mask: 0xffff,
+                g_bit_mask: 0xffff0000,
+                b_bit_mask: 0,
+                a_bit_mask: 0,
+            },
+            0,
+        );
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+        bytes.extend_from_slice(&[0u8; 4 * 4 * 4]);
+
+        let surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::Rg16Unorm, surface.image_format);
+    }
+
+    #[test]
+    fn write_read_dds_dxgi_formats() {
+        for (image_format, dxgi_format, width, height, mipmaps) in [
+            (ImageFormat::R16Unorm, 56, 3, 2, 2),
+            (ImageFormat::R32Uint, 42, 3, 2, 2),
+            (ImageFormat::Depth24UnormStencil8, 45, 2, 2, 1),
+            (ImageFormat::Nv12, 103, 4, 2, 1),
+        ] {
+            let mut surface = Surface {
+                width,
+                height,
+                depth: 1,
+                layers: 1,
+                mipmaps,
+                image_format,
+                alpha_mode: AlphaMode::Straight,
+                data: Vec::new(),
+            };
+            surface.data = (0..surface_size(&surface).unwrap())
+                .map(|i| i as u8)
+                .collect();
+
+            let mut bytes = Vec::new();
+            surface.write_dds(&mut bytes).unwrap();
+
+            let header = DdsHeader::read(&mut bytes.as_slice()).unwrap();
+            assert_eq!(dxgi_format, header.dx10.unwrap().dxgi_format);
+
+            assert_eq!(surface, Surface::read_dds(&mut bytes.as_slice()).unwrap());
+        }
+    }
+
+    #[test]
+    fn write_read_dds_legacy_formats() {
+        // Formats without a DXGI format are written with the channel masks.
+        for image_format in [
+            ImageFormat::Rgb8Unorm,
+            ImageFormat::Bgr8Unorm,
+            ImageFormat::Bgr5x1Unorm,
+            ImageFormat::La8Unorm,
+        ] {
+            let surface = Surface {
+                width: 3,
+                height: 2,
+                depth: 1,
+                layers: 1,
+                mipmaps: 2,
+                image_format,
+                alpha_mode: AlphaMode::Straight,
+                data: (0..(3 * 2 + 1) * image_format.block_size_in_bytes())
+                    .map(|i| i as u8)
+                    .collect::<Vec<_>>(),
+            };
+
+            let mut bytes = Vec::new();
+            surface.write_dds(&mut bytes).unwrap();
+
+            let header = DdsHeader::read(&mut bytes.as_slice()).unwrap();
+            assert_eq!(None, header.dx10);
+
+            let new_surface = Surface::from_dds_bytes(&bytes).unwrap();
+            assert_eq!(image_format, new_surface.image_format);
+            assert_eq!(surface.data, new_surface.data);
+        }
+    }
+
+    #[test]
+    fn write_dds_legacy_format_array() {
+        // The legacy header does not support array layers.
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 1,
+            image_format: ImageFormat::Bgr8Unorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &[0u8; 4 * 4 * 3 * 2][..],
+        };
+
+        let mut bytes = Vec::new();
+        assert!(matches!(
+            surface.write_dds(&mut bytes),
+            Err(DdsError::UnsupportedImageFormat(ImageFormat::Bgr8Unorm))
+        ));
+    }
+
+    #[test]
+    fn write_dds_nv12_pitch() {
+        // The pitch is the width of the 4x2 Y plane in bytes.
+        let surface = Surface {
+            width: 4,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Nv12,
+            alpha_mode: AlphaMode::Straight,
+            data: &[0u8; 12][..],
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+
+        let header = DdsHeader::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(DDSD_PITCH, header.flags & DDSD_PITCH);
+        assert_eq!(4, header.pitch_or_linear_size);
+    }
+
+    #[test]
+    fn write_read_dds_uyvy() {
+        let surface = Surface {
+            width: 3,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Uyvy,
+            alpha_mode: AlphaMode::Straight,
+            data: (0..8).collect::<Vec<_>>(),
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+
+        let header = DdsHeader::read(&mut bytes.as_slice()).unwrap();
+        assert!(header.dx10.is_none());
+        assert_eq!(UYVY, header.pixel_format.fourcc);
+        assert_eq!(8, header.pitch_or_linear_size);
+
+        let new_surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::Uyvy, new_surface.image_format);
+        assert_eq!(surface.data, new_surface.data);
+    }
+
+    #[test]
+    fn read_dds_typeless_depth32_stencil8() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Depth32FloatStencil8,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 8],
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+
+        // Replace DXGI_FORMAT_D32_FLOAT_S8X24_UINT with DXGI_FORMAT_R32G8X24_TYPELESS.
+        assert_eq!(20, bytes[128]);
+        bytes[128] = 19;
+
+        let new_surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::Depth32FloatStencil8, new_surface.image_format);
+    }
+
+    #[test]
+    fn read_dds_typeless_bc7() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 16],
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+
+        // Replace DXGI_FORMAT_BC7_UNORM with DXGI_FORMAT_BC7_TYPELESS.
+        assert_eq!(98, bytes[128]);
+        bytes[128] = 97;
+
+        let new_surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::BC7RgbaUnorm, new_surface.image_format);
+
+        let new_surface =
+            Surface::from_dds_bytes_with_typeless(&bytes, TypelessInterpretation::UnormSrgb)
+                .unwrap();
+        assert_eq!(ImageFormat::BC7RgbaUnormSrgb, new_surface.image_format);
+        assert_eq!(surface.data, new_surface.data);
+    }
+
+    #[test]
+    fn read_dds_typeless_rgba8_fallback() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Sint,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![1, 2, 3, 4],
+        };
+
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+
+        // Replace DXGI_FORMAT_R8G8B8A8_SINT with DXGI_FORMAT_R8G8B8A8_TYPELESS.
+        assert_eq!(32, bytes[128]);
+        bytes[128] = 27;
+
+        let new_surface =
+            Surface::read_dds_with_typeless(&mut bytes.as_slice(), TypelessInterpretation::Sint)
+                .unwrap();
+        assert_eq!(ImageFormat::Rgba8Sint, new_surface.image_format);
+
+        // RGBA8 has no snorm format in ImageFormat, so this uses the default.
+        let new_surface =
+            Surface::from_dds_bytes_with_typeless(&bytes, TypelessInterpretation::Snorm).unwrap();
+        assert_eq!(ImageFormat::Rgba8Unorm, new_surface.image_format);
+    }
+
+    #[test]
+    fn read_dds_legacy_masks_cubemap() {
+        let header = legacy_header(
+            DdsPixelFormat {
+                flags: DDPF_RGB | DDPF_ALPHAPIXELS,
+                fourcc: 0,
+                rgb_bit_count: 32,
+                r_bit_mask: 0xff0000,
+                g_bit_mask: 0xff00,
+                b_bit_mask: 0xff,
+                a_bit_mask: 0xff000000,
+            },
+            DDSCAPS2_CUBEMAP | DDSCAPS2_CUBEMAP_ALLFACES,
+        );
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+        bytes.extend_from_slice(&[0u8; 4 * 4 * 4 * 6]);
+
+        let surface = Surface::from_dds_bytes(&bytes).unwrap();
+        assert_eq!(ImageFormat::Bgra8Unorm, surface.image_format);
+        assert_eq!(6, surface.layers);
+    }
+
+    #[test]
+    fn read_dds_not_enough_data() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![0u8; 16],
+        };
+        let mut bytes = Vec::new();
+        surface.write_dds(&mut bytes).unwrap();
+        bytes.pop();
+
+        assert!(matches!(
+            Surface::from_dds_bytes(&bytes),
+            Err(DdsError::Surface(SurfaceError::NotEnoughData {
+                expected: 16,
+                actual: 15
+            }))
+        ));
+        assert!(matches!(
+            Surface::read_dds(&mut bytes.as_slice()),
+            Err(DdsError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn read_dds_invalid_magic() {
+        assert!(matches!(
+            Surface::from_dds_bytes(&[0u8; 4 + 124]),
+            Err(DdsError::InvalidMagic(0))
+        ));
+    }
+
+    #[test]
+    fn read_dds_unsupported_format() {
+        let header = legacy_header(
+            DdsPixelFormat {
+                flags: DDPF_FOURCC,
+                fourcc: u32::from_le_bytes(*b"ETC1"),
+                ..Default::default()
+            },
+            0,
+        );
+        let mut bytes = Vec::new();
+        header.write(&mut bytes).unwrap();
+
+        assert!(matches!(
+            Surface::from_dds_bytes(&bytes),
+            Err(DdsError::UnsupportedFormat {
+                dxgi_format: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn write_dds_unsupported_image_format() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Etc2RgbUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &[0u8; 8][..],
+        };
+
+        let mut bytes = Vec::new();
+        assert!(matches!(
+            surface.write_dds(&mut bytes),
+            Err(DdsError::UnsupportedImageFormat(ImageFormat::Etc2RgbUnorm))
+        ));
+    }
+}
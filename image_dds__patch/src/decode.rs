@@ -1,24 +1,43 @@
 use std::ops::Range;
 
 use crate::{
+    astc::{rgba8_from_astc, rgbaf32_from_astc},
     bcn::{self, rgba_from_bcn},
     error::SurfaceError,
-    mip_dimension,
+    etc, mip_dimension,
     rgba::{
-        rgba8_from_bgra4, rgba8_from_bgra8, rgba8_from_r8, rgba8_from_rgba8, rgba8_from_rgbaf16,
-        rgba8_from_rgbaf32, rgbaf32_from_rgbaf16, rgbaf32_from_rgbaf32,
+        rgba8_from_a8, rgba8_from_b5g6r5, rgba8_from_bgr5a1, rgba8_from_bgr5x1, rgba8_from_bgr8,
+        rgba8_from_bgra4, rgba8_from_bgra8, rgba8_from_bgrx8, rgba8_from_channels,
+        rgba8_from_d24s8, rgba8_from_d32fs8, rgba8_from_la8, rgba8_from_r8, rgba8_from_rgb10a2,
+        rgba8_from_rgb8, rgba8_from_rgba8, rgba8_from_rgbaf16, rgba8_from_rgbaf32,
+        rgbaf32_from_channels, rgbaf32_from_d24s8, rgbaf32_from_d32fs8, rgbaf32_from_rg11b10f,
+        rgbaf32_from_rgb10a2, rgbaf32_from_rgb9e5, rgbaf32_from_rgbaf16, rgbaf32_from_rgbaf32,
+        rgbau32_from_channels,
     },
-    ImageFormat, Surface, SurfaceRgba32Float, SurfaceRgba8,
+    yuv::{rgba8_from_yuv, rgbaf32_from_yuv},
+    ImageFormat, Pixel, Surface, SurfaceRgba32Float, SurfaceRgba32Uint, SurfaceRgba8, YuvMatrix,
 };
-use bcn::{Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use bcn::{Bc1, Bc2, Bc3, Bc4, Bc4Signed, Bc5, Bc5Signed, Bc6, Bc6Signed, Bc7};
+use half::f16;
+
+use etc::{EacR11, EacR11Signed, EacRg11, EacRg11Signed, Etc1, Etc2Rgb, Etc2Rgba1, Etc2Rgba8};
 
 impl<T: AsRef<[u8]>> Surface<T> {
     /// Decode all layers and mipmaps from `surface` to RGBA8.
+    ///
+    /// Signed normalized formats like [ImageFormat::BC5RgSnorm] are remapped
+    /// from the range `-1.0` to `1.0` to the range `0` to `255` with `0.0` mapping to `128`.
     pub fn decode_rgba8(&self) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
         self.decode_layers_mipmaps_rgba8(0..self.layers, 0..self.mipmaps)
     }
 
     /// Decode a specific range of layers and mipmaps from `surface` to RGBA8.
+    ///
+    /// Signed normalized formats like [ImageFormat::BC5RgSnorm] are remapped
+    /// from the range `-1.0` to `1.0` to the range `0` to `255` with `0.0` mapping to `128`.
     pub fn decode_layers_mipmaps_rgba8(
         &self,
         layers: Range<u32>,
@@ -26,7 +45,7 @@ impl<T: AsRef<[u8]>> Surface<T> {
     ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
         self.validate()?;
 
-        let data = decode_surface(self, layers.clone(), mipmaps.clone())?;
+        let data = decode_surface(self, layers.clone(), mipmaps.clone(), YuvMatrix::default())?;
 
         Ok(SurfaceRgba8 {
             width: mip_dimension(self.width, mipmaps.start),
@@ -34,6 +53,7 @@ impl<T: AsRef<[u8]>> Surface<T> {
             depth: mip_dimension(self.depth, mipmaps.start),
             layers: (layers.end - layers.start).max(1),
             mipmaps: (mipmaps.end - mipmaps.start).max(1),
+            alpha_mode: self.alpha_mode,
             data,
         })
     }
@@ -41,6 +61,7 @@ impl<T: AsRef<[u8]>> Surface<T> {
     /// Decode all layers and mipmaps from `surface` to RGBAF32.
     ///
     /// Non floating point formats are normalized to the range `0.0` to `1.0`.
+    /// Signed normalized formats like [ImageFormat::BC5RgSnorm] use the range `-1.0` to `1.0`.
     pub fn decode_rgbaf32(&self) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
         self.decode_layers_mipmaps_rgbaf32(0..self.layers, 0..self.mipmaps)
     }
@@ -48,6 +69,7 @@ impl<T: AsRef<[u8]>> Surface<T> {
     /// Decode a specific range of layers and mipmaps from `surface` to RGBAF32.
     ///
     /// Non floating point formats are normalized to the range `0.0` to `1.0`.
+    /// Signed normalized formats like [ImageFormat::BC5RgSnorm] use the range `-1.0` to `1.0`.
     pub fn decode_layers_mipmaps_rgbaf32(
         &self,
         layers: Range<u32>,
@@ -55,7 +77,7 @@ impl<T: AsRef<[u8]>> Surface<T> {
     ) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
         self.validate()?;
 
-        let data = decode_surface(self, layers.clone(), mipmaps.clone())?;
+        let data = decode_surface(self, layers.clone(), mipmaps.clone(), YuvMatrix::default())?;
 
         Ok(SurfaceRgba32Float {
             width: mip_dimension(self.width, mipmaps.start),
@@ -63,24 +85,155 @@ impl<T: AsRef<[u8]>> Surface<T> {
             depth: mip_dimension(self.depth, mipmaps.start),
             layers: (layers.end - layers.start).max(1),
             mipmaps: (mipmaps.end - mipmaps.start).max(1),
+            alpha_mode: self.alpha_mode,
+            data,
+        })
+    }
+
+    /// Decode all layers and mipmaps from `surface` to RGBA u32.
+    ///
+    /// Only integer formats like [ImageFormat::R8Uint] are supported.
+    /// Values are not normalized, and signed values are stored as two's complement.
+    pub fn decode_rgbau32(&self) -> Result<SurfaceRgba32Uint<Vec<u32>>, SurfaceError> {
+        self.decode_layers_mipmaps_rgbau32(0..self.layers, 0..self.mipmaps)
+    }
+
+    /// Decode a specific range of layers and mipmaps from `surface` to RGBA u32.
+    ///
+    /// Only integer formats like [ImageFormat::R8Uint] are supported.
+    /// Values are not normalized, and signed values are stored as two's complement.
+    pub fn decode_layers_mipmaps_rgbau32(
+        &self,
+        layers: Range<u32>,
+        mipmaps: Range<u32>,
+    ) -> Result<SurfaceRgba32Uint<Vec<u32>>, SurfaceError> {
+        self.validate()?;
+
+        let data = decode_surface(self, layers.clone(), mipmaps.clone(), YuvMatrix::default())?;
+
+        Ok(SurfaceRgba32Uint {
+            width: mip_dimension(self.width, mipmaps.start),
+            height: mip_dimension(self.height, mipmaps.start),
+            depth: mip_dimension(self.depth, mipmaps.start),
+            layers: (layers.end - layers.start).max(1),
+            mipmaps: (mipmaps.end - mipmaps.start).max(1),
+            alpha_mode: self.alpha_mode,
             data,
         })
     }
+
+    /// Decode all layers and mipmaps from `surface` to RGBA8 using `matrix` for YUV formats.
+    ///
+    /// YUV formats like [ImageFormat::Nv12] use [YuvMatrix::Bt601] for other decoding methods.
+    /// Other formats are decoded like [Surface::decode_rgba8].
+    pub fn decode_yuv_rgba8(
+        &self,
+        matrix: YuvMatrix,
+    ) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        self.validate()?;
+
+        let data = decode_surface(self, 0..self.layers, 0..self.mipmaps, matrix)?;
+
+        Ok(SurfaceRgba8 {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            alpha_mode: self.alpha_mode,
+            data,
+        })
+    }
+
+    /// Decode all layers and mipmaps from `surface` to RGBAF32 using `matrix` for YUV formats.
+    ///
+    /// YUV formats like [ImageFormat::Nv12] use [YuvMatrix::Bt601] for other decoding methods.
+    /// Other formats are decoded like [Surface::decode_rgbaf32].
+    pub fn decode_yuv_rgbaf32(
+        &self,
+        matrix: YuvMatrix,
+    ) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        self.validate()?;
+
+        let data = decode_surface(self, 0..self.layers, 0..self.mipmaps, matrix)?;
+
+        Ok(SurfaceRgba32Float {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            alpha_mode: self.alpha_mode,
+            data,
+        })
+    }
+
+    /// Decode all layers and mipmaps from a tangent space normal map to RGBA8.
+    ///
+    /// For [ImageFormat::BC5RgUnorm] and [ImageFormat::BC5RgSnorm],
+    /// the Z component is reconstructed from X and Y and stored in the blue channel.
+    /// Other formats are decoded like [Surface::decode_rgba8].
+    pub fn decode_normal_map_rgba8(&self) -> Result<SurfaceRgba8<Vec<u8>>, SurfaceError> {
+        let mut surface = self.decode_rgba8()?;
+        if self.is_bc5() {
+            reconstruct_normal_z(
+                &mut surface.data,
+                self.image_format.is_snorm() && u8::SIGNED,
+            );
+        }
+        Ok(surface)
+    }
+
+    /// Decode all layers and mipmaps from a tangent space normal map to RGBAF32.
+    ///
+    /// For [ImageFormat::BC5RgUnorm] and [ImageFormat::BC5RgSnorm],
+    /// the Z component is reconstructed from X and Y and stored in the blue channel.
+    /// Other formats are decoded like [Surface::decode_rgbaf32].
+    pub fn decode_normal_map_rgbaf32(&self) -> Result<SurfaceRgba32Float<Vec<f32>>, SurfaceError> {
+        let mut surface = self.decode_rgbaf32()?;
+        if self.is_bc5() {
+            reconstruct_normal_z(
+                &mut surface.data,
+                self.image_format.is_snorm() && f32::SIGNED,
+            );
+        }
+        Ok(surface)
+    }
+
+    fn is_bc5(&self) -> bool {
+        matches!(
+            self.image_format,
+            ImageFormat::BC5RgUnorm | ImageFormat::BC5RgSnorm
+        )
+    }
+}
+
+fn reconstruct_normal_z<P: Pixel>(data: &mut [P], signed: bool) {
+    for pixel in data.chunks_exact_mut(4) {
+        // Assume the vector has unit length and points away from the surface.
+        let x = pixel[0].to_snorm(signed);
+        let y = pixel[1].to_snorm(signed);
+        let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        pixel[2] = P::from_snorm(z, signed);
+    }
 }
 
 fn decode_surface<T, P>(
     surface: &Surface<T>,
     layers: Range<u32>,
     mipmaps: Range<u32>,
+    matrix: YuvMatrix,
 ) -> Result<Vec<P>, SurfaceError>
 where
     T: AsRef<[u8]>,
-    P: Decode + Copy,
+    P: Decode + Copy + Send,
 {
-    let mut combined_surface_data = Vec::new();
+    // Collect the data for each mipmap up front so the surface isn't shared between threads.
+    let mut units = Vec::new();
+    // Match the layout of the surface with all depth slices for a mipmap before the next mipmap.
     for layer in layers {
-        for level in 0..surface.depth {
-            for mipmap in mipmaps.clone() {
+        for mipmap in mipmaps.clone() {
+            for level in 0..mip_dimension(surface.depth, mipmap) {
                 let data = surface
                     .get(layer, level, mipmap)
                     .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
@@ -88,16 +241,22 @@ where
                 // The mipmap index is already validated by get above.
                 let width = mip_dimension(surface.width, mipmap);
                 let height = mip_dimension(surface.height, mipmap);
-
-                // TODO: Avoid additional copies?
-                let data = P::decode(width, height, surface.image_format, data)?;
-
-                combined_surface_data.extend_from_slice(&data);
+                units.push((width, height, data));
             }
         }
     }
 
-    Ok(combined_surface_data)
+    #[cfg(feature = "rayon")]
+    let units = units.into_par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let units = units.into_iter();
+
+    // TODO: Avoid additional copies?
+    let image_format = surface.image_format;
+    Ok(units
+        .map(|(width, height, data)| P::decode(width, height, image_format, data, matrix))
+        .collect::<Result<Vec<_>, _>>()?
+        .concat())
 }
 
 // Decoding only works on 2D surfaces.
@@ -107,6 +266,7 @@ trait Decode: Sized {
         height: u32,
         image_format: ImageFormat,
         data: &[u8],
+        matrix: YuvMatrix,
     ) -> Result<Vec<Self>, SurfaceError>;
 }
 
@@ -116,22 +276,128 @@ impl Decode for u8 {
         height: u32,
         image_format: ImageFormat,
         data: &[u8],
+        matrix: YuvMatrix,
     ) -> Result<Vec<Self>, SurfaceError> {
         use ImageFormat as F;
         match image_format {
             F::BC1RgbaUnorm | F::BC1RgbaUnormSrgb => rgba_from_bcn::<Bc1, u8>(width, height, data),
             F::BC2RgbaUnorm | F::BC2RgbaUnormSrgb => rgba_from_bcn::<Bc2, u8>(width, height, data),
             F::BC3RgbaUnorm | F::BC3RgbaUnormSrgb => rgba_from_bcn::<Bc3, u8>(width, height, data),
-            F::BC4RUnorm | F::BC4RSnorm => rgba_from_bcn::<Bc4, u8>(width, height, data),
-            F::BC5RgUnorm | F::BC5RgSnorm => rgba_from_bcn::<Bc5, u8>(width, height, data),
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => rgba_from_bcn::<Bc6, u8>(width, height, data),
+            F::BC4RUnorm => rgba_from_bcn::<Bc4, u8>(width, height, data),
+            F::BC4RSnorm => rgba_from_bcn::<Bc4Signed, u8>(width, height, data),
+            F::BC5RgUnorm => rgba_from_bcn::<Bc5, u8>(width, height, data),
+            F::BC5RgSnorm => rgba_from_bcn::<Bc5Signed, u8>(width, height, data),
+            F::BC6hRgbUfloat => rgba_from_bcn::<Bc6, u8>(width, height, data),
+            F::BC6hRgbSfloat => rgba_from_bcn::<Bc6Signed, u8>(width, height, data),
             F::BC7RgbaUnorm | F::BC7RgbaUnormSrgb => rgba_from_bcn::<Bc7, u8>(width, height, data),
+            F::Etc1RgbUnorm => rgba_from_bcn::<Etc1, u8>(width, height, data),
+            F::Etc2RgbUnorm | F::Etc2RgbUnormSrgb => {
+                rgba_from_bcn::<Etc2Rgb, u8>(width, height, data)
+            }
+            F::Etc2Rgba1Unorm | F::Etc2Rgba1UnormSrgb => {
+                rgba_from_bcn::<Etc2Rgba1, u8>(width, height, data)
+            }
+            F::Etc2Rgba8Unorm | F::Etc2Rgba8UnormSrgb => {
+                rgba_from_bcn::<Etc2Rgba8, u8>(width, height, data)
+            }
+            F::EacR11Unorm => rgba_from_bcn::<EacR11, u8>(width, height, data),
+            F::EacR11Snorm => rgba_from_bcn::<EacR11Signed, u8>(width, height, data),
+            F::EacRg11Unorm => rgba_from_bcn::<EacRg11, u8>(width, height, data),
+            F::EacRg11Snorm => rgba_from_bcn::<EacRg11Signed, u8>(width, height, data),
+            F::Astc4x4RgbaUnorm
+            | F::Astc4x4RgbaUnormSrgb
+            | F::Astc4x4RgbaFloat
+            | F::Astc5x4RgbaUnorm
+            | F::Astc5x4RgbaUnormSrgb
+            | F::Astc5x4RgbaFloat
+            | F::Astc5x5RgbaUnorm
+            | F::Astc5x5RgbaUnormSrgb
+            | F::Astc5x5RgbaFloat
+            | F::Astc6x5RgbaUnorm
+            | F::Astc6x5RgbaUnormSrgb
+            | F::Astc6x5RgbaFloat
+            | F::Astc6x6RgbaUnorm
+            | F::Astc6x6RgbaUnormSrgb
+            | F::Astc6x6RgbaFloat
+            | F::Astc8x5RgbaUnorm
+            | F::Astc8x5RgbaUnormSrgb
+            | F::Astc8x5RgbaFloat
+            | F::Astc8x6RgbaUnorm
+            | F::Astc8x6RgbaUnormSrgb
+            | F::Astc8x6RgbaFloat
+            | F::Astc8x8RgbaUnorm
+            | F::Astc8x8RgbaUnormSrgb
+            | F::Astc8x8RgbaFloat
+            | F::Astc10x5RgbaUnorm
+            | F::Astc10x5RgbaUnormSrgb
+            | F::Astc10x5RgbaFloat
+            | F::Astc10x6RgbaUnorm
+            | F::Astc10x6RgbaUnormSrgb
+            | F::Astc10x6RgbaFloat
+            | F::Astc10x8RgbaUnorm
+            | F::Astc10x8RgbaUnormSrgb
+            | F::Astc10x8RgbaFloat
+            | F::Astc10x10RgbaUnorm
+            | F::Astc10x10RgbaUnormSrgb
+            | F::Astc10x10RgbaFloat
+            | F::Astc12x10RgbaUnorm
+            | F::Astc12x10RgbaUnormSrgb
+            | F::Astc12x10RgbaFloat
+            | F::Astc12x12RgbaUnorm
+            | F::Astc12x12RgbaUnormSrgb
+            | F::Astc12x12RgbaFloat => rgba8_from_astc(width, height, image_format, data),
             F::R8Unorm => rgba8_from_r8(width, height, data),
+            F::Rg8Unorm => rgba8_from_channels::<u8, 2>(width, height, data),
+            F::Rgb8Unorm => rgba8_from_rgb8(width, height, data),
             F::Rgba8Unorm | F::Rgba8UnormSrgb => rgba8_from_rgba8(width, height, data),
+            F::R16Unorm => rgba8_from_channels::<u16, 1>(width, height, data),
+            F::Rg16Unorm => rgba8_from_channels::<u16, 2>(width, height, data),
+            F::Rg16Snorm => rgba8_from_channels::<i16, 2>(width, height, data),
+            F::Rgba16Unorm => rgba8_from_channels::<u16, 4>(width, height, data),
+            F::Rgba16Snorm => rgba8_from_channels::<i16, 4>(width, height, data),
+            F::R16Float => rgba8_from_channels::<f16, 1>(width, height, data),
+            F::Rg16Float => rgba8_from_channels::<f16, 2>(width, height, data),
             F::Rgba16Float => rgba8_from_rgbaf16(width, height, data),
+            F::R32Float => rgba8_from_channels::<f32, 1>(width, height, data),
+            F::Rg32Float => rgba8_from_channels::<f32, 2>(width, height, data),
             F::Rgba32Float => rgba8_from_rgbaf32(width, height, data),
+            F::Rgb10a2Unorm => rgba8_from_rgb10a2(width, height, data),
+            F::Rg11b10Ufloat | F::Rgb9e5Ufloat => {
+                // Truncate to clamp to 0 to 255 like other floating point formats.
+                let rgba = f32::decode(width, height, image_format, data, matrix)?;
+                Ok(rgba.into_iter().map(|f| (f * 255.0) as u8).collect())
+            }
             F::Bgra8Unorm | F::Bgra8UnormSrgb => rgba8_from_bgra8(width, height, data),
             F::Bgra4Unorm => rgba8_from_bgra4(width, height, data),
+            F::B5g6r5Unorm => rgba8_from_b5g6r5(width, height, data),
+            F::Bgr5a1Unorm => rgba8_from_bgr5a1(width, height, data),
+            F::Bgr5x1Unorm => rgba8_from_bgr5x1(width, height, data),
+            F::Bgr8Unorm => rgba8_from_bgr8(width, height, data),
+            F::Bgrx8Unorm => rgba8_from_bgrx8(width, height, data),
+            F::La8Unorm => rgba8_from_la8(width, height, data),
+            F::A8Unorm => rgba8_from_a8(width, height, data),
+            F::Depth16Unorm => rgba8_from_channels::<u16, 1>(width, height, data),
+            F::Depth24UnormStencil8 => rgba8_from_d24s8(width, height, data),
+            F::Depth32Float => rgba8_from_channels::<f32, 1>(width, height, data),
+            F::Depth32FloatStencil8 => rgba8_from_d32fs8(width, height, data),
+            F::Yuy2 | F::Uyvy | F::Nv12 | F::P010 => {
+                rgba8_from_yuv(width, height, image_format, data, matrix)
+            }
+            // Normalizing integer data would corrupt values like IDs.
+            F::R8Uint
+            | F::R8Sint
+            | F::R16Uint
+            | F::R16Sint
+            | F::R32Uint
+            | F::R32Sint
+            | F::Rgba8Uint
+            | F::Rgba8Sint
+            | F::Rgba16Uint
+            | F::Rgba16Sint
+            | F::Rgba32Uint
+            | F::Rgba32Sint => Err(SurfaceError::UnsupportedDecodeFormat {
+                format: image_format,
+            }),
         }
     }
 }
@@ -142,24 +408,99 @@ impl Decode for f32 {
         height: u32,
         image_format: ImageFormat,
         data: &[u8],
+        matrix: YuvMatrix,
     ) -> Result<Vec<Self>, SurfaceError> {
         use ImageFormat as F;
         match image_format {
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => rgba_from_bcn::<Bc6, f32>(width, height, data),
+            F::BC4RSnorm => rgba_from_bcn::<Bc4Signed, f32>(width, height, data),
+            F::BC5RgSnorm => rgba_from_bcn::<Bc5Signed, f32>(width, height, data),
+            F::BC6hRgbUfloat => rgba_from_bcn::<Bc6, f32>(width, height, data),
+            F::BC6hRgbSfloat => rgba_from_bcn::<Bc6Signed, f32>(width, height, data),
+            // EAC stores 11-bit values with more precision than RGBA8.
+            F::EacR11Unorm => rgba_from_bcn::<EacR11, f32>(width, height, data),
+            F::EacR11Snorm => rgba_from_bcn::<EacR11Signed, f32>(width, height, data),
+            F::EacRg11Unorm => rgba_from_bcn::<EacRg11, f32>(width, height, data),
+            F::EacRg11Snorm => rgba_from_bcn::<EacRg11Signed, f32>(width, height, data),
+            F::Astc4x4RgbaFloat
+            | F::Astc5x4RgbaFloat
+            | F::Astc5x5RgbaFloat
+            | F::Astc6x5RgbaFloat
+            | F::Astc6x6RgbaFloat
+            | F::Astc8x5RgbaFloat
+            | F::Astc8x6RgbaFloat
+            | F::Astc8x8RgbaFloat
+            | F::Astc10x5RgbaFloat
+            | F::Astc10x6RgbaFloat
+            | F::Astc10x8RgbaFloat
+            | F::Astc10x10RgbaFloat
+            | F::Astc12x10RgbaFloat
+            | F::Astc12x12RgbaFloat => rgbaf32_from_astc(width, height, image_format, data),
+            // 16-bit normalized formats have more precision than RGBA8.
+            F::R16Unorm => rgbaf32_from_channels::<u16, 1>(width, height, data),
+            F::Rg16Unorm => rgbaf32_from_channels::<u16, 2>(width, height, data),
+            F::Rg16Snorm => rgbaf32_from_channels::<i16, 2>(width, height, data),
+            F::Rgba16Unorm => rgbaf32_from_channels::<u16, 4>(width, height, data),
+            F::Rgba16Snorm => rgbaf32_from_channels::<i16, 4>(width, height, data),
+            F::R16Float => rgbaf32_from_channels::<f16, 1>(width, height, data),
+            F::Rg16Float => rgbaf32_from_channels::<f16, 2>(width, height, data),
             F::Rgba16Float => rgbaf32_from_rgbaf16(width, height, data),
+            F::R32Float => rgbaf32_from_channels::<f32, 1>(width, height, data),
+            F::Rg32Float => rgbaf32_from_channels::<f32, 2>(width, height, data),
             F::Rgba32Float => rgbaf32_from_rgbaf32(width, height, data),
+            F::Rgb10a2Unorm => rgbaf32_from_rgb10a2(width, height, data),
+            F::Rg11b10Ufloat => rgbaf32_from_rg11b10f(width, height, data),
+            F::Rgb9e5Ufloat => rgbaf32_from_rgb9e5(width, height, data),
+            // Depth buffers need more precision than RGBA8 for visualization.
+            F::Depth16Unorm => rgbaf32_from_channels::<u16, 1>(width, height, data),
+            F::Depth24UnormStencil8 => rgbaf32_from_d24s8(width, height, data),
+            F::Depth32Float => rgbaf32_from_channels::<f32, 1>(width, height, data),
+            F::Depth32FloatStencil8 => rgbaf32_from_d32fs8(width, height, data),
+            // P010 stores 10-bit samples with more precision than RGBA8.
+            F::Yuy2 | F::Uyvy | F::Nv12 | F::P010 => {
+                rgbaf32_from_yuv(width, height, image_format, data, matrix)
+            }
             _ => {
                 // Use existing decoding for formats that don't store floating point data.
-                let rgba8 = u8::decode(width, height, image_format, data)?;
+                let rgba8 = u8::decode(width, height, image_format, data, matrix)?;
                 Ok(rgba8.into_iter().map(|u| u as f32 / 255.0).collect())
             }
         }
     }
 }
 
+impl Decode for u32 {
+    fn decode(
+        width: u32,
+        height: u32,
+        image_format: ImageFormat,
+        data: &[u8],
+        _matrix: YuvMatrix,
+    ) -> Result<Vec<Self>, SurfaceError> {
+        use ImageFormat as F;
+        match image_format {
+            F::R8Uint => rgbau32_from_channels::<u8, 1>(width, height, data),
+            F::R8Sint => rgbau32_from_channels::<i8, 1>(width, height, data),
+            F::R16Uint => rgbau32_from_channels::<u16, 1>(width, height, data),
+            F::R16Sint => rgbau32_from_channels::<i16, 1>(width, height, data),
+            F::R32Uint => rgbau32_from_channels::<u32, 1>(width, height, data),
+            F::R32Sint => rgbau32_from_channels::<i32, 1>(width, height, data),
+            F::Rgba8Uint => rgbau32_from_channels::<u8, 4>(width, height, data),
+            F::Rgba8Sint => rgbau32_from_channels::<i8, 4>(width, height, data),
+            F::Rgba16Uint => rgbau32_from_channels::<u16, 4>(width, height, data),
+            F::Rgba16Sint => rgbau32_from_channels::<i16, 4>(width, height, data),
+            F::Rgba32Uint => rgbau32_from_channels::<u32, 4>(width, height, data),
+            F::Rgba32Sint => rgbau32_from_channels::<i32, 4>(width, height, data),
+            _ => Err(SurfaceError::UnsupportedDecodeFormat {
+                format: image_format,
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::AlphaMode;
 
     #[test]
     fn decode_surface_zero_size() {
@@ -170,6 +511,7 @@ mod tests {
             layers: 1,
             mipmaps: 1,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 0],
         }
         .decode_rgba8();
@@ -193,6 +535,7 @@ mod tests {
             layers: 1,
             mipmaps: 1,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 0],
         }
         .decode_rgba8();
@@ -216,6 +559,7 @@ mod tests {
             layers: 1,
             mipmaps: 10,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 4 * 4 * 4],
         }
         .decode_rgba8();
@@ -238,6 +582,7 @@ mod tests {
             layers: 1,
             mipmaps: 3,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 512],
         }
         .decode_layers_mipmaps_rgba8(0..1, 1..2)
@@ -250,6 +595,7 @@ mod tests {
                 depth: 1,
                 layers: 1,
                 mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
                 data: vec![0u8; 2 * 2 * 4]
             },
             rgba8
@@ -266,6 +612,7 @@ mod tests {
             layers: 1,
             mipmaps: 1,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 4 * 4 * 4],
         }
         .decode_layers_mipmaps_rgba8(0..1, 0..0)
@@ -278,6 +625,7 @@ mod tests {
                 depth: 1,
                 layers: 1,
                 mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
                 data: Vec::new()
             },
             rgba8
@@ -293,6 +641,7 @@ mod tests {
             layers: 1,
             mipmaps: 3,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 512],
         }
         .decode_layers_mipmaps_rgbaf32(0..1, 1..2)
@@ -305,6 +654,7 @@ mod tests {
                 depth: 1,
                 layers: 1,
                 mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
                 data: vec![0.0; 2 * 2 * 4]
             },
             rgbaf32
@@ -321,6 +671,7 @@ mod tests {
             layers: 1,
             mipmaps: 1,
             image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 4 * 4 * 4],
         }
         .decode_layers_mipmaps_rgbaf32(0..1, 0..0)
@@ -333,9 +684,185 @@ mod tests {
                 depth: 1,
                 layers: 1,
                 mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
                 data: Vec::new()
             },
             rgbaf32
         );
     }
+
+    #[test]
+    fn decode_bc5_snorm_rgbaf32_signed() {
+        // Red is -1.0 and green is 1.0 using the first endpoint for all pixels.
+        let mut block = [0u8; 16];
+        block[0] = 0x81;
+        block[8] = 0x7f;
+
+        let rgbaf32 = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC5RgSnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &block,
+        }
+        .decode_rgbaf32()
+        .unwrap();
+
+        assert_eq!([-1.0, 1.0, 0.0, 1.0].repeat(16), rgbaf32.data);
+    }
+
+    #[test]
+    fn decode_normal_map_bc5_unorm_rgba8() {
+        let mut block = [0u8; 16];
+        block[0] = 128;
+        block[8] = 128;
+
+        let rgba8 = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC5RgUnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &block,
+        }
+        .decode_normal_map_rgba8()
+        .unwrap();
+
+        assert_eq!([128, 128, 255, 255].repeat(16), rgba8.data);
+    }
+
+    #[test]
+    fn decode_normal_map_bc5_snorm_rgbaf32() {
+        // Red is -0.6 and green is 0.0 using the first endpoint for all pixels.
+        let mut block = [0u8; 16];
+        block[0] = (-0.6f32 * 127.0).round() as i8 as u8;
+
+        let rgbaf32 = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC5RgSnorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &block,
+        }
+        .decode_normal_map_rgbaf32()
+        .unwrap();
+
+        for pixel in rgbaf32.data.chunks_exact(4) {
+            assert!((pixel[0] + 0.6).abs() < 0.01);
+            assert_eq!(0.0, pixel[1]);
+            assert!((pixel[2] - 0.8).abs() < 0.01);
+            assert_eq!(1.0, pixel[3]);
+        }
+    }
+
+    #[test]
+    fn decode_surface_volume_mipmaps() {
+        // Each mipmap stores all of its depth slices before the next mipmap.
+        let data: Vec<u8> = (0..(4 * 2 * 2 + 2) * 4).map(|i| i as u8).collect();
+
+        let rgba8 = Surface {
+            width: 4,
+            height: 2,
+            depth: 2,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &data,
+        }
+        .decode_rgba8()
+        .unwrap();
+
+        assert_eq!(data, rgba8.data);
+    }
+
+    #[test]
+    fn decode_surface_integer() {
+        // Signed values are sign extended.
+        let rgbau32 = Surface {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::R16Sint,
+            alpha_mode: AlphaMode::Straight,
+            data: &[0x34, 0x12, 0xFE, 0xFF],
+        }
+        .decode_rgbau32()
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                0x1234,
+                0x1234,
+                0x1234,
+                1,
+                u32::MAX - 1,
+                u32::MAX - 1,
+                u32::MAX - 1,
+                1
+            ],
+            rgbau32.data
+        );
+    }
+
+    #[test]
+    fn decode_surface_integer_normalized() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Uint,
+            alpha_mode: AlphaMode::Straight,
+            data: &[1u8, 2, 3, 4],
+        };
+
+        // Integer data should not be normalized.
+        assert!(matches!(
+            surface.decode_rgba8(),
+            Err(SurfaceError::UnsupportedDecodeFormat {
+                format: ImageFormat::Rgba8Uint
+            })
+        ));
+        assert!(matches!(
+            surface.decode_rgbaf32(),
+            Err(SurfaceError::UnsupportedDecodeFormat {
+                format: ImageFormat::Rgba8Uint
+            })
+        ));
+        assert_eq!(vec![1, 2, 3, 4], surface.decode_rgbau32().unwrap().data);
+    }
+
+    #[test]
+    fn decode_surface_integer_unsupported() {
+        let result = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Straight,
+            data: &[1u8, 2, 3, 4],
+        }
+        .decode_rgbau32();
+
+        assert!(matches!(
+            result,
+            Err(SurfaceError::UnsupportedDecodeFormat {
+                format: ImageFormat::Rgba8Unorm
+            })
+        ));
+    }
 }
@@ -0,0 +1,549 @@
+// DXGI_FORMAT values and legacy pixel format masks shared by the ddsfile and built in DDS implementations.
+// https://learn.microsoft.com/en-us/windows/win32/api/dxgiformat/ne-dxgiformat-dxgi_format
+// https://learn.microsoft.com/en-us/windows/win32/direct3ddds/dds-pixelformat
+use crate::{ImageFormat, TypelessInterpretation};
+
+pub(crate) const DDPF_ALPHAPIXELS: u32 = 0x1;
+pub(crate) const DDPF_ALPHA: u32 = 0x2;
+pub(crate) const DDPF_RGB: u32 = 0x40;
+pub(crate) const DDPF_LUMINANCE: u32 = 0x20000;
+
+const DXGI_FORMAT_R32G32B32A32_TYPELESS: u32 = 1;
+const DXGI_FORMAT_R16G16B16A16_TYPELESS: u32 = 9;
+const DXGI_FORMAT_R32G32_TYPELESS: u32 = 15;
+const DXGI_FORMAT_R10G10B10A2_TYPELESS: u32 = 23;
+const DXGI_FORMAT_R8G8B8A8_TYPELESS: u32 = 27;
+const DXGI_FORMAT_R16G16_TYPELESS: u32 = 33;
+const DXGI_FORMAT_R32_TYPELESS: u32 = 39;
+const DXGI_FORMAT_R8G8_TYPELESS: u32 = 48;
+const DXGI_FORMAT_R16_TYPELESS: u32 = 53;
+const DXGI_FORMAT_R8_TYPELESS: u32 = 60;
+const DXGI_FORMAT_BC1_TYPELESS: u32 = 70;
+const DXGI_FORMAT_BC2_TYPELESS: u32 = 73;
+const DXGI_FORMAT_BC3_TYPELESS: u32 = 76;
+const DXGI_FORMAT_BC4_TYPELESS: u32 = 79;
+const DXGI_FORMAT_BC5_TYPELESS: u32 = 82;
+const DXGI_FORMAT_B8G8R8A8_TYPELESS: u32 = 90;
+const DXGI_FORMAT_B8G8R8X8_TYPELESS: u32 = 92;
+const DXGI_FORMAT_BC6H_TYPELESS: u32 = 94;
+const DXGI_FORMAT_BC7_TYPELESS: u32 = 97;
+
+pub(crate) fn image_format_from_dxgi(
+    format: u32,
+    typeless: TypelessInterpretation,
+) -> Option<ImageFormat> {
+    match format {
+        2 => Some(ImageFormat::Rgba32Float),
+        3 => Some(ImageFormat::Rgba32Uint),
+        4 => Some(ImageFormat::Rgba32Sint),
+        10 => Some(ImageFormat::Rgba16Float),
+        11 => Some(ImageFormat::Rgba16Unorm),
+        12 => Some(ImageFormat::Rgba16Uint),
+        13 => Some(ImageFormat::Rgba16Snorm),
+        14 => Some(ImageFormat::Rgba16Sint),
+        16 => Some(ImageFormat::Rg32Float),
+        // Depth buffers are often created as typeless to allow reading them in shaders.
+        19..=22 => Some(ImageFormat::Depth32FloatStencil8),
+        24 => Some(ImageFormat::Rgb10a2Unorm),
+        26 => Some(ImageFormat::Rg11b10Ufloat),
+        28 => Some(ImageFormat::Rgba8Unorm),
+        29 => Some(ImageFormat::Rgba8UnormSrgb),
+        30 => Some(ImageFormat::Rgba8Uint),
+        32 => Some(ImageFormat::Rgba8Sint),
+        34 => Some(ImageFormat::Rg16Float),
+        35 => Some(ImageFormat::Rg16Unorm),
+        37 => Some(ImageFormat::Rg16Snorm),
+        40 => Some(ImageFormat::Depth32Float),
+        41 => Some(ImageFormat::R32Float),
+        42 => Some(ImageFormat::R32Uint),
+        43 => Some(ImageFormat::R32Sint),
+        44..=47 => Some(ImageFormat::Depth24UnormStencil8),
+        49 => Some(ImageFormat::Rg8Unorm),
+        54 => Some(ImageFormat::R16Float),
+        55 => Some(ImageFormat::Depth16Unorm),
+        56 => Some(ImageFormat::R16Unorm),
+        57 => Some(ImageFormat::R16Uint),
+        59 => Some(ImageFormat::R16Sint),
+        61 => Some(ImageFormat::R8Unorm),
+        62 => Some(ImageFormat::R8Uint),
+        64 => Some(ImageFormat::R8Sint),
+        65 => Some(ImageFormat::A8Unorm),
+        67 => Some(ImageFormat::Rgb9e5Ufloat),
+        71 => Some(ImageFormat::BC1RgbaUnorm),
+        72 => Some(ImageFormat::BC1RgbaUnormSrgb),
+        74 => Some(ImageFormat::BC2RgbaUnorm),
+        75 => Some(ImageFormat::BC2RgbaUnormSrgb),
+        77 => Some(ImageFormat::BC3RgbaUnorm),
+        78 => Some(ImageFormat::BC3RgbaUnormSrgb),
+        80 => Some(ImageFormat::BC4RUnorm),
+        81 => Some(ImageFormat::BC4RSnorm),
+        83 => Some(ImageFormat::BC5RgUnorm),
+        84 => Some(ImageFormat::BC5RgSnorm),
+        85 => Some(ImageFormat::B5g6r5Unorm),
+        86 => Some(ImageFormat::Bgr5a1Unorm),
+        87 => Some(ImageFormat::Bgra8Unorm),
+        88 => Some(ImageFormat::Bgrx8Unorm),
+        91 => Some(ImageFormat::Bgra8UnormSrgb),
+        95 => Some(ImageFormat::BC6hRgbUfloat),
+        96 => Some(ImageFormat::BC6hRgbSfloat),
+        98 => Some(ImageFormat::BC7RgbaUnorm),
+        99 => Some(ImageFormat::BC7RgbaUnormSrgb),
+        103 => Some(ImageFormat::Nv12),
+        104 => Some(ImageFormat::P010),
+        107 => Some(ImageFormat::Yuy2),
+        115 => Some(ImageFormat::Bgra4Unorm),
+        _ => image_format_from_typeless(format, typeless),
+    }
+}
+
+fn image_format_from_typeless(
+    format: u32,
+    typeless: TypelessInterpretation,
+) -> Option<ImageFormat> {
+    use ImageFormat as F;
+    use TypelessInterpretation as T;
+
+    // The default format for each typeless format is listed first.
+    let formats: &[(T, F)] = match format {
+        DXGI_FORMAT_R32G32B32A32_TYPELESS => &[
+            (T::Float, F::Rgba32Float),
+            (T::Uint, F::Rgba32Uint),
+            (T::Sint, F::Rgba32Sint),
+        ],
+        DXGI_FORMAT_R16G16B16A16_TYPELESS => &[
+            (T::Unorm, F::Rgba16Unorm),
+            (T::Snorm, F::Rgba16Snorm),
+            (T::Float, F::Rgba16Float),
+            (T::Uint, F::Rgba16Uint),
+            (T::Sint, F::Rgba16Sint),
+        ],
+        DXGI_FORMAT_R32G32_TYPELESS => &[(T::Float, F::Rg32Float)],
+        DXGI_FORMAT_R10G10B10A2_TYPELESS => &[(T::Unorm, F::Rgb10a2Unorm)],
+        DXGI_FORMAT_R8G8B8A8_TYPELESS => &[
+            (T::Unorm, F::Rgba8Unorm),
+            (T::UnormSrgb, F::Rgba8UnormSrgb),
+            (T::Uint, F::Rgba8Uint),
+            (T::Sint, F::Rgba8Sint),
+        ],
+        DXGI_FORMAT_R16G16_TYPELESS => &[
+            (T::Unorm, F::Rg16Unorm),
+            (T::Snorm, F::Rg16Snorm),
+            (T::Float, F::Rg16Float),
+        ],
+        DXGI_FORMAT_R32_TYPELESS => &[
+            (T::Float, F::R32Float),
+            (T::Uint, F::R32Uint),
+            (T::Sint, F::R32Sint),
+        ],
+        DXGI_FORMAT_R8G8_TYPELESS => &[(T::Unorm, F::Rg8Unorm)],
+        DXGI_FORMAT_R16_TYPELESS => &[
+            (T::Unorm, F::R16Unorm),
+            (T::Float, F::R16Float),
+            (T::Uint, F::R16Uint),
+            (T::Sint, F::R16Sint),
+        ],
+        DXGI_FORMAT_R8_TYPELESS => &[
+            (T::Unorm, F::R8Unorm),
+            (T::Uint, F::R8Uint),
+            (T::Sint, F::R8Sint),
+        ],
+        DXGI_FORMAT_BC1_TYPELESS => &[
+            (T::Unorm, F::BC1RgbaUnorm),
+            (T::UnormSrgb, F::BC1RgbaUnormSrgb),
+        ],
+        DXGI_FORMAT_BC2_TYPELESS => &[
+            (T::Unorm, F::BC2RgbaUnorm),
+            (T::UnormSrgb, F::BC2RgbaUnormSrgb),
+        ],
+        DXGI_FORMAT_BC3_TYPELESS => &[
+            (T::Unorm, F::BC3RgbaUnorm),
+            (T::UnormSrgb, F::BC3RgbaUnormSrgb),
+        ],
+        DXGI_FORMAT_BC4_TYPELESS => &[(T::Unorm, F::BC4RUnorm), (T::Snorm, F::BC4RSnorm)],
+        DXGI_FORMAT_BC5_TYPELESS => &[(T::Unorm, F::BC5RgUnorm), (T::Snorm, F::BC5RgSnorm)],
+        DXGI_FORMAT_B8G8R8A8_TYPELESS => {
+            &[(T::Unorm, F::Bgra8Unorm), (T::UnormSrgb, F::Bgra8UnormSrgb)]
+        }
+        DXGI_FORMAT_B8G8R8X8_TYPELESS => &[(T::Unorm, F::Bgrx8Unorm)],
+        DXGI_FORMAT_BC6H_TYPELESS => &[(T::Float, F::BC6hRgbUfloat), (T::Snorm, F::BC6hRgbSfloat)],
+        DXGI_FORMAT_BC7_TYPELESS => &[
+            (T::Unorm, F::BC7RgbaUnorm),
+            (T::UnormSrgb, F::BC7RgbaUnormSrgb),
+        ],
+        _ => return None,
+    };
+
+    formats
+        .iter()
+        .find(|(t, _)| *t == typeless)
+        .or(formats.first())
+        .map(|(_, f)| *f)
+}
+
+pub(crate) fn dxgi_format(format: ImageFormat) -> Option<u32> {
+    Some(match format {
+        ImageFormat::Rgba32Float => 2,
+        ImageFormat::Rgba32Uint => 3,
+        ImageFormat::Rgba32Sint => 4,
+        ImageFormat::Rgba16Float => 10,
+        ImageFormat::Rgba16Unorm => 11,
+        ImageFormat::Rgba16Uint => 12,
+        ImageFormat::Rgba16Snorm => 13,
+        ImageFormat::Rgba16Sint => 14,
+        ImageFormat::Rg32Float => 16,
+        ImageFormat::Depth32FloatStencil8 => 20,
+        ImageFormat::Rgb10a2Unorm => 24,
+        ImageFormat::Rg11b10Ufloat => 26,
+        ImageFormat::Rgba8Unorm => 28,
+        ImageFormat::Rgba8UnormSrgb => 29,
+        ImageFormat::Rgba8Uint => 30,
+        ImageFormat::Rgba8Sint => 32,
+        ImageFormat::Rg16Float => 34,
+        ImageFormat::Rg16Unorm => 35,
+        ImageFormat::Rg16Snorm => 37,
+        ImageFormat::Depth32Float => 40,
+        ImageFormat::R32Float => 41,
+        ImageFormat::R32Uint => 42,
+        ImageFormat::R32Sint => 43,
+        ImageFormat::Depth24UnormStencil8 => 45,
+        ImageFormat::Rg8Unorm => 49,
+        ImageFormat::R16Float => 54,
+        ImageFormat::Depth16Unorm => 55,
+        ImageFormat::R16Unorm => 56,
+        ImageFormat::R16Uint => 57,
+        ImageFormat::R16Sint => 59,
+        ImageFormat::R8Unorm => 61,
+        ImageFormat::R8Uint => 62,
+        ImageFormat::R8Sint => 64,
+        ImageFormat::A8Unorm => 65,
+        ImageFormat::Rgb9e5Ufloat => 67,
+        ImageFormat::BC1RgbaUnorm => 71,
+        ImageFormat::BC1RgbaUnormSrgb => 72,
+        ImageFormat::BC2RgbaUnorm => 74,
+        ImageFormat::BC2RgbaUnormSrgb => 75,
+        ImageFormat::BC3RgbaUnorm => 77,
+        ImageFormat::BC3RgbaUnormSrgb => 78,
+        ImageFormat::BC4RUnorm => 80,
+        ImageFormat::BC4RSnorm => 81,
+        ImageFormat::BC5RgUnorm => 83,
+        ImageFormat::BC5RgSnorm => 84,
+        ImageFormat::B5g6r5Unorm => 85,
+        ImageFormat::Bgr5a1Unorm => 86,
+        ImageFormat::Bgra8Unorm => 87,
+        ImageFormat::Bgrx8Unorm => 88,
+        ImageFormat::Bgra8UnormSrgb => 91,
+        ImageFormat::BC6hRgbUfloat => 95,
+        ImageFormat::BC6hRgbSfloat => 96,
+        ImageFormat::BC7RgbaUnorm => 98,
+        ImageFormat::BC7RgbaUnormSrgb => 99,
+        ImageFormat::Nv12 => 103,
+        ImageFormat::P010 => 104,
+        ImageFormat::Yuy2 => 107,
+        ImageFormat::Bgra4Unorm => 115,
+        ImageFormat::Rgb8Unorm
+        | ImageFormat::Uyvy
+        | ImageFormat::Bgr5x1Unorm
+        | ImageFormat::Bgr8Unorm
+        | ImageFormat::La8Unorm
+        | ImageFormat::Etc1RgbUnorm
+        | ImageFormat::Etc2RgbUnorm
+        | ImageFormat::Etc2RgbUnormSrgb
+        | ImageFormat::Etc2Rgba1Unorm
+        | ImageFormat::Etc2Rgba1UnormSrgb
+        | ImageFormat::Etc2Rgba8Unorm
+        | ImageFormat::Etc2Rgba8UnormSrgb
+        | ImageFormat::EacR11Unorm
+        | ImageFormat::EacR11Snorm
+        | ImageFormat::EacRg11Unorm
+        | ImageFormat::EacRg11Snorm
+        | ImageFormat::Astc4x4RgbaUnorm
+        | ImageFormat::Astc4x4RgbaUnormSrgb
+        | ImageFormat::Astc4x4RgbaFloat
+        | ImageFormat::Astc5x4RgbaUnorm
+        | ImageFormat::Astc5x4RgbaUnormSrgb
+        | ImageFormat::Astc5x4RgbaFloat
+        | ImageFormat::Astc5x5RgbaUnorm
+        | ImageFormat::Astc5x5RgbaUnormSrgb
+        | ImageFormat::Astc5x5RgbaFloat
+        | ImageFormat::Astc6x5RgbaUnorm
+        | ImageFormat::Astc6x5RgbaUnormSrgb
+        | ImageFormat::Astc6x5RgbaFloat
+        | ImageFormat::Astc6x6RgbaUnorm
+        | ImageFormat::Astc6x6RgbaUnormSrgb
+        | ImageFormat::Astc6x6RgbaFloat
+        | ImageFormat::Astc8x5RgbaUnorm
+        | ImageFormat::Astc8x5RgbaUnormSrgb
+        | ImageFormat::Astc8x5RgbaFloat
+        | ImageFormat::Astc8x6RgbaUnorm
+        | ImageFormat::Astc8x6RgbaUnormSrgb
+        | ImageFormat::Astc8x6RgbaFloat
+        | ImageFormat::Astc8x8RgbaUnorm
+        | ImageFormat::Astc8x8RgbaUnormSrgb
+        | ImageFormat::Astc8x8RgbaFloat
+        | ImageFormat::Astc10x5RgbaUnorm
+        | ImageFormat::Astc10x5RgbaUnormSrgb
+        | ImageFormat::Astc10x5RgbaFloat
+        | ImageFormat::Astc10x6RgbaUnorm
+        | ImageFormat::Astc10x6RgbaUnormSrgb
+        | ImageFormat::Astc10x6RgbaFloat
+        | ImageFormat::Astc10x8RgbaUnorm
+        | ImageFormat::Astc10x8RgbaUnormSrgb
+        | ImageFormat::Astc10x8RgbaFloat
+        | ImageFormat::Astc10x10RgbaUnorm
+        | ImageFormat::Astc10x10RgbaUnormSrgb
+        | ImageFormat::Astc10x10RgbaFloat
+        | ImageFormat::Astc12x10RgbaUnorm
+        | ImageFormat::Astc12x10RgbaUnormSrgb
+        | ImageFormat::Astc12x10RgbaFloat
+        | ImageFormat::Astc12x12RgbaUnorm
+        | ImageFormat::Astc12x12RgbaUnormSrgb
+        | ImageFormat::Astc12x12RgbaFloat => return None,
+    })
+}
+
+/// Find the format for a legacy DDS pixel format with the given `flags`, bit count, and channel masks.
+pub(crate) fn image_format_from_masks(
+    flags: u32,
+    bit_count: u32,
+    r: u32,
+    g: u32,
+    b: u32,
+    a: u32,
+) -> Option<ImageFormat> {
+    // Ignore the alpha mask if the format has no alpha.
+    let a = if flags & (DDPF_ALPHAPIXELS | DDPF_ALPHA) != 0 {
+        a
+    } else {
+        0
+    };
+
+    if flags & DDPF_LUMINANCE != 0 {
+        // ddsfile only reads the color masks for RGB formats.
+        return match (bit_count, a) {
+            (8, 0) => Some(ImageFormat::R8Unorm),
+            (16, 0) => Some(ImageFormat::R16Unorm),
+            (16, 0xff00) => Some(ImageFormat::La8Unorm),
+            _ => None,
+        };
+    }
+    if flags & DDPF_RGB == 0 {
+        // Alpha only formats may not set the bit count.
+        return (flags & DDPF_ALPHA != 0 && a == 0xff).then_some(ImageFormat::A8Unorm);
+    }
+
+    match (bit_count, r, g, b, a) {
+        (32, 0xff, 0xff00, 0xff0000, 0xff000000) => Some(ImageFormat::Rgba8Unorm),
+        (32, 0xff0000, 0xff00, 0xff, 0xff000000) => Some(ImageFormat::Bgra8Unorm),
+        (32, 0xff0000, 0xff00, 0xff, 0) => Some(ImageFormat::Bgrx8Unorm),
+        (24, 0xff, 0xff00, 0xff0000, 0) => Some(ImageFormat::Rgb8Unorm),
+        (24, 0xff0000, 0xff00, 0xff, 0) => Some(ImageFormat::Bgr8Unorm),
+        (16, 0xf00, 0xf0, 0xf, 0xf000) => Some(ImageFormat::Bgra4Unorm),
+        (16, 0xf800, 0x7e0, 0x1f, 0) => Some(ImageFormat::B5g6r5Unorm),
+        (16, 0x7c00, 0x3e0, 0x1f, 0x8000) => Some(ImageFormat::Bgr5a1Unorm),
+        (16, 0x7c00, 0x3e0, 0x1f, 0) => Some(ImageFormat::Bgr5x1Unorm),
+        (32, 0x3ff, 0xffc00, 0x3ff00000, 0xc0000000) => Some(ImageFormat::Rgb10a2Unorm),
+        // D3DX writes DXGI_FORMAT_R10G10B10A2_UNORM with the masks for A2R10G10B10.
+        // Treat both channel orders as RGBA like DirectXTex.
+        (32, 0x3ff00000, 0xffc00, 0x3ff, 0xc0000000) => Some(ImageFormat::Rgb10a2Unorm),
+        (32, 0xffff, 0xffff0000, 0, 0) => Some(ImageFormat::Rg16Unorm),
+        // Some older tools write these formats as RGB instead of luminance.
+        (8, 0xff, 0, 0, 0) => Some(ImageFormat::R8Unorm),
+        (16, 0xff, 0xff00, 0, 0) => Some(ImageFormat::Rg8Unorm),
+        (16, 0xff, 0, 0, 0xff00) => Some(ImageFormat::La8Unorm),
+        (16, 0xffff, 0, 0, 0) => Some(ImageFormat::R16Unorm),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_format_from_typeless_interpretation() {
+        assert_eq!(
+            Some(ImageFormat::BC5RgUnorm),
+            image_format_from_typeless(DXGI_FORMAT_BC5_TYPELESS, TypelessInterpretation::Unorm)
+        );
+        assert_eq!(
+            Some(ImageFormat::BC5RgSnorm),
+            image_format_from_typeless(DXGI_FORMAT_BC5_TYPELESS, TypelessInterpretation::Snorm)
+        );
+        assert_eq!(
+            Some(ImageFormat::BC5RgUnorm),
+            image_format_from_typeless(DXGI_FORMAT_BC5_TYPELESS, TypelessInterpretation::UnormSrgb)
+        );
+
+        // DXGI_FORMAT_R32_TYPELESS has no unorm format.
+        assert_eq!(
+            Some(ImageFormat::R32Float),
+            image_format_from_typeless(DXGI_FORMAT_R32_TYPELESS, TypelessInterpretation::Unorm)
+        );
+
+        // DXGI_FORMAT_BC7_UNORM is not typeless.
+        assert_eq!(
+            None,
+            image_format_from_typeless(98, TypelessInterpretation::UnormSrgb)
+        );
+    }
+
+    #[test]
+    fn image_format_from_masks_rgb() {
+        let rgba = DDPF_RGB | DDPF_ALPHAPIXELS;
+        for (flags, bit_count, r, g, b, a, image_format) in [
+            (
+                rgba,
+                32,
+                0xff,
+                0xff00,
+                0xff0000,
+                0xff000000,
+                ImageFormat::Rgba8Unorm,
+            ),
+            (
+                rgba,
+                32,
+                0xff0000,
+                0xff00,
+                0xff,
+                0xff000000,
+                ImageFormat::Bgra8Unorm,
+            ),
+            (
+                DDPF_RGB,
+                32,
+                0xff0000,
+                0xff00,
+                0xff,
+                0,
+                ImageFormat::Bgrx8Unorm,
+            ),
+            (
+                DDPF_RGB,
+                24,
+                0xff,
+                0xff00,
+                0xff0000,
+                0,
+                ImageFormat::Rgb8Unorm,
+            ),
+            (
+                DDPF_RGB,
+                24,
+                0xff0000,
+                0xff00,
+                0xff,
+                0,
+                ImageFormat::Bgr8Unorm,
+            ),
+            (rgba, 16, 0xf00, 0xf0, 0xf, 0xf000, ImageFormat::Bgra4Unorm),
+            (
+                DDPF_RGB,
+                16,
+                0xf800,
+                0x7e0,
+                0x1f,
+                0,
+                ImageFormat::B5g6r5Unorm,
+            ),
+            (
+                rgba,
+                16,
+                0x7c00,
+                0x3e0,
+                0x1f,
+                0x8000,
+                ImageFormat::Bgr5a1Unorm,
+            ),
+            (
+                DDPF_RGB,
+                16,
+                0x7c00,
+                0x3e0,
+                0x1f,
+                0,
+                ImageFormat::Bgr5x1Unorm,
+            ),
+            // The alpha mask is ignored without an alpha flag.
+            (
+                DDPF_RGB,
+                16,
+                0x7c00,
+                0x3e0,
+                0x1f,
+                0x8000,
+                ImageFormat::Bgr5x1Unorm,
+            ),
+            (
+                rgba,
+                32,
+                0x3ff,
+                0xffc00,
+                0x3ff00000,
+                0xc0000000,
+                ImageFormat::Rgb10a2Unorm,
+            ),
+            (
+                rgba,
+                32,
+                0x3ff00000,
+                0xffc00,
+                0x3ff,
+                0xc0000000,
+                ImageFormat::Rgb10a2Unorm,
+            ),
+            (
+                DDPF_RGB,
+                32,
+                0xffff,
+                0xffff0000,
+                0,
+                0,
+                ImageFormat::Rg16Unorm,
+            ),
+            (DDPF_RGB, 8, 0xff, 0, 0, 0, ImageFormat::R8Unorm),
+            (DDPF_RGB, 16, 0xff, 0xff00, 0, 0, ImageFormat::Rg8Unorm),
+            (rgba, 16, 0xff, 0, 0, 0xff00, ImageFormat::La8Unorm),
+            (DDPF_RGB, 16, 0xffff, 0, 0, 0, ImageFormat::R16Unorm),
+        ] {
+            assert_eq!(
+                Some(image_format),
+                image_format_from_masks(flags, bit_count, r, g, b, a)
+            );
+        }
+    }
+
+    #[test]
+    fn image_format_from_masks_luminance_alpha() {
+        let la = DDPF_LUMINANCE | DDPF_ALPHAPIXELS;
+        for (flags, bit_count, r, a, image_format) in [
+            (DDPF_LUMINANCE, 8, 0xff, 0, ImageFormat::R8Unorm),
+            (DDPF_LUMINANCE, 16, 0xffff, 0, ImageFormat::R16Unorm),
+            (la, 16, 0xff, 0xff00, ImageFormat::La8Unorm),
+            // ddsfile does not read the red mask for luminance formats.
+            (DDPF_LUMINANCE, 8, 0, 0, ImageFormat::R8Unorm),
+            (la, 16, 0, 0xff00, ImageFormat::La8Unorm),
+            // ddsfile does not read the bit count for alpha only formats.
+            (DDPF_ALPHA, 0, 0, 0xff, ImageFormat::A8Unorm),
+            (DDPF_ALPHA, 8, 0, 0xff, ImageFormat::A8Unorm),
+        ] {
+            assert_eq!(
+                Some(image_format),
+                image_format_from_masks(flags, bit_count, r, 0, 0, a)
+            );
+        }
+    }
+
+    #[test]
+    fn image_format_from_masks_unknown() {
+        assert_eq!(
+            None,
+            image_format_from_masks(0, 32, 0xff, 0xff00, 0xff0000, 0)
+        );
+        assert_eq!(
+            None,
+            image_format_from_masks(DDPF_RGB, 32, 0xff00, 0xff, 0xff0000, 0)
+        );
+        assert_eq!(None, image_format_from_masks(DDPF_ALPHA, 8, 0, 0, 0, 0xf0));
+    }
+}
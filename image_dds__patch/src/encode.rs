@@ -1,15 +1,32 @@
 use std::borrow::Cow;
 
-use crate::bcn::{bcn_from_rgba, Bc1, Bc2, Bc3, Bc4, Bc5, Bc6, Bc7};
+use half::f16;
+
+use crate::bcn::{
+    bcn_from_rgba, Bc1, Bc2, Bc3, Bc4, Bc4Signed, Bc5, Bc5Signed, Bc6, Bc6Signed, Bc7,
+};
+use crate::filter::{
+    alpha_coverage, downsample_rgba_filtered, downsample_rgba_point, renormalize_rgb,
+    scale_alpha_to_coverage,
+};
 use crate::rgba::{
-    bgra4_from_rgba8, bgra8_from_rgba8, r8_from_rgba8, rgba8_from_rgba8, rgbaf16_from_rgba8,
-    rgbaf16_from_rgbaf32, rgbaf32_from_rgba8, rgbaf32_from_rgbaf32,
+    a8_from_rgba8, b5g6r5_from_rgba8, bgr5a1_from_rgba8, bgr5x1_from_rgba8, bgr8_from_rgba8,
+    bgra4_from_rgba8, bgra8_from_rgba8, bgrx8_from_rgba8, channels_from_rgba8,
+    channels_from_rgbaf32, channels_from_rgbau32, d24s8_from_rgbaf32, d32fs8_from_rgbaf32,
+    la8_from_rgba8, r8_from_rgba8, rg11b10f_from_rgbaf32, rgb10a2_from_rgbaf32, rgb8_from_rgba8,
+    rgb9e5_from_rgbaf32, rgba8_from_rgba8, rgbaf16_from_rgba8, rgbaf16_from_rgbaf32,
+    rgbaf32_from_rgba8, rgbaf32_from_rgbaf32,
 };
+use crate::yuv::yuv_from_rgbaf32;
 use crate::{
-    downsample_rgba, error::SurfaceError, max_mipmap_count, mip_dimension, round_up, ImageFormat,
-    Mipmaps, Quality, Surface, SurfaceRgba8,
+    error::SurfaceError, max_mipmap_count, mip_dimension, round_up, AlphaMode, ImageFormat,
+    MipmapFilter, MipmapSettings, Mipmaps, NormalMapLayout, Quality, Surface, SurfaceRgba8,
+    YuvMatrix,
 };
-use crate::{Pixel, SurfaceRgba32Float};
+use crate::{Pixel, SurfaceRgba32Float, SurfaceRgba32Uint};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 impl<T: AsRef<[u8]>> Surface<T> {
     /// Encode a surface to the given `format`.
@@ -20,9 +37,29 @@ impl<T: AsRef<[u8]>> Surface<T> {
         format: ImageFormat,
         quality: Quality,
         mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.encode_with_mipmap_settings(format, quality, mipmaps, MipmapSettings::default())
+    }
+
+    /// Encode a surface to the given `format` using `settings` for any generated mipmaps.
+    ///
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_with_mipmap_settings(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+        settings: MipmapSettings,
     ) -> Result<Surface<Vec<u8>>, SurfaceError> {
         self.validate()?;
-        encode_surface(self, format, quality, mipmaps)
+        encode_surface(
+            self,
+            format,
+            quality,
+            YuvMatrix::default(),
+            mipmaps,
+            settings,
+        )
     }
 }
 
@@ -35,9 +72,50 @@ impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
         format: ImageFormat,
         quality: Quality,
         mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.encode_with_mipmap_settings(format, quality, mipmaps, MipmapSettings::default())
+    }
+
+    /// Encode an RGBA8 surface to the given `format` using `settings` for any generated mipmaps.
+    ///
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_with_mipmap_settings(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+        settings: MipmapSettings,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.validate()?;
+        encode_surface(
+            self,
+            format,
+            quality,
+            YuvMatrix::default(),
+            mipmaps,
+            settings,
+        )
+    }
+
+    /// Encode an RGBA8 surface to a YUV `format` like [ImageFormat::Nv12] using `matrix`.
+    ///
+    /// Other encoding methods use [YuvMatrix::Bt601] for YUV formats.
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_yuv(
+        &self,
+        format: ImageFormat,
+        matrix: YuvMatrix,
+        mipmaps: Mipmaps,
     ) -> Result<Surface<Vec<u8>>, SurfaceError> {
         self.validate()?;
-        encode_surface(self, format, quality, mipmaps)
+        encode_surface(
+            self,
+            format,
+            Quality::Normal,
+            matrix,
+            mipmaps,
+            MipmapSettings::default(),
+        )
     }
 }
 
@@ -51,9 +129,87 @@ impl<T: AsRef<[f32]>> SurfaceRgba32Float<T> {
         format: ImageFormat,
         quality: Quality,
         mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.encode_with_mipmap_settings(format, quality, mipmaps, MipmapSettings::default())
+    }
+
+    /// Encode an RGBAF32 surface to the given `format` using `settings` for any generated mipmaps.
+    ///
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_with_mipmap_settings(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+        settings: MipmapSettings,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.validate()?;
+        encode_surface(
+            self,
+            format,
+            quality,
+            YuvMatrix::default(),
+            mipmaps,
+            settings,
+        )
+    }
+
+    /// Encode an RGBAF32 surface to a YUV `format` like [ImageFormat::P010] using `matrix`.
+    ///
+    /// Other encoding methods use [YuvMatrix::Bt601] for YUV formats.
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_yuv(
+        &self,
+        format: ImageFormat,
+        matrix: YuvMatrix,
+        mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.validate()?;
+        encode_surface(
+            self,
+            format,
+            Quality::Normal,
+            matrix,
+            mipmaps,
+            MipmapSettings::default(),
+        )
+    }
+}
+
+impl<T: AsRef<[u32]>> SurfaceRgba32Uint<T> {
+    /// Encode an RGBA u32 surface to the given integer `format` like [ImageFormat::R8Uint].
+    ///
+    /// Values outside the range of `format` are clamped.
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+    ) -> Result<Surface<Vec<u8>>, SurfaceError> {
+        self.encode_with_mipmap_settings(format, quality, mipmaps, MipmapSettings::default())
+    }
+
+    /// Encode an RGBA u32 surface to the given integer `format` using `settings` for any generated mipmaps.
+    ///
+    /// Generated mipmaps use [MipmapFilter::Nearest] or [MipmapFilter::Majority] and never average values.
+    /// The number of mipmaps generated depends on the `mipmaps` parameter.
+    pub fn encode_with_mipmap_settings(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        mipmaps: Mipmaps,
+        settings: MipmapSettings,
     ) -> Result<Surface<Vec<u8>>, SurfaceError> {
         self.validate()?;
-        encode_surface(self, format, quality, mipmaps)
+        encode_surface(
+            self,
+            format,
+            quality,
+            YuvMatrix::default(),
+            mipmaps,
+            settings,
+        )
     }
 }
 
@@ -61,11 +217,13 @@ fn encode_surface<S, P>(
     surface: &S,
     format: ImageFormat,
     quality: Quality,
+    matrix: YuvMatrix,
     mipmaps: Mipmaps,
+    settings: MipmapSettings,
 ) -> Result<Surface<Vec<u8>>, SurfaceError>
 where
     S: GetMipmap<P>,
-    P: Default + Copy + Encode + Pixel,
+    P: Default + Copy + Encode + GenerateMipmaps + Send + Sync,
 {
     // TODO: Encode the correct number of array layers.
     let num_mipmaps = match mipmaps {
@@ -79,22 +237,50 @@ where
 
     let use_surface = mipmaps == Mipmaps::FromSurface;
 
-    // TODO: Does this work if the base mip level is smaller than 4x4?
-    let mut surface_data = Vec::new();
-
+    // Each layer has an independent chain of mipmaps.
+    // Volume textures store all depth slices for a mipmap before the next mipmap.
+    // Collect the input data up front so the surface isn't shared between threads.
+    let (width, height, depth) = (surface.width(), surface.height(), surface.depth());
+    let mut chains = Vec::new();
     for layer in 0..surface.layers() {
-        // Encode 2D or 3D data for this layer.
-        encode_mipmaps_rgba(
-            &mut surface_data,
-            surface,
-            format,
-            quality,
-            num_mipmaps,
-            use_surface,
-            layer,
-        )?;
+        // Generated mipmaps only need the base level.
+        // TODO: Error if surface does not have the appropriate number of mipmaps?
+        let count = if use_surface { num_mipmaps } else { 1 };
+        let chain = (0..count)
+            .map(|mipmap| {
+                (0..mip_dimension(depth, mipmap))
+                    .map(|level| {
+                        surface
+                            .get(layer, level, mipmap)
+                            .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        chains.push(chain);
     }
 
+    #[cfg(feature = "rayon")]
+    let chains = chains.into_par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let chains = chains.into_iter();
+
+    // TODO: Does this work if the base mip level is smaller than 4x4?
+    let surface_data = chains
+        .map(|chain| {
+            encode_mipmaps_rgba(
+                &chain,
+                (width, height, depth),
+                format,
+                quality,
+                matrix,
+                num_mipmaps,
+                settings,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .concat();
+
     Ok(Surface {
         width: surface.width(),
         height: surface.height(),
@@ -102,79 +288,195 @@ where
         layers: surface.layers(),
         mipmaps: num_mipmaps,
         image_format: format,
+        alpha_mode: surface.alpha_mode(),
         data: surface_data,
     })
 }
 
-// TODO: Find a way to simplify this.
-fn encode_mipmaps_rgba<S, P>(
-    surface_data: &mut Vec<u8>,
-    surface: &S,
+fn encode_mipmaps_rgba<P>(
+    chain: &[Vec<&[P]>],
+    dimensions: (u32, u32, u32),
     format: ImageFormat,
     quality: Quality,
+    matrix: YuvMatrix,
     num_mipmaps: u32,
-    use_surface: bool,
-    layer: u32,
-) -> Result<(), SurfaceError>
+    settings: MipmapSettings,
+) -> Result<Vec<u8>, SurfaceError>
 where
-    S: GetMipmap<P>,
-    P: Default + Copy + Encode + Pixel,
+    P: Default + Copy + Encode + GenerateMipmaps + Send + Sync,
 {
-    let block_dimensions = format.block_dimensions();
+    let mips = P::generate_mipmaps(chain, dimensions, format, num_mipmaps, settings);
+
+    // Downsampling is sequential, but each mipmap can be encoded independently.
+    #[cfg(feature = "rayon")]
+    let mips = mips.par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let mips = mips.iter();
+
+    Ok(mips
+        .map(|mip_data| mip_data.encode(format, quality, matrix))
+        .collect::<Result<Vec<_>, _>>()?
+        .concat())
+}
 
-    for level in 0..surface.depth() {
-        // Track the previous image data and dimensions.
-        // This enables generating mipmaps from a single base layer.
-        let mut mip_data = get_mipmap_data(surface, layer, level, 0, block_dimensions)?;
+// Integer data can't be filtered like normalized data,
+// so each pixel type determines how missing mipmaps are generated.
+trait GenerateMipmaps: Sized {
+    fn generate_mipmaps(
+        chain: &[Vec<&[Self]>],
+        dimensions: (u32, u32, u32),
+        format: ImageFormat,
+        num_mipmaps: u32,
+        settings: MipmapSettings,
+    ) -> Vec<MipData<Self>>;
+}
 
-        let encoded = mip_data.encode(format, quality)?;
-        surface_data.extend_from_slice(&encoded);
+impl GenerateMipmaps for u8 {
+    fn generate_mipmaps(
+        chain: &[Vec<&[Self]>],
+        dimensions: (u32, u32, u32),
+        format: ImageFormat,
+        num_mipmaps: u32,
+        settings: MipmapSettings,
+    ) -> Vec<MipData<Self>> {
+        generate_mipmaps_rgba(chain, dimensions, format, num_mipmaps, settings)
+    }
+}
+
+impl GenerateMipmaps for f32 {
+    fn generate_mipmaps(
+        chain: &[Vec<&[Self]>],
+        dimensions: (u32, u32, u32),
+        format: ImageFormat,
+        num_mipmaps: u32,
+        settings: MipmapSettings,
+    ) -> Vec<MipData<Self>> {
+        generate_mipmaps_rgba(chain, dimensions, format, num_mipmaps, settings)
+    }
+}
 
+impl GenerateMipmaps for u32 {
+    fn generate_mipmaps(
+        chain: &[Vec<&[Self]>],
+        dimensions: (u32, u32, u32),
+        _format: ImageFormat,
+        num_mipmaps: u32,
+        settings: MipmapSettings,
+    ) -> Vec<MipData<Self>> {
+        // Averaging integer values like IDs would create values not in the original data.
+        let majority = settings.filter != MipmapFilter::Nearest;
+
+        let mut mips = vec![get_mipmap_data(&chain[0], dimensions, 0)];
         for mipmap in 1..num_mipmaps {
-            mip_data = if use_surface {
-                // TODO: Error if surface does not have the appropriate number of mipmaps?
-                get_mipmap_data(surface, layer, level, mipmap, block_dimensions)?
-            } else {
-                mip_data.downsample(surface.width(), surface.height(), block_dimensions, mipmap)
+            let mip_data = match chain.get(mipmap as usize) {
+                Some(slices) => get_mipmap_data(slices, dimensions, mipmap),
+                None => mips[mips.len() - 1].downsample_point(majority),
             };
+            mips.push(mip_data);
+        }
+        mips
+    }
+}
 
-            let encoded = mip_data.encode(format, quality)?;
-            surface_data.extend_from_slice(&encoded);
+fn generate_mipmaps_rgba<P: Pixel>(
+    chain: &[Vec<&[P]>],
+    dimensions: (u32, u32, u32),
+    format: ImageFormat,
+    num_mipmaps: u32,
+    settings: MipmapSettings,
+) -> Vec<MipData<P>> {
+    // Normal maps store vectors instead of colors even with sRGB formats.
+    let srgb = settings
+        .srgb
+        .unwrap_or(format.is_srgb() && settings.normal_map.is_none());
+    let signed = format.is_snorm() && P::SIGNED;
+
+    // Track the previous image data and dimensions.
+    // This enables generating mipmaps from a single base layer.
+    let mut mips = vec![get_mipmap_data(&chain[0], dimensions, 0)];
+    for mipmap in 1..num_mipmaps {
+        let mip_data = match chain.get(mipmap as usize) {
+            Some(slices) => get_mipmap_data(slices, dimensions, mipmap),
+            None => {
+                let mut mip_data = mips[mips.len() - 1].downsample(settings.filter, srgb);
+                if settings.normal_map.is_some() {
+                    renormalize_rgb(&mut mip_data.data, signed);
+                }
+                mip_data
+            }
+        };
+        mips.push(mip_data);
+    }
+
+    // Z is still needed for renormalizing, so only change the layout after downsampling.
+    if settings.normal_map == Some(NormalMapLayout::Rg) {
+        for mip_data in &mut mips {
+            for pixel in mip_data.data.chunks_exact_mut(4) {
+                pixel[2] = P::default();
+            }
+        }
+    }
+
+    // Scale alpha after downsampling to avoid compounding the scaling for each mipmap.
+    if let Some(reference) = settings.alpha_test_reference {
+        let coverage = alpha_coverage(&mips[0].data, reference);
+        for mip_data in mips.iter_mut().skip(chain.len()) {
+            scale_alpha_to_coverage(&mut mip_data.data, coverage, reference);
         }
     }
 
-    Ok(())
+    mips
 }
 
+// The unpadded data for a single mipmap.
+// Padding is only applied for encoding to avoid filtering the padding.
 struct MipData<T> {
     width: usize,
     height: usize,
+    depth: usize,
     data: Vec<T>,
 }
 
 impl<T: Pixel> MipData<T> {
-    fn downsample(
-        &self,
-        base_width: u32,
-        base_height: u32,
-        block_dimensions: (u32, u32, u32),
-        mipmap: u32,
-    ) -> MipData<T> {
-        // Mip dimensions are the padded virtual size of the mipmap.
-        // Padding the physical size of the previous mip produces incorrect results.
-        let (width, height, depth) = physical_dimensions(
-            mip_dimension(base_width, mipmap),
-            mip_dimension(base_height, mipmap),
-            1,
-            block_dimensions,
+    fn downsample(&self, filter: MipmapFilter, srgb: bool) -> MipData<T> {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let depth = (self.depth / 2).max(1);
+
+        let data = downsample_rgba_filtered(
+            (width, height, depth),
+            (self.width, self.height, self.depth),
+            &self.data,
+            filter,
+            srgb,
         );
 
-        // Assume the data is already padded.
-        let data = downsample_rgba(width, height, depth, self.width, self.height, 1, &self.data);
+        MipData {
+            width,
+            height,
+            depth,
+            data,
+        }
+    }
+}
+
+impl<T: Default + Copy + PartialEq> MipData<T> {
+    fn downsample_point(&self, majority: bool) -> MipData<T> {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let depth = (self.depth / 2).max(1);
+
+        let data = downsample_rgba_point(
+            (width, height, depth),
+            (self.width, self.height, self.depth),
+            &self.data,
+            majority,
+        );
 
         MipData {
             width,
             height,
+            depth,
             data,
         }
     }
@@ -182,16 +484,37 @@ impl<T: Pixel> MipData<T> {
 
 impl<T> MipData<T>
 where
-    T: Encode,
+    T: Encode + Default + Copy,
 {
-    fn encode(&self, format: ImageFormat, quality: Quality) -> Result<Vec<u8>, SurfaceError> {
-        T::encode(
+    fn encode(
+        &self,
+        format: ImageFormat,
+        quality: Quality,
+        matrix: YuvMatrix,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // The physical size must have integral dimensions in blocks.
+        let (width, height, _) = physical_dimensions(
             self.width as u32,
             self.height as u32,
-            &self.data,
-            format,
-            quality,
-        )
+            1,
+            format.block_dimensions(),
+        );
+
+        // Encoding only works on 2D surfaces, so encode each depth slice separately.
+        let slice_size = self.width * self.height * 4;
+        let mut encoded = Vec::new();
+        for slice in self.data.chunks_exact(slice_size) {
+            let data = pad_mipmap_rgba(self.width, self.height, 1, width, height, 1, slice);
+            encoded.extend(T::encode(
+                width as u32,
+                height as u32,
+                &data,
+                format,
+                quality,
+                matrix,
+            )?);
+        }
+        Ok(encoded)
     }
 }
 
@@ -201,10 +524,10 @@ trait GetMipmap<P> {
     fn depth(&self) -> u32;
     fn layers(&self) -> u32;
     fn mipmaps(&self) -> u32;
+    fn alpha_mode(&self) -> AlphaMode;
     fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[P]>;
 }
 
-
 impl<T> GetMipmap<u8> for Surface<T>
 where
     T: AsRef<[u8]>,
@@ -229,6 +552,10 @@ where
         self.mipmaps
     }
 
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
     fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[u8]> {
         self.get(layer, depth_level, mipmap)
     }
@@ -258,6 +585,10 @@ where
         self.mipmaps
     }
 
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
     fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[u8]> {
         self.get(layer, depth_level, mipmap)
     }
@@ -287,45 +618,67 @@ where
         self.mipmaps
     }
 
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
     fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[f32]> {
         self.get(layer, depth_level, mipmap)
     }
 }
 
-fn get_mipmap_data<S, P>(
-    surface: &S,
-    layer: u32,
-    depth_level: u32,
-    mipmap: u32,
-    block_dimensions: (u32, u32, u32),
-) -> Result<MipData<P>, SurfaceError>
+impl<T> GetMipmap<u32> for SurfaceRgba32Uint<T>
 where
-    S: GetMipmap<P>,
-    P: Default + Copy,
+    T: AsRef<[u32]>,
 {
-    let mip_width = mip_dimension(surface.width(), mipmap);
-    let mip_height = mip_dimension(surface.height(), mipmap);
+    fn width(&self) -> u32 {
+        self.width
+    }
 
-    let data = surface.get(layer, depth_level, mipmap).unwrap();
+    fn height(&self) -> u32 {
+        self.height
+    }
 
-    let (width, height, _) = physical_dimensions(mip_width, mip_height, 1, block_dimensions);
+    fn depth(&self) -> u32 {
+        self.depth
+    }
 
-    let data = pad_mipmap_rgba(
-        mip_width as usize,
-        mip_height as usize,
-        1,
-        width,
-        height,
-        1,
-        data,
-    )
-    .to_vec();
+    fn layers(&self) -> u32 {
+        self.layers
+    }
+
+    fn mipmaps(&self) -> u32 {
+        self.mipmaps
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[u32]> {
+        self.get(layer, depth_level, mipmap)
+    }
+}
+
+fn get_mipmap_data<P>(slices: &[&[P]], dimensions: (u32, u32, u32), mipmap: u32) -> MipData<P>
+where
+    P: Copy,
+{
+    let (width, height, depth) = dimensions;
+    let width = mip_dimension(width, mipmap) as usize;
+    let height = mip_dimension(height, mipmap) as usize;
+    let depth = mip_dimension(depth, mipmap) as usize;
 
-    Ok(MipData {
+    MipData {
         width,
         height,
-        data,
-    })
+        depth,
+        data: slices
+            .iter()
+            .flat_map(|slice| &slice[..width * height * 4])
+            .copied()
+            .collect(),
+    }
 }
 
 fn physical_dimensions(
@@ -389,6 +742,7 @@ trait Encode: Sized {
         data: &[Self],
         format: ImageFormat,
         quality: Quality,
+        matrix: YuvMatrix,
     ) -> Result<Vec<u8>, SurfaceError>;
 }
 
@@ -399,6 +753,7 @@ impl Encode for u8 {
         data: &[Self],
         format: ImageFormat,
         quality: Quality,
+        matrix: YuvMatrix,
     ) -> Result<Vec<u8>, SurfaceError> {
         // Unorm and srgb only affect how the data is read.
         // Use the same conversion code for both.
@@ -413,22 +768,130 @@ impl Encode for u8 {
             F::BC3RgbaUnorm | F::BC3RgbaUnormSrgb => {
                 bcn_from_rgba::<Bc3, u8>(width, height, data, quality)
             }
-            F::BC4RUnorm | F::BC4RSnorm => bcn_from_rgba::<Bc4, u8>(width, height, data, quality),
-            F::BC5RgUnorm | F::BC5RgSnorm => bcn_from_rgba::<Bc5, u8>(width, height, data, quality),
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => {
-                bcn_from_rgba::<Bc6, u8>(width, height, data, quality)
+            F::BC4RUnorm => bcn_from_rgba::<Bc4, u8>(width, height, data, quality),
+            F::BC5RgUnorm => bcn_from_rgba::<Bc5, u8>(width, height, data, quality),
+            F::BC4RSnorm | F::BC5RgSnorm => {
+                // Invert the remapping used for decoding so that 128 maps to 0.0.
+                let rgba: Vec<_> = data.iter().map(|u| *u as f32 / 255.0 * 2.0 - 1.0).collect();
+                f32::encode(width, height, &rgba, format, quality, matrix)
+            }
+            F::BC6hRgbUfloat => bcn_from_rgba::<Bc6, u8>(width, height, data, quality),
+            F::BC6hRgbSfloat => {
+                let rgba: Vec<_> = data.iter().map(|u| *u as f32 / 255.0).collect();
+                f32::encode(width, height, &rgba, format, quality, matrix)
             }
             F::BC7RgbaUnorm | F::BC7RgbaUnormSrgb => {
                 bcn_from_rgba::<Bc7, u8>(width, height, data, quality)
             }
             F::R8Unorm => r8_from_rgba8(width, height, data),
+            F::Rg8Unorm => channels_from_rgba8::<u8, 2>(width, height, data),
+            F::Rgb8Unorm => rgb8_from_rgba8(width, height, data),
             F::Rgba8Unorm => rgba8_from_rgba8(width, height, data),
             F::Rgba8UnormSrgb => rgba8_from_rgba8(width, height, data),
+            F::R16Unorm => channels_from_rgba8::<u16, 1>(width, height, data),
+            F::Rg16Unorm => channels_from_rgba8::<u16, 2>(width, height, data),
+            F::Rg16Snorm => channels_from_rgba8::<i16, 2>(width, height, data),
+            F::Rgba16Unorm => channels_from_rgba8::<u16, 4>(width, height, data),
+            F::Rgba16Snorm => channels_from_rgba8::<i16, 4>(width, height, data),
+            F::R16Float => channels_from_rgba8::<f16, 1>(width, height, data),
+            F::Rg16Float => channels_from_rgba8::<f16, 2>(width, height, data),
             F::Rgba16Float => rgbaf16_from_rgba8(width, height, data),
+            F::R32Float => channels_from_rgba8::<f32, 1>(width, height, data),
+            F::Rg32Float => channels_from_rgba8::<f32, 2>(width, height, data),
             F::Rgba32Float => rgbaf32_from_rgba8(width, height, data),
+            F::Rgb10a2Unorm | F::Rg11b10Ufloat | F::Rgb9e5Ufloat => {
+                // Packed formats are encoded from floating point data.
+                let rgba: Vec<_> = data.iter().map(|u| *u as f32 / 255.0).collect();
+                f32::encode(width, height, &rgba, format, quality, matrix)
+            }
             F::Bgra8Unorm => bgra8_from_rgba8(width, height, data),
             F::Bgra8UnormSrgb => bgra8_from_rgba8(width, height, data),
             F::Bgra4Unorm => bgra4_from_rgba8(width, height, data),
+            // Dithering preserves gradients at the cost of some noise.
+            F::B5g6r5Unorm => b5g6r5_from_rgba8(width, height, data, quality == Quality::Slow),
+            F::Bgr5a1Unorm => bgr5a1_from_rgba8(width, height, data, quality == Quality::Slow),
+            F::Bgr5x1Unorm => bgr5x1_from_rgba8(width, height, data, quality == Quality::Slow),
+            F::Bgr8Unorm => bgr8_from_rgba8(width, height, data),
+            F::Bgrx8Unorm => bgrx8_from_rgba8(width, height, data),
+            F::La8Unorm => la8_from_rgba8(width, height, data),
+            F::A8Unorm => a8_from_rgba8(width, height, data),
+            F::Depth16Unorm => channels_from_rgba8::<u16, 1>(width, height, data),
+            F::Depth32Float => channels_from_rgba8::<f32, 1>(width, height, data),
+            F::Depth24UnormStencil8 | F::Depth32FloatStencil8 => {
+                // Depth stencil formats are encoded from floating point data.
+                let rgba: Vec<_> = data.iter().map(|u| *u as f32 / 255.0).collect();
+                f32::encode(width, height, &rgba, format, quality, matrix)
+            }
+            F::Yuy2 | F::Uyvy | F::Nv12 | F::P010 => {
+                // YUV formats are encoded from floating point data.
+                let rgba: Vec<_> = data.iter().map(|u| *u as f32 / 255.0).collect();
+                f32::encode(width, height, &rgba, format, quality, matrix)
+            }
+            F::Etc1RgbUnorm
+            | F::Etc2RgbUnorm
+            | F::Etc2RgbUnormSrgb
+            | F::Etc2Rgba1Unorm
+            | F::Etc2Rgba1UnormSrgb
+            | F::Etc2Rgba8Unorm
+            | F::Etc2Rgba8UnormSrgb
+            | F::EacR11Unorm
+            | F::EacR11Snorm
+            | F::EacRg11Unorm
+            | F::EacRg11Snorm
+            | F::Astc4x4RgbaUnorm
+            | F::Astc4x4RgbaUnormSrgb
+            | F::Astc4x4RgbaFloat
+            | F::Astc5x4RgbaUnorm
+            | F::Astc5x4RgbaUnormSrgb
+            | F::Astc5x4RgbaFloat
+            | F::Astc5x5RgbaUnorm
+            | F::Astc5x5RgbaUnormSrgb
+            | F::Astc5x5RgbaFloat
+            | F::Astc6x5RgbaUnorm
+            | F::Astc6x5RgbaUnormSrgb
+            | F::Astc6x5RgbaFloat
+            | F::Astc6x6RgbaUnorm
+            | F::Astc6x6RgbaUnormSrgb
+            | F::Astc6x6RgbaFloat
+            | F::Astc8x5RgbaUnorm
+            | F::Astc8x5RgbaUnormSrgb
+            | F::Astc8x5RgbaFloat
+            | F::Astc8x6RgbaUnorm
+            | F::Astc8x6RgbaUnormSrgb
+            | F::Astc8x6RgbaFloat
+            | F::Astc8x8RgbaUnorm
+            | F::Astc8x8RgbaUnormSrgb
+            | F::Astc8x8RgbaFloat
+            | F::Astc10x5RgbaUnorm
+            | F::Astc10x5RgbaUnormSrgb
+            | F::Astc10x5RgbaFloat
+            | F::Astc10x6RgbaUnorm
+            | F::Astc10x6RgbaUnormSrgb
+            | F::Astc10x6RgbaFloat
+            | F::Astc10x8RgbaUnorm
+            | F::Astc10x8RgbaUnormSrgb
+            | F::Astc10x8RgbaFloat
+            | F::Astc10x10RgbaUnorm
+            | F::Astc10x10RgbaUnormSrgb
+            | F::Astc10x10RgbaFloat
+            | F::Astc12x10RgbaUnorm
+            | F::Astc12x10RgbaUnormSrgb
+            | F::Astc12x10RgbaFloat
+            | F::Astc12x12RgbaUnorm
+            | F::Astc12x12RgbaUnormSrgb
+            | F::Astc12x12RgbaFloat
+            | F::R8Uint
+            | F::R8Sint
+            | F::R16Uint
+            | F::R16Sint
+            | F::R32Uint
+            | F::R32Sint
+            | F::Rgba8Uint
+            | F::Rgba8Sint
+            | F::Rgba16Uint
+            | F::Rgba16Sint
+            | F::Rgba32Uint
+            | F::Rgba32Sint => Err(SurfaceError::UnsupportedEncodeFormat { format }),
         }
     }
 }
@@ -440,13 +903,36 @@ impl Encode for f32 {
         data: &[Self],
         format: ImageFormat,
         quality: Quality,
+        matrix: YuvMatrix,
     ) -> Result<Vec<u8>, SurfaceError> {
         // Unorm and srgb only affect how the data is read.
         // Use the same conversion code for both.
         use ImageFormat as F;
         match format {
-            F::BC6hRgbUfloat | F::BC6hRgbSfloat => {
-                bcn_from_rgba::<Bc6, f32>(width, height, data, quality)
+            // Signed formats need to preserve negative values.
+            F::BC4RSnorm => bcn_from_rgba::<Bc4Signed, f32>(width, height, data, quality),
+            F::BC5RgSnorm => bcn_from_rgba::<Bc5Signed, f32>(width, height, data, quality),
+            F::BC6hRgbUfloat => bcn_from_rgba::<Bc6, f32>(width, height, data, quality),
+            F::BC6hRgbSfloat => bcn_from_rgba::<Bc6Signed, f32>(width, height, data, quality),
+            // Preserve the precision and range of 16-bit and floating point formats.
+            F::R16Unorm => channels_from_rgbaf32::<u16, 1>(width, height, data),
+            F::Rg16Unorm => channels_from_rgbaf32::<u16, 2>(width, height, data),
+            F::Rg16Snorm => channels_from_rgbaf32::<i16, 2>(width, height, data),
+            F::Rgba16Unorm => channels_from_rgbaf32::<u16, 4>(width, height, data),
+            F::Rgba16Snorm => channels_from_rgbaf32::<i16, 4>(width, height, data),
+            F::R16Float => channels_from_rgbaf32::<f16, 1>(width, height, data),
+            F::Rg16Float => channels_from_rgbaf32::<f16, 2>(width, height, data),
+            F::R32Float => channels_from_rgbaf32::<f32, 1>(width, height, data),
+            F::Rg32Float => channels_from_rgbaf32::<f32, 2>(width, height, data),
+            F::Rgb10a2Unorm => rgb10a2_from_rgbaf32(width, height, data),
+            F::Rg11b10Ufloat => rg11b10f_from_rgbaf32(width, height, data),
+            F::Rgb9e5Ufloat => rgb9e5_from_rgbaf32(width, height, data),
+            F::Depth16Unorm => channels_from_rgbaf32::<u16, 1>(width, height, data),
+            F::Depth24UnormStencil8 => d24s8_from_rgbaf32(width, height, data),
+            F::Depth32Float => channels_from_rgbaf32::<f32, 1>(width, height, data),
+            F::Depth32FloatStencil8 => d32fs8_from_rgbaf32(width, height, data),
+            F::Yuy2 | F::Uyvy | F::Nv12 | F::P010 => {
+                yuv_from_rgbaf32(width, height, format, data, matrix)
             }
             F::Rgba16Float => {
                 // TODO: Create conversion functions that don't require a cast?
@@ -458,12 +944,41 @@ impl Encode for f32 {
                 .map(|d| bytemuck::cast_slice(&d).to_vec()),
             _ => {
                 let rgba8: Vec<_> = data.iter().map(|f| (f * 255.0) as u8).collect();
-                u8::encode(width, height, &rgba8, format, quality)
+                u8::encode(width, height, &rgba8, format, quality, matrix)
             }
         }
     }
 }
 
+impl Encode for u32 {
+    fn encode(
+        width: u32,
+        height: u32,
+        data: &[Self],
+        format: ImageFormat,
+        _quality: Quality,
+        _matrix: YuvMatrix,
+    ) -> Result<Vec<u8>, SurfaceError> {
+        // Integer data is only encoded to integer formats to avoid normalizing values.
+        use ImageFormat as F;
+        match format {
+            F::R8Uint => channels_from_rgbau32::<u8, 1>(width, height, data),
+            F::R8Sint => channels_from_rgbau32::<i8, 1>(width, height, data),
+            F::R16Uint => channels_from_rgbau32::<u16, 1>(width, height, data),
+            F::R16Sint => channels_from_rgbau32::<i16, 1>(width, height, data),
+            F::R32Uint => channels_from_rgbau32::<u32, 1>(width, height, data),
+            F::R32Sint => channels_from_rgbau32::<i32, 1>(width, height, data),
+            F::Rgba8Uint => channels_from_rgbau32::<u8, 4>(width, height, data),
+            F::Rgba8Sint => channels_from_rgbau32::<i8, 4>(width, height, data),
+            F::Rgba16Uint => channels_from_rgbau32::<u16, 4>(width, height, data),
+            F::Rgba16Sint => channels_from_rgbau32::<i16, 4>(width, height, data),
+            F::Rgba32Uint => channels_from_rgbau32::<u32, 4>(width, height, data),
+            F::Rgba32Sint => channels_from_rgbau32::<i32, 4>(width, height, data),
+            _ => Err(SurfaceError::UnsupportedEncodeFormat { format }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -477,6 +992,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 12 * 12 * 4],
         }
         .encode(
@@ -505,7 +1021,8 @@ mod tests {
             depth: 1,
             layers: 6,
             mipmaps: 3,
-            data: &[0u8; (4 * 4 + 2 * 2 + 1 * 1) * 6 * 4],
+            alpha_mode: AlphaMode::Straight,
+            data: &[0u8; (4 * 4 + 2 * 2 + 1) * 6 * 4],
         }
         .encode(
             ImageFormat::BC7RgbaUnormSrgb,
@@ -532,6 +1049,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 3,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 64 + 16 + 4],
         }
         .encode(
@@ -558,6 +1076,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 2,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 64 + 16],
         }
         .encode(
@@ -585,6 +1104,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 256],
         }
         .encode(
@@ -612,6 +1132,7 @@ mod tests {
             depth: 0,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: &[0u8; 0],
         }
         .encode(
@@ -638,6 +1159,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: &[0.0; 12 * 12 * 4],
         }
         .encode(
@@ -666,7 +1188,8 @@ mod tests {
             depth: 1,
             layers: 6,
             mipmaps: 3,
-            data: &[0.0; (4 * 4 + 2 * 2 + 1 * 1) * 6 * 4],
+            alpha_mode: AlphaMode::Straight,
+            data: &[0.0; (4 * 4 + 2 * 2 + 1) * 6 * 4],
         }
         .encode(
             ImageFormat::BC7RgbaUnormSrgb,
@@ -693,6 +1216,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 3,
+            alpha_mode: AlphaMode::Straight,
             data: &[0.0; 64 + 16 + 4],
         }
         .encode(
@@ -719,6 +1243,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 2,
+            alpha_mode: AlphaMode::Straight,
             data: &[0.0; 64 + 16],
         }
         .encode(
@@ -746,6 +1271,7 @@ mod tests {
             depth: 1,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: &[0.0; 256],
         }
         .encode(
@@ -773,6 +1299,7 @@ mod tests {
             depth: 0,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: &[0.0; 0],
         }
         .encode(
@@ -837,4 +1364,496 @@ mod tests {
         assert_eq!((4, 4, 1), physical_dimensions(2, 2, 1, (4, 4, 1)));
         assert_eq!((4, 4, 1), physical_dimensions(1, 1, 1, (4, 4, 1)));
     }
+
+    #[test]
+    fn physical_dimensions_astc() {
+        assert_eq!((10, 8, 1), physical_dimensions(7, 7, 1, (5, 4, 1)));
+        assert_eq!((24, 10, 1), physical_dimensions(13, 7, 1, (12, 10, 1)));
+        assert_eq!((12, 12, 1), physical_dimensions(1, 1, 1, (12, 12, 1)));
+    }
+
+    #[test]
+    fn encode_surface_float32_signed_round_trip() {
+        // Negative values should survive for signed formats.
+        let data: Vec<_> = (0..4 * 4)
+            .flat_map(|i| {
+                let v = i as f32 / 64.0;
+                [-0.5 - v, 0.5 + v, 0.0, 1.0]
+            })
+            .collect();
+
+        for format in [
+            ImageFormat::BC4RSnorm,
+            ImageFormat::BC5RgSnorm,
+            ImageFormat::BC6hRgbSfloat,
+            ImageFormat::Rg16Snorm,
+            ImageFormat::Rgba16Snorm,
+            ImageFormat::Rg16Float,
+            ImageFormat::Rg32Float,
+        ] {
+            let decoded = SurfaceRgba32Float {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &data,
+            }
+            .encode(format, Quality::Normal, Mipmaps::Disabled)
+            .unwrap()
+            .decode_rgbaf32()
+            .unwrap();
+
+            for (expected, actual) in data.chunks_exact(4).zip(decoded.data.chunks_exact(4)) {
+                assert!((expected[0] - actual[0]).abs() <= 0.025, "{format:?}");
+                if format != ImageFormat::BC4RSnorm {
+                    assert!((expected[1] - actual[1]).abs() <= 0.025, "{format:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encode_surface_float32_packed_hdr_round_trip() {
+        // Values above 1.0 should survive for packed floating point formats.
+        let data: Vec<_> = (0..4 * 4)
+            .flat_map(|i| {
+                let v = i as f32 / 4.0;
+                [v, 2.0 * v, 0.5 + v, 1.0]
+            })
+            .collect();
+
+        for format in [ImageFormat::Rg11b10Ufloat, ImageFormat::Rgb9e5Ufloat] {
+            let decoded = SurfaceRgba32Float {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &data,
+            }
+            .encode(format, Quality::Normal, Mipmaps::Disabled)
+            .unwrap()
+            .decode_rgbaf32()
+            .unwrap();
+
+            for (expected, actual) in data.iter().zip(decoded.data.iter()) {
+                assert!((expected - actual).abs() <= expected * 0.02, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn encode_surface_mipmap_filters() {
+        for filter in [
+            MipmapFilter::Box,
+            MipmapFilter::Triangle,
+            MipmapFilter::Kaiser,
+            MipmapFilter::Lanczos3,
+            MipmapFilter::Mitchell,
+            MipmapFilter::Nearest,
+            MipmapFilter::Majority,
+        ] {
+            let surface = SurfaceRgba8 {
+                width: 5,
+                height: 3,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &[64u8; 5 * 3 * 4],
+            }
+            .encode_with_mipmap_settings(
+                ImageFormat::Rgba8Unorm,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+                MipmapSettings {
+                    filter,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            // Solid colors should be preserved for odd dimensions.
+            assert_eq!(3, surface.mipmaps);
+            assert_eq!(vec![64u8; (5 * 3 + 2 + 1) * 4], surface.data);
+        }
+    }
+
+    #[test]
+    fn encode_surface_srgb_mipmaps() {
+        // Average black and white in linear space.
+        let data = [[0u8, 0, 0, 255], [255, 255, 255, 255]].repeat(2).concat();
+        let encode = |format, srgb| {
+            let surface = SurfaceRgba8 {
+                width: 2,
+                height: 2,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &data,
+            }
+            .encode_with_mipmap_settings(
+                format,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+                MipmapSettings {
+                    srgb,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            surface.data[2 * 2 * 4..].to_vec()
+        };
+
+        assert_eq!(
+            vec![188, 188, 188, 255],
+            encode(ImageFormat::Rgba8UnormSrgb, None)
+        );
+        assert_eq!(
+            vec![127, 127, 127, 255],
+            encode(ImageFormat::Rgba8UnormSrgb, Some(false))
+        );
+        assert_eq!(
+            vec![127, 127, 127, 255],
+            encode(ImageFormat::Rgba8Unorm, None)
+        );
+        assert_eq!(
+            vec![188, 188, 188, 255],
+            encode(ImageFormat::Rgba8Unorm, Some(true))
+        );
+    }
+
+    #[test]
+    fn encode_surface_float32_alpha_test_coverage() {
+        // Each 2x2 block has 3 opaque pixels on the left half and 1 on the right half.
+        let data: Vec<f32> = (0..8 * 8)
+            .flat_map(|i| {
+                let (x, y) = (i % 8, i / 8);
+                let opaque = if x < 4 {
+                    (x % 2, y % 2) != (1, 1)
+                } else {
+                    (x % 2, y % 2) == (0, 0)
+                };
+                [1.0, 1.0, 1.0, if opaque { 1.0 } else { 0.0 }]
+            })
+            .collect();
+        let encode = |alpha_test_reference| {
+            SurfaceRgba32Float {
+                width: 8,
+                height: 8,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &data,
+            }
+            .encode_with_mipmap_settings(
+                ImageFormat::Rgba32Float,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+                MipmapSettings {
+                    alpha_test_reference,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .decode_rgbaf32()
+            .unwrap()
+            .data
+        };
+        let coverage = |data: &[f32]| crate::filter::alpha_coverage(data, 0.9);
+        assert_eq!(0.5, coverage(&data));
+
+        // Scaling alpha keeps the coverage of each mipmap close to the base level.
+        let decoded = encode(Some(0.9));
+        let mip1 = &decoded[8 * 8 * 4..(8 * 8 + 4 * 4) * 4];
+        let mip2 = &decoded[(8 * 8 + 4 * 4) * 4..(8 * 8 + 4 * 4 + 2 * 2) * 4];
+        assert!((coverage(mip1) - 0.5).abs() <= 0.1, "{}", coverage(mip1));
+        assert!((coverage(mip2) - 0.5).abs() <= 0.1, "{}", coverage(mip2));
+
+        // Averaging alone makes every pixel in lower mipmaps fail the test.
+        let decoded = encode(None);
+        let mip1 = &decoded[8 * 8 * 4..(8 * 8 + 4 * 4) * 4];
+        assert_eq!(0.0, coverage(mip1));
+    }
+
+    #[test]
+    fn encode_surface_normal_map_mipmaps() {
+        // Average (1, 0, 0) and (0, 1, 0) and renormalize.
+        let data = [[255u8, 128, 128, 255], [128, 255, 128, 255]]
+            .repeat(2)
+            .concat();
+        let encode = |normal_map| {
+            SurfaceRgba8 {
+                width: 2,
+                height: 2,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &data,
+            }
+            .encode_with_mipmap_settings(
+                ImageFormat::Rgba8Unorm,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+                MipmapSettings {
+                    normal_map,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .data
+        };
+
+        assert_eq!(vec![191, 191, 128, 255], encode(None)[2 * 2 * 4..].to_vec());
+        assert_eq!(
+            vec![218, 218, 128, 255],
+            encode(Some(NormalMapLayout::Rgb))[2 * 2 * 4..].to_vec()
+        );
+
+        // The blue channel is only cleared after renormalizing.
+        let rg = encode(Some(NormalMapLayout::Rg));
+        assert_eq!(vec![255, 128, 0, 255], rg[..4].to_vec());
+        assert_eq!(vec![218, 218, 0, 255], rg[2 * 2 * 4..].to_vec());
+    }
+
+    #[test]
+    fn encode_surface_volume_mipmaps() {
+        // The depth also halves for each mipmap.
+        let data = [vec![0u8; 8 * 4 * 4], vec![255u8; 8 * 4 * 4]].concat();
+        let surface = SurfaceRgba8 {
+            width: 8,
+            height: 4,
+            depth: 2,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &data,
+        }
+        .encode(
+            ImageFormat::Rgba8Unorm,
+            Quality::Fast,
+            Mipmaps::GeneratedAutomatic,
+        )
+        .unwrap();
+
+        assert_eq!(4, surface.mipmaps);
+        assert_eq!(2, surface.depth);
+        assert_eq!(
+            [data, vec![127u8; (4 * 2 + 2 + 1) * 4]].concat(),
+            surface.data
+        );
+        assert_eq!(surface.data, surface.decode_rgba8().unwrap().data);
+    }
+
+    #[test]
+    fn encode_surface_volume_mipmaps_bc7() {
+        let data = [64u8, 128, 192, 255].repeat(8 * 8 * 3);
+        let surface = SurfaceRgba8 {
+            width: 8,
+            height: 8,
+            depth: 3,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &data,
+        }
+        .encode(
+            ImageFormat::BC7RgbaUnorm,
+            Quality::Fast,
+            Mipmaps::GeneratedAutomatic,
+        )
+        .unwrap();
+
+        // Mipmaps are 8x8x3, 4x4x1, 2x2x1, and 1x1x1.
+        assert_eq!(4, surface.mipmaps);
+        assert_eq!((4 * 3 + 1 + 1 + 1) * 16, surface.data.len());
+
+        // BCN compression is lossy even for solid colors.
+        let decoded = surface.decode_rgba8().unwrap();
+        let expected = [64u8, 128, 192, 255].repeat(8 * 8 * 3 + 4 * 4 + 2 * 2 + 1);
+        assert_eq!(3, decoded.depth);
+        assert_eq!(expected.len(), decoded.data.len());
+        assert!(expected
+            .iter()
+            .zip(&decoded.data)
+            .all(|(a, b)| a.abs_diff(*b) <= 2));
+    }
+
+    #[test]
+    fn encode_surface_integer_round_trip() {
+        // Values outside the normalized range and negative values are preserved.
+        let data = [70000, 1, 2, 3, -5i32 as u32, 0, 0, u32::MAX].repeat(2);
+        for format in [ImageFormat::Rgba32Uint, ImageFormat::Rgba32Sint] {
+            let surface = SurfaceRgba32Uint {
+                width: 2,
+                height: 2,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                alpha_mode: AlphaMode::Straight,
+                data: &data,
+            }
+            .encode(format, Quality::Fast, Mipmaps::Disabled)
+            .unwrap();
+
+            assert_eq!(data, surface.decode_rgbau32().unwrap().data);
+        }
+    }
+
+    #[test]
+    fn encode_surface_integer_clamp() {
+        let surface = SurfaceRgba32Uint {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &[300, -200i32 as u32, 0, 0],
+        };
+
+        let r8 = surface
+            .encode(ImageFormat::R8Uint, Quality::Fast, Mipmaps::Disabled)
+            .unwrap();
+        assert_eq!(vec![255], r8.data);
+
+        let rgba8 = surface
+            .encode(ImageFormat::Rgba8Sint, Quality::Fast, Mipmaps::Disabled)
+            .unwrap();
+        assert_eq!(vec![127, 128, 0, 0], rgba8.data);
+    }
+
+    #[test]
+    fn encode_surface_integer_mipmaps() {
+        // IDs should never be averaged into new values.
+        let ids = [1, 1, 9, 2, 1, 3, 2, 2];
+        let data: Vec<u32> = ids.into_iter().flat_map(|i| [i, 0, 0, 1]).collect();
+        let surface = SurfaceRgba32Uint {
+            width: 4,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &data,
+        };
+
+        let majority = surface
+            .encode(
+                ImageFormat::R16Uint,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+            )
+            .unwrap();
+        assert_eq!(3, majority.mipmaps);
+        let values: Vec<u16> = bytemuck::pod_collect_to_vec(&majority.data);
+        assert_eq!(vec![1, 1, 9, 2, 1, 3, 2, 2, 1, 2, 1], values);
+
+        let nearest = surface
+            .encode_with_mipmap_settings(
+                ImageFormat::R16Uint,
+                Quality::Fast,
+                Mipmaps::GeneratedAutomatic,
+                MipmapSettings {
+                    filter: MipmapFilter::Nearest,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let values: Vec<u16> = bytemuck::pod_collect_to_vec(&nearest.data);
+        assert_eq!(vec![1, 1, 9, 2, 1, 3, 2, 2, 1, 9, 1], values);
+    }
+
+    #[test]
+    fn encode_surface_integer_unsupported() {
+        let result = SurfaceRgba32Uint {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &[0u32; 4 * 4 * 4],
+        }
+        .encode(ImageFormat::BC7RgbaUnorm, Quality::Fast, Mipmaps::Disabled);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::UnsupportedEncodeFormat {
+                format: ImageFormat::BC7RgbaUnorm
+            })
+        ));
+
+        // Normalized data should not be encoded to integer formats.
+        let result = SurfaceRgba8 {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &[0u8; 4],
+        }
+        .encode(ImageFormat::R8Uint, Quality::Fast, Mipmaps::Disabled);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::UnsupportedEncodeFormat {
+                format: ImageFormat::R8Uint
+            })
+        ));
+    }
+
+    #[test]
+    fn encode_surface_nv12_odd_dimensions() {
+        let surface = SurfaceRgba8 {
+            width: 3,
+            height: 3,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &[128u8, 128, 128, 255].repeat(3 * 3),
+        }
+        .encode(
+            ImageFormat::Nv12,
+            Quality::Fast,
+            Mipmaps::GeneratedAutomatic,
+        )
+        .unwrap();
+
+        // Each mipmap is padded to even dimensions.
+        assert_eq!(2, surface.mipmaps);
+        assert_eq!(4 * 4 + 2 * 2 * 2 + 2 * 2 + 2, surface.data.len());
+
+        let rgba8 = surface.decode_rgba8().unwrap();
+        assert_eq!([128u8, 128, 128, 255].repeat(3 * 3 + 1), rgba8.data);
+    }
+
+    #[test]
+    fn encode_yuv_bt709() {
+        let surface = SurfaceRgba32Float {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: &[1.0f32, 0.0, 0.0, 1.0].repeat(2 * 2),
+        }
+        .encode_yuv(ImageFormat::P010, YuvMatrix::Bt709, Mipmaps::Disabled)
+        .unwrap();
+
+        let rgba = surface.decode_yuv_rgbaf32(YuvMatrix::Bt709).unwrap();
+        assert!((rgba.data[0] - 1.0).abs() < 0.01);
+        assert!(rgba.data[1] < 0.01 && rgba.data[2] < 0.01);
+
+        // Decoding with the wrong matrix shifts the colors.
+        let rgba = surface.decode_rgbaf32().unwrap();
+        assert!(rgba.data[0] < 0.95);
+    }
 }
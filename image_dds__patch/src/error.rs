@@ -43,6 +43,9 @@ pub enum SurfaceError {
     #[error("encoding data to format {format:?} is not supported")]
     UnsupportedEncodeFormat { format: ImageFormat },
 
+    #[error("decoding data from format {format:?} is not supported for this surface type")]
+    UnsupportedDecodeFormat { format: ImageFormat },
+
     #[error("mipmap count {mipmaps} exceeds the maximum value of {max_total_mipmaps}")]
     InvalidMipmapCount {
         mipmaps: u32,
@@ -50,6 +53,9 @@ pub enum SurfaceError {
         max_total_mipmaps: u32,
     },
 
+    #[error("surface with {layers} layers and depth {depth} is not a valid cube map. Expected a 2D surface with a multiple of 6 layers")]
+    InvalidCubeMap { layers: u32, depth: u32 },
+
     #[error("failed to get image data for layer {layer} mipmap {mipmap}")]
     MipmapDataOutOfBounds { layer: u32, mipmap: u32 },
 
@@ -0,0 +1,331 @@
+// ETC and EAC formats use 4x4 pixel blocks like BCN and share the same decoding code.
+use crate::bcn::{unorm8_from_snorm, BcnDecode, BLOCK_HEIGHT, BLOCK_WIDTH};
+
+const CHANNELS: usize = 4;
+
+pub struct Etc1;
+pub struct Etc2Rgb;
+pub struct Etc2Rgba1;
+pub struct Etc2Rgba8;
+pub struct EacR11;
+pub struct EacR11Signed;
+pub struct EacRg11;
+pub struct EacRg11Signed;
+
+impl BcnDecode<[u8; 4]> for Etc1 {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed = [[[0u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::etc1(
+            block,
+            bytemuck::cast_slice_mut(&mut decompressed),
+            BLOCK_WIDTH * CHANNELS,
+        );
+
+        decompressed
+    }
+}
+
+impl BcnDecode<[u8; 4]> for Etc2Rgb {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed = [[[0u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::etc2_rgb(
+            block,
+            bytemuck::cast_slice_mut(&mut decompressed),
+            BLOCK_WIDTH * CHANNELS,
+        );
+
+        decompressed
+    }
+}
+
+impl BcnDecode<[u8; 4]> for Etc2Rgba1 {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed = [[[0u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::etc2_rgba1(
+            block,
+            bytemuck::cast_slice_mut(&mut decompressed),
+            BLOCK_WIDTH * CHANNELS,
+        );
+
+        decompressed
+    }
+}
+
+impl BcnDecode<[u8; 4]> for Etc2Rgba8 {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed = [[[0u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::etc2_rgba8(
+            block,
+            bytemuck::cast_slice_mut(&mut decompressed),
+            BLOCK_WIDTH * CHANNELS,
+        );
+
+        decompressed
+    }
+}
+
+impl BcnDecode<[f32; 4]> for EacR11 {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed_r = [[0u16; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::eac_r11(block, decompressed_r.as_flattened_mut(), BLOCK_WIDTH);
+
+        // Use grayscale like BC4 to avoid confusing it with colored data.
+        decompressed_r.map(|row| {
+            row.map(|r| {
+                let r = unorm11_to_f32(r);
+                [r, r, r, 1.0]
+            })
+        })
+    }
+}
+
+impl BcnDecode<[u8; 4]> for EacR11 {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed_r = [[0u16; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::eac_r11(block, decompressed_r.as_flattened_mut(), BLOCK_WIDTH);
+
+        decompressed_r.map(|row| {
+            row.map(|r| {
+                let r = unorm8_from_unorm11(r);
+                [r, r, r, 255u8]
+            })
+        })
+    }
+}
+
+impl BcnDecode<[f32; 4]> for EacR11Signed {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed_r = [[0i16; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::eac_r11_signed(block, decompressed_r.as_flattened_mut(), BLOCK_WIDTH);
+
+        decompressed_r.map(|row| {
+            row.map(|r| {
+                let r = snorm11_to_f32(r);
+                [r, r, r, 1.0]
+            })
+        })
+    }
+}
+
+impl BcnDecode<[u8; 4]> for EacR11Signed {
+    type CompressedBlock = [u8; 8];
+
+    fn decompress_block(block: &[u8; 8]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            EacR11Signed::decompress_block(block);
+        decompressed.map(|row| {
+            row.map(|[r, _, _, _]| {
+                let r = unorm8_from_snorm(r);
+                [r, r, r, 255u8]
+            })
+        })
+    }
+}
+
+impl BcnDecode<[f32; 4]> for EacRg11 {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed_rg = [[[0u16; 2]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::eac_rg11(
+            block,
+            decompressed_rg.as_flattened_mut().as_flattened_mut(),
+            BLOCK_WIDTH * 2,
+        );
+
+        // Zero the blue channel like BC5.
+        decompressed_rg
+            .map(|row| row.map(|[r, g]| [unorm11_to_f32(r), unorm11_to_f32(g), 0.0, 1.0]))
+    }
+}
+
+impl BcnDecode<[u8; 4]> for EacRg11 {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed_rg = [[[0u16; 2]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::eac_rg11(
+            block,
+            decompressed_rg.as_flattened_mut().as_flattened_mut(),
+            BLOCK_WIDTH * 2,
+        );
+
+        decompressed_rg.map(|row| {
+            row.map(|[r, g]| [unorm8_from_unorm11(r), unorm8_from_unorm11(g), 0u8, 255u8])
+        })
+    }
+}
+
+impl BcnDecode<[f32; 4]> for EacRg11Signed {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let mut decompressed_rg = [[[0i16; 2]; BLOCK_WIDTH]; BLOCK_HEIGHT];
+
+        etcdec_rs::eac_rg11_signed(
+            block,
+            decompressed_rg.as_flattened_mut().as_flattened_mut(),
+            BLOCK_WIDTH * 2,
+        );
+
+        decompressed_rg
+            .map(|row| row.map(|[r, g]| [snorm11_to_f32(r), snorm11_to_f32(g), 0.0, 1.0]))
+    }
+}
+
+impl BcnDecode<[u8; 4]> for EacRg11Signed {
+    type CompressedBlock = [u8; 16];
+
+    fn decompress_block(block: &[u8; 16]) -> [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] {
+        let decompressed: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            EacRg11Signed::decompress_block(block);
+
+        // The blue channel is zero after remapping to be consistent with unsigned RG11.
+        decompressed.map(|row| {
+            row.map(|[r, g, _, _]| [unorm8_from_snorm(r), unorm8_from_snorm(g), 0u8, 255u8])
+        })
+    }
+}
+
+fn unorm11_to_f32(x: u16) -> f32 {
+    x as f32 / 2047.0
+}
+
+fn unorm8_from_unorm11(x: u16) -> u8 {
+    ((x as u32 * 255 + 1023) / 2047) as u8
+}
+
+fn snorm11_to_f32(x: i16) -> f32 {
+    x as f32 / 1023.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_etc1_individual_mode() {
+        // Base colors 0x88 and 0x44 with table 0 and all pixels using +8.
+        let block = [0x84, 0x84, 0x84, 0x00, 0x00, 0x00, 0xFF, 0xFF];
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Etc1::decompress_block(&block);
+        // The left 2x4 subblock uses the first color.
+        assert_eq!([0x88 + 8, 0x88 + 8, 0x88 + 8, 255], pixels[0][0]);
+        assert_eq!([0x88 + 8, 0x88 + 8, 0x88 + 8, 255], pixels[3][1]);
+        assert_eq!([0x44 + 8, 0x44 + 8, 0x44 + 8, 255], pixels[0][2]);
+        assert_eq!([0x44 + 8, 0x44 + 8, 0x44 + 8, 255], pixels[3][3]);
+    }
+
+    #[test]
+    fn decode_etc1_differential_flip() {
+        // Base color 31 and delta -1 for all channels with table 7.
+        // The flip bit splits the block into top and bottom subblocks.
+        let block = [0xFF; 8];
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Etc1::decompress_block(&block);
+        // Index 3 applies -183 to each channel.
+        assert_eq!([255 - 183, 255 - 183, 255 - 183, 255], pixels[0][3]);
+        assert_eq!([247 - 183, 247 - 183, 247 - 183, 255], pixels[3][0]);
+    }
+
+    #[test]
+    fn decode_etc2_planar_mode() {
+        // Only the blue channel overflows to select planar mode.
+        // Use a solid color with the same origin, horizontal, and vertical colors.
+        let r = 0b111111u64;
+        let g = 0b0111111u64;
+        let b = 0u64;
+        let block = (r << 57)
+            | ((g >> 6) << 56)
+            | ((g & 0x3F) << 49)
+            | (1 << 42) // overflow blue with B=0 and dB=-4
+            | (((r >> 1) << 34) | ((r & 1) << 32))
+            | (g << 25)
+            | (b << 19)
+            | (r << 13)
+            | (g << 6)
+            | b
+            | (1 << 33);
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            Etc2Rgb::decompress_block(&block.to_be_bytes());
+        assert_eq!([[[255, 126, 0, 255]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+    }
+
+    #[test]
+    fn decode_etc2_rgba1_transparent() {
+        // Differential mode with the opaque bit unset makes index 2 transparent.
+        let block = [0x80, 0x80, 0x80, 0, 0xFF, 0xFF, 0x00, 0x00];
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Etc2Rgba1::decompress_block(&block);
+        assert_eq!([[[0u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+
+        // Index 0 uses the base color without a modifier.
+        let block = [0x80, 0x80, 0x80, 0, 0, 0, 0, 0];
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Etc2Rgba1::decompress_block(&block);
+        assert_eq!([[[132, 132, 132, 255]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+    }
+
+    #[test]
+    fn decode_etc2_rgba8_alpha() {
+        // Base alpha 128 with multiplier 1 and modifier -15 for index 3.
+        let mut block = [0u8; 16];
+        block[..8].copy_from_slice(&[128, 0x10, 0x6D, 0xB6, 0xDB, 0x6D, 0xB6, 0xDB]);
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = Etc2Rgba8::decompress_block(&block);
+        // The color block is black with the +2 modifier.
+        assert_eq!([[[2, 2, 2, 128 - 15]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+    }
+
+    #[test]
+    fn decode_eac_r11_extremes() {
+        // Base 255 with modifier 14 * 15 * 8 clamps to 2047.
+        let block = [255, 0xF0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        let pixels: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = EacR11::decompress_block(&block);
+        assert_eq!([[[1.0, 1.0, 1.0, 1.0]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+
+        // Base 0 with modifier -15 * 15 * 8 clamps to 0.
+        let block = [0, 0xF0, 0x6D, 0xB6, 0xDB, 0x6D, 0xB6, 0xDB];
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] = EacR11::decompress_block(&block);
+        assert_eq!([[[0, 0, 0, 255]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+    }
+
+    #[test]
+    fn decode_eac_rg11_signed_zero() {
+        // A multiplier of 0 adds the modifier directly with table 13 index 4 adding 0.
+        let block = [0, 0x0D, 0x92, 0x49, 0x24, 0x92, 0x49, 0x24];
+        let block: [u8; 16] = [block, block].concat().try_into().unwrap();
+        let pixels: [[[f32; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            EacRg11Signed::decompress_block(&block);
+        assert_eq!([[[0.0, 0.0, 0.0, 1.0]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+
+        let pixels: [[[u8; 4]; BLOCK_WIDTH]; BLOCK_HEIGHT] =
+            EacRg11Signed::decompress_block(&block);
+        assert_eq!([[[128, 128, 0, 255]; BLOCK_WIDTH]; BLOCK_HEIGHT], pixels);
+    }
+
+    #[test]
+    fn unorm8_from_unorm11_range() {
+        assert_eq!(0, unorm8_from_unorm11(0));
+        assert_eq!(128, unorm8_from_unorm11(1028));
+        assert_eq!(255, unorm8_from_unorm11(2047));
+    }
+}
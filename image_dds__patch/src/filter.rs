@@ -0,0 +1,591 @@
+// Separable resampling filters for generating mipmaps.
+// The filter kernels and default parameters are similar to those used by NVTT.
+// https://github.com/castano/nvidia-texture-tools/blob/master/src/nvimage/Filter.cpp
+use std::f32::consts::PI;
+
+use crate::{downsample_rgba, MipmapFilter, Pixel};
+
+/// Downsample `data` to the new dimensions using the given `filter`.
+///
+/// If `srgb` is `true`, the RGB channels are filtered in linear space.
+pub fn downsample_rgba_filtered<T: Pixel>(
+    new_dimensions: (usize, usize, usize),
+    dimensions: (usize, usize, usize),
+    data: &[T],
+    filter: MipmapFilter,
+    srgb: bool,
+) -> Vec<T> {
+    // Selecting existing pixels is the same in linear and sRGB space.
+    let kernel = match filter {
+        MipmapFilter::Nearest => {
+            return downsample_rgba_point(new_dimensions, dimensions, data, false)
+        }
+        MipmapFilter::Majority => {
+            return downsample_rgba_point(new_dimensions, dimensions, data, true)
+        }
+        MipmapFilter::Box => Kernel::Box,
+        MipmapFilter::Triangle => Kernel::Triangle,
+        MipmapFilter::Kaiser => Kernel::Kaiser,
+        MipmapFilter::Lanczos3 => Kernel::Lanczos3,
+        MipmapFilter::Mitchell => Kernel::Mitchell,
+    };
+
+    if !srgb {
+        return downsample_rgba_with_filter(new_dimensions, dimensions, data, kernel);
+    }
+
+    // Averaging sRGB values directly makes lower mipmaps appear darker.
+    let (width, height, depth) = dimensions;
+    let linear: Vec<f32> = data[..width * height * depth * 4]
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let [r, g, b, a] = [0, 1, 2, 3].map(|c| pixel[c].to_f32() / T::NORMALIZED_MAX);
+            [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a]
+        })
+        .collect();
+
+    let linear = downsample_rgba_with_filter(new_dimensions, dimensions, &linear, kernel);
+
+    linear
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            [linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a]
+        })
+        .map(|v| T::from_f32_nearest(v * T::NORMALIZED_MAX))
+        .collect()
+}
+
+/// Downsample `data` to the new dimensions by selecting a pixel from each 2x2x2 region.
+///
+/// If `majority` is `true`, select the most common pixel instead of the first pixel.
+/// This never creates new pixel values, which is required for integer data like IDs.
+pub fn downsample_rgba_point<T: Default + Copy + PartialEq>(
+    new_dimensions: (usize, usize, usize),
+    dimensions: (usize, usize, usize),
+    data: &[T],
+    majority: bool,
+) -> Vec<T> {
+    let (new_width, new_height, new_depth) = new_dimensions;
+    let (width, height, depth) = dimensions;
+
+    let mut new_data = Vec::with_capacity(new_width * new_height * new_depth * 4);
+    for z in 0..new_depth {
+        for y in 0..new_height {
+            for x in 0..new_width {
+                // Use the same region as the box filter for odd dimensions.
+                let mut region: [&[T]; 8] = [&[]; 8];
+                let mut count = 0;
+                for sampled_z in (z * 2..z * 2 + 2).filter(|z| *z < depth) {
+                    for sampled_y in (y * 2..y * 2 + 2).filter(|y| *y < height) {
+                        for sampled_x in (x * 2..x * 2 + 2).filter(|x| *x < width) {
+                            let index =
+                                (sampled_z * width * height) + (sampled_y * width) + sampled_x;
+                            region[count] = &data[index * 4..index * 4 + 4];
+                            count += 1;
+                        }
+                    }
+                }
+                let region = &region[..count];
+
+                let mut pixel = region.first().copied();
+                if majority {
+                    // Only replace the selected pixel if a later pixel is strictly more common.
+                    let mut max_count = 0;
+                    for p in region {
+                        let count = region.iter().filter(|p2| p2 == &p).count();
+                        if count > max_count {
+                            max_count = count;
+                            pixel = Some(*p);
+                        }
+                    }
+                }
+
+                match pixel {
+                    Some(pixel) => new_data.extend_from_slice(pixel),
+                    None => new_data.extend_from_slice(&[T::default(); 4]),
+                }
+            }
+        }
+    }
+
+    new_data
+}
+
+/// The fraction of pixels in `data` with alpha above the normalized `reference` value.
+pub fn alpha_coverage<T: Pixel>(data: &[T], reference: f32) -> f32 {
+    let pixels = data.len() / 4;
+    if pixels == 0 {
+        return 0.0;
+    }
+
+    let passed = data
+        .chunks_exact(4)
+        .filter(|pixel| pixel[3].to_f32() / T::NORMALIZED_MAX > reference)
+        .count();
+    passed as f32 / pixels as f32
+}
+
+/// Scale the alpha in `data` so the result of [alpha_coverage] is close to `coverage`.
+pub fn scale_alpha_to_coverage<T: Pixel>(data: &mut [T], coverage: f32, reference: f32) {
+    // Coverage decreases as the reference increases, so use a binary search.
+    // Find the reference value for the unscaled data with the desired coverage.
+    let mut min = 0.0;
+    let mut max = 1.0;
+    let mut scaled_reference = reference;
+    for _ in 0..16 {
+        let current = alpha_coverage(data, scaled_reference);
+        if current < coverage {
+            max = scaled_reference;
+        } else if current > coverage {
+            min = scaled_reference;
+        } else {
+            break;
+        }
+        scaled_reference = (min + max) / 2.0;
+    }
+
+    // Map the new reference value to the original reference value.
+    if scaled_reference > 0.0 {
+        let scale = reference / scaled_reference;
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = (pixel[3].to_f32() / T::NORMALIZED_MAX * scale).min(1.0);
+            pixel[3] = T::from_f32_nearest(alpha * T::NORMALIZED_MAX);
+        }
+    }
+}
+
+/// Normalize the XYZ vectors stored in the RGB channels of `data`.
+///
+/// If `signed` is `false`, the values are first mapped to the range `-1.0` to `1.0`.
+pub fn renormalize_rgb<T: Pixel>(data: &mut [T], signed: bool) {
+    for pixel in data.chunks_exact_mut(4) {
+        let [x, y, z] = [0, 1, 2].map(|c| pixel[c].to_snorm(signed));
+        let length = (x * x + y * y + z * z).sqrt();
+
+        // Opposing vectors can cancel out, so point zero length vectors straight up.
+        let normal = if length > 0.0 {
+            [x / length, y / length, z / length]
+        } else {
+            [0.0, 0.0, 1.0]
+        };
+        for c in 0..3 {
+            pixel[c] = T::from_snorm(normal[c], signed);
+        }
+    }
+}
+
+fn downsample_rgba_with_filter<T: Pixel>(
+    new_dimensions: (usize, usize, usize),
+    dimensions: (usize, usize, usize),
+    data: &[T],
+    kernel: Kernel,
+) -> Vec<T> {
+    let (new_width, new_height, new_depth) = new_dimensions;
+    let (width, height, depth) = dimensions;
+    if kernel == Kernel::Box || width * height * depth == 0 {
+        // Use the faster 2x2x2 averaging for the box filter.
+        return downsample_rgba(new_width, new_height, new_depth, width, height, depth, data);
+    }
+
+    // Filter each dimension separately using floats to avoid rounding intermediate values.
+    let data: Vec<f32> = data[..width * height * depth * 4]
+        .iter()
+        .map(Pixel::to_f32)
+        .collect();
+
+    let data = resample_axis(&data, (width, height, depth), new_width, 0, kernel);
+    let data = resample_axis(&data, (new_width, height, depth), new_height, 1, kernel);
+    let data = resample_axis(&data, (new_width, new_height, depth), new_depth, 2, kernel);
+
+    // Filtering produces fractional values even for integer pixels.
+    data.into_iter().map(T::from_f32_nearest).collect()
+}
+
+fn resample_axis(
+    data: &[f32],
+    dimensions: (usize, usize, usize),
+    new_size: usize,
+    axis: usize,
+    kernel: Kernel,
+) -> Vec<f32> {
+    let (width, height, depth) = dimensions;
+    let size = [width, height, depth][axis];
+    if size == new_size {
+        return data.to_vec();
+    }
+
+    // Adjacent pixels along the axis are stride elements apart.
+    let stride = [1, width, width * height][axis] * 4;
+    let weights = filter_weights(size, new_size, kernel);
+
+    let mut new_dimensions = [width, height, depth];
+    new_dimensions[axis] = new_size;
+    let [new_width, new_height, new_depth] = new_dimensions;
+
+    let mut new_data = vec![0.0; new_width * new_height * new_depth * 4];
+    for z in 0..new_depth {
+        for y in 0..new_height {
+            for x in 0..new_width {
+                let mut position = [x, y, z];
+                let i = position[axis];
+                position[axis] = 0;
+                let [x0, y0, z0] = position;
+                let base = ((z0 * height + y0) * width + x0) * 4;
+
+                let new_index = ((z * new_height + y) * new_width + x) * 4;
+                for (j, w) in &weights[i] {
+                    for c in 0..4 {
+                        new_data[new_index + c] += data[base + j * stride + c] * w;
+                    }
+                }
+            }
+        }
+    }
+
+    new_data
+}
+
+/// Calculate the normalized source pixel weights for each destination pixel.
+fn filter_weights(size: usize, new_size: usize, kernel: Kernel) -> Vec<Vec<(usize, f32)>> {
+    // Widen the filter when downsampling to avoid aliasing.
+    let scale = size as f32 / new_size as f32;
+    let support = filter_radius(kernel) * scale;
+
+    (0..new_size)
+        .map(|i| {
+            // Compare pixel centers in the source image.
+            let center = (i as f32 + 0.5) * scale;
+            let start = (center - support).floor() as isize;
+            let end = (center + support).ceil() as isize;
+
+            let mut weights: Vec<(usize, f32)> = Vec::new();
+            for j in start..=end {
+                let w = evaluate_filter(kernel, (j as f32 + 0.5 - center) / scale);
+                if w != 0.0 {
+                    // Clamp to the edge of the image.
+                    let index = j.clamp(0, size as isize - 1) as usize;
+                    match weights.iter_mut().find(|(k, _)| *k == index) {
+                        Some((_, total)) => *total += w,
+                        None => weights.push((index, w)),
+                    }
+                }
+            }
+
+            let sum: f32 = weights.iter().map(|(_, w)| w).sum();
+            if sum != 0.0 {
+                for (_, w) in &mut weights {
+                    *w /= sum;
+                }
+            }
+            weights
+        })
+        .collect()
+}
+
+// The filters that blend pixels using weights.
+// Point filters are handled separately since they only select existing pixels.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Kernel {
+    Box,
+    Triangle,
+    Kaiser,
+    Lanczos3,
+    Mitchell,
+}
+
+fn filter_radius(kernel: Kernel) -> f32 {
+    match kernel {
+        Kernel::Box => 0.5,
+        Kernel::Triangle => 1.0,
+        Kernel::Kaiser => 3.0,
+        Kernel::Lanczos3 => 3.0,
+        Kernel::Mitchell => 2.0,
+    }
+}
+
+fn evaluate_filter(kernel: Kernel, x: f32) -> f32 {
+    let x = x.abs();
+    match kernel {
+        Kernel::Box => {
+            if x <= 0.5 {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        Kernel::Triangle => (1.0 - x).max(0.0),
+        Kernel::Kaiser => {
+            let radius = filter_radius(kernel);
+            if x < radius {
+                // Kaiser window with alpha = 4.0.
+                let alpha = 4.0;
+                let t = x / radius;
+                sinc(x) * bessel_i0(alpha * (1.0 - t * t).sqrt()) / bessel_i0(alpha)
+            } else {
+                0.0
+            }
+        }
+        Kernel::Lanczos3 => {
+            if x < 3.0 {
+                sinc(x) * sinc(x / 3.0)
+            } else {
+                0.0
+            }
+        }
+        Kernel::Mitchell => mitchell(x, 1.0 / 3.0, 1.0 / 3.0),
+    }
+}
+
+// https://en.wikipedia.org/wiki/SRGB#Transformation
+fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(x: f32) -> f32 {
+    if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-4 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+fn bessel_i0(x: f32) -> f32 {
+    // Power series for the zeroth order modified Bessel function of the first kind.
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-7 {
+        term *= (x / (2.0 * k)) * (x / (2.0 * k));
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+fn mitchell(x: f32, b: f32, c: f32) -> f32 {
+    // Mitchell-Netravali cubic filter.
+    if x < 1.0 {
+        ((12.0 - 9.0 * b - 6.0 * c) * x * x * x
+            + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+            + (6.0 - 2.0 * b))
+            / 6.0
+    } else if x < 2.0 {
+        ((-b - 6.0 * c) * x * x * x
+            + (6.0 * b + 30.0 * c) * x * x
+            + (-12.0 * b - 48.0 * c) * x
+            + (8.0 * b + 24.0 * c))
+            / 6.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILTERS: [MipmapFilter; 5] = [
+        MipmapFilter::Box,
+        MipmapFilter::Triangle,
+        MipmapFilter::Kaiser,
+        MipmapFilter::Lanczos3,
+        MipmapFilter::Mitchell,
+    ];
+
+    #[test]
+    fn downsample_rgba8_constant() {
+        // Normalized weights and point filters preserve solid colors.
+        let original = [10u8, 20, 30, 40].repeat(8 * 6);
+        for filter in FILTERS
+            .into_iter()
+            .chain([MipmapFilter::Nearest, MipmapFilter::Majority])
+        {
+            assert_eq!(
+                [10u8, 20, 30, 40].repeat(4 * 3),
+                downsample_rgba_filtered((4, 3, 1), (8, 6, 1), &original, filter, false),
+                "{filter:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn downsample_rgbaf32_constant_3d() {
+        let original = [0.5f32, -1.0, 2.0, 1.0].repeat(4 * 4 * 4);
+        for filter in FILTERS {
+            let data = downsample_rgba_filtered((2, 2, 2), (4, 4, 4), &original, filter, false);
+            for (a, b) in data.iter().zip([0.5f32, -1.0, 2.0, 1.0].repeat(2 * 2 * 2)) {
+                assert!((a - b).abs() < 1e-5, "{filter:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn downsample_rgba8_triangle_3x1() {
+        // The last column of odd dimensions contributes to the result.
+        let original = [[0u8; 4], [0; 4], [255; 4]].concat();
+        let data = downsample_rgba_filtered(
+            (1, 1, 1),
+            (3, 1, 1),
+            &original,
+            MipmapFilter::Triangle,
+            false,
+        );
+        assert_eq!(vec![85; 4], data);
+    }
+
+    #[test]
+    fn downsample_rgba8_box_matches_average() {
+        let original: Vec<_> = (0..4 * 4 * 4).map(|i| i as u8).collect();
+        assert_eq!(
+            downsample_rgba(2, 2, 1, 4, 4, 1, &original),
+            downsample_rgba_filtered((2, 2, 1), (4, 4, 1), &original, MipmapFilter::Box, false)
+        );
+    }
+
+    #[test]
+    fn downsample_rgba_point_nearest() {
+        // Each pixel is the first pixel of its 2x2 region.
+        let original: Vec<u32> = (0..4 * 2).flat_map(|i| [i; 4]).collect();
+        assert_eq!(
+            vec![0, 0, 0, 0, 2, 2, 2, 2],
+            downsample_rgba_point((2, 1, 1), (4, 2, 1), &original, false)
+        );
+    }
+
+    #[test]
+    fn downsample_rgba_point_majority() {
+        // The most common pixel wins, and ties use the first pixel.
+        let original: Vec<u32> = [1, 7, 3, 4, 7, 7, 4, 3]
+            .into_iter()
+            .flat_map(|i| [i; 4])
+            .collect();
+        assert_eq!(
+            vec![7, 7, 7, 7, 3, 3, 3, 3],
+            downsample_rgba_point((2, 1, 1), (4, 2, 1), &original, true)
+        );
+    }
+
+    #[test]
+    fn downsample_rgba_point_majority_3d() {
+        // Compare entire pixels across both depth slices.
+        let original = [
+            [1u8, 2, 3, 4],
+            [5, 6, 7, 8],
+            [1, 2, 3, 0],
+            [5, 6, 7, 8],
+            [9, 9, 9, 9],
+            [5, 6, 7, 8],
+            [1, 2, 3, 4],
+            [1, 2, 3, 0],
+        ]
+        .concat();
+        assert_eq!(
+            vec![5, 6, 7, 8],
+            downsample_rgba_point((1, 1, 1), (2, 2, 2), &original, true)
+        );
+    }
+
+    #[test]
+    fn downsample_rgba_point_0x0() {
+        assert_eq!(
+            vec![0u32; 4],
+            downsample_rgba_point((1, 1, 1), (0, 0, 1), &[], true)
+        );
+    }
+
+    #[test]
+    fn alpha_coverage_rgba8() {
+        let data = [
+            [0u8, 0, 0, 64],
+            [0, 0, 0, 128],
+            [0, 0, 0, 192],
+            [0, 0, 0, 255],
+        ]
+        .concat();
+        assert_eq!(0.75, alpha_coverage(&data, 0.5));
+        assert_eq!(1.0, alpha_coverage(&data, 0.0));
+        assert_eq!(0.0, alpha_coverage(&data, 1.0));
+        assert_eq!(0.0, alpha_coverage::<u8>(&[], 0.5));
+    }
+
+    #[test]
+    fn scale_alpha_to_coverage_rgba8() {
+        // Averaging lowers alpha, so only 1 of 4 pixels passes the test.
+        let mut data = [
+            [0u8, 0, 0, 96],
+            [0, 0, 0, 112],
+            [0, 0, 0, 120],
+            [0, 0, 0, 160],
+        ]
+        .concat();
+        scale_alpha_to_coverage(&mut data, 0.75, 0.5);
+        assert_eq!(0.75, alpha_coverage(&data, 0.5));
+    }
+
+    #[test]
+    fn scale_alpha_to_coverage_rgbaf32() {
+        let mut data = [
+            [0.0f32, 0.0, 0.0, 0.1],
+            [0.0, 0.0, 0.0, 0.2],
+            [0.0, 0.0, 0.0, 0.3],
+        ]
+        .concat();
+        scale_alpha_to_coverage(&mut data, 1.0 / 3.0, 0.5);
+        assert!((alpha_coverage(&data, 0.5) - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn renormalize_rgb_rgba8() {
+        // The average of (1, 0, 0) and (0, 1, 0) is too short.
+        let mut data = vec![191u8, 191, 128, 255, 128, 128, 255, 64];
+        renormalize_rgb(&mut data, false);
+        assert_eq!(vec![218, 218, 128, 255, 128, 128, 255, 64], data);
+    }
+
+    #[test]
+    fn renormalize_rgb_rgbaf32() {
+        let mut data = vec![0.0f32, -3.0, 4.0, 1.0, 0.0, 0.0, 0.0, 0.5];
+        renormalize_rgb(&mut data, true);
+        assert_eq!(vec![0.0, -0.6, 0.8, 1.0, 0.0, 0.0, 1.0, 0.5], data);
+    }
+
+    #[test]
+    fn filter_weights_normalized() {
+        for kernel in [
+            Kernel::Box,
+            Kernel::Triangle,
+            Kernel::Kaiser,
+            Kernel::Lanczos3,
+            Kernel::Mitchell,
+        ] {
+            for (size, new_size) in [(8, 4), (5, 2), (2, 1), (1, 1)] {
+                for weights in filter_weights(size, new_size, kernel) {
+                    let sum: f32 = weights.iter().map(|(_, w)| w).sum();
+                    assert!((sum - 1.0).abs() < 1e-5, "{kernel:?}");
+                    assert!(weights.iter().all(|(i, _)| *i < size));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filter_kernels_at_zero() {
+        assert_eq!(1.0, evaluate_filter(Kernel::Triangle, 0.0));
+        assert_eq!(1.0, evaluate_filter(Kernel::Kaiser, 0.0));
+        assert_eq!(1.0, evaluate_filter(Kernel::Lanczos3, 0.0));
+        assert!((evaluate_filter(Kernel::Mitchell, 0.0) - 8.0 / 9.0).abs() < 1e-6);
+    }
+}
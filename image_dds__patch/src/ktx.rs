@@ -0,0 +1,1196 @@
+// KTX 1.1 reading and writing for OpenGL and OpenGL ES textures.
+// https://registry.khronos.org/KTX/specs/1.0/ktxspec.v1.html
+use thiserror::Error;
+
+use crate::{div_round_up, mip_dimension, round_up, AlphaMode, ImageFormat, Surface, SurfaceError};
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const HEADER_SIZE: usize = 64;
+const ENDIANNESS: u32 = 0x04030201;
+
+const GL_BYTE: u32 = 0x1400;
+const GL_UNSIGNED_BYTE: u32 = 0x1401;
+const GL_SHORT: u32 = 0x1402;
+const GL_UNSIGNED_SHORT: u32 = 0x1403;
+const GL_INT: u32 = 0x1404;
+const GL_UNSIGNED_INT: u32 = 0x1405;
+const GL_FLOAT: u32 = 0x1406;
+const GL_HALF_FLOAT: u32 = 0x140B;
+const GL_UNSIGNED_SHORT_5_6_5: u32 = 0x8363;
+const GL_UNSIGNED_SHORT_4_4_4_4_REV: u32 = 0x8365;
+const GL_UNSIGNED_SHORT_1_5_5_5_REV: u32 = 0x8366;
+const GL_UNSIGNED_INT_2_10_10_10_REV: u32 = 0x8368;
+const GL_UNSIGNED_INT_10F_11F_11F_REV: u32 = 0x8C3B;
+const GL_UNSIGNED_INT_5_9_9_9_REV: u32 = 0x8C3E;
+const GL_FLOAT_32_UNSIGNED_INT_24_8_REV: u32 = 0x8DAD;
+
+const GL_RED: u32 = 0x1903;
+const GL_RG: u32 = 0x8227;
+const GL_RGB: u32 = 0x1907;
+const GL_RGBA: u32 = 0x1908;
+const GL_BGR: u32 = 0x80E0;
+const GL_BGRA: u32 = 0x80E1;
+const GL_ALPHA: u32 = 0x1906;
+const GL_LUMINANCE_ALPHA: u32 = 0x190A;
+const GL_RED_INTEGER: u32 = 0x8D94;
+const GL_RGBA_INTEGER: u32 = 0x8D99;
+const GL_DEPTH_COMPONENT: u32 = 0x1902;
+const GL_DEPTH_STENCIL: u32 = 0x84F9;
+
+const GL_R8: u32 = 0x8229;
+const GL_RG8: u32 = 0x822B;
+const GL_RGB5: u32 = 0x8050;
+const GL_RGB8: u32 = 0x8051;
+const GL_RGB565: u32 = 0x8D62;
+const GL_RGBA4: u32 = 0x8056;
+const GL_RGB5_A1: u32 = 0x8057;
+const GL_RGBA8: u32 = 0x8058;
+const GL_ALPHA8: u32 = 0x803C;
+const GL_LUMINANCE8: u32 = 0x8040;
+const GL_LUMINANCE16: u32 = 0x8042;
+const GL_LUMINANCE8_ALPHA8: u32 = 0x8045;
+const GL_SRGB8_ALPHA8: u32 = 0x8C43;
+const GL_R16: u32 = 0x822A;
+const GL_RG16: u32 = 0x822C;
+const GL_RG16_SNORM: u32 = 0x8F99;
+const GL_RGBA16: u32 = 0x805B;
+const GL_RGBA16_SNORM: u32 = 0x8F9B;
+const GL_R16F: u32 = 0x822D;
+const GL_RG16F: u32 = 0x822F;
+const GL_RGBA16F: u32 = 0x881A;
+const GL_R32F: u32 = 0x822E;
+const GL_RG32F: u32 = 0x8230;
+const GL_RGBA32F: u32 = 0x8814;
+const GL_RGB10_A2: u32 = 0x8059;
+const GL_R11F_G11F_B10F: u32 = 0x8C3A;
+const GL_RGB9_E5: u32 = 0x8C3D;
+const GL_R8UI: u32 = 0x8232;
+const GL_R8I: u32 = 0x8231;
+const GL_R16UI: u32 = 0x8234;
+const GL_R16I: u32 = 0x8233;
+const GL_R32UI: u32 = 0x8236;
+const GL_R32I: u32 = 0x8235;
+const GL_RGBA8UI: u32 = 0x8D7C;
+const GL_RGBA8I: u32 = 0x8D8E;
+const GL_RGBA16UI: u32 = 0x8D76;
+const GL_RGBA16I: u32 = 0x8D88;
+const GL_RGBA32UI: u32 = 0x8D70;
+const GL_RGBA32I: u32 = 0x8D82;
+const GL_DEPTH_COMPONENT16: u32 = 0x81A5;
+const GL_DEPTH_COMPONENT32F: u32 = 0x8CAC;
+const GL_DEPTH32F_STENCIL8: u32 = 0x8CAD;
+
+const GL_COMPRESSED_RGBA_S3TC_DXT1_EXT: u32 = 0x83F1;
+const GL_COMPRESSED_RGBA_S3TC_DXT3_EXT: u32 = 0x83F2;
+const GL_COMPRESSED_RGBA_S3TC_DXT5_EXT: u32 = 0x83F3;
+const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT: u32 = 0x8C4D;
+const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT: u32 = 0x8C4E;
+const GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT: u32 = 0x8C4F;
+const GL_COMPRESSED_RED_RGTC1: u32 = 0x8DBB;
+const GL_COMPRESSED_SIGNED_RED_RGTC1: u32 = 0x8DBC;
+const GL_COMPRESSED_RG_RGTC2: u32 = 0x8DBD;
+const GL_COMPRESSED_SIGNED_RG_RGTC2: u32 = 0x8DBE;
+const GL_COMPRESSED_RGBA_BPTC_UNORM: u32 = 0x8E8C;
+const GL_COMPRESSED_SRGB_ALPHA_BPTC_UNORM: u32 = 0x8E8D;
+const GL_COMPRESSED_RGB_BPTC_SIGNED_FLOAT: u32 = 0x8E8E;
+const GL_COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT: u32 = 0x8E8F;
+const GL_ETC1_RGB8_OES: u32 = 0x8D64;
+const GL_COMPRESSED_R11_EAC: u32 = 0x9270;
+const GL_COMPRESSED_SIGNED_R11_EAC: u32 = 0x9271;
+const GL_COMPRESSED_RG11_EAC: u32 = 0x9272;
+const GL_COMPRESSED_SIGNED_RG11_EAC: u32 = 0x9273;
+const GL_COMPRESSED_RGB8_ETC2: u32 = 0x9274;
+const GL_COMPRESSED_SRGB8_ETC2: u32 = 0x9275;
+const GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9276;
+const GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2: u32 = 0x9277;
+const GL_COMPRESSED_RGBA8_ETC2_EAC: u32 = 0x9278;
+const GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC: u32 = 0x9279;
+const GL_COMPRESSED_RGBA_ASTC_4X4: u32 = 0x93B0;
+const GL_COMPRESSED_RGBA_ASTC_5X4: u32 = 0x93B1;
+const GL_COMPRESSED_RGBA_ASTC_5X5: u32 = 0x93B2;
+const GL_COMPRESSED_RGBA_ASTC_6X5: u32 = 0x93B3;
+const GL_COMPRESSED_RGBA_ASTC_6X6: u32 = 0x93B4;
+const GL_COMPRESSED_RGBA_ASTC_8X5: u32 = 0x93B5;
+const GL_COMPRESSED_RGBA_ASTC_8X6: u32 = 0x93B6;
+const GL_COMPRESSED_RGBA_ASTC_8X8: u32 = 0x93B7;
+const GL_COMPRESSED_RGBA_ASTC_10X5: u32 = 0x93B8;
+const GL_COMPRESSED_RGBA_ASTC_10X6: u32 = 0x93B9;
+const GL_COMPRESSED_RGBA_ASTC_10X8: u32 = 0x93BA;
+const GL_COMPRESSED_RGBA_ASTC_10X10: u32 = 0x93BB;
+const GL_COMPRESSED_RGBA_ASTC_12X10: u32 = 0x93BC;
+const GL_COMPRESSED_RGBA_ASTC_12X12: u32 = 0x93BD;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4X4: u32 = 0x93D0;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X4: u32 = 0x93D1;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X5: u32 = 0x93D2;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X5: u32 = 0x93D3;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X6: u32 = 0x93D4;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X5: u32 = 0x93D5;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X6: u32 = 0x93D6;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X8: u32 = 0x93D7;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X5: u32 = 0x93D8;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X6: u32 = 0x93D9;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X8: u32 = 0x93DA;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X10: u32 = 0x93DB;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12X10: u32 = 0x93DC;
+const GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12X12: u32 = 0x93DD;
+
+/// Errors that can occur while reading or writing KTX files.
+#[derive(Debug, Error)]
+pub enum KtxError {
+    #[error("invalid KTX file identifier")]
+    InvalidIdentifier,
+
+    #[error("invalid KTX endianness {0:#x}")]
+    InvalidEndianness(u32),
+
+    #[error("expected KTX file to have at least {expected} bytes but found {actual}")]
+    NotEnoughData { expected: usize, actual: usize },
+
+    #[error("KTX format with glInternalFormat {gl_internal_format:#x} and glFormat {gl_format:#x} is not supported")]
+    UnsupportedFormat {
+        gl_internal_format: u32,
+        gl_format: u32,
+    },
+
+    #[error("image format {0:?} has no equivalent OpenGL format")]
+    UnsupportedImageFormat(ImageFormat),
+
+    #[error("error reading or writing surface: {0}")]
+    Surface(#[from] SurfaceError),
+}
+
+// The OpenGL enums describing an image format.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct GlFormat {
+    gl_type: u32,
+    gl_type_size: u32,
+    gl_format: u32,
+    gl_internal_format: u32,
+    gl_base_internal_format: u32,
+}
+
+impl Surface<Vec<u8>> {
+    /// Read a KTX 1.1 file from `bytes` in either little or big endian byte order.
+    ///
+    /// Cube map faces are stored as separate array layers like DDS.
+    /// The alpha mode is always [AlphaMode::Unknown].
+    pub fn from_ktx(bytes: &[u8]) -> Result<Self, KtxError> {
+        if bytes.get(..IDENTIFIER.len()) != Some(&IDENTIFIER) {
+            return Err(KtxError::InvalidIdentifier);
+        }
+
+        let is_big_endian = match read_u32(bytes, 12, false)? {
+            ENDIANNESS => false,
+            0x01020304 => true,
+            endianness => return Err(KtxError::InvalidEndianness(endianness)),
+        };
+        let header = |i: usize| read_u32(bytes, 16 + i * 4, is_big_endian);
+
+        let gl_type_size = header(1)?;
+        let gl_format = header(2)?;
+        let gl_internal_format = header(3)?;
+        let width = header(5)?;
+        let height = header(6)?.max(1);
+        let depth = header(7)?.max(1);
+        let array_elements = header(8)?;
+        let faces = header(9)?.max(1);
+        let mipmaps = header(10)?.max(1);
+        let key_value_data_size = header(11)? as usize;
+
+        let image_format = image_format_from_gl(gl_internal_format, gl_format).ok_or(
+            KtxError::UnsupportedFormat {
+                gl_internal_format,
+                gl_format,
+            },
+        )?;
+
+        // Faces are stored after each other for each layer like DDS cube maps.
+        let overflow = || SurfaceError::PixelCountWouldOverflow {
+            width,
+            height,
+            depth,
+        };
+        let layers = array_elements
+            .max(1)
+            .checked_mul(faces)
+            .ok_or_else(overflow)?;
+        let is_cubemap = faces == 6 && array_elements == 0;
+
+        // KTX uses level, layer, face, depth instead of layer, level, depth.
+        let mut levels = Vec::new();
+        let mut offset = HEADER_SIZE + key_value_data_size;
+        for mipmap in 0..mipmaps {
+            let image_size = read_u32(bytes, offset, is_big_endian)? as usize;
+            offset += 4;
+
+            // Non array cube maps store the size of a single face.
+            let level_size = if is_cubemap {
+                round_up(image_size, 4).checked_mul(6)
+            } else {
+                Some(image_size)
+            }
+            .ok_or_else(overflow)?;
+            let level_end = offset.checked_add(level_size).ok_or_else(overflow)?;
+
+            let level_data = bytes
+                .get(offset..level_end)
+                .ok_or(KtxError::NotEnoughData {
+                    expected: level_end,
+                    actual: bytes.len(),
+                })?;
+
+            let (row_size, rows) =
+                row_sizes(width, height, image_format, mipmap).ok_or_else(overflow)?;
+            let slices = mip_dimension(depth, mipmap) as usize;
+            let expected = round_up(row_size, 4)
+                .checked_mul(rows)
+                .and_then(|size| size.checked_mul(slices))
+                .ok_or_else(overflow)?;
+            let face_size = if is_cubemap {
+                round_up(expected, 4)
+            } else {
+                expected
+            };
+            let level_expected = face_size
+                .checked_mul(layers as usize)
+                .ok_or_else(overflow)?;
+            if level_data.len() < level_expected {
+                return Err(KtxError::NotEnoughData {
+                    expected: level_expected,
+                    actual: level_data.len(),
+                });
+            }
+
+            let faces = (0..layers as usize)
+                .map(|layer| {
+                    let face = &level_data[layer * face_size..layer * face_size + expected];
+                    remove_row_padding(face, row_size, rows * slices)
+                })
+                .collect::<Vec<_>>();
+            levels.push(faces);
+
+            offset += round_up(level_size, 4);
+        }
+
+        let mut data = Vec::new();
+        for layer in 0..layers as usize {
+            for level in &levels {
+                data.extend_from_slice(&level[layer]);
+            }
+        }
+
+        // Swap bytes for each element of data types larger than a single byte.
+        if is_big_endian && gl_type_size > 1 {
+            for element in data.chunks_exact_mut(gl_type_size as usize) {
+                element.reverse();
+            }
+        }
+
+        Ok(Surface {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps,
+            image_format,
+            // KTX 1.1 has no flag for premultiplied alpha.
+            alpha_mode: AlphaMode::Unknown,
+            data,
+        })
+    }
+}
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Create a little endian KTX 1.1 file with the same image data and format.
+    pub fn to_ktx(&self) -> Result<Vec<u8>, KtxError> {
+        self.create_ktx(false)
+    }
+
+    /// Create a little endian KTX 1.1 cube map file with the same image data and format.
+    ///
+    /// See [layers](#structfield.layers) for the order of the cube map faces.
+    pub fn to_ktx_cubemap(&self) -> Result<Vec<u8>, KtxError> {
+        self.validate_cube_map()?;
+        self.create_ktx(true)
+    }
+
+    fn create_ktx(&self, is_cubemap: bool) -> Result<Vec<u8>, KtxError> {
+        self.validate()?;
+
+        let gl = gl_format(self.image_format)
+            .ok_or(KtxError::UnsupportedImageFormat(self.image_format))?;
+        let (array_elements, faces) = match (is_cubemap, self.layers) {
+            (true, 6) => (0, 6),
+            (true, layers) => (layers / 6, 6),
+            (false, 1) => (0, 1),
+            (false, layers) => (layers, 1),
+        };
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&IDENTIFIER);
+        for value in [
+            ENDIANNESS,
+            gl.gl_type,
+            gl.gl_type_size,
+            gl.gl_format,
+            gl.gl_internal_format,
+            gl.gl_base_internal_format,
+            self.width,
+            self.height,
+            if self.depth > 1 { self.depth } else { 0 },
+            array_elements,
+            faces,
+            self.mipmaps,
+            0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        // KTX uses level, layer, face, depth instead of layer, level, depth.
+        for mipmap in 0..self.mipmaps {
+            let (row_size, _) = row_sizes(self.width, 1, self.image_format, mipmap).ok_or(
+                SurfaceError::PixelCountWouldOverflow {
+                    width: self.width,
+                    height: self.height,
+                    depth: self.depth,
+                },
+            )?;
+
+            let mut level_data = Vec::new();
+            let mut face_size = 0;
+            for layer in 0..self.layers {
+                let start = level_data.len();
+                for level in 0..mip_dimension(self.depth, mipmap) {
+                    let data = self
+                        .get(layer, level, mipmap)
+                        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                    add_row_padding(&mut level_data, data, row_size);
+                }
+                face_size = level_data.len() - start;
+
+                // Cube padding is only used for non array cube maps.
+                if array_elements == 0 && faces == 6 {
+                    level_data.resize(round_up(level_data.len(), 4), 0);
+                }
+            }
+
+            // Non array cube maps store the size of a single face.
+            let image_size = if array_elements == 0 && faces == 6 {
+                face_size
+            } else {
+                level_data.len()
+            };
+            bytes.extend_from_slice(&(image_size as u32).to_le_bytes());
+            bytes.extend_from_slice(&level_data);
+            bytes.resize(round_up(bytes.len(), 4), 0);
+        }
+
+        Ok(bytes)
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize, is_big_endian: bool) -> Result<u32, KtxError> {
+    let value: [u8; 4] = bytes
+        .get(offset..offset + 4)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(KtxError::NotEnoughData {
+            expected: offset + 4,
+            actual: bytes.len(),
+        })?;
+    if is_big_endian {
+        Ok(u32::from_be_bytes(value))
+    } else {
+        Ok(u32::from_le_bytes(value))
+    }
+}
+
+// The unpadded size of a row of pixels or blocks and the number of rows.
+fn row_sizes(width: u32, height: u32, format: ImageFormat, mipmap: u32) -> Option<(usize, usize)> {
+    let (block_width, block_height, _) = format.block_dimensions();
+    let columns = div_round_up(mip_dimension(width, mipmap) as usize, block_width as usize);
+    let rows = div_round_up(
+        mip_dimension(height, mipmap) as usize,
+        block_height as usize,
+    );
+    let row_size = columns.checked_mul(format.block_size_in_bytes())?;
+    Some((row_size, rows))
+}
+
+// Rows of pixels are padded to 4 bytes like the default OpenGL unpack alignment.
+fn add_row_padding(output: &mut Vec<u8>, data: &[u8], row_size: usize) {
+    for row in data.chunks_exact(row_size) {
+        output.extend_from_slice(row);
+        output.resize(output.len() + round_up(row_size, 4) - row_size, 0);
+    }
+}
+
+fn remove_row_padding(data: &[u8], row_size: usize, rows: usize) -> Vec<u8> {
+    data.chunks_exact(round_up(row_size, 4))
+        .take(rows)
+        .flat_map(|row| &row[..row_size])
+        .copied()
+        .collect()
+}
+
+fn image_format_from_gl(gl_internal_format: u32, gl_format: u32) -> Option<ImageFormat> {
+    match (gl_internal_format, gl_format) {
+        (GL_R8, _) => Some(ImageFormat::R8Unorm),
+        (GL_RG8, _) => Some(ImageFormat::Rg8Unorm),
+        (GL_RGBA8, GL_BGRA) => Some(ImageFormat::Bgra8Unorm),
+        (GL_RGBA8, _) => Some(ImageFormat::Rgba8Unorm),
+        (GL_SRGB8_ALPHA8, GL_BGRA) => Some(ImageFormat::Bgra8UnormSrgb),
+        (GL_SRGB8_ALPHA8, _) => Some(ImageFormat::Rgba8UnormSrgb),
+        (GL_RGBA4, GL_BGRA) => Some(ImageFormat::Bgra4Unorm),
+        (GL_RGB565, GL_RGB) => Some(ImageFormat::B5g6r5Unorm),
+        (GL_RGB5_A1, GL_BGRA) => Some(ImageFormat::Bgr5a1Unorm),
+        (GL_RGB5, GL_BGRA) => Some(ImageFormat::Bgr5x1Unorm),
+        (GL_RGB8, GL_BGRA) => Some(ImageFormat::Bgrx8Unorm),
+        (GL_RGB8, GL_BGR) => Some(ImageFormat::Bgr8Unorm),
+        (GL_RGB8, _) => Some(ImageFormat::Rgb8Unorm),
+        (GL_LUMINANCE8_ALPHA8, _) => Some(ImageFormat::La8Unorm),
+        (GL_ALPHA8, _) => Some(ImageFormat::A8Unorm),
+        // Luminance decodes to grayscale like single channel formats.
+        (GL_LUMINANCE8, _) => Some(ImageFormat::R8Unorm),
+        (GL_LUMINANCE16, _) => Some(ImageFormat::R16Unorm),
+        (GL_R16, _) => Some(ImageFormat::R16Unorm),
+        (GL_RG16, _) => Some(ImageFormat::Rg16Unorm),
+        (GL_RG16_SNORM, _) => Some(ImageFormat::Rg16Snorm),
+        (GL_RGBA16, _) => Some(ImageFormat::Rgba16Unorm),
+        (GL_RGBA16_SNORM, _) => Some(ImageFormat::Rgba16Snorm),
+        (GL_R16F, _) => Some(ImageFormat::R16Float),
+        (GL_RG16F, _) => Some(ImageFormat::Rg16Float),
+        (GL_RGBA16F, _) => Some(ImageFormat::Rgba16Float),
+        (GL_R32F, _) => Some(ImageFormat::R32Float),
+        (GL_RG32F, _) => Some(ImageFormat::Rg32Float),
+        (GL_RGBA32F, _) => Some(ImageFormat::Rgba32Float),
+        (GL_RGB10_A2, _) => Some(ImageFormat::Rgb10a2Unorm),
+        (GL_R11F_G11F_B10F, _) => Some(ImageFormat::Rg11b10Ufloat),
+        (GL_RGB9_E5, _) => Some(ImageFormat::Rgb9e5Ufloat),
+        (GL_DEPTH_COMPONENT16, _) => Some(ImageFormat::Depth16Unorm),
+        (GL_DEPTH_COMPONENT32F, _) => Some(ImageFormat::Depth32Float),
+        (GL_DEPTH32F_STENCIL8, _) => Some(ImageFormat::Depth32FloatStencil8),
+        (GL_R8UI, _) => Some(ImageFormat::R8Uint),
+        (GL_R8I, _) => Some(ImageFormat::R8Sint),
+        (GL_R16UI, _) => Some(ImageFormat::R16Uint),
+        (GL_R16I, _) => Some(ImageFormat::R16Sint),
+        (GL_R32UI, _) => Some(ImageFormat::R32Uint),
+        (GL_R32I, _) => Some(ImageFormat::R32Sint),
+        (GL_RGBA8UI, _) => Some(ImageFormat::Rgba8Uint),
+        (GL_RGBA8I, _) => Some(ImageFormat::Rgba8Sint),
+        (GL_RGBA16UI, _) => Some(ImageFormat::Rgba16Uint),
+        (GL_RGBA16I, _) => Some(ImageFormat::Rgba16Sint),
+        (GL_RGBA32UI, _) => Some(ImageFormat::Rgba32Uint),
+        (GL_RGBA32I, _) => Some(ImageFormat::Rgba32Sint),
+        (GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, _) => Some(ImageFormat::BC1RgbaUnorm),
+        (GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT, _) => Some(ImageFormat::BC1RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, _) => Some(ImageFormat::BC2RgbaUnorm),
+        (GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT, _) => Some(ImageFormat::BC2RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, _) => Some(ImageFormat::BC3RgbaUnorm),
+        (GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT, _) => Some(ImageFormat::BC3RgbaUnormSrgb),
+        (GL_COMPRESSED_RED_RGTC1, _) => Some(ImageFormat::BC4RUnorm),
+        (GL_COMPRESSED_SIGNED_RED_RGTC1, _) => Some(ImageFormat::BC4RSnorm),
+        (GL_COMPRESSED_RG_RGTC2, _) => Some(ImageFormat::BC5RgUnorm),
+        (GL_COMPRESSED_SIGNED_RG_RGTC2, _) => Some(ImageFormat::BC5RgSnorm),
+        (GL_COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT, _) => Some(ImageFormat::BC6hRgbUfloat),
+        (GL_COMPRESSED_RGB_BPTC_SIGNED_FLOAT, _) => Some(ImageFormat::BC6hRgbSfloat),
+        (GL_COMPRESSED_RGBA_BPTC_UNORM, _) => Some(ImageFormat::BC7RgbaUnorm),
+        (GL_COMPRESSED_SRGB_ALPHA_BPTC_UNORM, _) => Some(ImageFormat::BC7RgbaUnormSrgb),
+        (GL_ETC1_RGB8_OES, _) => Some(ImageFormat::Etc1RgbUnorm),
+        (GL_COMPRESSED_RGB8_ETC2, _) => Some(ImageFormat::Etc2RgbUnorm),
+        (GL_COMPRESSED_SRGB8_ETC2, _) => Some(ImageFormat::Etc2RgbUnormSrgb),
+        (GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2, _) => Some(ImageFormat::Etc2Rgba1Unorm),
+        (GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2, _) => Some(ImageFormat::Etc2Rgba1UnormSrgb),
+        (GL_COMPRESSED_RGBA8_ETC2_EAC, _) => Some(ImageFormat::Etc2Rgba8Unorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC, _) => Some(ImageFormat::Etc2Rgba8UnormSrgb),
+        (GL_COMPRESSED_R11_EAC, _) => Some(ImageFormat::EacR11Unorm),
+        (GL_COMPRESSED_SIGNED_R11_EAC, _) => Some(ImageFormat::EacR11Snorm),
+        (GL_COMPRESSED_RG11_EAC, _) => Some(ImageFormat::EacRg11Unorm),
+        (GL_COMPRESSED_SIGNED_RG11_EAC, _) => Some(ImageFormat::EacRg11Snorm),
+        (GL_COMPRESSED_RGBA_ASTC_4X4, _) => Some(ImageFormat::Astc4x4RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4X4, _) => Some(ImageFormat::Astc4x4RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_5X4, _) => Some(ImageFormat::Astc5x4RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X4, _) => Some(ImageFormat::Astc5x4RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_5X5, _) => Some(ImageFormat::Astc5x5RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X5, _) => Some(ImageFormat::Astc5x5RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_6X5, _) => Some(ImageFormat::Astc6x5RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X5, _) => Some(ImageFormat::Astc6x5RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_6X6, _) => Some(ImageFormat::Astc6x6RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X6, _) => Some(ImageFormat::Astc6x6RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_8X5, _) => Some(ImageFormat::Astc8x5RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X5, _) => Some(ImageFormat::Astc8x5RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_8X6, _) => Some(ImageFormat::Astc8x6RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X6, _) => Some(ImageFormat::Astc8x6RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_8X8, _) => Some(ImageFormat::Astc8x8RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X8, _) => Some(ImageFormat::Astc8x8RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_10X5, _) => Some(ImageFormat::Astc10x5RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X5, _) => Some(ImageFormat::Astc10x5RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_10X6, _) => Some(ImageFormat::Astc10x6RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X6, _) => Some(ImageFormat::Astc10x6RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_10X8, _) => Some(ImageFormat::Astc10x8RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X8, _) => Some(ImageFormat::Astc10x8RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_10X10, _) => Some(ImageFormat::Astc10x10RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X10, _) => Some(ImageFormat::Astc10x10RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_12X10, _) => Some(ImageFormat::Astc12x10RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12X10, _) => Some(ImageFormat::Astc12x10RgbaUnormSrgb),
+        (GL_COMPRESSED_RGBA_ASTC_12X12, _) => Some(ImageFormat::Astc12x12RgbaUnorm),
+        (GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12X12, _) => Some(ImageFormat::Astc12x12RgbaUnormSrgb),
+        _ => None,
+    }
+}
+
+fn gl_format(format: ImageFormat) -> Option<GlFormat> {
+    // Compressed formats use 0 for the type and format.
+    let uncompressed = |gl_type, gl_type_size, gl_format, gl_internal_format| GlFormat {
+        gl_type,
+        gl_type_size,
+        gl_format,
+        gl_internal_format,
+        gl_base_internal_format: GL_RGBA,
+    };
+    let compressed = |gl_internal_format, gl_base_internal_format| GlFormat {
+        gl_type: 0,
+        gl_type_size: 1,
+        gl_format: 0,
+        gl_internal_format,
+        gl_base_internal_format,
+    };
+
+    Some(match format {
+        ImageFormat::R8Unorm => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_RED, GL_R8)
+        },
+        ImageFormat::Rg8Unorm => GlFormat {
+            gl_base_internal_format: GL_RG,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_RG, GL_RG8)
+        },
+        ImageFormat::Rgb8Unorm => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_RGB, GL_RGB8)
+        },
+        ImageFormat::Rgba8Unorm => uncompressed(GL_UNSIGNED_BYTE, 1, GL_RGBA, GL_RGBA8),
+        ImageFormat::Rgba8UnormSrgb => uncompressed(GL_UNSIGNED_BYTE, 1, GL_RGBA, GL_SRGB8_ALPHA8),
+        ImageFormat::R16Unorm => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_UNSIGNED_SHORT, 2, GL_RED, GL_R16)
+        },
+        ImageFormat::Rg16Unorm => GlFormat {
+            gl_base_internal_format: GL_RG,
+            ..uncompressed(GL_UNSIGNED_SHORT, 2, GL_RG, GL_RG16)
+        },
+        ImageFormat::Rg16Snorm => GlFormat {
+            gl_base_internal_format: GL_RG,
+            ..uncompressed(GL_SHORT, 2, GL_RG, GL_RG16_SNORM)
+        },
+        ImageFormat::Rgba16Unorm => uncompressed(GL_UNSIGNED_SHORT, 2, GL_RGBA, GL_RGBA16),
+        ImageFormat::Rgba16Snorm => uncompressed(GL_SHORT, 2, GL_RGBA, GL_RGBA16_SNORM),
+        ImageFormat::R16Float => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_HALF_FLOAT, 2, GL_RED, GL_R16F)
+        },
+        ImageFormat::Rg16Float => GlFormat {
+            gl_base_internal_format: GL_RG,
+            ..uncompressed(GL_HALF_FLOAT, 2, GL_RG, GL_RG16F)
+        },
+        ImageFormat::Rgba16Float => uncompressed(GL_HALF_FLOAT, 2, GL_RGBA, GL_RGBA16F),
+        ImageFormat::R32Float => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_FLOAT, 4, GL_RED, GL_R32F)
+        },
+        ImageFormat::Rg32Float => GlFormat {
+            gl_base_internal_format: GL_RG,
+            ..uncompressed(GL_FLOAT, 4, GL_RG, GL_RG32F)
+        },
+        ImageFormat::Rgba32Float => uncompressed(GL_FLOAT, 4, GL_RGBA, GL_RGBA32F),
+        // The reversed packed types store red in the lowest bits like DXGI.
+        ImageFormat::Rgb10a2Unorm => {
+            uncompressed(GL_UNSIGNED_INT_2_10_10_10_REV, 4, GL_RGBA, GL_RGB10_A2)
+        }
+        ImageFormat::Rg11b10Ufloat => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(
+                GL_UNSIGNED_INT_10F_11F_11F_REV,
+                4,
+                GL_RGB,
+                GL_R11F_G11F_B10F,
+            )
+        },
+        ImageFormat::Rgb9e5Ufloat => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(GL_UNSIGNED_INT_5_9_9_9_REV, 4, GL_RGB, GL_RGB9_E5)
+        },
+        ImageFormat::R8Uint => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_RED_INTEGER, GL_R8UI)
+        },
+        ImageFormat::R8Sint => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_BYTE, 1, GL_RED_INTEGER, GL_R8I)
+        },
+        ImageFormat::R16Uint => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_UNSIGNED_SHORT, 2, GL_RED_INTEGER, GL_R16UI)
+        },
+        ImageFormat::R16Sint => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_SHORT, 2, GL_RED_INTEGER, GL_R16I)
+        },
+        ImageFormat::R32Uint => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_UNSIGNED_INT, 4, GL_RED_INTEGER, GL_R32UI)
+        },
+        ImageFormat::R32Sint => GlFormat {
+            gl_base_internal_format: GL_RED,
+            ..uncompressed(GL_INT, 4, GL_RED_INTEGER, GL_R32I)
+        },
+        ImageFormat::Rgba8Uint => uncompressed(GL_UNSIGNED_BYTE, 1, GL_RGBA_INTEGER, GL_RGBA8UI),
+        ImageFormat::Rgba8Sint => uncompressed(GL_BYTE, 1, GL_RGBA_INTEGER, GL_RGBA8I),
+        ImageFormat::Rgba16Uint => uncompressed(GL_UNSIGNED_SHORT, 2, GL_RGBA_INTEGER, GL_RGBA16UI),
+        ImageFormat::Rgba16Sint => uncompressed(GL_SHORT, 2, GL_RGBA_INTEGER, GL_RGBA16I),
+        ImageFormat::Rgba32Uint => uncompressed(GL_UNSIGNED_INT, 4, GL_RGBA_INTEGER, GL_RGBA32UI),
+        ImageFormat::Rgba32Sint => uncompressed(GL_INT, 4, GL_RGBA_INTEGER, GL_RGBA32I),
+        ImageFormat::Bgra8Unorm => uncompressed(GL_UNSIGNED_BYTE, 1, GL_BGRA, GL_RGBA8),
+        ImageFormat::Bgra8UnormSrgb => uncompressed(GL_UNSIGNED_BYTE, 1, GL_BGRA, GL_SRGB8_ALPHA8),
+        // The reversed packed type stores blue in the lowest bits like DXGI.
+        ImageFormat::Bgra4Unorm => {
+            uncompressed(GL_UNSIGNED_SHORT_4_4_4_4_REV, 2, GL_BGRA, GL_RGBA4)
+        }
+        ImageFormat::B5g6r5Unorm => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(GL_UNSIGNED_SHORT_5_6_5, 2, GL_RGB, GL_RGB565)
+        },
+        ImageFormat::Bgr5a1Unorm => {
+            uncompressed(GL_UNSIGNED_SHORT_1_5_5_5_REV, 2, GL_BGRA, GL_RGB5_A1)
+        }
+        // Formats without alpha ignore the alpha bits in the data.
+        ImageFormat::Bgr5x1Unorm => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(GL_UNSIGNED_SHORT_1_5_5_5_REV, 2, GL_BGRA, GL_RGB5)
+        },
+        ImageFormat::Bgr8Unorm => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_BGR, GL_RGB8)
+        },
+        ImageFormat::Bgrx8Unorm => GlFormat {
+            gl_base_internal_format: GL_RGB,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_BGRA, GL_RGB8)
+        },
+        ImageFormat::La8Unorm => GlFormat {
+            gl_base_internal_format: GL_LUMINANCE_ALPHA,
+            ..uncompressed(
+                GL_UNSIGNED_BYTE,
+                1,
+                GL_LUMINANCE_ALPHA,
+                GL_LUMINANCE8_ALPHA8,
+            )
+        },
+        ImageFormat::A8Unorm => GlFormat {
+            gl_base_internal_format: GL_ALPHA,
+            ..uncompressed(GL_UNSIGNED_BYTE, 1, GL_ALPHA, GL_ALPHA8)
+        },
+        ImageFormat::Depth16Unorm => GlFormat {
+            gl_base_internal_format: GL_DEPTH_COMPONENT,
+            ..uncompressed(
+                GL_UNSIGNED_SHORT,
+                2,
+                GL_DEPTH_COMPONENT,
+                GL_DEPTH_COMPONENT16,
+            )
+        },
+        ImageFormat::Depth32Float => GlFormat {
+            gl_base_internal_format: GL_DEPTH_COMPONENT,
+            ..uncompressed(GL_FLOAT, 4, GL_DEPTH_COMPONENT, GL_DEPTH_COMPONENT32F)
+        },
+        ImageFormat::Depth32FloatStencil8 => GlFormat {
+            gl_base_internal_format: GL_DEPTH_STENCIL,
+            ..uncompressed(
+                GL_FLOAT_32_UNSIGNED_INT_24_8_REV,
+                4,
+                GL_DEPTH_STENCIL,
+                GL_DEPTH32F_STENCIL8,
+            )
+        },
+        // GL_UNSIGNED_INT_24_8 stores depth in the most significant bits unlike DXGI.
+        ImageFormat::Depth24UnormStencil8 => return None,
+        // OpenGL has no standard formats for subsampled YUV data.
+        ImageFormat::Yuy2 | ImageFormat::Uyvy | ImageFormat::Nv12 | ImageFormat::P010 => {
+            return None
+        }
+        // OpenGL uses the same internal formats for LDR and HDR ASTC.
+        ImageFormat::Astc4x4RgbaFloat
+        | ImageFormat::Astc5x4RgbaFloat
+        | ImageFormat::Astc5x5RgbaFloat
+        | ImageFormat::Astc6x5RgbaFloat
+        | ImageFormat::Astc6x6RgbaFloat
+        | ImageFormat::Astc8x5RgbaFloat
+        | ImageFormat::Astc8x6RgbaFloat
+        | ImageFormat::Astc8x8RgbaFloat
+        | ImageFormat::Astc10x5RgbaFloat
+        | ImageFormat::Astc10x6RgbaFloat
+        | ImageFormat::Astc10x8RgbaFloat
+        | ImageFormat::Astc10x10RgbaFloat
+        | ImageFormat::Astc12x10RgbaFloat
+        | ImageFormat::Astc12x12RgbaFloat => return None,
+        ImageFormat::BC1RgbaUnorm => compressed(GL_COMPRESSED_RGBA_S3TC_DXT1_EXT, GL_RGBA),
+        ImageFormat::BC1RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT, GL_RGBA)
+        }
+        ImageFormat::BC2RgbaUnorm => compressed(GL_COMPRESSED_RGBA_S3TC_DXT3_EXT, GL_RGBA),
+        ImageFormat::BC2RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT, GL_RGBA)
+        }
+        ImageFormat::BC3RgbaUnorm => compressed(GL_COMPRESSED_RGBA_S3TC_DXT5_EXT, GL_RGBA),
+        ImageFormat::BC3RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT, GL_RGBA)
+        }
+        ImageFormat::BC4RUnorm => compressed(GL_COMPRESSED_RED_RGTC1, GL_RED),
+        ImageFormat::BC4RSnorm => compressed(GL_COMPRESSED_SIGNED_RED_RGTC1, GL_RED),
+        ImageFormat::BC5RgUnorm => compressed(GL_COMPRESSED_RG_RGTC2, GL_RG),
+        ImageFormat::BC5RgSnorm => compressed(GL_COMPRESSED_SIGNED_RG_RGTC2, GL_RG),
+        ImageFormat::BC6hRgbUfloat => compressed(GL_COMPRESSED_RGB_BPTC_UNSIGNED_FLOAT, GL_RGB),
+        ImageFormat::BC6hRgbSfloat => compressed(GL_COMPRESSED_RGB_BPTC_SIGNED_FLOAT, GL_RGB),
+        ImageFormat::BC7RgbaUnorm => compressed(GL_COMPRESSED_RGBA_BPTC_UNORM, GL_RGBA),
+        ImageFormat::BC7RgbaUnormSrgb => compressed(GL_COMPRESSED_SRGB_ALPHA_BPTC_UNORM, GL_RGBA),
+        ImageFormat::Etc1RgbUnorm => compressed(GL_ETC1_RGB8_OES, GL_RGB),
+        ImageFormat::Etc2RgbUnorm => compressed(GL_COMPRESSED_RGB8_ETC2, GL_RGB),
+        ImageFormat::Etc2RgbUnormSrgb => compressed(GL_COMPRESSED_SRGB8_ETC2, GL_RGB),
+        ImageFormat::Etc2Rgba1Unorm => {
+            compressed(GL_COMPRESSED_RGB8_PUNCHTHROUGH_ALPHA1_ETC2, GL_RGBA)
+        }
+        ImageFormat::Etc2Rgba1UnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_PUNCHTHROUGH_ALPHA1_ETC2, GL_RGBA)
+        }
+        ImageFormat::Etc2Rgba8Unorm => compressed(GL_COMPRESSED_RGBA8_ETC2_EAC, GL_RGBA),
+        ImageFormat::Etc2Rgba8UnormSrgb => compressed(GL_COMPRESSED_SRGB8_ALPHA8_ETC2_EAC, GL_RGBA),
+        ImageFormat::EacR11Unorm => compressed(GL_COMPRESSED_R11_EAC, GL_RED),
+        ImageFormat::EacR11Snorm => compressed(GL_COMPRESSED_SIGNED_R11_EAC, GL_RED),
+        ImageFormat::EacRg11Unorm => compressed(GL_COMPRESSED_RG11_EAC, GL_RG),
+        ImageFormat::EacRg11Snorm => compressed(GL_COMPRESSED_SIGNED_RG11_EAC, GL_RG),
+        ImageFormat::Astc4x4RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_4X4, GL_RGBA),
+        ImageFormat::Astc4x4RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_4X4, GL_RGBA)
+        }
+        ImageFormat::Astc5x4RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_5X4, GL_RGBA),
+        ImageFormat::Astc5x4RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X4, GL_RGBA)
+        }
+        ImageFormat::Astc5x5RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_5X5, GL_RGBA),
+        ImageFormat::Astc5x5RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_5X5, GL_RGBA)
+        }
+        ImageFormat::Astc6x5RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_6X5, GL_RGBA),
+        ImageFormat::Astc6x5RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X5, GL_RGBA)
+        }
+        ImageFormat::Astc6x6RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_6X6, GL_RGBA),
+        ImageFormat::Astc6x6RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_6X6, GL_RGBA)
+        }
+        ImageFormat::Astc8x5RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_8X5, GL_RGBA),
+        ImageFormat::Astc8x5RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X5, GL_RGBA)
+        }
+        ImageFormat::Astc8x6RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_8X6, GL_RGBA),
+        ImageFormat::Astc8x6RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X6, GL_RGBA)
+        }
+        ImageFormat::Astc8x8RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_8X8, GL_RGBA),
+        ImageFormat::Astc8x8RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_8X8, GL_RGBA)
+        }
+        ImageFormat::Astc10x5RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_10X5, GL_RGBA),
+        ImageFormat::Astc10x5RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X5, GL_RGBA)
+        }
+        ImageFormat::Astc10x6RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_10X6, GL_RGBA),
+        ImageFormat::Astc10x6RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X6, GL_RGBA)
+        }
+        ImageFormat::Astc10x8RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_10X8, GL_RGBA),
+        ImageFormat::Astc10x8RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X8, GL_RGBA)
+        }
+        ImageFormat::Astc10x10RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_10X10, GL_RGBA),
+        ImageFormat::Astc10x10RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_10X10, GL_RGBA)
+        }
+        ImageFormat::Astc12x10RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_12X10, GL_RGBA),
+        ImageFormat::Astc12x10RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12X10, GL_RGBA)
+        }
+        ImageFormat::Astc12x12RgbaUnorm => compressed(GL_COMPRESSED_RGBA_ASTC_12X12, GL_RGBA),
+        ImageFormat::Astc12x12RgbaUnormSrgb => {
+            compressed(GL_COMPRESSED_SRGB8_ALPHA8_ASTC_12X12, GL_RGBA)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATS: [ImageFormat; 97] = [
+        ImageFormat::R8Unorm,
+        ImageFormat::Rg8Unorm,
+        ImageFormat::R16Unorm,
+        ImageFormat::Rg16Unorm,
+        ImageFormat::Rg16Snorm,
+        ImageFormat::Rgba16Unorm,
+        ImageFormat::Rgba16Snorm,
+        ImageFormat::R16Float,
+        ImageFormat::Rg16Float,
+        ImageFormat::R32Float,
+        ImageFormat::Rg32Float,
+        ImageFormat::Rgb10a2Unorm,
+        ImageFormat::Rg11b10Ufloat,
+        ImageFormat::Rgb9e5Ufloat,
+        ImageFormat::Rgba8Unorm,
+        ImageFormat::Rgba8UnormSrgb,
+        ImageFormat::Rgba16Float,
+        ImageFormat::Rgba32Float,
+        ImageFormat::Bgra8Unorm,
+        ImageFormat::Bgra8UnormSrgb,
+        ImageFormat::Bgra4Unorm,
+        ImageFormat::B5g6r5Unorm,
+        ImageFormat::Bgr5a1Unorm,
+        ImageFormat::Bgr5x1Unorm,
+        ImageFormat::Rgb8Unorm,
+        ImageFormat::Bgr8Unorm,
+        ImageFormat::Bgrx8Unorm,
+        ImageFormat::La8Unorm,
+        ImageFormat::A8Unorm,
+        ImageFormat::BC1RgbaUnorm,
+        ImageFormat::BC1RgbaUnormSrgb,
+        ImageFormat::BC2RgbaUnorm,
+        ImageFormat::BC2RgbaUnormSrgb,
+        ImageFormat::BC3RgbaUnorm,
+        ImageFormat::BC3RgbaUnormSrgb,
+        ImageFormat::BC4RUnorm,
+        ImageFormat::BC4RSnorm,
+        ImageFormat::BC5RgUnorm,
+        ImageFormat::BC5RgSnorm,
+        ImageFormat::BC6hRgbUfloat,
+        ImageFormat::BC6hRgbSfloat,
+        ImageFormat::BC7RgbaUnorm,
+        ImageFormat::BC7RgbaUnormSrgb,
+        ImageFormat::Etc1RgbUnorm,
+        ImageFormat::Etc2RgbUnorm,
+        ImageFormat::Etc2RgbUnormSrgb,
+        ImageFormat::Etc2Rgba1Unorm,
+        ImageFormat::Etc2Rgba1UnormSrgb,
+        ImageFormat::Etc2Rgba8Unorm,
+        ImageFormat::Etc2Rgba8UnormSrgb,
+        ImageFormat::EacR11Unorm,
+        ImageFormat::EacR11Snorm,
+        ImageFormat::EacRg11Unorm,
+        ImageFormat::EacRg11Snorm,
+        ImageFormat::Astc4x4RgbaUnorm,
+        ImageFormat::Astc4x4RgbaUnormSrgb,
+        ImageFormat::Astc5x4RgbaUnorm,
+        ImageFormat::Astc5x4RgbaUnormSrgb,
+        ImageFormat::Astc5x5RgbaUnorm,
+        ImageFormat::Astc5x5RgbaUnormSrgb,
+        ImageFormat::Astc6x5RgbaUnorm,
+        ImageFormat::Astc6x5RgbaUnormSrgb,
+        ImageFormat::Astc6x6RgbaUnorm,
+        ImageFormat::Astc6x6RgbaUnormSrgb,
+        ImageFormat::Astc8x5RgbaUnorm,
+        ImageFormat::Astc8x5RgbaUnormSrgb,
+        ImageFormat::Astc8x6RgbaUnorm,
+        ImageFormat::Astc8x6RgbaUnormSrgb,
+        ImageFormat::Astc8x8RgbaUnorm,
+        ImageFormat::Astc8x8RgbaUnormSrgb,
+        ImageFormat::Astc10x5RgbaUnorm,
+        ImageFormat::Astc10x5RgbaUnormSrgb,
+        ImageFormat::Astc10x6RgbaUnorm,
+        ImageFormat::Astc10x6RgbaUnormSrgb,
+        ImageFormat::Astc10x8RgbaUnorm,
+        ImageFormat::Astc10x8RgbaUnormSrgb,
+        ImageFormat::Astc10x10RgbaUnorm,
+        ImageFormat::Astc10x10RgbaUnormSrgb,
+        ImageFormat::Astc12x10RgbaUnorm,
+        ImageFormat::Astc12x10RgbaUnormSrgb,
+        ImageFormat::Astc12x12RgbaUnorm,
+        ImageFormat::Astc12x12RgbaUnormSrgb,
+        ImageFormat::R8Uint,
+        ImageFormat::R8Sint,
+        ImageFormat::R16Uint,
+        ImageFormat::R16Sint,
+        ImageFormat::R32Uint,
+        ImageFormat::R32Sint,
+        ImageFormat::Rgba8Uint,
+        ImageFormat::Rgba8Sint,
+        ImageFormat::Rgba16Uint,
+        ImageFormat::Rgba16Sint,
+        ImageFormat::Rgba32Uint,
+        ImageFormat::Rgba32Sint,
+        ImageFormat::Depth16Unorm,
+        ImageFormat::Depth32Float,
+        ImageFormat::Depth32FloatStencil8,
+    ];
+
+    const ASTC_HDR_FORMATS: [ImageFormat; 14] = [
+        ImageFormat::Astc4x4RgbaFloat,
+        ImageFormat::Astc5x4RgbaFloat,
+        ImageFormat::Astc5x5RgbaFloat,
+        ImageFormat::Astc6x5RgbaFloat,
+        ImageFormat::Astc6x6RgbaFloat,
+        ImageFormat::Astc8x5RgbaFloat,
+        ImageFormat::Astc8x6RgbaFloat,
+        ImageFormat::Astc8x8RgbaFloat,
+        ImageFormat::Astc10x5RgbaFloat,
+        ImageFormat::Astc10x6RgbaFloat,
+        ImageFormat::Astc10x8RgbaFloat,
+        ImageFormat::Astc10x10RgbaFloat,
+        ImageFormat::Astc12x10RgbaFloat,
+        ImageFormat::Astc12x12RgbaFloat,
+    ];
+
+    fn round_trip(surface: Surface<Vec<u8>>) -> Vec<u8> {
+        let bytes = surface.to_ktx().unwrap();
+        assert_eq!(surface, Surface::from_ktx(&bytes).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn gl_format_round_trip_all_formats() {
+        for format in FORMATS {
+            let gl = gl_format(format).unwrap();
+            assert_eq!(
+                Some(format),
+                image_format_from_gl(gl.gl_internal_format, gl.gl_format)
+            );
+        }
+    }
+
+    #[test]
+    fn ktx_round_trip_r8_row_padding() {
+        // Rows of 3 bytes are padded to 4 bytes.
+        let bytes = round_trip(Surface {
+            width: 3,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::R8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (1..=7).collect(),
+        });
+
+        assert_eq!(8, read_u32(&bytes, HEADER_SIZE, false).unwrap());
+        assert_eq!(
+            [1, 2, 3, 0, 4, 5, 6, 0],
+            bytes[HEADER_SIZE + 4..HEADER_SIZE + 12]
+        );
+        // The mipmap size does not include padding.
+        assert_eq!(4, read_u32(&bytes, HEADER_SIZE + 12, false).unwrap());
+        assert_eq!(HEADER_SIZE + 12 + 4 + 4, bytes.len());
+    }
+
+    #[test]
+    fn ktx_round_trip_bc7_layers_mipmaps() {
+        round_trip(Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 2,
+            mipmaps: 4,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..(4 + 1 + 1 + 1) * 16 * 2).map(|i| i as u8).collect(),
+        });
+    }
+
+    #[test]
+    fn ktx_round_trip_volume() {
+        round_trip(Surface {
+            width: 4,
+            height: 2,
+            depth: 3,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::Bgra8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..(4 * 2 * 3 + 2) * 4).map(|i| i as u8).collect(),
+        });
+    }
+
+    #[test]
+    fn ktx_round_trip_cubemap() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 6,
+            mipmaps: 1,
+            image_format: ImageFormat::Bgra4Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..2 * 6).collect::<Vec<u8>>(),
+        };
+        let bytes = surface.to_ktx_cubemap().unwrap();
+
+        // Non array cube maps store the size of each padded face.
+        assert_eq!(0, read_u32(&bytes, 16 + 8 * 4, false).unwrap());
+        assert_eq!(6, read_u32(&bytes, 16 + 9 * 4, false).unwrap());
+        assert_eq!(4, read_u32(&bytes, HEADER_SIZE, false).unwrap());
+        assert_eq!([0, 1, 0, 0, 2, 3], bytes[HEADER_SIZE + 4..HEADER_SIZE + 10]);
+
+        assert_eq!(surface, Surface::from_ktx(&bytes).unwrap());
+    }
+
+    #[test]
+    fn ktx_read_big_endian() {
+        let surface = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba16Float,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![1u8, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let mut bytes = surface.to_ktx().unwrap();
+
+        // Convert the header, image size, and 16-bit elements to big endian.
+        for value in bytes[12..HEADER_SIZE + 4].chunks_exact_mut(4) {
+            value.reverse();
+        }
+        for value in bytes[HEADER_SIZE + 4..].chunks_exact_mut(2) {
+            value.reverse();
+        }
+
+        assert_eq!(surface, Surface::from_ktx(&bytes).unwrap());
+    }
+
+    #[test]
+    fn ktx_cubemap_invalid_layers() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 8 * 2],
+        };
+        assert!(matches!(
+            surface.to_ktx_cubemap(),
+            Err(KtxError::Surface(SurfaceError::InvalidCubeMap {
+                layers: 2,
+                depth: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn ktx_unsupported_image_format() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Depth24UnormStencil8,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 4 * 4 * 4],
+        };
+        assert!(matches!(
+            surface.to_ktx(),
+            Err(KtxError::UnsupportedImageFormat(
+                ImageFormat::Depth24UnormStencil8
+            ))
+        ));
+    }
+
+    #[test]
+    fn ktx_astc_hdr_unsupported() {
+        // HDR ASTC would be read back as LDR ASTC.
+        for format in ASTC_HDR_FORMATS {
+            let (block_width, block_height, _) = format.block_dimensions();
+            let surface = Surface {
+                width: block_width,
+                height: block_height,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format: format,
+                alpha_mode: AlphaMode::Unknown,
+                data: vec![0u8; 16],
+            };
+            assert!(matches!(
+                surface.to_ktx(),
+                Err(KtxError::UnsupportedImageFormat(f)) if f == format
+            ));
+        }
+    }
+
+    #[test]
+    fn ktx_invalid_identifier() {
+        assert!(matches!(
+            Surface::from_ktx(&[0u8; 64]),
+            Err(KtxError::InvalidIdentifier)
+        ));
+    }
+
+    #[test]
+    fn ktx_invalid_endianness() {
+        let mut bytes = IDENTIFIER.to_vec();
+        bytes.extend_from_slice(&[0u8; 52]);
+        assert!(matches!(
+            Surface::from_ktx(&bytes),
+            Err(KtxError::InvalidEndianness(0))
+        ));
+    }
+
+    #[test]
+    fn ktx_not_enough_data() {
+        let bytes = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC4RUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 8],
+        }
+        .to_ktx()
+        .unwrap();
+
+        assert!(matches!(
+            Surface::from_ktx(&bytes[..bytes.len() - 1]),
+            Err(KtxError::NotEnoughData { .. })
+        ));
+    }
+
+    #[test]
+    fn ktx_level_size_overflow() {
+        let mut bytes = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba32Float,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 16],
+        }
+        .to_ktx()
+        .unwrap();
+        // Each layer is 2^52 bytes, so 2^16 layers overflows the level size.
+        for (offset, value) in [(36, 65536u32), (40, 65536), (44, 65536), (48, 65536)] {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        assert!(matches!(
+            Surface::from_ktx(&bytes),
+            Err(KtxError::Surface(
+                SurfaceError::PixelCountWouldOverflow { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,1443 @@
+// KTX2 reading and writing with optional Zstandard supercompression.
+// https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html
+use std::borrow::Cow;
+
+use thiserror::Error;
+
+use crate::{mip_dimension, mip_size, AlphaMode, ImageFormat, Surface, SurfaceError};
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const HEADER_SIZE: usize = 80;
+const LEVEL_INDEX_ENTRY_SIZE: usize = 24;
+
+const SUPERCOMPRESSION_NONE: u32 = 0;
+const SUPERCOMPRESSION_ZSTD: u32 = 2;
+
+// Data format descriptor values from the Khronos Data Format Specification.
+const KHR_DF_MODEL_RGBSDA: u8 = 1;
+const KHR_DF_MODEL_BC1A: u8 = 128;
+const KHR_DF_MODEL_BC2: u8 = 129;
+const KHR_DF_MODEL_BC3: u8 = 130;
+const KHR_DF_MODEL_BC4: u8 = 131;
+const KHR_DF_MODEL_BC5: u8 = 132;
+const KHR_DF_MODEL_BC6H: u8 = 133;
+const KHR_DF_MODEL_BC7: u8 = 134;
+const KHR_DF_MODEL_ETC2: u8 = 161;
+const KHR_DF_MODEL_ASTC: u8 = 162;
+const KHR_DF_PRIMARIES_BT709: u8 = 1;
+const KHR_DF_TRANSFER_LINEAR: u8 = 1;
+const KHR_DF_TRANSFER_SRGB: u8 = 2;
+const KHR_DF_FLAG_ALPHA_PREMULTIPLIED: u8 = 1;
+const KHR_DF_CHANNEL_RED: u8 = 0;
+const KHR_DF_CHANNEL_GREEN: u8 = 1;
+const KHR_DF_CHANNEL_BLUE: u8 = 2;
+const KHR_DF_CHANNEL_DEPTH: u8 = 14;
+const KHR_DF_CHANNEL_ALPHA: u8 = 15;
+const KHR_DF_CHANNEL_ETC2_COLOR: u8 = 2;
+const KHR_DF_SAMPLE_LINEAR: u8 = 0x10;
+const KHR_DF_SAMPLE_EXPONENT: u8 = 0x20;
+const KHR_DF_SAMPLE_SIGNED: u8 = 0x40;
+const KHR_DF_SAMPLE_FLOAT: u8 = 0x80;
+
+/// Errors that can occur while reading or writing KTX2 files.
+#[derive(Debug, Error)]
+pub enum Ktx2Error {
+    #[error("invalid KTX2 file identifier")]
+    InvalidIdentifier,
+
+    #[error("expected KTX2 file to have at least {expected} bytes but found {actual}")]
+    NotEnoughData { expected: usize, actual: usize },
+
+    #[error("KTX2 VkFormat {0} is not supported")]
+    UnsupportedVkFormat(u32),
+
+    #[error("image format {0:?} has no equivalent VkFormat")]
+    UnsupportedImageFormat(ImageFormat),
+
+    #[error("KTX2 supercompression scheme {0} is not supported")]
+    UnsupportedSupercompression(u32),
+
+    #[error("error decompressing or compressing Zstandard data: {0}")]
+    Zstd(String),
+
+    #[error("error reading or writing surface: {0}")]
+    Surface(#[from] SurfaceError),
+}
+
+/// Supercompression schemes for the mipmap data in KTX2 files.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Ktx2Supercompression {
+    /// Store the data for each mipmap without any additional compression.
+    #[default]
+    None,
+    /// Compress the data for each mipmap using Zstandard.
+    /// This requires the `"zstd"` feature.
+    Zstd,
+}
+
+impl Surface<Vec<u8>> {
+    /// Read a KTX2 file from `bytes`.
+    ///
+    /// Cube map faces are stored as separate array layers like DDS.
+    /// Mipmap data compressed with [Ktx2Supercompression::Zstd] requires the `"zstd"` feature.
+    /// Files without the premultiplied alpha flag use [AlphaMode::Unknown].
+    pub fn from_ktx2(bytes: &[u8]) -> Result<Self, Ktx2Error> {
+        if bytes.get(..IDENTIFIER.len()) != Some(&IDENTIFIER) {
+            return Err(Ktx2Error::InvalidIdentifier);
+        }
+
+        let vk_format = read_u32(bytes, 12)?;
+        let width = read_u32(bytes, 20)?;
+        let height = read_u32(bytes, 24)?.max(1);
+        let depth = read_u32(bytes, 28)?.max(1);
+        let layer_count = read_u32(bytes, 32)?.max(1);
+        let face_count = read_u32(bytes, 36)?.max(1);
+        let level_count = read_u32(bytes, 40)?.max(1);
+        let supercompression = read_u32(bytes, 44)?;
+
+        let image_format =
+            image_format_from_vk(vk_format).ok_or(Ktx2Error::UnsupportedVkFormat(vk_format))?;
+
+        // The flags follow the total size, block header, and color information of the DFD.
+        let dfd_offset = read_u32(bytes, 48)? as usize;
+        let dfd_length = read_u32(bytes, 52)? as usize;
+        let dfd_flags = if dfd_length >= 16 {
+            dfd_offset
+                .checked_add(15)
+                .and_then(|i| bytes.get(i))
+                .copied()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let alpha_mode = if dfd_flags & KHR_DF_FLAG_ALPHA_PREMULTIPLIED != 0 {
+            AlphaMode::Premultiplied
+        } else {
+            AlphaMode::Unknown
+        };
+
+        // Faces are stored after each other for each layer like DDS cube maps.
+        let overflow = || SurfaceError::PixelCountWouldOverflow {
+            width,
+            height,
+            depth,
+        };
+        let layers = layer_count.checked_mul(face_count).ok_or_else(overflow)?;
+        let mut surface = Surface {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps: level_count,
+            image_format,
+            alpha_mode,
+            data: Vec::new(),
+        };
+
+        // KTX2 uses level, layer, face, depth instead of layer, level, depth.
+        let mut levels = Vec::new();
+        for level in 0..level_count {
+            let index_offset = HEADER_SIZE + level as usize * LEVEL_INDEX_ENTRY_SIZE;
+            let offset = read_u64(bytes, index_offset)? as usize;
+            let length = read_u64(bytes, index_offset + 8)? as usize;
+
+            let level_data = bytes.get(offset..offset.saturating_add(length)).ok_or(
+                Ktx2Error::NotEnoughData {
+                    expected: offset.saturating_add(length),
+                    actual: bytes.len(),
+                },
+            )?;
+
+            let expected = image_size(&surface, level)?
+                .checked_mul(layers as usize)
+                .ok_or_else(overflow)?;
+
+            let level_data = decompress_level(level_data, supercompression, expected)?;
+            if level_data.len() < expected {
+                return Err(Ktx2Error::NotEnoughData {
+                    expected,
+                    actual: level_data.len(),
+                });
+            }
+            levels.push(level_data);
+        }
+
+        for layer in 0..layers as usize {
+            for (level, level_data) in levels.iter().enumerate() {
+                let size = image_size(&surface, level as u32)?;
+                surface
+                    .data
+                    .extend_from_slice(&level_data[layer * size..(layer + 1) * size]);
+            }
+        }
+
+        Ok(surface)
+    }
+}
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Create a KTX2 file with the same image data and format.
+    ///
+    /// [Ktx2Supercompression::Zstd] requires the `"zstd"` feature.
+    /// [ImageFormat::Etc1RgbUnorm] is written as [ImageFormat::Etc2RgbUnorm].
+    pub fn to_ktx2(&self, supercompression: Ktx2Supercompression) -> Result<Vec<u8>, Ktx2Error> {
+        self.create_ktx2(supercompression, false)
+    }
+
+    /// Create a cube map KTX2 file with the same image data and format.
+    ///
+    /// See [layers](#structfield.layers) for the order of the cube map faces.
+    pub fn to_ktx2_cubemap(
+        &self,
+        supercompression: Ktx2Supercompression,
+    ) -> Result<Vec<u8>, Ktx2Error> {
+        self.validate_cube_map()?;
+        self.create_ktx2(supercompression, true)
+    }
+
+    fn create_ktx2(
+        &self,
+        supercompression: Ktx2Supercompression,
+        is_cubemap: bool,
+    ) -> Result<Vec<u8>, Ktx2Error> {
+        self.validate()?;
+
+        let vk_format = vk_format(self.image_format)
+            .ok_or(Ktx2Error::UnsupportedImageFormat(self.image_format))?;
+
+        let scheme = match supercompression {
+            Ktx2Supercompression::None => SUPERCOMPRESSION_NONE,
+            Ktx2Supercompression::Zstd => SUPERCOMPRESSION_ZSTD,
+        };
+
+        // KTX2 uses level, layer, face, depth instead of layer, level, depth.
+        let mut levels = Vec::new();
+        for mipmap in 0..self.mipmaps {
+            let mut uncompressed = Vec::new();
+            for layer in 0..self.layers {
+                for level in 0..mip_dimension(self.depth, mipmap) {
+                    let data = self
+                        .get(layer, level, mipmap)
+                        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                    uncompressed.extend_from_slice(data);
+                }
+            }
+            let data = compress_level(&uncompressed, scheme)?;
+            levels.push((data, uncompressed.len()));
+        }
+
+        let dfd = data_format_descriptor(
+            self.image_format,
+            self.alpha_mode,
+            scheme != SUPERCOMPRESSION_NONE,
+        );
+
+        let index_end = HEADER_SIZE + LEVEL_INDEX_ENTRY_SIZE * levels.len();
+        let dfd_offset = index_end;
+        let mut data_offset = dfd_offset + dfd.len();
+
+        // Mipmaps are stored from smallest to largest with alignment for uncompressed data.
+        let alignment = if scheme == SUPERCOMPRESSION_NONE {
+            lcm(self.image_format.block_size_in_bytes(), 4)
+        } else {
+            1
+        };
+        let mut level_offsets = vec![0; levels.len()];
+        for (i, (data, _)) in levels.iter().enumerate().rev() {
+            data_offset = data_offset.next_multiple_of(alignment);
+            level_offsets[i] = data_offset;
+            data_offset += data.len();
+        }
+
+        let (layer_count, face_count) = if is_cubemap {
+            (self.layers / 6, 6)
+        } else {
+            (self.layers, 1)
+        };
+
+        let mut bytes = Vec::with_capacity(data_offset);
+        bytes.extend_from_slice(&IDENTIFIER);
+        for value in [
+            vk_format,
+            type_size(self.image_format),
+            self.width,
+            self.height,
+            if self.depth > 1 { self.depth } else { 0 },
+            // A layer count of 0 indicates the texture is not an array.
+            if layer_count > 1 { layer_count } else { 0 },
+            face_count,
+            self.mipmaps,
+            scheme,
+            dfd_offset as u32,
+            dfd.len() as u32,
+            // Key value data is optional.
+            0,
+            0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        // There is no supercompression global data for Zstandard.
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+
+        for ((data, uncompressed_length), offset) in levels.iter().zip(&level_offsets) {
+            for value in [*offset, data.len(), *uncompressed_length] {
+                bytes.extend_from_slice(&(value as u64).to_le_bytes());
+            }
+        }
+
+        bytes.extend_from_slice(&dfd);
+
+        for ((data, _), offset) in levels.iter().zip(&level_offsets).rev() {
+            bytes.resize(*offset, 0);
+            bytes.extend_from_slice(data);
+        }
+
+        Ok(bytes)
+    }
+}
+
+// The size in bytes of a single layer or face for the given mipmap.
+fn image_size<T>(surface: &Surface<T>, mipmap: u32) -> Result<usize, SurfaceError> {
+    let (block_width, block_height, block_depth) = surface.image_format.block_dimensions();
+    mip_size(
+        mip_dimension(surface.width, mipmap) as usize,
+        mip_dimension(surface.height, mipmap) as usize,
+        mip_dimension(surface.depth, mipmap) as usize,
+        block_width as usize,
+        block_height as usize,
+        block_depth as usize,
+        surface.image_format.block_size_in_bytes(),
+    )
+    .ok_or(SurfaceError::PixelCountWouldOverflow {
+        width: surface.width,
+        height: surface.height,
+        depth: surface.depth,
+    })
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, Ktx2Error> {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or(Ktx2Error::NotEnoughData {
+            expected: offset + 4,
+            actual: bytes.len(),
+        })
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, Ktx2Error> {
+    bytes
+        .get(offset..offset + 8)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(Ktx2Error::NotEnoughData {
+            expected: offset + 8,
+            actual: bytes.len(),
+        })
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let gcd = |mut a: usize, mut b: usize| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    a / gcd(a, b) * b
+}
+
+fn decompress_level(
+    data: &[u8],
+    scheme: u32,
+    expected_length: usize,
+) -> Result<Cow<'_, [u8]>, Ktx2Error> {
+    match scheme {
+        SUPERCOMPRESSION_NONE => Ok(data.into()),
+        #[cfg(feature = "zstd")]
+        SUPERCOMPRESSION_ZSTD => {
+            use std::io::Read;
+
+            let decoder = ruzstd::decoding::StreamingDecoder::new(data)
+                .map_err(|e| Ktx2Error::Zstd(e.to_string()))?;
+            // Limit the output to avoid allocating more than the level requires.
+            let mut decompressed = Vec::new();
+            decoder
+                .take(expected_length as u64)
+                .read_to_end(&mut decompressed)
+                .map_err(|e| Ktx2Error::Zstd(e.to_string()))?;
+            Ok(decompressed.into())
+        }
+        _ => Err(Ktx2Error::UnsupportedSupercompression(scheme)),
+    }
+}
+
+fn compress_level(data: &[u8], scheme: u32) -> Result<Vec<u8>, Ktx2Error> {
+    match scheme {
+        SUPERCOMPRESSION_NONE => Ok(data.to_vec()),
+        #[cfg(feature = "zstd")]
+        SUPERCOMPRESSION_ZSTD => Ok(ruzstd::encoding::compress_to_vec(
+            data,
+            ruzstd::encoding::CompressionLevel::Fastest,
+        )),
+        _ => Err(Ktx2Error::UnsupportedSupercompression(scheme)),
+    }
+}
+
+fn image_format_from_vk(format: u32) -> Option<ImageFormat> {
+    match format {
+        4 => Some(ImageFormat::B5g6r5Unorm),
+        8 => Some(ImageFormat::Bgr5a1Unorm),
+        9 => Some(ImageFormat::R8Unorm),
+        13 => Some(ImageFormat::R8Uint),
+        14 => Some(ImageFormat::R8Sint),
+        16 => Some(ImageFormat::Rg8Unorm),
+        23 => Some(ImageFormat::Rgb8Unorm),
+        30 => Some(ImageFormat::Bgr8Unorm),
+        37 => Some(ImageFormat::Rgba8Unorm),
+        41 => Some(ImageFormat::Rgba8Uint),
+        42 => Some(ImageFormat::Rgba8Sint),
+        43 => Some(ImageFormat::Rgba8UnormSrgb),
+        44 => Some(ImageFormat::Bgra8Unorm),
+        50 => Some(ImageFormat::Bgra8UnormSrgb),
+        74 => Some(ImageFormat::R16Uint),
+        75 => Some(ImageFormat::R16Sint),
+        95 => Some(ImageFormat::Rgba16Uint),
+        96 => Some(ImageFormat::Rgba16Sint),
+        98 => Some(ImageFormat::R32Uint),
+        99 => Some(ImageFormat::R32Sint),
+        107 => Some(ImageFormat::Rgba32Uint),
+        108 => Some(ImageFormat::Rgba32Sint),
+        // VK_FORMAT_A2B10G10R10_UNORM_PACK32 has the same bit layout as DXGI_FORMAT_R10G10B10A2_UNORM.
+        64 => Some(ImageFormat::Rgb10a2Unorm),
+        70 => Some(ImageFormat::R16Unorm),
+        76 => Some(ImageFormat::R16Float),
+        77 => Some(ImageFormat::Rg16Unorm),
+        78 => Some(ImageFormat::Rg16Snorm),
+        83 => Some(ImageFormat::Rg16Float),
+        91 => Some(ImageFormat::Rgba16Unorm),
+        92 => Some(ImageFormat::Rgba16Snorm),
+        97 => Some(ImageFormat::Rgba16Float),
+        100 => Some(ImageFormat::R32Float),
+        103 => Some(ImageFormat::Rg32Float),
+        109 => Some(ImageFormat::Rgba32Float),
+        122 => Some(ImageFormat::Rg11b10Ufloat),
+        123 => Some(ImageFormat::Rgb9e5Ufloat),
+        124 => Some(ImageFormat::Depth16Unorm),
+        126 => Some(ImageFormat::Depth32Float),
+        133 => Some(ImageFormat::BC1RgbaUnorm),
+        134 => Some(ImageFormat::BC1RgbaUnormSrgb),
+        135 => Some(ImageFormat::BC2RgbaUnorm),
+        136 => Some(ImageFormat::BC2RgbaUnormSrgb),
+        137 => Some(ImageFormat::BC3RgbaUnorm),
+        138 => Some(ImageFormat::BC3RgbaUnormSrgb),
+        139 => Some(ImageFormat::BC4RUnorm),
+        140 => Some(ImageFormat::BC4RSnorm),
+        141 => Some(ImageFormat::BC5RgUnorm),
+        142 => Some(ImageFormat::BC5RgSnorm),
+        143 => Some(ImageFormat::BC6hRgbUfloat),
+        144 => Some(ImageFormat::BC6hRgbSfloat),
+        145 => Some(ImageFormat::BC7RgbaUnorm),
+        146 => Some(ImageFormat::BC7RgbaUnormSrgb),
+        147 => Some(ImageFormat::Etc2RgbUnorm),
+        148 => Some(ImageFormat::Etc2RgbUnormSrgb),
+        149 => Some(ImageFormat::Etc2Rgba1Unorm),
+        150 => Some(ImageFormat::Etc2Rgba1UnormSrgb),
+        151 => Some(ImageFormat::Etc2Rgba8Unorm),
+        152 => Some(ImageFormat::Etc2Rgba8UnormSrgb),
+        153 => Some(ImageFormat::EacR11Unorm),
+        154 => Some(ImageFormat::EacR11Snorm),
+        155 => Some(ImageFormat::EacRg11Unorm),
+        156 => Some(ImageFormat::EacRg11Snorm),
+        157 => Some(ImageFormat::Astc4x4RgbaUnorm),
+        158 => Some(ImageFormat::Astc4x4RgbaUnormSrgb),
+        159 => Some(ImageFormat::Astc5x4RgbaUnorm),
+        160 => Some(ImageFormat::Astc5x4RgbaUnormSrgb),
+        161 => Some(ImageFormat::Astc5x5RgbaUnorm),
+        162 => Some(ImageFormat::Astc5x5RgbaUnormSrgb),
+        163 => Some(ImageFormat::Astc6x5RgbaUnorm),
+        164 => Some(ImageFormat::Astc6x5RgbaUnormSrgb),
+        165 => Some(ImageFormat::Astc6x6RgbaUnorm),
+        166 => Some(ImageFormat::Astc6x6RgbaUnormSrgb),
+        167 => Some(ImageFormat::Astc8x5RgbaUnorm),
+        168 => Some(ImageFormat::Astc8x5RgbaUnormSrgb),
+        169 => Some(ImageFormat::Astc8x6RgbaUnorm),
+        170 => Some(ImageFormat::Astc8x6RgbaUnormSrgb),
+        171 => Some(ImageFormat::Astc8x8RgbaUnorm),
+        172 => Some(ImageFormat::Astc8x8RgbaUnormSrgb),
+        173 => Some(ImageFormat::Astc10x5RgbaUnorm),
+        174 => Some(ImageFormat::Astc10x5RgbaUnormSrgb),
+        175 => Some(ImageFormat::Astc10x6RgbaUnorm),
+        176 => Some(ImageFormat::Astc10x6RgbaUnormSrgb),
+        177 => Some(ImageFormat::Astc10x8RgbaUnorm),
+        178 => Some(ImageFormat::Astc10x8RgbaUnormSrgb),
+        179 => Some(ImageFormat::Astc10x10RgbaUnorm),
+        180 => Some(ImageFormat::Astc10x10RgbaUnormSrgb),
+        181 => Some(ImageFormat::Astc12x10RgbaUnorm),
+        182 => Some(ImageFormat::Astc12x10RgbaUnormSrgb),
+        183 => Some(ImageFormat::Astc12x12RgbaUnorm),
+        184 => Some(ImageFormat::Astc12x12RgbaUnormSrgb),
+        1000066000 => Some(ImageFormat::Astc4x4RgbaFloat),
+        1000066001 => Some(ImageFormat::Astc5x4RgbaFloat),
+        1000066002 => Some(ImageFormat::Astc5x5RgbaFloat),
+        1000066003 => Some(ImageFormat::Astc6x5RgbaFloat),
+        1000066004 => Some(ImageFormat::Astc6x6RgbaFloat),
+        1000066005 => Some(ImageFormat::Astc8x5RgbaFloat),
+        1000066006 => Some(ImageFormat::Astc8x6RgbaFloat),
+        1000066007 => Some(ImageFormat::Astc8x8RgbaFloat),
+        1000066008 => Some(ImageFormat::Astc10x5RgbaFloat),
+        1000066009 => Some(ImageFormat::Astc10x6RgbaFloat),
+        1000066010 => Some(ImageFormat::Astc10x8RgbaFloat),
+        1000066011 => Some(ImageFormat::Astc10x10RgbaFloat),
+        1000066012 => Some(ImageFormat::Astc12x10RgbaFloat),
+        1000066013 => Some(ImageFormat::Astc12x12RgbaFloat),
+        1000340000 => Some(ImageFormat::Bgra4Unorm),
+        1000470001 => Some(ImageFormat::A8Unorm),
+        _ => None,
+    }
+}
+
+fn vk_format(format: ImageFormat) -> Option<u32> {
+    Some(match format {
+        ImageFormat::B5g6r5Unorm => 4,
+        ImageFormat::Bgr5a1Unorm => 8,
+        ImageFormat::R8Unorm => 9,
+        ImageFormat::R8Uint => 13,
+        ImageFormat::R8Sint => 14,
+        ImageFormat::Rg8Unorm => 16,
+        ImageFormat::Rgb8Unorm => 23,
+        ImageFormat::Bgr8Unorm => 30,
+        ImageFormat::Rgba8Unorm => 37,
+        ImageFormat::Rgba8Uint => 41,
+        ImageFormat::Rgba8Sint => 42,
+        ImageFormat::Rgba8UnormSrgb => 43,
+        ImageFormat::Bgra8Unorm => 44,
+        ImageFormat::Bgra8UnormSrgb => 50,
+        ImageFormat::Rgb10a2Unorm => 64,
+        ImageFormat::R16Unorm => 70,
+        ImageFormat::R16Uint => 74,
+        ImageFormat::R16Sint => 75,
+        ImageFormat::R16Float => 76,
+        ImageFormat::Rg16Unorm => 77,
+        ImageFormat::Rg16Snorm => 78,
+        ImageFormat::Rg16Float => 83,
+        ImageFormat::Rgba16Unorm => 91,
+        ImageFormat::Rgba16Snorm => 92,
+        ImageFormat::Rgba16Uint => 95,
+        ImageFormat::Rgba16Sint => 96,
+        ImageFormat::Rgba16Float => 97,
+        ImageFormat::R32Uint => 98,
+        ImageFormat::R32Sint => 99,
+        ImageFormat::R32Float => 100,
+        ImageFormat::Rg32Float => 103,
+        ImageFormat::Rgba32Uint => 107,
+        ImageFormat::Rgba32Sint => 108,
+        ImageFormat::Rgba32Float => 109,
+        ImageFormat::Rg11b10Ufloat => 122,
+        ImageFormat::Rgb9e5Ufloat => 123,
+        ImageFormat::Depth16Unorm => 124,
+        ImageFormat::Depth32Float => 126,
+        ImageFormat::BC1RgbaUnorm => 133,
+        ImageFormat::BC1RgbaUnormSrgb => 134,
+        ImageFormat::BC2RgbaUnorm => 135,
+        ImageFormat::BC2RgbaUnormSrgb => 136,
+        ImageFormat::BC3RgbaUnorm => 137,
+        ImageFormat::BC3RgbaUnormSrgb => 138,
+        ImageFormat::BC4RUnorm => 139,
+        ImageFormat::BC4RSnorm => 140,
+        ImageFormat::BC5RgUnorm => 141,
+        ImageFormat::BC5RgSnorm => 142,
+        ImageFormat::BC6hRgbUfloat => 143,
+        ImageFormat::BC6hRgbSfloat => 144,
+        ImageFormat::BC7RgbaUnorm => 145,
+        ImageFormat::BC7RgbaUnormSrgb => 146,
+        // ETC1 is a subset of ETC2, so ETC1 data is stored and read back as ETC2.
+        ImageFormat::Etc1RgbUnorm => 147,
+        ImageFormat::Etc2RgbUnorm => 147,
+        ImageFormat::Etc2RgbUnormSrgb => 148,
+        ImageFormat::Etc2Rgba1Unorm => 149,
+        ImageFormat::Etc2Rgba1UnormSrgb => 150,
+        ImageFormat::Etc2Rgba8Unorm => 151,
+        ImageFormat::Etc2Rgba8UnormSrgb => 152,
+        ImageFormat::EacR11Unorm => 153,
+        ImageFormat::EacR11Snorm => 154,
+        ImageFormat::EacRg11Unorm => 155,
+        ImageFormat::EacRg11Snorm => 156,
+        ImageFormat::Astc4x4RgbaUnorm => 157,
+        ImageFormat::Astc4x4RgbaUnormSrgb => 158,
+        ImageFormat::Astc4x4RgbaFloat => 1000066000,
+        ImageFormat::Astc5x4RgbaUnorm => 159,
+        ImageFormat::Astc5x4RgbaUnormSrgb => 160,
+        ImageFormat::Astc5x4RgbaFloat => 1000066001,
+        ImageFormat::Astc5x5RgbaUnorm => 161,
+        ImageFormat::Astc5x5RgbaUnormSrgb => 162,
+        ImageFormat::Astc5x5RgbaFloat => 1000066002,
+        ImageFormat::Astc6x5RgbaUnorm => 163,
+        ImageFormat::Astc6x5RgbaUnormSrgb => 164,
+        ImageFormat::Astc6x5RgbaFloat => 1000066003,
+        ImageFormat::Astc6x6RgbaUnorm => 165,
+        ImageFormat::Astc6x6RgbaUnormSrgb => 166,
+        ImageFormat::Astc6x6RgbaFloat => 1000066004,
+        ImageFormat::Astc8x5RgbaUnorm => 167,
+        ImageFormat::Astc8x5RgbaUnormSrgb => 168,
+        ImageFormat::Astc8x5RgbaFloat => 1000066005,
+        ImageFormat::Astc8x6RgbaUnorm => 169,
+        ImageFormat::Astc8x6RgbaUnormSrgb => 170,
+        ImageFormat::Astc8x6RgbaFloat => 1000066006,
+        ImageFormat::Astc8x8RgbaUnorm => 171,
+        ImageFormat::Astc8x8RgbaUnormSrgb => 172,
+        ImageFormat::Astc8x8RgbaFloat => 1000066007,
+        ImageFormat::Astc10x5RgbaUnorm => 173,
+        ImageFormat::Astc10x5RgbaUnormSrgb => 174,
+        ImageFormat::Astc10x5RgbaFloat => 1000066008,
+        ImageFormat::Astc10x6RgbaUnorm => 175,
+        ImageFormat::Astc10x6RgbaUnormSrgb => 176,
+        ImageFormat::Astc10x6RgbaFloat => 1000066009,
+        ImageFormat::Astc10x8RgbaUnorm => 177,
+        ImageFormat::Astc10x8RgbaUnormSrgb => 178,
+        ImageFormat::Astc10x8RgbaFloat => 1000066010,
+        ImageFormat::Astc10x10RgbaUnorm => 179,
+        ImageFormat::Astc10x10RgbaUnormSrgb => 180,
+        ImageFormat::Astc10x10RgbaFloat => 1000066011,
+        ImageFormat::Astc12x10RgbaUnorm => 181,
+        ImageFormat::Astc12x10RgbaUnormSrgb => 182,
+        ImageFormat::Astc12x10RgbaFloat => 1000066012,
+        ImageFormat::Astc12x12RgbaUnorm => 183,
+        ImageFormat::Astc12x12RgbaUnormSrgb => 184,
+        ImageFormat::Astc12x12RgbaFloat => 1000066013,
+        // VK_FORMAT_A4R4G4B4_UNORM_PACK16 has the same bit layout as DXGI_FORMAT_B4G4R4A4_UNORM.
+        ImageFormat::Bgra4Unorm => 1000340000,
+        ImageFormat::A8Unorm => 1000470001,
+        // KTX2 does not allow combined depth stencil formats.
+        ImageFormat::Bgr5x1Unorm
+        | ImageFormat::Bgrx8Unorm
+        | ImageFormat::La8Unorm
+        | ImageFormat::Depth24UnormStencil8
+        | ImageFormat::Depth32FloatStencil8 => return None,
+        // KTX2 does not allow multi-planar formats or the 4:2:2 formats without a DXGI layout.
+        ImageFormat::Yuy2 | ImageFormat::Uyvy | ImageFormat::Nv12 | ImageFormat::P010 => {
+            return None
+        }
+    })
+}
+
+// The size of the data type for endianness conversion or 1 for block compressed formats.
+fn type_size(format: ImageFormat) -> u32 {
+    match format {
+        ImageFormat::R16Unorm
+        | ImageFormat::Rg16Unorm
+        | ImageFormat::Rg16Snorm
+        | ImageFormat::Rgba16Unorm
+        | ImageFormat::Rgba16Snorm
+        | ImageFormat::R16Float
+        | ImageFormat::Rg16Float
+        | ImageFormat::Rgba16Float
+        | ImageFormat::Bgra4Unorm
+        | ImageFormat::B5g6r5Unorm
+        | ImageFormat::Bgr5a1Unorm
+        | ImageFormat::R16Uint
+        | ImageFormat::R16Sint
+        | ImageFormat::Rgba16Uint
+        | ImageFormat::Rgba16Sint
+        | ImageFormat::Depth16Unorm => 2,
+        ImageFormat::R32Float
+        | ImageFormat::Rg32Float
+        | ImageFormat::Rgba32Float
+        | ImageFormat::Rgb10a2Unorm
+        | ImageFormat::Rg11b10Ufloat
+        | ImageFormat::Rgb9e5Ufloat
+        | ImageFormat::R32Uint
+        | ImageFormat::R32Sint
+        | ImageFormat::Rgba32Uint
+        | ImageFormat::Rgba32Sint
+        | ImageFormat::Depth32Float => 4,
+        _ => 1,
+    }
+}
+
+struct Sample {
+    bit_offset: u16,
+    bit_length: u8,
+    channel: u8,
+    lower: u32,
+    upper: u32,
+}
+
+impl Sample {
+    fn new(bit_offset: u16, bit_length: u8, channel: u8) -> Self {
+        // Unsigned normalized data maps the max value to 1.0.
+        let upper = if bit_length >= 32 {
+            u32::MAX
+        } else {
+            (1u32 << bit_length) - 1
+        };
+        Self {
+            bit_offset,
+            bit_length,
+            channel,
+            lower: 0,
+            upper,
+        }
+    }
+
+    fn signed(self) -> Self {
+        let upper = (self.upper >> 1) as i32;
+        Self {
+            channel: self.channel | KHR_DF_SAMPLE_SIGNED,
+            lower: (-upper - 1) as u32,
+            upper: upper as u32,
+            ..self
+        }
+    }
+
+    fn float(self) -> Self {
+        Self {
+            channel: self.channel | KHR_DF_SAMPLE_FLOAT | KHR_DF_SAMPLE_SIGNED,
+            lower: (-1.0f32).to_bits(),
+            upper: 1.0f32.to_bits(),
+            ..self
+        }
+    }
+
+    fn unsigned_float(self) -> Self {
+        Self {
+            channel: self.channel | KHR_DF_SAMPLE_FLOAT,
+            lower: 0,
+            upper: 1.0f32.to_bits(),
+            ..self
+        }
+    }
+
+    fn uint(self) -> Self {
+        // Integer data is not normalized, so the upper value is 1.
+        Self {
+            lower: 0,
+            upper: 1,
+            ..self
+        }
+    }
+
+    fn sint(self) -> Self {
+        Self {
+            channel: self.channel | KHR_DF_SAMPLE_SIGNED,
+            lower: -1i32 as u32,
+            upper: 1,
+            ..self
+        }
+    }
+
+    fn linear(self) -> Self {
+        Self {
+            channel: self.channel | KHR_DF_SAMPLE_LINEAR,
+            ..self
+        }
+    }
+}
+
+fn dfd_samples(format: ImageFormat) -> (u8, Vec<Sample>) {
+    use KHR_DF_CHANNEL_ALPHA as A;
+    use KHR_DF_CHANNEL_BLUE as B;
+    use KHR_DF_CHANNEL_DEPTH as D;
+    use KHR_DF_CHANNEL_GREEN as G;
+    use KHR_DF_CHANNEL_RED as R;
+
+    let channels = |bits: u8, names: &[u8]| {
+        names
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(i, c)| Sample::new(i as u16 * bits as u16, bits, c))
+            .collect::<Vec<_>>()
+    };
+    let signed =
+        |samples: Vec<Sample>| -> Vec<_> { samples.into_iter().map(Sample::signed).collect() };
+    let float =
+        |samples: Vec<Sample>| -> Vec<_> { samples.into_iter().map(Sample::float).collect() };
+    let uint = |samples: Vec<Sample>| -> Vec<_> { samples.into_iter().map(Sample::uint).collect() };
+    let sint = |samples: Vec<Sample>| -> Vec<_> { samples.into_iter().map(Sample::sint).collect() };
+    let srgb_alpha = |mut samples: Vec<Sample>| {
+        // The alpha channel is always linear.
+        let alpha = samples.pop().unwrap().linear();
+        samples.push(alpha);
+        samples
+    };
+
+    match format {
+        ImageFormat::R8Unorm => (KHR_DF_MODEL_RGBSDA, vec![Sample::new(0, 8, R)]),
+        ImageFormat::Rg8Unorm => (KHR_DF_MODEL_RGBSDA, channels(8, &[R, G])),
+        ImageFormat::R16Unorm => (KHR_DF_MODEL_RGBSDA, channels(16, &[R])),
+        ImageFormat::Rg16Unorm => (KHR_DF_MODEL_RGBSDA, channels(16, &[R, G])),
+        ImageFormat::Rg16Snorm => (KHR_DF_MODEL_RGBSDA, signed(channels(16, &[R, G]))),
+        ImageFormat::Rgba16Unorm => (KHR_DF_MODEL_RGBSDA, channels(16, &[R, G, B, A])),
+        ImageFormat::Rgba16Snorm => (KHR_DF_MODEL_RGBSDA, signed(channels(16, &[R, G, B, A]))),
+        ImageFormat::R16Float => (KHR_DF_MODEL_RGBSDA, float(channels(16, &[R]))),
+        ImageFormat::Rg16Float => (KHR_DF_MODEL_RGBSDA, float(channels(16, &[R, G]))),
+        ImageFormat::R32Float => (KHR_DF_MODEL_RGBSDA, float(channels(32, &[R]))),
+        ImageFormat::Rg32Float => (KHR_DF_MODEL_RGBSDA, float(channels(32, &[R, G]))),
+        ImageFormat::Rgba8Unorm => (KHR_DF_MODEL_RGBSDA, channels(8, &[R, G, B, A])),
+        ImageFormat::R8Uint => (KHR_DF_MODEL_RGBSDA, uint(channels(8, &[R]))),
+        ImageFormat::R8Sint => (KHR_DF_MODEL_RGBSDA, sint(channels(8, &[R]))),
+        ImageFormat::R16Uint => (KHR_DF_MODEL_RGBSDA, uint(channels(16, &[R]))),
+        ImageFormat::R16Sint => (KHR_DF_MODEL_RGBSDA, sint(channels(16, &[R]))),
+        ImageFormat::R32Uint => (KHR_DF_MODEL_RGBSDA, uint(channels(32, &[R]))),
+        ImageFormat::R32Sint => (KHR_DF_MODEL_RGBSDA, sint(channels(32, &[R]))),
+        ImageFormat::Rgba8Uint => (KHR_DF_MODEL_RGBSDA, uint(channels(8, &[R, G, B, A]))),
+        ImageFormat::Rgba8Sint => (KHR_DF_MODEL_RGBSDA, sint(channels(8, &[R, G, B, A]))),
+        ImageFormat::Rgba16Uint => (KHR_DF_MODEL_RGBSDA, uint(channels(16, &[R, G, B, A]))),
+        ImageFormat::Rgba16Sint => (KHR_DF_MODEL_RGBSDA, sint(channels(16, &[R, G, B, A]))),
+        ImageFormat::Rgba32Uint => (KHR_DF_MODEL_RGBSDA, uint(channels(32, &[R, G, B, A]))),
+        ImageFormat::Rgba32Sint => (KHR_DF_MODEL_RGBSDA, sint(channels(32, &[R, G, B, A]))),
+        ImageFormat::Rgba8UnormSrgb => {
+            (KHR_DF_MODEL_RGBSDA, srgb_alpha(channels(8, &[R, G, B, A])))
+        }
+        ImageFormat::Bgra8Unorm => (KHR_DF_MODEL_RGBSDA, channels(8, &[B, G, R, A])),
+        ImageFormat::Bgra8UnormSrgb => {
+            (KHR_DF_MODEL_RGBSDA, srgb_alpha(channels(8, &[B, G, R, A])))
+        }
+        ImageFormat::Bgra4Unorm => (KHR_DF_MODEL_RGBSDA, channels(4, &[B, G, R, A])),
+        ImageFormat::B5g6r5Unorm => (
+            KHR_DF_MODEL_RGBSDA,
+            vec![
+                Sample::new(0, 5, B),
+                Sample::new(5, 6, G),
+                Sample::new(11, 5, R),
+            ],
+        ),
+        ImageFormat::Bgr5a1Unorm => (
+            KHR_DF_MODEL_RGBSDA,
+            vec![
+                Sample::new(0, 5, B),
+                Sample::new(5, 5, G),
+                Sample::new(10, 5, R),
+                Sample::new(15, 1, A),
+            ],
+        ),
+        ImageFormat::Rgb8Unorm => (KHR_DF_MODEL_RGBSDA, channels(8, &[R, G, B])),
+        ImageFormat::Bgr8Unorm => (KHR_DF_MODEL_RGBSDA, channels(8, &[B, G, R])),
+        ImageFormat::A8Unorm => (KHR_DF_MODEL_RGBSDA, vec![Sample::new(0, 8, A)]),
+        ImageFormat::Depth16Unorm => (KHR_DF_MODEL_RGBSDA, vec![Sample::new(0, 16, D)]),
+        ImageFormat::Depth32Float => (KHR_DF_MODEL_RGBSDA, float(vec![Sample::new(0, 32, D)])),
+        ImageFormat::Bgr5x1Unorm
+        | ImageFormat::Bgrx8Unorm
+        | ImageFormat::La8Unorm
+        | ImageFormat::Depth24UnormStencil8
+        | ImageFormat::Depth32FloatStencil8
+        | ImageFormat::Yuy2
+        | ImageFormat::Uyvy
+        | ImageFormat::Nv12
+        | ImageFormat::P010 => {
+            unreachable!("formats without a VkFormat are not written")
+        }
+        ImageFormat::Rgba16Float => (KHR_DF_MODEL_RGBSDA, float(channels(16, &[R, G, B, A]))),
+        ImageFormat::Rgba32Float => (KHR_DF_MODEL_RGBSDA, float(channels(32, &[R, G, B, A]))),
+        ImageFormat::Rgb10a2Unorm => (
+            KHR_DF_MODEL_RGBSDA,
+            vec![
+                Sample::new(0, 10, R),
+                Sample::new(10, 10, G),
+                Sample::new(20, 10, B),
+                Sample::new(30, 2, A),
+            ],
+        ),
+        ImageFormat::Rg11b10Ufloat => (
+            KHR_DF_MODEL_RGBSDA,
+            vec![
+                Sample::new(0, 11, R).unsigned_float(),
+                Sample::new(11, 11, G).unsigned_float(),
+                Sample::new(22, 10, B).unsigned_float(),
+            ],
+        ),
+        ImageFormat::Rgb9e5Ufloat => {
+            // Each channel has a mantissa sample and a sample for the shared exponent.
+            let exponent = |channel| Sample {
+                channel: channel | KHR_DF_SAMPLE_EXPONENT,
+                lower: 15,
+                upper: 31,
+                ..Sample::new(27, 5, channel)
+            };
+            let mantissa = |bit_offset, channel| Sample {
+                upper: 8448,
+                ..Sample::new(bit_offset, 9, channel)
+            };
+            (
+                KHR_DF_MODEL_RGBSDA,
+                vec![
+                    mantissa(0, R),
+                    exponent(R),
+                    mantissa(9, G),
+                    exponent(G),
+                    mantissa(18, B),
+                    exponent(B),
+                ],
+            )
+        }
+        // BC1 with alpha uses the alpha present channel.
+        ImageFormat::BC1RgbaUnorm | ImageFormat::BC1RgbaUnormSrgb => {
+            (KHR_DF_MODEL_BC1A, vec![Sample::new(0, 64, 1)])
+        }
+        ImageFormat::BC2RgbaUnorm => (
+            KHR_DF_MODEL_BC2,
+            vec![Sample::new(0, 64, A), Sample::new(64, 64, R)],
+        ),
+        ImageFormat::BC2RgbaUnormSrgb => (
+            KHR_DF_MODEL_BC2,
+            vec![Sample::new(0, 64, A).linear(), Sample::new(64, 64, R)],
+        ),
+        ImageFormat::BC3RgbaUnorm => (
+            KHR_DF_MODEL_BC3,
+            vec![Sample::new(0, 64, A), Sample::new(64, 64, R)],
+        ),
+        ImageFormat::BC3RgbaUnormSrgb => (
+            KHR_DF_MODEL_BC3,
+            vec![Sample::new(0, 64, A).linear(), Sample::new(64, 64, R)],
+        ),
+        ImageFormat::BC4RUnorm => (KHR_DF_MODEL_BC4, vec![Sample::new(0, 64, R)]),
+        ImageFormat::BC4RSnorm => (KHR_DF_MODEL_BC4, vec![Sample::new(0, 64, R).signed()]),
+        ImageFormat::BC5RgUnorm => (
+            KHR_DF_MODEL_BC5,
+            vec![Sample::new(0, 64, R), Sample::new(64, 64, G)],
+        ),
+        ImageFormat::BC5RgSnorm => (
+            KHR_DF_MODEL_BC5,
+            vec![
+                Sample::new(0, 64, R).signed(),
+                Sample::new(64, 64, G).signed(),
+            ],
+        ),
+        ImageFormat::BC6hRgbUfloat => (
+            KHR_DF_MODEL_BC6H,
+            vec![Sample::new(0, 128, R).unsigned_float()],
+        ),
+        ImageFormat::BC6hRgbSfloat => (KHR_DF_MODEL_BC6H, vec![Sample::new(0, 128, R).float()]),
+        ImageFormat::BC7RgbaUnorm | ImageFormat::BC7RgbaUnormSrgb => {
+            (KHR_DF_MODEL_BC7, vec![Sample::new(0, 128, R)])
+        }
+        ImageFormat::Etc1RgbUnorm
+        | ImageFormat::Etc2RgbUnorm
+        | ImageFormat::Etc2RgbUnormSrgb
+        | ImageFormat::Etc2Rgba1Unorm
+        | ImageFormat::Etc2Rgba1UnormSrgb => (
+            KHR_DF_MODEL_ETC2,
+            vec![Sample::new(0, 64, KHR_DF_CHANNEL_ETC2_COLOR)],
+        ),
+        ImageFormat::Etc2Rgba8Unorm => (
+            KHR_DF_MODEL_ETC2,
+            vec![
+                Sample::new(0, 64, A),
+                Sample::new(64, 64, KHR_DF_CHANNEL_ETC2_COLOR),
+            ],
+        ),
+        ImageFormat::Etc2Rgba8UnormSrgb => (
+            KHR_DF_MODEL_ETC2,
+            vec![
+                Sample::new(0, 64, A).linear(),
+                Sample::new(64, 64, KHR_DF_CHANNEL_ETC2_COLOR),
+            ],
+        ),
+        ImageFormat::EacR11Unorm => (KHR_DF_MODEL_ETC2, vec![Sample::new(0, 64, R)]),
+        ImageFormat::EacR11Snorm => (KHR_DF_MODEL_ETC2, vec![Sample::new(0, 64, R).signed()]),
+        ImageFormat::EacRg11Unorm => (
+            KHR_DF_MODEL_ETC2,
+            vec![Sample::new(0, 64, R), Sample::new(64, 64, G)],
+        ),
+        ImageFormat::EacRg11Snorm => (
+            KHR_DF_MODEL_ETC2,
+            vec![
+                Sample::new(0, 64, R).signed(),
+                Sample::new(64, 64, G).signed(),
+            ],
+        ),
+        ImageFormat::Astc4x4RgbaUnorm
+        | ImageFormat::Astc4x4RgbaUnormSrgb
+        | ImageFormat::Astc5x4RgbaUnorm
+        | ImageFormat::Astc5x4RgbaUnormSrgb
+        | ImageFormat::Astc5x5RgbaUnorm
+        | ImageFormat::Astc5x5RgbaUnormSrgb
+        | ImageFormat::Astc6x5RgbaUnorm
+        | ImageFormat::Astc6x5RgbaUnormSrgb
+        | ImageFormat::Astc6x6RgbaUnorm
+        | ImageFormat::Astc6x6RgbaUnormSrgb
+        | ImageFormat::Astc8x5RgbaUnorm
+        | ImageFormat::Astc8x5RgbaUnormSrgb
+        | ImageFormat::Astc8x6RgbaUnorm
+        | ImageFormat::Astc8x6RgbaUnormSrgb
+        | ImageFormat::Astc8x8RgbaUnorm
+        | ImageFormat::Astc8x8RgbaUnormSrgb
+        | ImageFormat::Astc10x5RgbaUnorm
+        | ImageFormat::Astc10x5RgbaUnormSrgb
+        | ImageFormat::Astc10x6RgbaUnorm
+        | ImageFormat::Astc10x6RgbaUnormSrgb
+        | ImageFormat::Astc10x8RgbaUnorm
+        | ImageFormat::Astc10x8RgbaUnormSrgb
+        | ImageFormat::Astc10x10RgbaUnorm
+        | ImageFormat::Astc10x10RgbaUnormSrgb
+        | ImageFormat::Astc12x10RgbaUnorm
+        | ImageFormat::Astc12x10RgbaUnormSrgb
+        | ImageFormat::Astc12x12RgbaUnorm
+        | ImageFormat::Astc12x12RgbaUnormSrgb => (KHR_DF_MODEL_ASTC, vec![Sample::new(0, 128, 0)]),
+        ImageFormat::Astc4x4RgbaFloat
+        | ImageFormat::Astc5x4RgbaFloat
+        | ImageFormat::Astc5x5RgbaFloat
+        | ImageFormat::Astc6x5RgbaFloat
+        | ImageFormat::Astc6x6RgbaFloat
+        | ImageFormat::Astc8x5RgbaFloat
+        | ImageFormat::Astc8x6RgbaFloat
+        | ImageFormat::Astc8x8RgbaFloat
+        | ImageFormat::Astc10x5RgbaFloat
+        | ImageFormat::Astc10x6RgbaFloat
+        | ImageFormat::Astc10x8RgbaFloat
+        | ImageFormat::Astc10x10RgbaFloat
+        | ImageFormat::Astc12x10RgbaFloat
+        | ImageFormat::Astc12x12RgbaFloat => {
+            (KHR_DF_MODEL_ASTC, vec![Sample::new(0, 128, 0).float()])
+        }
+    }
+}
+
+// A basic data format descriptor for the layout of each block of data.
+fn data_format_descriptor(
+    format: ImageFormat,
+    alpha_mode: AlphaMode,
+    is_supercompressed: bool,
+) -> Vec<u8> {
+    let (model, samples) = dfd_samples(format);
+    let (block_width, block_height, block_depth) = format.block_dimensions();
+
+    let transfer = if format.is_srgb() {
+        KHR_DF_TRANSFER_SRGB
+    } else {
+        KHR_DF_TRANSFER_LINEAR
+    };
+
+    let flags = if alpha_mode == AlphaMode::Premultiplied {
+        KHR_DF_FLAG_ALPHA_PREMULTIPLIED
+    } else {
+        0
+    };
+
+    // Supercompressed data has no defined size for each plane.
+    let bytes_plane0 = if is_supercompressed {
+        0
+    } else {
+        format.block_size_in_bytes() as u8
+    };
+
+    let descriptor_block_size = 24 + 16 * samples.len();
+
+    let mut dfd = Vec::new();
+    dfd.extend_from_slice(&(4 + descriptor_block_size as u32).to_le_bytes());
+    // The vendor ID and descriptor type for a basic descriptor block are both 0.
+    dfd.extend_from_slice(&0u32.to_le_bytes());
+    dfd.extend_from_slice(&2u16.to_le_bytes());
+    dfd.extend_from_slice(&(descriptor_block_size as u16).to_le_bytes());
+    dfd.extend_from_slice(&[model, KHR_DF_PRIMARIES_BT709, transfer, flags]);
+    dfd.extend_from_slice(&[
+        block_width as u8 - 1,
+        block_height as u8 - 1,
+        block_depth as u8 - 1,
+        0,
+    ]);
+    dfd.extend_from_slice(&[bytes_plane0, 0, 0, 0, 0, 0, 0, 0]);
+
+    for sample in samples {
+        dfd.extend_from_slice(&sample.bit_offset.to_le_bytes());
+        dfd.extend_from_slice(&[sample.bit_length - 1, sample.channel]);
+        dfd.extend_from_slice(&[0u8; 4]);
+        dfd.extend_from_slice(&sample.lower.to_le_bytes());
+        dfd.extend_from_slice(&sample.upper.to_le_bytes());
+    }
+
+    dfd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(surface: Surface<Vec<u8>>, supercompression: Ktx2Supercompression) {
+        let bytes = surface.to_ktx2(supercompression).unwrap();
+        assert_eq!(surface, Surface::from_ktx2(&bytes).unwrap());
+    }
+
+    #[test]
+    fn ktx2_round_trip_bc7_layers_mipmaps() {
+        round_trip(
+            Surface {
+                width: 8,
+                height: 8,
+                depth: 1,
+                layers: 2,
+                mipmaps: 4,
+                image_format: ImageFormat::BC7RgbaUnormSrgb,
+                alpha_mode: AlphaMode::Unknown,
+                data: (0..(4 + 1 + 1 + 1) * 16 * 2).map(|i| i as u8).collect(),
+            },
+            Ktx2Supercompression::None,
+        );
+    }
+
+    #[test]
+    fn ktx2_round_trip_premultiplied() {
+        round_trip(
+            Surface {
+                width: 4,
+                height: 4,
+                depth: 1,
+                layers: 1,
+                mipmaps: 1,
+                image_format: ImageFormat::BC3RgbaUnorm,
+                alpha_mode: AlphaMode::Premultiplied,
+                data: (0..16).collect(),
+            },
+            Ktx2Supercompression::None,
+        );
+    }
+
+    #[test]
+    fn ktx2_round_trip_volume() {
+        round_trip(
+            Surface {
+                width: 4,
+                height: 2,
+                depth: 3,
+                layers: 1,
+                mipmaps: 2,
+                image_format: ImageFormat::Rgba8Unorm,
+                alpha_mode: AlphaMode::Unknown,
+                data: (0..(4 * 2 * 3 + 2) * 4).map(|i| i as u8).collect(),
+            },
+            Ktx2Supercompression::None,
+        );
+    }
+
+    #[test]
+    fn ktx2_round_trip_vk_formats() {
+        for (image_format, vk_format, width, height, mipmaps) in [
+            (ImageFormat::Rgba16Snorm, 92, 3, 2, 2),
+            (ImageFormat::Rgba16Sint, 96, 3, 2, 2),
+            (ImageFormat::Depth32Float, 126, 3, 2, 2),
+        ] {
+            let mut surface = Surface {
+                width,
+                height,
+                depth: 1,
+                layers: 1,
+                mipmaps,
+                image_format,
+                alpha_mode: AlphaMode::Unknown,
+                data: Vec::new(),
+            };
+            let size: usize = (0..mipmaps)
+                .map(|mipmap| image_size(&surface, mipmap).unwrap())
+                .sum();
+            surface.data = (0..size).map(|i| i as u8).collect();
+
+            let bytes = surface.to_ktx2(Ktx2Supercompression::None).unwrap();
+            assert_eq!(vk_format, read_u32(&bytes, 12).unwrap());
+
+            assert_eq!(surface, Surface::from_ktx2(&bytes).unwrap());
+        }
+    }
+
+    #[test]
+    fn ktx2_round_trip_rgb8_mipmaps() {
+        // Mipmaps are aligned to a multiple of the 3 byte pixel size.
+        round_trip(
+            Surface {
+                width: 3,
+                height: 2,
+                depth: 1,
+                layers: 1,
+                mipmaps: 2,
+                image_format: ImageFormat::Rgb8Unorm,
+                alpha_mode: AlphaMode::Unknown,
+                data: (0..(3 * 2 + 1) * 3).map(|i| i as u8).collect(),
+            },
+            Ktx2Supercompression::None,
+        );
+    }
+
+    #[test]
+    fn ktx2_unsupported_image_format() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::La8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 4 * 4 * 2],
+        };
+        assert!(matches!(
+            surface.to_ktx2(Ktx2Supercompression::None),
+            Err(Ktx2Error::UnsupportedImageFormat(ImageFormat::La8Unorm))
+        ));
+    }
+
+    #[test]
+    fn ktx2_round_trip_astc_10x8_mipmaps() {
+        // Each mipmap has at least one 10x8 block.
+        round_trip(
+            Surface {
+                width: 20,
+                height: 9,
+                depth: 1,
+                layers: 1,
+                mipmaps: 3,
+                image_format: ImageFormat::Astc10x8RgbaFloat,
+                alpha_mode: AlphaMode::Unknown,
+                data: (0..(4 + 1 + 1) * 16).map(|i| i as u8).collect(),
+            },
+            Ktx2Supercompression::None,
+        );
+    }
+
+    #[test]
+    fn ktx2_round_trip_etc1() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Etc1RgbUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..8).collect::<Vec<u8>>(),
+        };
+        let bytes = surface.to_ktx2(Ktx2Supercompression::None).unwrap();
+
+        // ETC1 uses the ETC2 color model to match the VkFormat.
+        let dfd_offset = read_u32(&bytes, 48).unwrap() as usize;
+        assert_eq!(KHR_DF_MODEL_ETC2, bytes[dfd_offset + 12]);
+
+        let new_surface = Surface::from_ktx2(&bytes).unwrap();
+        assert_eq!(ImageFormat::Etc2RgbUnorm, new_surface.image_format);
+        assert_eq!(surface.data, new_surface.data);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn ktx2_round_trip_zstd() {
+        round_trip(
+            Surface {
+                width: 8,
+                height: 8,
+                depth: 1,
+                layers: 1,
+                mipmaps: 4,
+                image_format: ImageFormat::Rgba16Float,
+                alpha_mode: AlphaMode::Unknown,
+                data: (0..(64 + 16 + 4 + 1) * 8).map(|i| (i % 7) as u8).collect(),
+            },
+            Ktx2Supercompression::Zstd,
+        );
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn ktx2_zstd_unsupported() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 8],
+        };
+        assert!(matches!(
+            surface.to_ktx2(Ktx2Supercompression::Zstd),
+            Err(Ktx2Error::UnsupportedSupercompression(2))
+        ));
+    }
+
+    #[test]
+    fn ktx2_header_and_level_index() {
+        let surface = Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 12,
+            mipmaps: 2,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; (4 + 1) * 8 * 12],
+        };
+        let bytes = surface.to_ktx2_cubemap(Ktx2Supercompression::None).unwrap();
+
+        assert_eq!(&IDENTIFIER, &bytes[..12]);
+        assert_eq!(133, read_u32(&bytes, 12).unwrap());
+        assert_eq!(2, read_u32(&bytes, 32).unwrap());
+        assert_eq!(6, read_u32(&bytes, 36).unwrap());
+        assert_eq!(2, read_u32(&bytes, 40).unwrap());
+
+        // The smallest mipmap is stored first.
+        let offset0 = read_u64(&bytes, HEADER_SIZE).unwrap();
+        let offset1 = read_u64(&bytes, HEADER_SIZE + LEVEL_INDEX_ENTRY_SIZE).unwrap();
+        assert_eq!(4 * 8 * 12, read_u64(&bytes, HEADER_SIZE + 8).unwrap());
+        assert_eq!(8 * 12, read_u64(&bytes, HEADER_SIZE + 24 + 8).unwrap());
+        assert!(offset1 < offset0);
+        assert_eq!(0, offset0 % 8);
+        assert_eq!(0, offset1 % 8);
+
+        let new_surface = Surface::from_ktx2(&bytes).unwrap();
+        assert_eq!(12, new_surface.layers);
+        assert_eq!(surface, new_surface);
+    }
+
+    #[test]
+    fn ktx2_cubemap_invalid_layers() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 3,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 8 * 3],
+        };
+        assert!(matches!(
+            surface.to_ktx2_cubemap(Ktx2Supercompression::None),
+            Err(Ktx2Error::Surface(SurfaceError::InvalidCubeMap {
+                layers: 3,
+                depth: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn ktx2_invalid_identifier() {
+        assert!(matches!(
+            Surface::from_ktx2(&[0u8; 80]),
+            Err(Ktx2Error::InvalidIdentifier)
+        ));
+    }
+
+    #[test]
+    fn ktx2_unsupported_format() {
+        let mut bytes = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 4],
+        }
+        .to_ktx2(Ktx2Supercompression::None)
+        .unwrap();
+        bytes[12..16].copy_from_slice(&1u32.to_le_bytes());
+
+        assert!(matches!(
+            Surface::from_ktx2(&bytes),
+            Err(Ktx2Error::UnsupportedVkFormat(1))
+        ));
+    }
+
+    #[test]
+    fn ktx2_not_enough_data() {
+        let bytes = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 16],
+        }
+        .to_ktx2(Ktx2Supercompression::None)
+        .unwrap();
+
+        assert!(matches!(
+            Surface::from_ktx2(&bytes[..bytes.len() - 1]),
+            Err(Ktx2Error::NotEnoughData { .. })
+        ));
+    }
+
+    #[test]
+    fn ktx2_level_size_overflow() {
+        let mut bytes = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba32Float,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 16],
+        }
+        .to_ktx2(Ktx2Supercompression::None)
+        .unwrap();
+        // Each layer is 2^52 bytes, so 2^16 layers overflows the level size.
+        for (offset, value) in [(20, 65536u32), (24, 65536), (28, 65536), (32, 65536)] {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        assert!(matches!(
+            Surface::from_ktx2(&bytes),
+            Err(Ktx2Error::Surface(
+                SurfaceError::PixelCountWouldOverflow { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn data_format_descriptor_rgba8_srgb() {
+        let dfd = data_format_descriptor(ImageFormat::Rgba8UnormSrgb, AlphaMode::Straight, false);
+        assert_eq!(4 + 24 + 16 * 4, dfd.len());
+        assert_eq!(dfd.len() as u32, read_u32(&dfd, 0).unwrap());
+        assert_eq!(
+            [
+                KHR_DF_MODEL_RGBSDA,
+                KHR_DF_PRIMARIES_BT709,
+                KHR_DF_TRANSFER_SRGB
+            ],
+            dfd[12..15]
+        );
+        // The alpha sample is linear.
+        assert_eq!(
+            KHR_DF_CHANNEL_ALPHA | KHR_DF_SAMPLE_LINEAR,
+            dfd[28 + 16 * 3 + 3]
+        );
+    }
+
+    #[test]
+    fn data_format_descriptor_bc7() {
+        let dfd = data_format_descriptor(ImageFormat::BC7RgbaUnorm, AlphaMode::Straight, false);
+        assert_eq!(4 + 24 + 16, dfd.len());
+        assert_eq!([3, 3, 0, 0], dfd[16..20]);
+        assert_eq!(16, dfd[20]);
+        assert_eq!(
+            0,
+            data_format_descriptor(ImageFormat::BC7RgbaUnorm, AlphaMode::Straight, true)[20]
+        );
+    }
+
+    #[test]
+    fn data_format_descriptor_rg16_snorm() {
+        let dfd = data_format_descriptor(ImageFormat::Rg16Snorm, AlphaMode::Straight, false);
+        assert_eq!(4 + 24 + 16 * 2, dfd.len());
+        assert_eq!(4, dfd[20]);
+        // The green sample starts after the red sample.
+        assert_eq!(
+            [16, 0, 15, KHR_DF_CHANNEL_GREEN | KHR_DF_SAMPLE_SIGNED],
+            dfd[44..48]
+        );
+        assert_eq!(-32768, read_u32(&dfd, 52).unwrap() as i32);
+        assert_eq!(32767, read_u32(&dfd, 56).unwrap());
+    }
+}
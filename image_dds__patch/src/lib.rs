@@ -28,6 +28,16 @@
 //! These methods are ideal for internal conversions in libraries
 //! or applications that want to use [Surface] instead of DDS as an intermediate format.
 //!
+//! Depth formats like [ImageFormat::Depth32Float] decode depth to the red channel.
+//! Use [SurfaceRgba32Float::linearize_depth] to visualize depth from a perspective projection.
+//!
+//! Integer formats like [ImageFormat::R16Uint] store values like material IDs that should not be normalized.
+//! Decode these formats losslessly with [Surface::decode_rgbau32] and encode them from [SurfaceRgba32Uint].
+//!
+//! YUV formats like [ImageFormat::Nv12] store video frames with subsampled chroma.
+//! Decoding and encoding use [YuvMatrix::Bt601] by default.
+//! Use [Surface::decode_yuv_rgba8] or [SurfaceRgba8::encode_yuv] to select a different [YuvMatrix].
+//!
 //! Surfaces may use owned or borrowed data depending on whether the operation is lossless or not.
 //! A [SurfaceRgba8] can represent a view over an [image::RgbaImage] without any copies, for example.
 //!
@@ -38,17 +48,44 @@
 //! The `"encode"` feature is enabled by default but can be disabled
 //! to resolve compilation errors on some targets if not needed.
 //!
+//! The `"encode-rs"` feature enables a safe, pure Rust encoder for all BCN formats
+//! that compiles on all targets. Enable `"encode-rs"` instead of `"encode"`
+//! on targets without precompiled ISPC kernels.
+//! The encoding API is the same for both features.
+//! If both features are enabled, the ISPC encoder from `"encode"` is used.
+//! BC2 and the signed formats BC4 SNORM, BC5 SNORM, and BC6H SFLOAT are not supported
+//! by intel-tex-rs-2 and always use the pure Rust encoder.
+//! Encode signed formats from [SurfaceRgba32Float] to preserve negative values.
+//!
+//! The `"dds"` feature enables reading and writing DDS files without the ddsfile crate.
+//! [Surface::from_dds_bytes] creates a view over the surface data in a DDS file without any copies.
+//! [Surface::read_dds] and [Surface::write_dds] work with any [std::io::Read] or [std::io::Write].
+//! The conversions for [ddsfile] from the `"ddsfile"` feature can still be used alongside the `"dds"` feature.
+//!
+//! The `"ktx2"` feature enables reading and writing KTX2 files with [Surface::from_ktx2] and [Surface::to_ktx2].
+//! The `"zstd"` feature adds support for KTX2 files using Zstandard supercompression.
+//! The `"ktx"` feature enables reading and writing legacy KTX 1.1 files with [Surface::from_ktx] and [Surface::to_ktx].
+//! The `"pvr"` feature enables reading and writing PowerVR PVR v3 files with [Surface::from_pvr] and [Surface::to_pvr].
+//!
+//! The `"rayon"` feature encodes and decodes layers, depth slices, mipmaps,
+//! and rows of blocks within large mipmaps in parallel.
+//! The output is identical to single threaded encoding and decoding.
+//!
 //! # Limitations
-//! BC2 data can be decoded but not encoded due to limitations in intel-tex-rs-2.
-//! This format is very rarely used in practice.
 //! Not all targets will compile by default due to intel-tex-rs-2 using the Intel ISPC compiler
 //! and lacking precompiled kernels for all targets.
+//! The `"encode-rs"` encoder is optimized for speed over quality and only uses a single BC6H and BC7 mode.
+//! ETC1, ETC2, EAC, and ASTC formats are supported for decoding but not encoding.
+//! ASTC only supports 2D block footprints.
 
+mod astc;
 mod bcn;
+mod etc;
 mod rgba;
 mod surface;
+mod yuv;
 
-pub use surface::{Surface, SurfaceRgba32Float, SurfaceRgba8};
+pub use surface::{Surface, SurfaceRgba32Float, SurfaceRgba32Uint, SurfaceRgba8};
 
 pub mod error;
 use error::*;
@@ -61,14 +98,39 @@ pub use image;
 
 mod decode;
 
-#[cfg(feature = "encode")]
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
 mod encode;
+#[cfg(any(feature = "encode", feature = "encode-rs"))]
+mod filter;
+
+#[cfg(any(feature = "ddsfile", feature = "dds"))]
+mod dxgi;
 
 #[cfg(feature = "ddsfile")]
 mod dds;
 #[cfg(feature = "ddsfile")]
 pub use dds::*;
 
+#[cfg(feature = "dds")]
+mod dds_io;
+#[cfg(feature = "dds")]
+pub use dds_io::{DdsError, DdsHeader, DdsHeaderDx10, DdsPixelFormat};
+
+#[cfg(feature = "ktx2")]
+mod ktx2;
+#[cfg(feature = "ktx2")]
+pub use ktx2::{Ktx2Error, Ktx2Supercompression};
+
+#[cfg(feature = "ktx")]
+mod ktx;
+#[cfg(feature = "ktx")]
+pub use ktx::KtxError;
+
+#[cfg(feature = "pvr")]
+mod pvr;
+#[cfg(feature = "pvr")]
+pub use pvr::{PvrError, PvrFormatInfo};
+
 /// The conversion quality when encoding to compressed formats.
 ///
 /// Higher quality settings run significantly slower.
@@ -113,6 +175,160 @@ pub enum Mipmaps {
     GeneratedAutomatic,
 }
 
+/// Filters for downsampling when generating mipmaps.
+///
+/// Wider filters produce sharper mipmaps with less aliasing but are slower.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum MipmapFilter {
+    /// Average each 2x2x2 region of pixels. This is the fastest filter.
+    #[default]
+    Box,
+    /// A tent filter that also blends in neighboring pixels.
+    Triangle,
+    /// A Kaiser windowed sinc filter for sharp results with little ringing.
+    Kaiser,
+    /// A Lanczos windowed sinc filter with 3 lobes. Sharp but may ring near edges.
+    Lanczos3,
+    /// The Mitchell-Netravali cubic filter that balances blurring and ringing.
+    Mitchell,
+    /// Use the first pixel of each 2x2x2 region without any blending.
+    Nearest,
+    /// Use the most common pixel in each 2x2x2 region without any blending.
+    /// Ties use the first pixel like [MipmapFilter::Nearest].
+    ///
+    /// Integer formats like [ImageFormat::R8Uint] always use this filter
+    /// unless [MipmapFilter::Nearest] is selected since averaging would create new values.
+    Majority,
+}
+
+/// The channels used to store the XYZ components of a tangent space normal map.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum NormalMapLayout {
+    /// Store XYZ in the RGB channels.
+    #[default]
+    Rgb,
+    /// Store XY in the RG channels and set the blue channel to `0`.
+    /// This improves the precision of XY for formats like [ImageFormat::BC7RgbaUnorm].
+    /// [ImageFormat::BC5RgUnorm] and [ImageFormat::BC5RgSnorm] only store RG regardless of the layout.
+    ///
+    /// Z can be reconstructed when decoding BC5 with methods like [Surface::decode_normal_map_rgba8].
+    Rg,
+}
+
+/// The color matrix for converting between RGB and YUV formats like [ImageFormat::Nv12].
+///
+/// YUV values use the limited range of `16` to `235` for luma and `16` to `240` for chroma.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum YuvMatrix {
+    /// ITU-R BT.601 for standard definition video.
+    #[default]
+    Bt601,
+    /// ITU-R BT.709 for high definition video.
+    Bt709,
+}
+
+/// How the alpha channel of a surface should be interpreted.
+///
+/// The values match the `DDS_ALPHA_MODE_*` values in the DX10 header of DDS files.
+/// Convert between straight and premultiplied alpha with methods like [SurfaceRgba8::premultiply_alpha].
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum AlphaMode {
+    /// The alpha mode is not known.
+    /// This is the case for most DDS files without a DX10 header.
+    Unknown,
+    /// RGB is stored independently of alpha.
+    #[default]
+    Straight,
+    /// RGB is already multiplied by alpha like DDS files using `DXT2` or `DXT4`.
+    Premultiplied,
+    /// Alpha should be ignored and treated as fully opaque.
+    Opaque,
+    /// Alpha stores custom data that is not transparency.
+    Custom,
+}
+
+/// The interpretation of typeless DXGI formats like `DXGI_FORMAT_BC7_TYPELESS` when reading DDS files.
+///
+/// Typeless formats without a variant for the interpretation use their default format.
+/// This is the unorm format or the float format for formats without a unorm variant
+/// like `DXGI_FORMAT_R32_TYPELESS` and `DXGI_FORMAT_BC6H_TYPELESS`.
+/// Formats that are not typeless are unaffected.
+#[cfg(any(feature = "ddsfile", feature = "dds"))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "strum",
+    derive(strum::EnumString, strum::Display, strum::EnumIter)
+)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum TypelessInterpretation {
+    /// Unsigned normalized formats like [ImageFormat::BC7RgbaUnorm].
+    #[default]
+    Unorm,
+    /// sRGB formats like [ImageFormat::BC7RgbaUnormSrgb].
+    UnormSrgb,
+    /// Signed formats like [ImageFormat::BC5RgSnorm] or [ImageFormat::BC6hRgbSfloat].
+    Snorm,
+    /// Floating point formats like [ImageFormat::R16Float].
+    Float,
+    /// Unsigned integer formats like [ImageFormat::R8Uint].
+    Uint,
+    /// Signed integer formats like [ImageFormat::R8Sint].
+    Sint,
+}
+
+/// Settings for generating mipmaps with [Mipmaps::GeneratedExact] or [Mipmaps::GeneratedAutomatic].
+///
+/// Integer data from [SurfaceRgba32Uint] only uses the filter and ignores the other settings.
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct MipmapSettings {
+    /// The filter for downsampling each mipmap from the previous mipmap.
+    pub filter: MipmapFilter,
+    /// Override whether the RGB channels are filtered in linear space by treating them as sRGB.
+    ///
+    /// The default of `None` only uses linear filtering for sRGB formats like [ImageFormat::BC7RgbaUnormSrgb].
+    /// Use `Some(false)` for data with an sRGB format that does not actually store sRGB colors.
+    pub srgb: Option<bool>,
+    /// Scale the alpha of generated mipmaps to preserve the fraction of pixels
+    /// with alpha above this reference value in the base level.
+    ///
+    /// This prevents alpha tested geometry like foliage from thinning out at lower mipmaps.
+    /// The reference value uses the normalized range `0.0` to `1.0` like `0.5` for an alpha cutoff of `128`.
+    pub alpha_test_reference: Option<f32>,
+    /// Treat the data as a tangent space normal map with the given layout.
+    ///
+    /// Vectors are renormalized after downsampling each generated mipmap
+    /// instead of filtering the RGB channels as colors.
+    /// Unsigned formats and RGBA8 data map the range `-1.0` to `1.0` to the range `0.0` to `1.0`.
+    pub normal_map: Option<NormalMapLayout>,
+}
+
 /// Supported image formats for encoding and decoding.
 ///
 /// Not all DDS formats are supported,
@@ -127,13 +343,70 @@ pub enum Mipmaps {
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ImageFormat {
     R8Unorm,
+    Rg8Unorm,
+    /// RGB8 with no alpha like GL_RGB8
+    Rgb8Unorm,
     Rgba8Unorm,
     Rgba8UnormSrgb,
+    R16Unorm,
+    Rg16Unorm,
+    Rg16Snorm,
+    Rgba16Unorm,
+    Rgba16Snorm,
+    R16Float,
+    Rg16Float,
     Rgba16Float,
+    R32Float,
+    Rg32Float,
     Rgba32Float,
+    Rgb10a2Unorm,
+    Rg11b10Ufloat,
+    Rgb9e5Ufloat,
+    R8Uint,
+    R8Sint,
+    R16Uint,
+    R16Sint,
+    R32Uint,
+    R32Sint,
+    Rgba8Uint,
+    Rgba8Sint,
+    Rgba16Uint,
+    Rgba16Sint,
+    Rgba32Uint,
+    Rgba32Sint,
     Bgra8Unorm,
     Bgra8UnormSrgb,
     Bgra4Unorm,
+    /// R5G6B5
+    B5g6r5Unorm,
+    /// A1R5G5B5
+    Bgr5a1Unorm,
+    /// X1R5G5B5
+    Bgr5x1Unorm,
+    /// R8G8B8
+    Bgr8Unorm,
+    /// X8R8G8B8
+    Bgrx8Unorm,
+    /// A8L8
+    La8Unorm,
+    /// A8
+    A8Unorm,
+    /// D16
+    Depth16Unorm,
+    /// D24S8
+    Depth24UnormStencil8,
+    /// D32
+    Depth32Float,
+    /// D32S8X24
+    Depth32FloatStencil8,
+    /// YUY2 with 4:2:2 subsampling
+    Yuy2,
+    /// UYVY with 4:2:2 subsampling
+    Uyvy,
+    /// NV12 with 4:2:0 subsampling
+    Nv12,
+    /// P010 with 4:2:0 subsampling
+    P010,
     /// DXT1
     BC1RgbaUnorm,
     /// DXT1
@@ -162,6 +435,112 @@ pub enum ImageFormat {
     BC7RgbaUnorm,
     /// BPTC (unorm)
     BC7RgbaUnormSrgb,
+    /// ETC1
+    Etc1RgbUnorm,
+    /// ETC2
+    Etc2RgbUnorm,
+    /// ETC2
+    Etc2RgbUnormSrgb,
+    /// ETC2 with punchthrough alpha
+    Etc2Rgba1Unorm,
+    /// ETC2 with punchthrough alpha
+    Etc2Rgba1UnormSrgb,
+    /// ETC2 with EAC alpha
+    Etc2Rgba8Unorm,
+    /// ETC2 with EAC alpha
+    Etc2Rgba8UnormSrgb,
+    /// EAC R11
+    EacR11Unorm,
+    /// EAC R11
+    EacR11Snorm,
+    /// EAC RG11
+    EacRg11Unorm,
+    /// EAC RG11
+    EacRg11Snorm,
+    /// ASTC 4x4 LDR
+    Astc4x4RgbaUnorm,
+    /// ASTC 4x4 LDR
+    Astc4x4RgbaUnormSrgb,
+    /// ASTC 4x4 HDR
+    Astc4x4RgbaFloat,
+    /// ASTC 5x4 LDR
+    Astc5x4RgbaUnorm,
+    /// ASTC 5x4 LDR
+    Astc5x4RgbaUnormSrgb,
+    /// ASTC 5x4 HDR
+    Astc5x4RgbaFloat,
+    /// ASTC 5x5 LDR
+    Astc5x5RgbaUnorm,
+    /// ASTC 5x5 LDR
+    Astc5x5RgbaUnormSrgb,
+    /// ASTC 5x5 HDR
+    Astc5x5RgbaFloat,
+    /// ASTC 6x5 LDR
+    Astc6x5RgbaUnorm,
+    /// ASTC 6x5 LDR
+    Astc6x5RgbaUnormSrgb,
+    /// ASTC 6x5 HDR
+    Astc6x5RgbaFloat,
+    /// ASTC 6x6 LDR
+    Astc6x6RgbaUnorm,
+    /// ASTC 6x6 LDR
+    Astc6x6RgbaUnormSrgb,
+    /// ASTC 6x6 HDR
+    Astc6x6RgbaFloat,
+    /// ASTC 8x5 LDR
+    Astc8x5RgbaUnorm,
+    /// ASTC 8x5 LDR
+    Astc8x5RgbaUnormSrgb,
+    /// ASTC 8x5 HDR
+    Astc8x5RgbaFloat,
+    /// ASTC 8x6 LDR
+    Astc8x6RgbaUnorm,
+    /// ASTC 8x6 LDR
+    Astc8x6RgbaUnormSrgb,
+    /// ASTC 8x6 HDR
+    Astc8x6RgbaFloat,
+    /// ASTC 8x8 LDR
+    Astc8x8RgbaUnorm,
+    /// ASTC 8x8 LDR
+    Astc8x8RgbaUnormSrgb,
+    /// ASTC 8x8 HDR
+    Astc8x8RgbaFloat,
+    /// ASTC 10x5 LDR
+    Astc10x5RgbaUnorm,
+    /// ASTC 10x5 LDR
+    Astc10x5RgbaUnormSrgb,
+    /// ASTC 10x5 HDR
+    Astc10x5RgbaFloat,
+    /// ASTC 10x6 LDR
+    Astc10x6RgbaUnorm,
+    /// ASTC 10x6 LDR
+    Astc10x6RgbaUnormSrgb,
+    /// ASTC 10x6 HDR
+    Astc10x6RgbaFloat,
+    /// ASTC 10x8 LDR
+    Astc10x8RgbaUnorm,
+    /// ASTC 10x8 LDR
+    Astc10x8RgbaUnormSrgb,
+    /// ASTC 10x8 HDR
+    Astc10x8RgbaFloat,
+    /// ASTC 10x10 LDR
+    Astc10x10RgbaUnorm,
+    /// ASTC 10x10 LDR
+    Astc10x10RgbaUnormSrgb,
+    /// ASTC 10x10 HDR
+    Astc10x10RgbaFloat,
+    /// ASTC 12x10 LDR
+    Astc12x10RgbaUnorm,
+    /// ASTC 12x10 LDR
+    Astc12x10RgbaUnormSrgb,
+    /// ASTC 12x10 HDR
+    Astc12x10RgbaFloat,
+    /// ASTC 12x12 LDR
+    Astc12x12RgbaUnorm,
+    /// ASTC 12x12 LDR
+    Astc12x12RgbaUnormSrgb,
+    /// ASTC 12x12 HDR
+    Astc12x12RgbaFloat,
 }
 
 impl ImageFormat {
@@ -182,24 +561,146 @@ impl ImageFormat {
             ImageFormat::BC6hRgbSfloat => (4, 4, 1),
             ImageFormat::BC7RgbaUnorm => (4, 4, 1),
             ImageFormat::BC7RgbaUnormSrgb => (4, 4, 1),
+            ImageFormat::Etc1RgbUnorm => (4, 4, 1),
+            ImageFormat::Etc2RgbUnorm => (4, 4, 1),
+            ImageFormat::Etc2RgbUnormSrgb => (4, 4, 1),
+            ImageFormat::Etc2Rgba1Unorm => (4, 4, 1),
+            ImageFormat::Etc2Rgba1UnormSrgb => (4, 4, 1),
+            ImageFormat::Etc2Rgba8Unorm => (4, 4, 1),
+            ImageFormat::Etc2Rgba8UnormSrgb => (4, 4, 1),
+            ImageFormat::EacR11Unorm => (4, 4, 1),
+            ImageFormat::EacR11Snorm => (4, 4, 1),
+            ImageFormat::EacRg11Unorm => (4, 4, 1),
+            ImageFormat::EacRg11Snorm => (4, 4, 1),
+            ImageFormat::Astc4x4RgbaUnorm => (4, 4, 1),
+            ImageFormat::Astc4x4RgbaUnormSrgb => (4, 4, 1),
+            ImageFormat::Astc4x4RgbaFloat => (4, 4, 1),
+            ImageFormat::Astc5x4RgbaUnorm => (5, 4, 1),
+            ImageFormat::Astc5x4RgbaUnormSrgb => (5, 4, 1),
+            ImageFormat::Astc5x4RgbaFloat => (5, 4, 1),
+            ImageFormat::Astc5x5RgbaUnorm => (5, 5, 1),
+            ImageFormat::Astc5x5RgbaUnormSrgb => (5, 5, 1),
+            ImageFormat::Astc5x5RgbaFloat => (5, 5, 1),
+            ImageFormat::Astc6x5RgbaUnorm => (6, 5, 1),
+            ImageFormat::Astc6x5RgbaUnormSrgb => (6, 5, 1),
+            ImageFormat::Astc6x5RgbaFloat => (6, 5, 1),
+            ImageFormat::Astc6x6RgbaUnorm => (6, 6, 1),
+            ImageFormat::Astc6x6RgbaUnormSrgb => (6, 6, 1),
+            ImageFormat::Astc6x6RgbaFloat => (6, 6, 1),
+            ImageFormat::Astc8x5RgbaUnorm => (8, 5, 1),
+            ImageFormat::Astc8x5RgbaUnormSrgb => (8, 5, 1),
+            ImageFormat::Astc8x5RgbaFloat => (8, 5, 1),
+            ImageFormat::Astc8x6RgbaUnorm => (8, 6, 1),
+            ImageFormat::Astc8x6RgbaUnormSrgb => (8, 6, 1),
+            ImageFormat::Astc8x6RgbaFloat => (8, 6, 1),
+            ImageFormat::Astc8x8RgbaUnorm => (8, 8, 1),
+            ImageFormat::Astc8x8RgbaUnormSrgb => (8, 8, 1),
+            ImageFormat::Astc8x8RgbaFloat => (8, 8, 1),
+            ImageFormat::Astc10x5RgbaUnorm => (10, 5, 1),
+            ImageFormat::Astc10x5RgbaUnormSrgb => (10, 5, 1),
+            ImageFormat::Astc10x5RgbaFloat => (10, 5, 1),
+            ImageFormat::Astc10x6RgbaUnorm => (10, 6, 1),
+            ImageFormat::Astc10x6RgbaUnormSrgb => (10, 6, 1),
+            ImageFormat::Astc10x6RgbaFloat => (10, 6, 1),
+            ImageFormat::Astc10x8RgbaUnorm => (10, 8, 1),
+            ImageFormat::Astc10x8RgbaUnormSrgb => (10, 8, 1),
+            ImageFormat::Astc10x8RgbaFloat => (10, 8, 1),
+            ImageFormat::Astc10x10RgbaUnorm => (10, 10, 1),
+            ImageFormat::Astc10x10RgbaUnormSrgb => (10, 10, 1),
+            ImageFormat::Astc10x10RgbaFloat => (10, 10, 1),
+            ImageFormat::Astc12x10RgbaUnorm => (12, 10, 1),
+            ImageFormat::Astc12x10RgbaUnormSrgb => (12, 10, 1),
+            ImageFormat::Astc12x10RgbaFloat => (12, 10, 1),
+            ImageFormat::Astc12x12RgbaUnorm => (12, 12, 1),
+            ImageFormat::Astc12x12RgbaUnormSrgb => (12, 12, 1),
+            ImageFormat::Astc12x12RgbaFloat => (12, 12, 1),
             ImageFormat::R8Unorm => (1, 1, 1),
+            ImageFormat::Rg8Unorm => (1, 1, 1),
             ImageFormat::Rgba8Unorm => (1, 1, 1),
             ImageFormat::Rgba8UnormSrgb => (1, 1, 1),
+            ImageFormat::R16Unorm => (1, 1, 1),
+            ImageFormat::Rg16Unorm => (1, 1, 1),
+            ImageFormat::Rg16Snorm => (1, 1, 1),
+            ImageFormat::Rgba16Unorm => (1, 1, 1),
+            ImageFormat::Rgba16Snorm => (1, 1, 1),
+            ImageFormat::R16Float => (1, 1, 1),
+            ImageFormat::Rg16Float => (1, 1, 1),
             ImageFormat::Rgba16Float => (1, 1, 1),
+            ImageFormat::R32Float => (1, 1, 1),
+            ImageFormat::Rg32Float => (1, 1, 1),
             ImageFormat::Rgba32Float => (1, 1, 1),
+            ImageFormat::Rgb10a2Unorm => (1, 1, 1),
+            ImageFormat::Rg11b10Ufloat => (1, 1, 1),
+            ImageFormat::Rgb9e5Ufloat => (1, 1, 1),
+            ImageFormat::R8Uint => (1, 1, 1),
+            ImageFormat::R8Sint => (1, 1, 1),
+            ImageFormat::R16Uint => (1, 1, 1),
+            ImageFormat::R16Sint => (1, 1, 1),
+            ImageFormat::R32Uint => (1, 1, 1),
+            ImageFormat::R32Sint => (1, 1, 1),
+            ImageFormat::Rgba8Uint => (1, 1, 1),
+            ImageFormat::Rgba8Sint => (1, 1, 1),
+            ImageFormat::Rgba16Uint => (1, 1, 1),
+            ImageFormat::Rgba16Sint => (1, 1, 1),
+            ImageFormat::Rgba32Uint => (1, 1, 1),
+            ImageFormat::Rgba32Sint => (1, 1, 1),
             ImageFormat::Bgra8Unorm => (1, 1, 1),
             ImageFormat::Bgra8UnormSrgb => (1, 1, 1),
             ImageFormat::Bgra4Unorm => (1, 1, 1),
+            ImageFormat::B5g6r5Unorm => (1, 1, 1),
+            ImageFormat::Bgr5a1Unorm => (1, 1, 1),
+            ImageFormat::Bgr5x1Unorm => (1, 1, 1),
+            ImageFormat::Rgb8Unorm => (1, 1, 1),
+            ImageFormat::Bgr8Unorm => (1, 1, 1),
+            ImageFormat::Bgrx8Unorm => (1, 1, 1),
+            ImageFormat::La8Unorm => (1, 1, 1),
+            ImageFormat::A8Unorm => (1, 1, 1),
+            ImageFormat::Depth16Unorm => (1, 1, 1),
+            ImageFormat::Depth24UnormStencil8 => (1, 1, 1),
+            ImageFormat::Depth32Float => (1, 1, 1),
+            ImageFormat::Depth32FloatStencil8 => (1, 1, 1),
+            // Each block covers the pixels that share a single chroma sample.
+            // Planar formats store a full resolution Y plane followed by an interleaved UV plane.
+            // The sizes of both planes still scale with the number of blocks for even dimensions.
+            ImageFormat::Yuy2 => (2, 1, 1),
+            ImageFormat::Uyvy => (2, 1, 1),
+            ImageFormat::Nv12 => (2, 2, 1),
+            ImageFormat::P010 => (2, 2, 1),
         }
     }
 
     fn block_size_in_bytes(&self) -> usize {
         match self {
             ImageFormat::R8Unorm => 1,
+            ImageFormat::Rg8Unorm => 2,
             ImageFormat::Rgba8Unorm => 4,
             ImageFormat::Rgba8UnormSrgb => 4,
+            ImageFormat::R16Unorm => 2,
+            ImageFormat::Rg16Unorm => 4,
+            ImageFormat::Rg16Snorm => 4,
+            ImageFormat::Rgba16Unorm => 8,
+            ImageFormat::Rgba16Snorm => 8,
+            ImageFormat::R16Float => 2,
+            ImageFormat::Rg16Float => 4,
             ImageFormat::Rgba16Float => 8,
+            ImageFormat::R32Float => 4,
+            ImageFormat::Rg32Float => 8,
             ImageFormat::Rgba32Float => 16,
+            ImageFormat::Rgb10a2Unorm => 4,
+            ImageFormat::Rg11b10Ufloat => 4,
+            ImageFormat::Rgb9e5Ufloat => 4,
+            ImageFormat::R8Uint => 1,
+            ImageFormat::R8Sint => 1,
+            ImageFormat::R16Uint => 2,
+            ImageFormat::R16Sint => 2,
+            ImageFormat::R32Uint => 4,
+            ImageFormat::R32Sint => 4,
+            ImageFormat::Rgba8Uint => 4,
+            ImageFormat::Rgba8Sint => 4,
+            ImageFormat::Rgba16Uint => 8,
+            ImageFormat::Rgba16Sint => 8,
+            ImageFormat::Rgba32Uint => 16,
+            ImageFormat::Rgba32Sint => 16,
             ImageFormat::Bgra8Unorm => 4,
             ImageFormat::Bgra8UnormSrgb => 4,
             ImageFormat::BC1RgbaUnorm => 8,
@@ -216,9 +717,119 @@ impl ImageFormat {
             ImageFormat::BC6hRgbSfloat => 16,
             ImageFormat::BC7RgbaUnorm => 16,
             ImageFormat::BC7RgbaUnormSrgb => 16,
+            ImageFormat::Etc1RgbUnorm => 8,
+            ImageFormat::Etc2RgbUnorm => 8,
+            ImageFormat::Etc2RgbUnormSrgb => 8,
+            ImageFormat::Etc2Rgba1Unorm => 8,
+            ImageFormat::Etc2Rgba1UnormSrgb => 8,
+            ImageFormat::Etc2Rgba8Unorm => 16,
+            ImageFormat::Etc2Rgba8UnormSrgb => 16,
+            ImageFormat::EacR11Unorm => 8,
+            ImageFormat::EacR11Snorm => 8,
+            ImageFormat::EacRg11Unorm => 16,
+            ImageFormat::EacRg11Snorm => 16,
+            ImageFormat::Astc4x4RgbaUnorm => 16,
+            ImageFormat::Astc4x4RgbaUnormSrgb => 16,
+            ImageFormat::Astc4x4RgbaFloat => 16,
+            ImageFormat::Astc5x4RgbaUnorm => 16,
+            ImageFormat::Astc5x4RgbaUnormSrgb => 16,
+            ImageFormat::Astc5x4RgbaFloat => 16,
+            ImageFormat::Astc5x5RgbaUnorm => 16,
+            ImageFormat::Astc5x5RgbaUnormSrgb => 16,
+            ImageFormat::Astc5x5RgbaFloat => 16,
+            ImageFormat::Astc6x5RgbaUnorm => 16,
+            ImageFormat::Astc6x5RgbaUnormSrgb => 16,
+            ImageFormat::Astc6x5RgbaFloat => 16,
+            ImageFormat::Astc6x6RgbaUnorm => 16,
+            ImageFormat::Astc6x6RgbaUnormSrgb => 16,
+            ImageFormat::Astc6x6RgbaFloat => 16,
+            ImageFormat::Astc8x5RgbaUnorm => 16,
+            ImageFormat::Astc8x5RgbaUnormSrgb => 16,
+            ImageFormat::Astc8x5RgbaFloat => 16,
+            ImageFormat::Astc8x6RgbaUnorm => 16,
+            ImageFormat::Astc8x6RgbaUnormSrgb => 16,
+            ImageFormat::Astc8x6RgbaFloat => 16,
+            ImageFormat::Astc8x8RgbaUnorm => 16,
+            ImageFormat::Astc8x8RgbaUnormSrgb => 16,
+            ImageFormat::Astc8x8RgbaFloat => 16,
+            ImageFormat::Astc10x5RgbaUnorm => 16,
+            ImageFormat::Astc10x5RgbaUnormSrgb => 16,
+            ImageFormat::Astc10x5RgbaFloat => 16,
+            ImageFormat::Astc10x6RgbaUnorm => 16,
+            ImageFormat::Astc10x6RgbaUnormSrgb => 16,
+            ImageFormat::Astc10x6RgbaFloat => 16,
+            ImageFormat::Astc10x8RgbaUnorm => 16,
+            ImageFormat::Astc10x8RgbaUnormSrgb => 16,
+            ImageFormat::Astc10x8RgbaFloat => 16,
+            ImageFormat::Astc10x10RgbaUnorm => 16,
+            ImageFormat::Astc10x10RgbaUnormSrgb => 16,
+            ImageFormat::Astc10x10RgbaFloat => 16,
+            ImageFormat::Astc12x10RgbaUnorm => 16,
+            ImageFormat::Astc12x10RgbaUnormSrgb => 16,
+            ImageFormat::Astc12x10RgbaFloat => 16,
+            ImageFormat::Astc12x12RgbaUnorm => 16,
+            ImageFormat::Astc12x12RgbaUnormSrgb => 16,
+            ImageFormat::Astc12x12RgbaFloat => 16,
             ImageFormat::Bgra4Unorm => 2,
+            ImageFormat::B5g6r5Unorm => 2,
+            ImageFormat::Bgr5a1Unorm => 2,
+            ImageFormat::Bgr5x1Unorm => 2,
+            ImageFormat::Rgb8Unorm => 3,
+            ImageFormat::Bgr8Unorm => 3,
+            ImageFormat::Bgrx8Unorm => 4,
+            ImageFormat::La8Unorm => 2,
+            ImageFormat::A8Unorm => 1,
+            ImageFormat::Depth16Unorm => 2,
+            ImageFormat::Depth24UnormStencil8 => 4,
+            ImageFormat::Depth32Float => 4,
+            ImageFormat::Depth32FloatStencil8 => 8,
+            ImageFormat::Yuy2 => 4,
+            ImageFormat::Uyvy => 4,
+            ImageFormat::Nv12 => 6,
+            ImageFormat::P010 => 12,
         }
     }
+
+    fn is_snorm(&self) -> bool {
+        matches!(
+            self,
+            ImageFormat::Rg16Snorm
+                | ImageFormat::Rgba16Snorm
+                | ImageFormat::BC4RSnorm
+                | ImageFormat::BC5RgSnorm
+                | ImageFormat::EacR11Snorm
+                | ImageFormat::EacRg11Snorm
+        )
+    }
+
+    fn is_srgb(&self) -> bool {
+        matches!(
+            self,
+            ImageFormat::Rgba8UnormSrgb
+                | ImageFormat::Bgra8UnormSrgb
+                | ImageFormat::BC1RgbaUnormSrgb
+                | ImageFormat::BC2RgbaUnormSrgb
+                | ImageFormat::BC3RgbaUnormSrgb
+                | ImageFormat::BC7RgbaUnormSrgb
+                | ImageFormat::Etc2RgbUnormSrgb
+                | ImageFormat::Etc2Rgba1UnormSrgb
+                | ImageFormat::Etc2Rgba8UnormSrgb
+                | ImageFormat::Astc4x4RgbaUnormSrgb
+                | ImageFormat::Astc5x4RgbaUnormSrgb
+                | ImageFormat::Astc5x5RgbaUnormSrgb
+                | ImageFormat::Astc6x5RgbaUnormSrgb
+                | ImageFormat::Astc6x6RgbaUnormSrgb
+                | ImageFormat::Astc8x5RgbaUnormSrgb
+                | ImageFormat::Astc8x6RgbaUnormSrgb
+                | ImageFormat::Astc8x8RgbaUnormSrgb
+                | ImageFormat::Astc10x5RgbaUnormSrgb
+                | ImageFormat::Astc10x6RgbaUnormSrgb
+                | ImageFormat::Astc10x8RgbaUnormSrgb
+                | ImageFormat::Astc10x10RgbaUnormSrgb
+                | ImageFormat::Astc12x10RgbaUnormSrgb
+                | ImageFormat::Astc12x12RgbaUnormSrgb
+        )
+    }
 }
 
 fn max_mipmap_count(max_dimension: u32) -> u32 {
@@ -233,22 +844,60 @@ pub fn mip_dimension(base_dimension: u32, mipmap: u32) -> u32 {
 }
 
 // TODO: Is this the best way to handle this?
-trait Pixel: Default + Copy {
+trait Pixel: Default + Copy + PartialEq {
+    /// The value of `1.0` in normalized data.
+    const NORMALIZED_MAX: f32;
+    /// `true` if signed normalized formats store negative values directly
+    /// instead of remapping the range `-1.0` to `1.0` to the range `0.0` to `1.0`.
+    const SIGNED: bool;
+
     fn from_f32(f: f32) -> Self;
     fn to_f32(&self) -> f32;
+
+    /// Convert to the nearest value instead of truncating.
+    fn from_f32_nearest(f: f32) -> Self {
+        Self::from_f32(f)
+    }
+
+    /// Convert to the range `-1.0` to `1.0`.
+    /// Unsigned data maps the range `0.0` to `1.0` to the range `-1.0` to `1.0`.
+    fn to_snorm(&self, signed: bool) -> f32 {
+        let f = self.to_f32() / Self::NORMALIZED_MAX;
+        if signed {
+            f
+        } else {
+            f * 2.0 - 1.0
+        }
+    }
+
+    /// The inverse of [Pixel::to_snorm].
+    fn from_snorm(f: f32, signed: bool) -> Self {
+        let f = if signed { f } else { f * 0.5 + 0.5 };
+        Self::from_f32_nearest(f * Self::NORMALIZED_MAX)
+    }
 }
 
 impl Pixel for u8 {
+    const NORMALIZED_MAX: f32 = 255.0;
+    const SIGNED: bool = false;
+
     fn from_f32(f: f32) -> Self {
         f as Self
     }
 
+    fn from_f32_nearest(f: f32) -> Self {
+        f.round() as Self
+    }
+
     fn to_f32(&self) -> f32 {
         *self as f32
     }
 }
 
 impl Pixel for f32 {
+    const NORMALIZED_MAX: f32 = 1.0;
+    const SIGNED: bool = true;
+
     fn from_f32(f: f32) -> Self {
         f
     }
@@ -559,4 +1208,38 @@ mod tests {
             calculate_offset(0, 3, 0, (16, 16, 16), (1, 1, 1), 4, 1).unwrap()
         );
     }
+
+    #[test]
+    fn calculate_offset_layer1_mip2_5x4() {
+        // The mipmaps 11x9, 5x4, and 2x2 use 3x3, 1x1, and 1x1 blocks.
+        assert_eq!(
+            (9 + 1 + 1 + 1) * 16 + (9 + 1) * 16,
+            calculate_offset(1, 0, 2, (11, 9, 1), (5, 4, 1), 16, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn calculate_offset_nv12_layer1() {
+        // Each layer has a 4x4 Y plane and a 2x2 interleaved UV plane.
+        assert_eq!(
+            4 * 4 + 2 * 2 * 2,
+            calculate_offset(
+                1,
+                0,
+                0,
+                (4, 4, 1),
+                ImageFormat::Nv12.block_dimensions(),
+                ImageFormat::Nv12.block_size_in_bytes(),
+                1
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn mip_size_12x10() {
+        // Partial blocks still use a full block of data.
+        assert_eq!(Some(2 * 16), mip_size(13, 7, 1, 12, 10, 1, 16));
+        assert_eq!(Some(16), mip_size(1, 1, 1, 12, 10, 1, 16));
+    }
 }
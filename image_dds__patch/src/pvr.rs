@@ -0,0 +1,1095 @@
+// PowerVR PVR v3 reading and writing.
+// https://docs.imgtec.com/specifications/pvr-container-format-specification/html/topics/pvr-header-format.html
+use thiserror::Error;
+
+use crate::{mip_dimension, mip_size, AlphaMode, ImageFormat, Surface, SurfaceError};
+
+const VERSION: u32 = 0x03525650;
+const HEADER_SIZE: usize = 52;
+const METADATA_HEADER_SIZE: usize = 12;
+
+const FLAG_PREMULTIPLIED: u32 = 0x02;
+
+const COLOR_SPACE_LINEAR: u32 = 0;
+const COLOR_SPACE_SRGB: u32 = 1;
+
+const CHANNEL_TYPE_UNSIGNED_BYTE_NORM: u32 = 0;
+const CHANNEL_TYPE_SIGNED_BYTE_NORM: u32 = 1;
+const CHANNEL_TYPE_UNSIGNED_BYTE: u32 = 2;
+const CHANNEL_TYPE_SIGNED_BYTE: u32 = 3;
+const CHANNEL_TYPE_UNSIGNED_SHORT_NORM: u32 = 4;
+const CHANNEL_TYPE_SIGNED_SHORT_NORM: u32 = 5;
+const CHANNEL_TYPE_UNSIGNED_SHORT: u32 = 6;
+const CHANNEL_TYPE_SIGNED_SHORT: u32 = 7;
+const CHANNEL_TYPE_UNSIGNED_INTEGER_NORM: u32 = 8;
+const CHANNEL_TYPE_UNSIGNED_INTEGER: u32 = 10;
+const CHANNEL_TYPE_SIGNED_INTEGER: u32 = 11;
+const CHANNEL_TYPE_SIGNED_FLOAT: u32 = 12;
+const CHANNEL_TYPE_UNSIGNED_FLOAT: u32 = 13;
+
+// Compressed formats only use the lower 32 bits of the pixel format.
+const PIXEL_FORMAT_DXT1: u64 = 7;
+const PIXEL_FORMAT_DXT3: u64 = 9;
+const PIXEL_FORMAT_DXT5: u64 = 11;
+const PIXEL_FORMAT_BC4: u64 = 12;
+const PIXEL_FORMAT_BC5: u64 = 13;
+const PIXEL_FORMAT_BC6: u64 = 14;
+const PIXEL_FORMAT_BC7: u64 = 15;
+const PIXEL_FORMAT_UYVY: u64 = 16;
+const PIXEL_FORMAT_YUY2: u64 = 17;
+const PIXEL_FORMAT_ETC1: u64 = 6;
+const PIXEL_FORMAT_SHARED_EXPONENT_R9G9B9E5: u64 = 19;
+const PIXEL_FORMAT_ETC2_RGB: u64 = 22;
+const PIXEL_FORMAT_ETC2_RGBA: u64 = 23;
+const PIXEL_FORMAT_ETC2_RGB_A1: u64 = 24;
+const PIXEL_FORMAT_EAC_R11: u64 = 25;
+const PIXEL_FORMAT_EAC_RG11: u64 = 26;
+const PIXEL_FORMAT_ASTC_4X4: u64 = 27;
+const PIXEL_FORMAT_ASTC_5X4: u64 = 28;
+const PIXEL_FORMAT_ASTC_5X5: u64 = 29;
+const PIXEL_FORMAT_ASTC_6X5: u64 = 30;
+const PIXEL_FORMAT_ASTC_6X6: u64 = 31;
+const PIXEL_FORMAT_ASTC_8X5: u64 = 32;
+const PIXEL_FORMAT_ASTC_8X6: u64 = 33;
+const PIXEL_FORMAT_ASTC_8X8: u64 = 34;
+const PIXEL_FORMAT_ASTC_10X5: u64 = 35;
+const PIXEL_FORMAT_ASTC_10X6: u64 = 36;
+const PIXEL_FORMAT_ASTC_10X8: u64 = 37;
+const PIXEL_FORMAT_ASTC_10X10: u64 = 38;
+const PIXEL_FORMAT_ASTC_12X10: u64 = 39;
+const PIXEL_FORMAT_ASTC_12X12: u64 = 40;
+
+/// Errors that can occur while reading or writing PVR files.
+#[derive(Debug, Error)]
+pub enum PvrError {
+    #[error("invalid PVR version {0:#x}")]
+    InvalidVersion(u32),
+
+    #[error("expected PVR file to have at least {expected} bytes but found {actual}")]
+    NotEnoughData { expected: usize, actual: usize },
+
+    #[error("PVR image format {0:?} is not supported")]
+    UnsupportedFormat(PvrFormatInfo),
+
+    #[error("image format {0:?} has no equivalent PVR format")]
+    UnsupportedImageFormat(ImageFormat),
+
+    #[error("error reading or writing surface: {0}")]
+    Surface(#[from] SurfaceError),
+}
+
+/// Format information for PVR files.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PvrFormatInfo {
+    /// A compressed format in the lower 32 bits or up to 4 channel names
+    /// in the lower 32 bits and their bit counts in the upper 32 bits.
+    pub pixel_format: u64,
+    pub color_space: u32,
+    pub channel_type: u32,
+}
+
+impl Surface<Vec<u8>> {
+    /// Read a little endian PVR v3 file from `bytes`.
+    ///
+    /// Cube map faces are stored as separate array layers like DDS.
+    /// Metadata blocks are validated but not included in the surface.
+    /// Files without the premultiplied alpha flag use [AlphaMode::Unknown].
+    pub fn from_pvr(bytes: &[u8]) -> Result<Self, PvrError> {
+        let version = read_u32(bytes, 0)?;
+        if version != VERSION {
+            return Err(PvrError::InvalidVersion(version));
+        }
+        let flags = read_u32(bytes, 4)?;
+
+        let info = PvrFormatInfo {
+            pixel_format: read_u64(bytes, 8)?,
+            color_space: read_u32(bytes, 16)?,
+            channel_type: read_u32(bytes, 20)?,
+        };
+        let height = read_u32(bytes, 24)?.max(1);
+        let width = read_u32(bytes, 28)?;
+        let depth = read_u32(bytes, 32)?.max(1);
+        let surfaces = read_u32(bytes, 36)?.max(1);
+        let faces = read_u32(bytes, 40)?.max(1);
+        let mipmaps = read_u32(bytes, 44)?.max(1);
+        let metadata_size = read_u32(bytes, 48)? as usize;
+
+        let image_format = image_format_from_pvr(info).ok_or(PvrError::UnsupportedFormat(info))?;
+
+        // Each metadata block has a FourCC, key, and size followed by the data.
+        let too_large = || PvrError::NotEnoughData {
+            expected: usize::MAX,
+            actual: bytes.len(),
+        };
+        let metadata_end = HEADER_SIZE
+            .checked_add(metadata_size)
+            .ok_or_else(too_large)?;
+        let mut offset = HEADER_SIZE;
+        while offset < metadata_end {
+            let size = read_u32(bytes, offset + 8)? as usize;
+            offset = offset
+                .checked_add(METADATA_HEADER_SIZE)
+                .and_then(|offset| offset.checked_add(size))
+                .ok_or_else(too_large)?;
+        }
+        if offset != metadata_end || bytes.len() < metadata_end {
+            return Err(PvrError::NotEnoughData {
+                expected: offset.max(metadata_end),
+                actual: bytes.len().min(metadata_end),
+            });
+        }
+
+        // Faces are stored after each other for each surface like DDS cube maps.
+        let overflow = || SurfaceError::PixelCountWouldOverflow {
+            width,
+            height,
+            depth,
+        };
+        let layers = surfaces.checked_mul(faces).ok_or_else(overflow)?;
+        let mut surface = Surface {
+            width,
+            height,
+            depth,
+            layers,
+            mipmaps,
+            image_format,
+            alpha_mode: if flags & FLAG_PREMULTIPLIED != 0 {
+                AlphaMode::Premultiplied
+            } else {
+                AlphaMode::Unknown
+            },
+            data: Vec::new(),
+        };
+
+        // PVR uses mipmap, surface, face, depth instead of layer, mipmap, depth.
+        let mut levels = Vec::new();
+        let mut offset = metadata_end;
+        for mipmap in 0..mipmaps {
+            let length = image_size(&surface, mipmap)?
+                .checked_mul(layers as usize)
+                .ok_or_else(overflow)?;
+            let level_end = offset.checked_add(length).ok_or_else(too_large)?;
+            let level_data = bytes
+                .get(offset..level_end)
+                .ok_or(PvrError::NotEnoughData {
+                    expected: level_end,
+                    actual: bytes.len(),
+                })?;
+            levels.push(level_data);
+            offset += length;
+        }
+
+        for layer in 0..layers as usize {
+            for (mipmap, level_data) in levels.iter().enumerate() {
+                let size = image_size(&surface, mipmap as u32)?;
+                surface
+                    .data
+                    .extend_from_slice(&level_data[layer * size..(layer + 1) * size]);
+            }
+        }
+
+        Ok(surface)
+    }
+}
+
+impl<T: AsRef<[u8]>> Surface<T> {
+    /// Create a PVR v3 file with the same image data and format.
+    pub fn to_pvr(&self) -> Result<Vec<u8>, PvrError> {
+        self.create_pvr(false)
+    }
+
+    /// Create a cube map PVR v3 file with the same image data and format.
+    ///
+    /// See [layers](#structfield.layers) for the order of the cube map faces.
+    pub fn to_pvr_cubemap(&self) -> Result<Vec<u8>, PvrError> {
+        self.validate_cube_map()?;
+        self.create_pvr(true)
+    }
+
+    fn create_pvr(&self, is_cubemap: bool) -> Result<Vec<u8>, PvrError> {
+        self.validate()?;
+
+        let info = pvr_format(self.image_format)
+            .ok_or(PvrError::UnsupportedImageFormat(self.image_format))?;
+        let (surfaces, faces) = if is_cubemap {
+            (self.layers / 6, 6)
+        } else {
+            (self.layers, 1)
+        };
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        let flags = if self.alpha_mode == AlphaMode::Premultiplied {
+            FLAG_PREMULTIPLIED
+        } else {
+            0
+        };
+        bytes.extend_from_slice(&flags.to_le_bytes());
+        bytes.extend_from_slice(&info.pixel_format.to_le_bytes());
+        for value in [
+            info.color_space,
+            info.channel_type,
+            self.height,
+            self.width,
+            self.depth,
+            surfaces,
+            faces,
+            self.mipmaps,
+            // Metadata is optional.
+            0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        // PVR uses mipmap, surface, face, depth instead of layer, mipmap, depth.
+        for mipmap in 0..self.mipmaps {
+            for layer in 0..self.layers {
+                for level in 0..mip_dimension(self.depth, mipmap) {
+                    let data = self
+                        .get(layer, level, mipmap)
+                        .ok_or(SurfaceError::MipmapDataOutOfBounds { layer, mipmap })?;
+                    bytes.extend_from_slice(data);
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+fn image_size<T>(surface: &Surface<T>, mipmap: u32) -> Result<usize, SurfaceError> {
+    let (block_width, block_height, block_depth) = surface.image_format.block_dimensions();
+    mip_size(
+        mip_dimension(surface.width, mipmap) as usize,
+        mip_dimension(surface.height, mipmap) as usize,
+        mip_dimension(surface.depth, mipmap) as usize,
+        block_width as usize,
+        block_height as usize,
+        block_depth as usize,
+        surface.image_format.block_size_in_bytes(),
+    )
+    .ok_or(SurfaceError::PixelCountWouldOverflow {
+        width: surface.width,
+        height: surface.height,
+        depth: surface.depth,
+    })
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, PvrError> {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or(PvrError::NotEnoughData {
+            expected: offset + 4,
+            actual: bytes.len(),
+        })
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, PvrError> {
+    bytes
+        .get(offset..offset + 8)
+        .and_then(|b| b.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(PvrError::NotEnoughData {
+            expected: offset + 8,
+            actual: bytes.len(),
+        })
+}
+
+// Uncompressed formats list channel names followed by their bit counts.
+// Packed formats list channels from the most significant bits.
+const fn uncompressed(channels: [u8; 4], bits: [u8; 4]) -> u64 {
+    u32::from_le_bytes(channels) as u64 | ((u32::from_le_bytes(bits) as u64) << 32)
+}
+
+const R8: u64 = uncompressed(*b"r\0\0\0", [8, 0, 0, 0]);
+const RG8: u64 = uncompressed(*b"rg\0\0", [8, 8, 0, 0]);
+const RGBA8: u64 = uncompressed(*b"rgba", [8, 8, 8, 8]);
+const BGRA8: u64 = uncompressed(*b"bgra", [8, 8, 8, 8]);
+const ARGB4: u64 = uncompressed(*b"argb", [4, 4, 4, 4]);
+const RGB565: u64 = uncompressed(*b"rgb\0", [5, 6, 5, 0]);
+const ARGB1555: u64 = uncompressed(*b"argb", [1, 5, 5, 5]);
+const XRGB1555: u64 = uncompressed(*b"xrgb", [1, 5, 5, 5]);
+const RGB8: u64 = uncompressed(*b"rgb\0", [8, 8, 8, 0]);
+const BGR8: u64 = uncompressed(*b"bgr\0", [8, 8, 8, 0]);
+const BGRX8: u64 = uncompressed(*b"bgrx", [8, 8, 8, 8]);
+const L8: u64 = uncompressed(*b"l\0\0\0", [8, 0, 0, 0]);
+const L16: u64 = uncompressed(*b"l\0\0\0", [16, 0, 0, 0]);
+const LA8: u64 = uncompressed(*b"la\0\0", [8, 8, 0, 0]);
+const A8: u64 = uncompressed(*b"a\0\0\0", [8, 0, 0, 0]);
+const R16: u64 = uncompressed(*b"r\0\0\0", [16, 0, 0, 0]);
+const RG16: u64 = uncompressed(*b"rg\0\0", [16, 16, 0, 0]);
+const RGBA16: u64 = uncompressed(*b"rgba", [16, 16, 16, 16]);
+const R32: u64 = uncompressed(*b"r\0\0\0", [32, 0, 0, 0]);
+const RG32: u64 = uncompressed(*b"rg\0\0", [32, 32, 0, 0]);
+const RGBA32: u64 = uncompressed(*b"rgba", [32, 32, 32, 32]);
+const ABGR2101010: u64 = uncompressed(*b"abgr", [2, 10, 10, 10]);
+const BGR101111: u64 = uncompressed(*b"bgr\0", [10, 11, 11, 0]);
+const D16: u64 = uncompressed(*b"d\0\0\0", [16, 0, 0, 0]);
+const SD824: u64 = uncompressed(*b"sd\0\0", [8, 24, 0, 0]);
+const D32: u64 = uncompressed(*b"d\0\0\0", [32, 0, 0, 0]);
+const DSX32824: u64 = uncompressed(*b"dsx\0", [32, 8, 24, 0]);
+
+fn image_format_from_pvr(info: PvrFormatInfo) -> Option<ImageFormat> {
+    let srgb = info.color_space == COLOR_SPACE_SRGB;
+    let signed = matches!(
+        info.channel_type,
+        CHANNEL_TYPE_SIGNED_BYTE_NORM | CHANNEL_TYPE_SIGNED_FLOAT
+    );
+    let float = matches!(
+        info.channel_type,
+        CHANNEL_TYPE_SIGNED_FLOAT | CHANNEL_TYPE_UNSIGNED_FLOAT
+    );
+
+    match (info.pixel_format, srgb) {
+        (R8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::R8Unorm)
+        }
+        (RG8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Rg8Unorm)
+        }
+        (RGBA8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Rgba8Unorm)
+        }
+        (RGBA8, true) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Rgba8UnormSrgb)
+        }
+        (BGRA8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgra8Unorm)
+        }
+        (BGRA8, true) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgra8UnormSrgb)
+        }
+        (ARGB4, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgra4Unorm)
+        }
+        (RGB565, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::B5g6r5Unorm)
+        }
+        (ARGB1555, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgr5a1Unorm)
+        }
+        (XRGB1555, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgr5x1Unorm)
+        }
+        (RGB8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Rgb8Unorm)
+        }
+        (BGR8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgr8Unorm)
+        }
+        (BGRX8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::Bgrx8Unorm)
+        }
+        (LA8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::La8Unorm)
+        }
+        (A8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::A8Unorm)
+        }
+        // Luminance decodes to grayscale like single channel formats.
+        (L8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE_NORM => {
+            Some(ImageFormat::R8Unorm)
+        }
+        (L16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT_NORM => {
+            Some(ImageFormat::R16Unorm)
+        }
+        (R16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT_NORM => {
+            Some(ImageFormat::R16Unorm)
+        }
+        (RG16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT_NORM => {
+            Some(ImageFormat::Rg16Unorm)
+        }
+        (RG16, false) if info.channel_type == CHANNEL_TYPE_SIGNED_SHORT_NORM => {
+            Some(ImageFormat::Rg16Snorm)
+        }
+        (RGBA16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT_NORM => {
+            Some(ImageFormat::Rgba16Unorm)
+        }
+        (RGBA16, false) if info.channel_type == CHANNEL_TYPE_SIGNED_SHORT_NORM => {
+            Some(ImageFormat::Rgba16Snorm)
+        }
+        (R16, false) if float => Some(ImageFormat::R16Float),
+        (RG16, false) if float => Some(ImageFormat::Rg16Float),
+        (RGBA16, false) if float => Some(ImageFormat::Rgba16Float),
+        (R32, false) if float => Some(ImageFormat::R32Float),
+        (RG32, false) if float => Some(ImageFormat::Rg32Float),
+        (RGBA32, false) if float => Some(ImageFormat::Rgba32Float),
+        (ABGR2101010, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_INTEGER_NORM => {
+            Some(ImageFormat::Rgb10a2Unorm)
+        }
+        (BGR101111, false) if float => Some(ImageFormat::Rg11b10Ufloat),
+        (PIXEL_FORMAT_SHARED_EXPONENT_R9G9B9E5, false) => Some(ImageFormat::Rgb9e5Ufloat),
+        (D16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT_NORM => {
+            Some(ImageFormat::Depth16Unorm)
+        }
+        (SD824, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_INTEGER_NORM => {
+            Some(ImageFormat::Depth24UnormStencil8)
+        }
+        (D32, false) if float => Some(ImageFormat::Depth32Float),
+        (DSX32824, false) if float => Some(ImageFormat::Depth32FloatStencil8),
+        (R8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE => Some(ImageFormat::R8Uint),
+        (R8, false) if info.channel_type == CHANNEL_TYPE_SIGNED_BYTE => Some(ImageFormat::R8Sint),
+        (R16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT => {
+            Some(ImageFormat::R16Uint)
+        }
+        (R16, false) if info.channel_type == CHANNEL_TYPE_SIGNED_SHORT => {
+            Some(ImageFormat::R16Sint)
+        }
+        (R32, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_INTEGER => {
+            Some(ImageFormat::R32Uint)
+        }
+        (R32, false) if info.channel_type == CHANNEL_TYPE_SIGNED_INTEGER => {
+            Some(ImageFormat::R32Sint)
+        }
+        (RGBA8, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_BYTE => {
+            Some(ImageFormat::Rgba8Uint)
+        }
+        (RGBA8, false) if info.channel_type == CHANNEL_TYPE_SIGNED_BYTE => {
+            Some(ImageFormat::Rgba8Sint)
+        }
+        (RGBA16, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_SHORT => {
+            Some(ImageFormat::Rgba16Uint)
+        }
+        (RGBA16, false) if info.channel_type == CHANNEL_TYPE_SIGNED_SHORT => {
+            Some(ImageFormat::Rgba16Sint)
+        }
+        (RGBA32, false) if info.channel_type == CHANNEL_TYPE_UNSIGNED_INTEGER => {
+            Some(ImageFormat::Rgba32Uint)
+        }
+        (RGBA32, false) if info.channel_type == CHANNEL_TYPE_SIGNED_INTEGER => {
+            Some(ImageFormat::Rgba32Sint)
+        }
+        (PIXEL_FORMAT_DXT1, false) => Some(ImageFormat::BC1RgbaUnorm),
+        (PIXEL_FORMAT_DXT1, true) => Some(ImageFormat::BC1RgbaUnormSrgb),
+        (PIXEL_FORMAT_DXT3, false) => Some(ImageFormat::BC2RgbaUnorm),
+        (PIXEL_FORMAT_DXT3, true) => Some(ImageFormat::BC2RgbaUnormSrgb),
+        (PIXEL_FORMAT_DXT5, false) => Some(ImageFormat::BC3RgbaUnorm),
+        (PIXEL_FORMAT_DXT5, true) => Some(ImageFormat::BC3RgbaUnormSrgb),
+        (PIXEL_FORMAT_BC4, false) if signed => Some(ImageFormat::BC4RSnorm),
+        (PIXEL_FORMAT_BC4, false) => Some(ImageFormat::BC4RUnorm),
+        (PIXEL_FORMAT_BC5, false) if signed => Some(ImageFormat::BC5RgSnorm),
+        (PIXEL_FORMAT_BC5, false) => Some(ImageFormat::BC5RgUnorm),
+        (PIXEL_FORMAT_BC6, false) if signed => Some(ImageFormat::BC6hRgbSfloat),
+        (PIXEL_FORMAT_BC6, false) => Some(ImageFormat::BC6hRgbUfloat),
+        (PIXEL_FORMAT_BC7, false) => Some(ImageFormat::BC7RgbaUnorm),
+        (PIXEL_FORMAT_BC7, true) => Some(ImageFormat::BC7RgbaUnormSrgb),
+        (PIXEL_FORMAT_UYVY, false) => Some(ImageFormat::Uyvy),
+        (PIXEL_FORMAT_YUY2, false) => Some(ImageFormat::Yuy2),
+        (PIXEL_FORMAT_ETC1, false) => Some(ImageFormat::Etc1RgbUnorm),
+        (PIXEL_FORMAT_ETC2_RGB, false) => Some(ImageFormat::Etc2RgbUnorm),
+        (PIXEL_FORMAT_ETC2_RGB, true) => Some(ImageFormat::Etc2RgbUnormSrgb),
+        (PIXEL_FORMAT_ETC2_RGB_A1, false) => Some(ImageFormat::Etc2Rgba1Unorm),
+        (PIXEL_FORMAT_ETC2_RGB_A1, true) => Some(ImageFormat::Etc2Rgba1UnormSrgb),
+        (PIXEL_FORMAT_ETC2_RGBA, false) => Some(ImageFormat::Etc2Rgba8Unorm),
+        (PIXEL_FORMAT_ETC2_RGBA, true) => Some(ImageFormat::Etc2Rgba8UnormSrgb),
+        (PIXEL_FORMAT_EAC_R11, false) if signed => Some(ImageFormat::EacR11Snorm),
+        (PIXEL_FORMAT_EAC_R11, false) => Some(ImageFormat::EacR11Unorm),
+        (PIXEL_FORMAT_EAC_RG11, false) if signed => Some(ImageFormat::EacRg11Snorm),
+        (PIXEL_FORMAT_EAC_RG11, false) => Some(ImageFormat::EacRg11Unorm),
+        (PIXEL_FORMAT_ASTC_4X4, false) if float => Some(ImageFormat::Astc4x4RgbaFloat),
+        (PIXEL_FORMAT_ASTC_4X4, false) => Some(ImageFormat::Astc4x4RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_4X4, true) => Some(ImageFormat::Astc4x4RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_5X4, false) if float => Some(ImageFormat::Astc5x4RgbaFloat),
+        (PIXEL_FORMAT_ASTC_5X4, false) => Some(ImageFormat::Astc5x4RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_5X4, true) => Some(ImageFormat::Astc5x4RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_5X5, false) if float => Some(ImageFormat::Astc5x5RgbaFloat),
+        (PIXEL_FORMAT_ASTC_5X5, false) => Some(ImageFormat::Astc5x5RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_5X5, true) => Some(ImageFormat::Astc5x5RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_6X5, false) if float => Some(ImageFormat::Astc6x5RgbaFloat),
+        (PIXEL_FORMAT_ASTC_6X5, false) => Some(ImageFormat::Astc6x5RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_6X5, true) => Some(ImageFormat::Astc6x5RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_6X6, false) if float => Some(ImageFormat::Astc6x6RgbaFloat),
+        (PIXEL_FORMAT_ASTC_6X6, false) => Some(ImageFormat::Astc6x6RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_6X6, true) => Some(ImageFormat::Astc6x6RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_8X5, false) if float => Some(ImageFormat::Astc8x5RgbaFloat),
+        (PIXEL_FORMAT_ASTC_8X5, false) => Some(ImageFormat::Astc8x5RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_8X5, true) => Some(ImageFormat::Astc8x5RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_8X6, false) if float => Some(ImageFormat::Astc8x6RgbaFloat),
+        (PIXEL_FORMAT_ASTC_8X6, false) => Some(ImageFormat::Astc8x6RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_8X6, true) => Some(ImageFormat::Astc8x6RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_8X8, false) if float => Some(ImageFormat::Astc8x8RgbaFloat),
+        (PIXEL_FORMAT_ASTC_8X8, false) => Some(ImageFormat::Astc8x8RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_8X8, true) => Some(ImageFormat::Astc8x8RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_10X5, false) if float => Some(ImageFormat::Astc10x5RgbaFloat),
+        (PIXEL_FORMAT_ASTC_10X5, false) => Some(ImageFormat::Astc10x5RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_10X5, true) => Some(ImageFormat::Astc10x5RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_10X6, false) if float => Some(ImageFormat::Astc10x6RgbaFloat),
+        (PIXEL_FORMAT_ASTC_10X6, false) => Some(ImageFormat::Astc10x6RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_10X6, true) => Some(ImageFormat::Astc10x6RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_10X8, false) if float => Some(ImageFormat::Astc10x8RgbaFloat),
+        (PIXEL_FORMAT_ASTC_10X8, false) => Some(ImageFormat::Astc10x8RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_10X8, true) => Some(ImageFormat::Astc10x8RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_10X10, false) if float => Some(ImageFormat::Astc10x10RgbaFloat),
+        (PIXEL_FORMAT_ASTC_10X10, false) => Some(ImageFormat::Astc10x10RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_10X10, true) => Some(ImageFormat::Astc10x10RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_12X10, false) if float => Some(ImageFormat::Astc12x10RgbaFloat),
+        (PIXEL_FORMAT_ASTC_12X10, false) => Some(ImageFormat::Astc12x10RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_12X10, true) => Some(ImageFormat::Astc12x10RgbaUnormSrgb),
+        (PIXEL_FORMAT_ASTC_12X12, false) if float => Some(ImageFormat::Astc12x12RgbaFloat),
+        (PIXEL_FORMAT_ASTC_12X12, false) => Some(ImageFormat::Astc12x12RgbaUnorm),
+        (PIXEL_FORMAT_ASTC_12X12, true) => Some(ImageFormat::Astc12x12RgbaUnormSrgb),
+        _ => None,
+    }
+}
+
+fn pvr_format(format: ImageFormat) -> Option<PvrFormatInfo> {
+    let (pixel_format, channel_type) = match format {
+        ImageFormat::R8Unorm => (R8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Rg8Unorm => (RG8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Rgba8Unorm => (RGBA8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Rgba8UnormSrgb => (RGBA8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::R16Unorm => (R16, CHANNEL_TYPE_UNSIGNED_SHORT_NORM),
+        ImageFormat::Rg16Unorm => (RG16, CHANNEL_TYPE_UNSIGNED_SHORT_NORM),
+        ImageFormat::Rg16Snorm => (RG16, CHANNEL_TYPE_SIGNED_SHORT_NORM),
+        ImageFormat::Rgba16Unorm => (RGBA16, CHANNEL_TYPE_UNSIGNED_SHORT_NORM),
+        ImageFormat::Rgba16Snorm => (RGBA16, CHANNEL_TYPE_SIGNED_SHORT_NORM),
+        ImageFormat::R16Float => (R16, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Rg16Float => (RG16, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Rgba16Float => (RGBA16, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::R32Float => (R32, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Rg32Float => (RG32, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Rgba32Float => (RGBA32, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Rgb10a2Unorm => (ABGR2101010, CHANNEL_TYPE_UNSIGNED_INTEGER_NORM),
+        ImageFormat::Rg11b10Ufloat => (BGR101111, CHANNEL_TYPE_UNSIGNED_FLOAT),
+        ImageFormat::Rgb9e5Ufloat => (
+            PIXEL_FORMAT_SHARED_EXPONENT_R9G9B9E5,
+            CHANNEL_TYPE_UNSIGNED_FLOAT,
+        ),
+        ImageFormat::R8Uint => (R8, CHANNEL_TYPE_UNSIGNED_BYTE),
+        ImageFormat::R8Sint => (R8, CHANNEL_TYPE_SIGNED_BYTE),
+        ImageFormat::R16Uint => (R16, CHANNEL_TYPE_UNSIGNED_SHORT),
+        ImageFormat::R16Sint => (R16, CHANNEL_TYPE_SIGNED_SHORT),
+        ImageFormat::R32Uint => (R32, CHANNEL_TYPE_UNSIGNED_INTEGER),
+        ImageFormat::R32Sint => (R32, CHANNEL_TYPE_SIGNED_INTEGER),
+        ImageFormat::Rgba8Uint => (RGBA8, CHANNEL_TYPE_UNSIGNED_BYTE),
+        ImageFormat::Rgba8Sint => (RGBA8, CHANNEL_TYPE_SIGNED_BYTE),
+        ImageFormat::Rgba16Uint => (RGBA16, CHANNEL_TYPE_UNSIGNED_SHORT),
+        ImageFormat::Rgba16Sint => (RGBA16, CHANNEL_TYPE_SIGNED_SHORT),
+        ImageFormat::Rgba32Uint => (RGBA32, CHANNEL_TYPE_UNSIGNED_INTEGER),
+        ImageFormat::Rgba32Sint => (RGBA32, CHANNEL_TYPE_SIGNED_INTEGER),
+        ImageFormat::Bgra8Unorm => (BGRA8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Bgra8UnormSrgb => (BGRA8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Bgra4Unorm => (ARGB4, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::B5g6r5Unorm => (RGB565, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Bgr5a1Unorm => (ARGB1555, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Bgr5x1Unorm => (XRGB1555, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Rgb8Unorm => (RGB8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Bgr8Unorm => (BGR8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Bgrx8Unorm => (BGRX8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::La8Unorm => (LA8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::A8Unorm => (A8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Depth16Unorm => (D16, CHANNEL_TYPE_UNSIGNED_SHORT_NORM),
+        ImageFormat::Depth24UnormStencil8 => (SD824, CHANNEL_TYPE_UNSIGNED_INTEGER_NORM),
+        ImageFormat::Depth32Float => (D32, CHANNEL_TYPE_SIGNED_FLOAT),
+        // The unused bits after the stencil value are listed as a separate channel.
+        ImageFormat::Depth32FloatStencil8 => (DSX32824, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::BC1RgbaUnorm => (PIXEL_FORMAT_DXT1, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC1RgbaUnormSrgb => (PIXEL_FORMAT_DXT1, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC2RgbaUnorm => (PIXEL_FORMAT_DXT3, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC2RgbaUnormSrgb => (PIXEL_FORMAT_DXT3, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC3RgbaUnorm => (PIXEL_FORMAT_DXT5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC3RgbaUnormSrgb => (PIXEL_FORMAT_DXT5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC4RUnorm => (PIXEL_FORMAT_BC4, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC4RSnorm => (PIXEL_FORMAT_BC4, CHANNEL_TYPE_SIGNED_BYTE_NORM),
+        ImageFormat::BC5RgUnorm => (PIXEL_FORMAT_BC5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC5RgSnorm => (PIXEL_FORMAT_BC5, CHANNEL_TYPE_SIGNED_BYTE_NORM),
+        ImageFormat::BC6hRgbUfloat => (PIXEL_FORMAT_BC6, CHANNEL_TYPE_UNSIGNED_FLOAT),
+        ImageFormat::BC6hRgbSfloat => (PIXEL_FORMAT_BC6, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::BC7RgbaUnorm => (PIXEL_FORMAT_BC7, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::BC7RgbaUnormSrgb => (PIXEL_FORMAT_BC7, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Etc1RgbUnorm => (PIXEL_FORMAT_ETC1, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Etc2RgbUnorm => (PIXEL_FORMAT_ETC2_RGB, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Etc2RgbUnormSrgb => (PIXEL_FORMAT_ETC2_RGB, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Etc2Rgba1Unorm => (PIXEL_FORMAT_ETC2_RGB_A1, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Etc2Rgba1UnormSrgb => {
+            (PIXEL_FORMAT_ETC2_RGB_A1, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Etc2Rgba8Unorm => (PIXEL_FORMAT_ETC2_RGBA, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Etc2Rgba8UnormSrgb => {
+            (PIXEL_FORMAT_ETC2_RGBA, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::EacR11Unorm => (PIXEL_FORMAT_EAC_R11, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::EacR11Snorm => (PIXEL_FORMAT_EAC_R11, CHANNEL_TYPE_SIGNED_BYTE_NORM),
+        ImageFormat::EacRg11Unorm => (PIXEL_FORMAT_EAC_RG11, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::EacRg11Snorm => (PIXEL_FORMAT_EAC_RG11, CHANNEL_TYPE_SIGNED_BYTE_NORM),
+        ImageFormat::Astc4x4RgbaUnorm | ImageFormat::Astc4x4RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_4X4, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc4x4RgbaFloat => (PIXEL_FORMAT_ASTC_4X4, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc5x4RgbaUnorm | ImageFormat::Astc5x4RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_5X4, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc5x4RgbaFloat => (PIXEL_FORMAT_ASTC_5X4, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc5x5RgbaUnorm | ImageFormat::Astc5x5RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_5X5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc5x5RgbaFloat => (PIXEL_FORMAT_ASTC_5X5, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc6x5RgbaUnorm | ImageFormat::Astc6x5RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_6X5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc6x5RgbaFloat => (PIXEL_FORMAT_ASTC_6X5, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc6x6RgbaUnorm | ImageFormat::Astc6x6RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_6X6, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc6x6RgbaFloat => (PIXEL_FORMAT_ASTC_6X6, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc8x5RgbaUnorm | ImageFormat::Astc8x5RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_8X5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc8x5RgbaFloat => (PIXEL_FORMAT_ASTC_8X5, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc8x6RgbaUnorm | ImageFormat::Astc8x6RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_8X6, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc8x6RgbaFloat => (PIXEL_FORMAT_ASTC_8X6, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc8x8RgbaUnorm | ImageFormat::Astc8x8RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_8X8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc8x8RgbaFloat => (PIXEL_FORMAT_ASTC_8X8, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc10x5RgbaUnorm | ImageFormat::Astc10x5RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_10X5, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc10x5RgbaFloat => (PIXEL_FORMAT_ASTC_10X5, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc10x6RgbaUnorm | ImageFormat::Astc10x6RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_10X6, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc10x6RgbaFloat => (PIXEL_FORMAT_ASTC_10X6, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc10x8RgbaUnorm | ImageFormat::Astc10x8RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_10X8, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc10x8RgbaFloat => (PIXEL_FORMAT_ASTC_10X8, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc10x10RgbaUnorm | ImageFormat::Astc10x10RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_10X10, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc10x10RgbaFloat => (PIXEL_FORMAT_ASTC_10X10, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc12x10RgbaUnorm | ImageFormat::Astc12x10RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_12X10, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc12x10RgbaFloat => (PIXEL_FORMAT_ASTC_12X10, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Astc12x12RgbaUnorm | ImageFormat::Astc12x12RgbaUnormSrgb => {
+            (PIXEL_FORMAT_ASTC_12X12, CHANNEL_TYPE_UNSIGNED_BYTE_NORM)
+        }
+        ImageFormat::Astc12x12RgbaFloat => (PIXEL_FORMAT_ASTC_12X12, CHANNEL_TYPE_SIGNED_FLOAT),
+        ImageFormat::Uyvy => (PIXEL_FORMAT_UYVY, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        ImageFormat::Yuy2 => (PIXEL_FORMAT_YUY2, CHANNEL_TYPE_UNSIGNED_BYTE_NORM),
+        // PVR has no standard formats for planar YUV data.
+        ImageFormat::Nv12 | ImageFormat::P010 => return None,
+    };
+
+    let color_space = if format.is_srgb() {
+        COLOR_SPACE_SRGB
+    } else {
+        COLOR_SPACE_LINEAR
+    };
+
+    Some(PvrFormatInfo {
+        pixel_format,
+        color_space,
+        channel_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMATS: [ImageFormat; 114] = [
+        ImageFormat::R8Unorm,
+        ImageFormat::Rg8Unorm,
+        ImageFormat::R16Unorm,
+        ImageFormat::Rg16Unorm,
+        ImageFormat::Rg16Snorm,
+        ImageFormat::Rgba16Unorm,
+        ImageFormat::Rgba16Snorm,
+        ImageFormat::R16Float,
+        ImageFormat::Rg16Float,
+        ImageFormat::R32Float,
+        ImageFormat::Rg32Float,
+        ImageFormat::Rgb10a2Unorm,
+        ImageFormat::Rg11b10Ufloat,
+        ImageFormat::Rgb9e5Ufloat,
+        ImageFormat::Rgba8Unorm,
+        ImageFormat::Rgba8UnormSrgb,
+        ImageFormat::Rgba16Float,
+        ImageFormat::Rgba32Float,
+        ImageFormat::Bgra8Unorm,
+        ImageFormat::Bgra8UnormSrgb,
+        ImageFormat::Bgra4Unorm,
+        ImageFormat::B5g6r5Unorm,
+        ImageFormat::Bgr5a1Unorm,
+        ImageFormat::Bgr5x1Unorm,
+        ImageFormat::Rgb8Unorm,
+        ImageFormat::Bgr8Unorm,
+        ImageFormat::Bgrx8Unorm,
+        ImageFormat::La8Unorm,
+        ImageFormat::A8Unorm,
+        ImageFormat::Depth16Unorm,
+        ImageFormat::Depth24UnormStencil8,
+        ImageFormat::Depth32Float,
+        ImageFormat::Depth32FloatStencil8,
+        ImageFormat::BC1RgbaUnorm,
+        ImageFormat::BC1RgbaUnormSrgb,
+        ImageFormat::BC2RgbaUnorm,
+        ImageFormat::BC2RgbaUnormSrgb,
+        ImageFormat::BC3RgbaUnorm,
+        ImageFormat::BC3RgbaUnormSrgb,
+        ImageFormat::BC4RUnorm,
+        ImageFormat::BC4RSnorm,
+        ImageFormat::BC5RgUnorm,
+        ImageFormat::BC5RgSnorm,
+        ImageFormat::BC6hRgbUfloat,
+        ImageFormat::BC6hRgbSfloat,
+        ImageFormat::BC7RgbaUnorm,
+        ImageFormat::BC7RgbaUnormSrgb,
+        ImageFormat::Etc1RgbUnorm,
+        ImageFormat::Etc2RgbUnorm,
+        ImageFormat::Etc2RgbUnormSrgb,
+        ImageFormat::Etc2Rgba1Unorm,
+        ImageFormat::Etc2Rgba1UnormSrgb,
+        ImageFormat::Etc2Rgba8Unorm,
+        ImageFormat::Etc2Rgba8UnormSrgb,
+        ImageFormat::EacR11Unorm,
+        ImageFormat::EacR11Snorm,
+        ImageFormat::EacRg11Unorm,
+        ImageFormat::EacRg11Snorm,
+        ImageFormat::Astc4x4RgbaUnorm,
+        ImageFormat::Astc4x4RgbaUnormSrgb,
+        ImageFormat::Astc4x4RgbaFloat,
+        ImageFormat::Astc5x4RgbaUnorm,
+        ImageFormat::Astc5x4RgbaUnormSrgb,
+        ImageFormat::Astc5x4RgbaFloat,
+        ImageFormat::Astc5x5RgbaUnorm,
+        ImageFormat::Astc5x5RgbaUnormSrgb,
+        ImageFormat::Astc5x5RgbaFloat,
+        ImageFormat::Astc6x5RgbaUnorm,
+        ImageFormat::Astc6x5RgbaUnormSrgb,
+        ImageFormat::Astc6x5RgbaFloat,
+        ImageFormat::Astc6x6RgbaUnorm,
+        ImageFormat::Astc6x6RgbaUnormSrgb,
+        ImageFormat::Astc6x6RgbaFloat,
+        ImageFormat::Astc8x5RgbaUnorm,
+        ImageFormat::Astc8x5RgbaUnormSrgb,
+        ImageFormat::Astc8x5RgbaFloat,
+        ImageFormat::Astc8x6RgbaUnorm,
+        ImageFormat::Astc8x6RgbaUnormSrgb,
+        ImageFormat::Astc8x6RgbaFloat,
+        ImageFormat::Astc8x8RgbaUnorm,
+        ImageFormat::Astc8x8RgbaUnormSrgb,
+        ImageFormat::Astc8x8RgbaFloat,
+        ImageFormat::Astc10x5RgbaUnorm,
+        ImageFormat::Astc10x5RgbaUnormSrgb,
+        ImageFormat::Astc10x5RgbaFloat,
+        ImageFormat::Astc10x6RgbaUnorm,
+        ImageFormat::Astc10x6RgbaUnormSrgb,
+        ImageFormat::Astc10x6RgbaFloat,
+        ImageFormat::Astc10x8RgbaUnorm,
+        ImageFormat::Astc10x8RgbaUnormSrgb,
+        ImageFormat::Astc10x8RgbaFloat,
+        ImageFormat::Astc10x10RgbaUnorm,
+        ImageFormat::Astc10x10RgbaUnormSrgb,
+        ImageFormat::Astc10x10RgbaFloat,
+        ImageFormat::Astc12x10RgbaUnorm,
+        ImageFormat::Astc12x10RgbaUnormSrgb,
+        ImageFormat::Astc12x10RgbaFloat,
+        ImageFormat::Astc12x12RgbaUnorm,
+        ImageFormat::Astc12x12RgbaUnormSrgb,
+        ImageFormat::Astc12x12RgbaFloat,
+        ImageFormat::R8Uint,
+        ImageFormat::R8Sint,
+        ImageFormat::R16Uint,
+        ImageFormat::R16Sint,
+        ImageFormat::R32Uint,
+        ImageFormat::R32Sint,
+        ImageFormat::Rgba8Uint,
+        ImageFormat::Rgba8Sint,
+        ImageFormat::Rgba16Uint,
+        ImageFormat::Rgba16Sint,
+        ImageFormat::Rgba32Uint,
+        ImageFormat::Rgba32Sint,
+        ImageFormat::Yuy2,
+        ImageFormat::Uyvy,
+    ];
+
+    fn round_trip(surface: Surface<Vec<u8>>) -> Vec<u8> {
+        let bytes = surface.to_pvr().unwrap();
+        assert_eq!(surface, Surface::from_pvr(&bytes).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn pvr_format_round_trip_all_formats() {
+        for format in FORMATS {
+            assert_eq!(
+                Some(format),
+                image_format_from_pvr(pvr_format(format).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn pvr_unsupported_image_format() {
+        let surface = Surface {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Nv12,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 6],
+        };
+        assert!(matches!(
+            surface.to_pvr(),
+            Err(PvrError::UnsupportedImageFormat(ImageFormat::Nv12))
+        ));
+    }
+
+    #[test]
+    fn pvr_header_rgba8() {
+        let bytes = round_trip(Surface {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8UnormSrgb,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..8).collect(),
+        });
+
+        assert_eq!(b"PVR\x03", &bytes[0..4]);
+        assert_eq!(b"rgba", &bytes[8..12]);
+        assert_eq!([8, 8, 8, 8], bytes[12..16]);
+        assert_eq!(COLOR_SPACE_SRGB, read_u32(&bytes, 16).unwrap());
+        assert_eq!(1, read_u32(&bytes, 24).unwrap());
+        assert_eq!(2, read_u32(&bytes, 28).unwrap());
+        assert_eq!(HEADER_SIZE + 8, bytes.len());
+    }
+
+    #[test]
+    fn pvr_round_trip_bc7_layers_mipmaps() {
+        round_trip(Surface {
+            width: 8,
+            height: 8,
+            depth: 1,
+            layers: 2,
+            mipmaps: 4,
+            image_format: ImageFormat::BC7RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..(4 + 1 + 1 + 1) * 16 * 2).map(|i| i as u8).collect(),
+        });
+    }
+
+    #[test]
+    fn pvr_round_trip_premultiplied() {
+        let bytes = round_trip(Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Premultiplied,
+            data: vec![64, 32, 16, 128],
+        });
+        assert_eq!(FLAG_PREMULTIPLIED, read_u32(&bytes, 4).unwrap());
+    }
+
+    #[test]
+    fn pvr_round_trip_volume() {
+        round_trip(Surface {
+            width: 4,
+            height: 2,
+            depth: 3,
+            layers: 1,
+            mipmaps: 2,
+            image_format: ImageFormat::Rgba16Float,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..(4 * 2 * 3 + 2) * 8).map(|i| i as u8).collect(),
+        });
+    }
+
+    #[test]
+    fn pvr_round_trip_cubemap_mipmaps() {
+        let surface = Surface {
+            width: 2,
+            height: 2,
+            depth: 1,
+            layers: 6,
+            mipmaps: 2,
+            image_format: ImageFormat::R8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: (0..(4 + 1) * 6).collect::<Vec<u8>>(),
+        };
+        let bytes = surface.to_pvr_cubemap().unwrap();
+        assert_eq!(1, read_u32(&bytes, 36).unwrap());
+        assert_eq!(6, read_u32(&bytes, 40).unwrap());
+
+        // The base mipmap for all faces is stored before the next mipmap.
+        assert_eq!([0, 1, 2, 3, 5, 6], bytes[HEADER_SIZE..HEADER_SIZE + 6]);
+        assert_eq!([4, 9, 14], bytes[HEADER_SIZE + 24..HEADER_SIZE + 27]);
+
+        assert_eq!(surface, Surface::from_pvr(&bytes).unwrap());
+    }
+
+    #[test]
+    fn pvr_read_metadata() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![1u8; 8],
+        };
+        let mut bytes = surface.to_pvr().unwrap();
+
+        // Insert an orientation metadata block before the image data.
+        let metadata = [
+            b"PVR\x03".as_slice(),
+            &3u32.to_le_bytes(),
+            &3u32.to_le_bytes(),
+            &[0, 1, 0],
+        ]
+        .concat();
+        bytes[48..52].copy_from_slice(&(metadata.len() as u32).to_le_bytes());
+        bytes.splice(HEADER_SIZE..HEADER_SIZE, metadata);
+
+        assert_eq!(surface, Surface::from_pvr(&bytes).unwrap());
+    }
+
+    #[test]
+    fn pvr_invalid_metadata() {
+        let mut bytes = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 4],
+        }
+        .to_pvr()
+        .unwrap();
+
+        // The metadata block size extends past the metadata.
+        bytes[48..52].copy_from_slice(&12u32.to_le_bytes());
+        bytes.splice(
+            HEADER_SIZE..HEADER_SIZE,
+            [0u8, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0],
+        );
+
+        assert!(matches!(
+            Surface::from_pvr(&bytes),
+            Err(PvrError::NotEnoughData { .. })
+        ));
+    }
+
+    #[test]
+    fn pvr_unsupported_format() {
+        let mut bytes = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 4],
+        }
+        .to_pvr()
+        .unwrap();
+
+        // PVRTC 4bpp RGBA
+        bytes[8..16].copy_from_slice(&3u64.to_le_bytes());
+
+        assert!(matches!(
+            Surface::from_pvr(&bytes),
+            Err(PvrError::UnsupportedFormat(PvrFormatInfo {
+                pixel_format: 3,
+                color_space: COLOR_SPACE_LINEAR,
+                channel_type: CHANNEL_TYPE_UNSIGNED_BYTE_NORM
+            }))
+        ));
+    }
+
+    #[test]
+    fn pvr_cubemap_invalid_layers() {
+        let surface = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 2,
+            mipmaps: 1,
+            image_format: ImageFormat::BC1RgbaUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 8 * 2],
+        };
+        assert!(matches!(
+            surface.to_pvr_cubemap(),
+            Err(PvrError::Surface(SurfaceError::InvalidCubeMap {
+                layers: 2,
+                depth: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn pvr_invalid_version() {
+        assert!(matches!(
+            Surface::from_pvr(&[0u8; 52]),
+            Err(PvrError::InvalidVersion(0))
+        ));
+    }
+
+    #[test]
+    fn pvr_not_enough_data() {
+        let bytes = Surface {
+            width: 4,
+            height: 4,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::BC4RUnorm,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 8],
+        }
+        .to_pvr()
+        .unwrap();
+
+        assert!(matches!(
+            Surface::from_pvr(&bytes[..bytes.len() - 1]),
+            Err(PvrError::NotEnoughData { .. })
+        ));
+    }
+
+    #[test]
+    fn pvr_level_size_overflow() {
+        let mut bytes = Surface {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            image_format: ImageFormat::Rgba32Float,
+            alpha_mode: AlphaMode::Unknown,
+            data: vec![0u8; 16],
+        }
+        .to_pvr()
+        .unwrap();
+        // Each surface is 2^52 bytes, so 2^16 surfaces overflows the level size.
+        for (offset, value) in [(24, 65536u32), (28, 65536), (32, 65536), (36, 65536)] {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        assert!(matches!(
+            Surface::from_pvr(&bytes),
+            Err(PvrError::Surface(
+                SurfaceError::PixelCountWouldOverflow { .. }
+            ))
+        ));
+    }
+}
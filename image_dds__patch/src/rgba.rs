@@ -1,6 +1,6 @@
 use half::f16;
 
-use crate::SurfaceError;
+use crate::{bcn::unorm8_from_snorm, SurfaceError};
 
 pub fn rgba8_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
     validate_length(width, height, 4, data)?;
@@ -142,56 +142,1131 @@ pub fn bgra4_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>,
     Ok(bgra)
 }
 
-fn swap_red_blue(width: u32, height: u32, rgba: &mut [u8]) {
-    for i in 0..(width as usize * height as usize) {
-        // RGBA -> BGRA.
-        rgba.swap(i * 4, i * 4 + 2);
+pub fn rgba8_from_b5g6r5(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 2, data)?;
+
+    // Most significant bit -> RGB -> least significant bit.
+    let rgba = data
+        .chunks_exact(2)
+        .flat_map(|c| {
+            let x = u16::from_le_bytes([c[0], c[1]]);
+            [
+                unorm8_from_bits(x >> 11, 5),
+                unorm8_from_bits(x >> 5, 6),
+                unorm8_from_bits(x, 5),
+                255,
+            ]
+        })
+        .collect();
+    Ok(rgba)
+}
+
+pub fn b5g6r5_from_rgba8(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    dither: bool,
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 4, data)?;
+
+    // Most significant bit -> RGB -> least significant bit.
+    let rgb = quantize_rgb8(width, &data[..expected], [5, 6, 5], dither);
+    Ok(rgb
+        .iter()
+        .flat_map(|[r, g, b]| ((r << 11) | (g << 5) | b).to_le_bytes())
+        .collect())
+}
+
+pub fn rgba8_from_bgr5a1(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 2, data)?;
+    Ok(rgba8_from_bgr555(data, true))
+}
+
+pub fn rgba8_from_bgr5x1(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 2, data)?;
+    Ok(rgba8_from_bgr555(data, false))
+}
+
+fn rgba8_from_bgr555(data: &[u8], has_alpha: bool) -> Vec<u8> {
+    // Most significant bit -> ARGB -> least significant bit.
+    // The unused bit for X1R5G5B5 is often 0, so treat it as opaque.
+    data.chunks_exact(2)
+        .flat_map(|c| {
+            let x = u16::from_le_bytes([c[0], c[1]]);
+            [
+                unorm8_from_bits(x >> 10, 5),
+                unorm8_from_bits(x >> 5, 5),
+                unorm8_from_bits(x, 5),
+                if !has_alpha || x >> 15 != 0 { 255 } else { 0 },
+            ]
+        })
+        .collect()
+}
+
+pub fn bgr5a1_from_rgba8(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    dither: bool,
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 4, data)?;
+
+    // Alpha is only 1 bit, so use a threshold instead of dithering.
+    let data = &data[..expected];
+    let rgb = quantize_rgb8(width, data, [5, 5, 5], dither);
+    Ok(bgr555_from_rgb(
+        &rgb,
+        data.chunks_exact(4).map(|c| c[3] >= 128),
+    ))
+}
+
+pub fn bgr5x1_from_rgba8(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    dither: bool,
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 4, data)?;
+
+    // Set the unused bit to 1 in case readers treat it as alpha.
+    let rgb = quantize_rgb8(width, &data[..expected], [5, 5, 5], dither);
+    Ok(bgr555_from_rgb(&rgb, std::iter::repeat(true)))
+}
+
+fn bgr555_from_rgb(rgb: &[[u16; 3]], alpha: impl Iterator<Item = bool>) -> Vec<u8> {
+    // Most significant bit -> ARGB -> least significant bit.
+    rgb.iter()
+        .zip(alpha)
+        .flat_map(|([r, g, b], a)| (((a as u16) << 15) | (r << 10) | (g << 5) | b).to_le_bytes())
+        .collect()
+}
+
+fn unorm8_from_bits(x: u16, bits: u32) -> u8 {
+    let max = (1u32 << bits) - 1;
+    ((((x as u32) & max) * 255 + max / 2) / max) as u8
+}
+
+// Quantize RGB values to the given number of bits for each channel.
+fn quantize_rgb8(width: u32, data: &[u8], bits: [u32; 3], dither: bool) -> Vec<[u16; 3]> {
+    let max = bits.map(|b| (1u32 << b) - 1);
+    if !dither {
+        return data
+            .chunks_exact(4)
+            .map(|c| std::array::from_fn(|i| ((c[i] as u32 * max[i] + 127) / 255) as u16))
+            .collect();
+    }
+
+    // Floyd-Steinberg error diffusion preserves gradients at the cost of some noise.
+    let mut values: Vec<[f32; 3]> = data
+        .chunks_exact(4)
+        .map(|c| std::array::from_fn(|i| c[i] as f32 * max[i] as f32 / 255.0))
+        .collect();
+    floyd_steinberg(&mut values, width as usize, max.map(|m| m as f32));
+    values.iter().map(|v| v.map(|c| c as u16)).collect()
+}
+
+/// Round each channel in `values` to an integer from `0.0` to `max`
+/// using Floyd-Steinberg error diffusion for an image with the given `width`.
+pub fn floyd_steinberg<const N: usize>(values: &mut [[f32; N]], width: usize, max: [f32; N]) {
+    for i in 0..values.len() {
+        let (x, y) = (i % width, i / width);
+        for c in 0..N {
+            let quantized = values[i][c].round().clamp(0.0, max[c]);
+            let error = values[i][c] - quantized;
+            values[i][c] = quantized;
+
+            let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+                let nx = x as isize + dx;
+                if (0..width as isize).contains(&nx) {
+                    if let Some(value) = values.get_mut((y + dy) * width + nx as usize) {
+                        value[c] += error * weight;
+                    }
+                }
+            };
+            diffuse(1, 0, 7.0 / 16.0);
+            diffuse(-1, 1, 3.0 / 16.0);
+            diffuse(0, 1, 5.0 / 16.0);
+            diffuse(1, 1, 1.0 / 16.0);
+        }
     }
 }
 
-fn validate_length(
+pub fn rgba8_from_rgb8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 3, data)?;
+    Ok(data
+        .chunks_exact(3)
+        .flat_map(|c| [c[0], c[1], c[2], 255])
+        .collect())
+}
+
+pub fn rgb8_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 4, data)?;
+    Ok(data
+        .chunks_exact(4)
+        .flat_map(|c| [c[0], c[1], c[2]])
+        .collect())
+}
+
+pub fn rgba8_from_bgr8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 3, data)?;
+    Ok(data
+        .chunks_exact(3)
+        .flat_map(|c| [c[2], c[1], c[0], 255])
+        .collect())
+}
+
+pub fn bgr8_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 4, data)?;
+    Ok(data
+        .chunks_exact(4)
+        .flat_map(|c| [c[2], c[1], c[0]])
+        .collect())
+}
+
+pub fn rgba8_from_bgrx8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 4, data)?;
+    Ok(data
+        .chunks_exact(4)
+        .flat_map(|c| [c[2], c[1], c[0], 255])
+        .collect())
+}
+
+pub fn bgrx8_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 4, data)?;
+    Ok(data
+        .chunks_exact(4)
+        .flat_map(|c| [c[2], c[1], c[0], 255])
+        .collect())
+}
+
+pub fn rgba8_from_la8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 2, data)?;
+    Ok(data
+        .chunks_exact(2)
+        .flat_map(|c| [c[0], c[0], c[0], c[1]])
+        .collect())
+}
+
+pub fn la8_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 4, data)?;
+
+    // Use red for luminance like single channel formats.
+    Ok(data.chunks_exact(4).flat_map(|c| [c[0], c[3]]).collect())
+}
+
+pub fn rgba8_from_a8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 1, data)?;
+    Ok(data.iter().flat_map(|a| [0, 0, 0, *a]).collect())
+}
+
+pub fn a8_from_rgba8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    validate_length(width, height, 4, data)?;
+    Ok(data.iter().copied().skip(3).step_by(4).collect())
+}
+
+/// A channel type for uncompressed formats with 1, 2, or 4 channels.
+pub trait Channel: bytemuck::Pod {
+    fn to_unorm8(self) -> u8;
+    fn to_f32(self) -> f32;
+    fn from_unorm8(u: u8) -> Self;
+    fn from_f32(f: f32) -> Self;
+}
+
+impl Channel for u8 {
+    fn to_unorm8(self) -> u8 {
+        self
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32 / 255.0
+    }
+
+    fn from_unorm8(u: u8) -> Self {
+        u
+    }
+
+    fn from_f32(f: f32) -> Self {
+        (f * 255.0) as u8
+    }
+}
+
+impl Channel for u16 {
+    fn to_unorm8(self) -> u8 {
+        // Round to the nearest value like (x / 65535.0 * 255.0).round().
+        ((self as u32 * 255 + 32767) / 65535) as u8
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32 / 65535.0
+    }
+
+    fn from_unorm8(u: u8) -> Self {
+        u as u16 * 257
+    }
+
+    fn from_f32(f: f32) -> Self {
+        (f.clamp(0.0, 1.0) * 65535.0).round() as u16
+    }
+}
+
+impl Channel for i16 {
+    fn to_unorm8(self) -> u8 {
+        // Use the same remapping as signed BCN formats with 0.0 mapping to 128.
+        unorm8_from_snorm(self.to_f32())
+    }
+
+    fn to_f32(self) -> f32 {
+        // Both -32768 and -32767 map to -1.0.
+        (self as f32 / 32767.0).max(-1.0)
+    }
+
+    fn from_unorm8(u: u8) -> Self {
+        Self::from_f32(u as f32 / 255.0 * 2.0 - 1.0)
+    }
+
+    fn from_f32(f: f32) -> Self {
+        (f.clamp(-1.0, 1.0) * 32767.0).round() as i16
+    }
+}
+
+impl Channel for f16 {
+    fn to_unorm8(self) -> u8 {
+        (self.to_f32() * 255.0) as u8
+    }
+
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+
+    fn from_unorm8(u: u8) -> Self {
+        f16::from_f32(u as f32 / 255.0)
+    }
+
+    fn from_f32(f: f32) -> Self {
+        f16::from_f32(f)
+    }
+}
+
+impl Channel for f32 {
+    fn to_unorm8(self) -> u8 {
+        (self * 255.0) as u8
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_unorm8(u: u8) -> Self {
+        u as f32 / 255.0
+    }
+
+    fn from_f32(f: f32) -> Self {
+        f
+    }
+}
+
+/// Decode `N` channels of type `T` per pixel to RGBA8.
+///
+/// Single channel data is replicated to RGB like [ImageFormat::BC4RUnorm](crate::ImageFormat::BC4RUnorm).
+/// Two channel data sets blue to `0` like [ImageFormat::BC5RgUnorm](crate::ImageFormat::BC5RgUnorm).
+pub fn rgba8_from_channels<T: Channel, const N: usize>(
     width: u32,
     height: u32,
+    data: &[u8],
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, N * std::mem::size_of::<T>(), data)?;
+    Ok(read_pixels::<T, N>(&data[..expected])
+        .flat_map(|p| expand_rgba(p.map(T::to_unorm8), 0u8, 255u8))
+        .collect())
+}
 
-    bytes_per_pixel: usize,
+/// Decode `N` channels of type `T` per pixel to RGBAF32.
+///
+/// Missing channels use the same defaults as [rgba8_from_channels].
+pub fn rgbaf32_from_channels<T: Channel, const N: usize>(
+    width: u32,
+    height: u32,
     data: &[u8],
-) -> Result<usize, SurfaceError> {
-    let expected = expected_size(width, height, bytes_per_pixel).ok_or(
-        SurfaceError::PixelCountWouldOverflow {
-            width,
-            height,
-            depth: 1,
-        },
-    )?;
+) -> Result<Vec<f32>, SurfaceError> {
+    let expected = validate_length(width, height, N * std::mem::size_of::<T>(), data)?;
+    Ok(read_pixels::<T, N>(&data[..expected])
+        .flat_map(|p| expand_rgba(p.map(T::to_f32), 0.0, 1.0))
+        .collect())
+}
 
-    if data.len() < expected {
-        Err(SurfaceError::NotEnoughData {
-            expected,
-            actual: data.len(),
+/// Encode the first `N` channels of RGBA8 data with type `T`.
+pub fn channels_from_rgba8<T: Channel, const N: usize>(
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 4, data)?;
+    Ok(data[..expected]
+        .chunks_exact(4)
+        .flat_map(|rgba| {
+            let channels: [T; N] = std::array::from_fn(|i| T::from_unorm8(rgba[i]));
+            bytemuck::cast_slice::<T, u8>(&channels).to_vec()
         })
-    } else {
-        Ok(expected)
+        .collect())
+}
+
+/// Encode the first `N` channels of RGBAF32 data with type `T`.
+pub fn channels_from_rgbaf32<T: Channel, const N: usize>(
+    width: u32,
+    height: u32,
+    data: &[f32],
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 16, bytemuck::cast_slice(data))?;
+    Ok(data[..expected / 4]
+        .chunks_exact(4)
+        .flat_map(|rgba| {
+            let channels: [T; N] = std::array::from_fn(|i| T::from_f32(rgba[i]));
+            bytemuck::cast_slice::<T, u8>(&channels).to_vec()
+        })
+        .collect())
+}
+
+/// A channel of an integer format like [ImageFormat::R8Uint](crate::ImageFormat::R8Uint).
+///
+/// Values are converted without normalization to preserve IDs and indices.
+pub trait IntegerChannel: bytemuck::Pod {
+    /// Sign extend signed values and store them as two's complement.
+    fn to_u32(self) -> u32;
+    /// Saturate to the range of `Self`.
+    fn from_u32(u: u32) -> Self;
+}
+
+impl IntegerChannel for u8 {
+    fn to_u32(self) -> u32 {
+        self as u32
+    }
+
+    fn from_u32(u: u32) -> Self {
+        u.min(u8::MAX as u32) as u8
     }
 }
 
-fn expected_size(width: u32, height: u32, bytes_per_pixel: usize) -> Option<usize> {
-    (width as usize)
-        .checked_mul(height as usize)?
-        .checked_mul(bytes_per_pixel)
+impl IntegerChannel for u16 {
+    fn to_u32(self) -> u32 {
+        self as u32
+    }
+
+    fn from_u32(u: u32) -> Self {
+        u.min(u16::MAX as u32) as u16
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl IntegerChannel for u32 {
+    fn to_u32(self) -> u32 {
+        self
+    }
 
-    #[test]
-    fn r8_from_rgba8_valid() {
-        assert_eq!(vec![1], r8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap());
+    fn from_u32(u: u32) -> Self {
+        u
+    }
+}
+
+impl IntegerChannel for i8 {
+    fn to_u32(self) -> u32 {
+        self as i32 as u32
+    }
+
+    fn from_u32(u: u32) -> Self {
+        (u as i32).clamp(i8::MIN as i32, i8::MAX as i32) as i8
+    }
+}
+
+impl IntegerChannel for i16 {
+    fn to_u32(self) -> u32 {
+        self as i32 as u32
+    }
+
+    fn from_u32(u: u32) -> Self {
+        (u as i32).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+}
+
+impl IntegerChannel for i32 {
+    fn to_u32(self) -> u32 {
+        self as u32
+    }
+
+    fn from_u32(u: u32) -> Self {
+        u as i32
+    }
+}
+
+/// Decode `N` integer channels of type `T` per pixel to RGBA u32.
+///
+/// Missing channels use the same defaults as [rgba8_from_channels] with alpha set to `1`.
+pub fn rgbau32_from_channels<T: IntegerChannel, const N: usize>(
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<Vec<u32>, SurfaceError> {
+    let expected = validate_length(width, height, N * std::mem::size_of::<T>(), data)?;
+    Ok(read_pixels::<T, N>(&data[..expected])
+        .flat_map(|p| expand_rgba(p.map(T::to_u32), 0, 1))
+        .collect())
+}
+
+/// Encode the first `N` channels of RGBA u32 data with integer type `T`.
+pub fn channels_from_rgbau32<T: IntegerChannel, const N: usize>(
+    width: u32,
+    height: u32,
+    data: &[u32],
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 16, bytemuck::cast_slice(data))?;
+    Ok(data[..expected / 4]
+        .chunks_exact(4)
+        .flat_map(|rgba| {
+            let channels: [T; N] = std::array::from_fn(|i| T::from_u32(rgba[i]));
+            bytemuck::cast_slice::<T, u8>(&channels).to_vec()
+        })
+        .collect())
+}
+
+/// Decode packed DXGI_FORMAT_R11G11B10_FLOAT data to RGBAF32.
+pub fn rgbaf32_from_rg11b10f(
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<Vec<f32>, SurfaceError> {
+    rgbaf32_from_packed(width, height, data, |x| {
+        [
+            f32_from_packed_float(x & 0x7FF, 6),
+            f32_from_packed_float((x >> 11) & 0x7FF, 6),
+            f32_from_packed_float(x >> 22, 5),
+            1.0,
+        ]
+    })
+}
+
+/// Encode RGBAF32 data to packed DXGI_FORMAT_R11G11B10_FLOAT.
+pub fn rg11b10f_from_rgbaf32(
+    width: u32,
+    height: u32,
+    data: &[f32],
+) -> Result<Vec<u8>, SurfaceError> {
+    packed_from_rgbaf32(width, height, data, |[r, g, b, _]| {
+        packed_float_from_f32(r, 6)
+            | (packed_float_from_f32(g, 6) << 11)
+            | (packed_float_from_f32(b, 5) << 22)
+    })
+}
+
+/// Decode packed DXGI_FORMAT_R9G9B9E5_SHAREDEXP data to RGBAF32.
+pub fn rgbaf32_from_rgb9e5(width: u32, height: u32, data: &[u8]) -> Result<Vec<f32>, SurfaceError> {
+    rgbaf32_from_packed(width, height, data, |x| {
+        // Each 9-bit mantissa has no implied leading one.
+        let scale = 2.0f32.powi((x >> 27) as i32 - RGB9E5_EXPONENT_BIAS - RGB9E5_MANTISSA_BITS);
+        [
+            (x & 0x1FF) as f32 * scale,
+            ((x >> 9) & 0x1FF) as f32 * scale,
+            ((x >> 18) & 0x1FF) as f32 * scale,
+            1.0,
+        ]
+    })
+}
+
+/// Encode RGBAF32 data to packed DXGI_FORMAT_R9G9B9E5_SHAREDEXP.
+pub fn rgb9e5_from_rgbaf32(width: u32, height: u32, data: &[f32]) -> Result<Vec<u8>, SurfaceError> {
+    packed_from_rgbaf32(width, height, data, |[r, g, b, _]| {
+        // https://registry.khronos.org/OpenGL/extensions/EXT/EXT_texture_shared_exponent.txt
+        // The largest value is 511/512 * 2^16 = 65408.
+        const MAX: f32 = 65408.0;
+        // NaN is clamped to 0.0.
+        let rgb = [r, g, b].map(|c| if c > 0.0 { c.min(MAX) } else { 0.0 });
+        let max_c = rgb[0].max(rgb[1]).max(rgb[2]);
+
+        // Use the exponent bits to avoid precision issues with log2.
+        let floor_log2 = ((max_c.to_bits() >> 23) & 0xFF) as i32 - 127;
+        let mut exponent = floor_log2.max(-RGB9E5_EXPONENT_BIAS - 1) + 1 + RGB9E5_EXPONENT_BIAS;
+        let mut denom = 2.0f32.powi(exponent - RGB9E5_EXPONENT_BIAS - RGB9E5_MANTISSA_BITS);
+
+        // Rounding the largest channel may require a larger exponent.
+        if (max_c / denom).round() as u32 == 1 << RGB9E5_MANTISSA_BITS {
+            exponent += 1;
+            denom *= 2.0;
+        }
+
+        let [r, g, b] = rgb.map(|c| (c / denom).round() as u32);
+        r | (g << 9) | (b << 18) | ((exponent as u32) << 27)
+    })
+}
+
+/// Decode packed DXGI_FORMAT_R10G10B10A2_UNORM data to RGBAF32.
+pub fn rgbaf32_from_rgb10a2(
+    width: u32,
+    height: u32,
+    data: &[u8],
+) -> Result<Vec<f32>, SurfaceError> {
+    rgbaf32_from_packed(width, height, data, |x| {
+        [
+            (x & 0x3FF) as f32 / 1023.0,
+            ((x >> 10) & 0x3FF) as f32 / 1023.0,
+            ((x >> 20) & 0x3FF) as f32 / 1023.0,
+            (x >> 30) as f32 / 3.0,
+        ]
+    })
+}
+
+/// Decode packed DXGI_FORMAT_R10G10B10A2_UNORM data to RGBA8.
+pub fn rgba8_from_rgb10a2(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    // Round to the nearest value instead of truncating the normalized values.
+    let expected = validate_length(width, height, 4, data)?;
+    Ok(read_pixels::<u32, 1>(&data[..expected])
+        .flat_map(|[x]| {
+            let unorm8 = |shift: u32| ((((x >> shift) & 0x3FF) * 255 + 511) / 1023) as u8;
+            [unorm8(0), unorm8(10), unorm8(20), (x >> 30) as u8 * 85]
+        })
+        .collect())
+}
+
+/// Encode RGBAF32 data to packed DXGI_FORMAT_R10G10B10A2_UNORM.
+pub fn rgb10a2_from_rgbaf32(
+    width: u32,
+    height: u32,
+    data: &[f32],
+) -> Result<Vec<u8>, SurfaceError> {
+    packed_from_rgbaf32(width, height, data, |[r, g, b, a]| {
+        let unorm = |f: f32, max: f32| (f.clamp(0.0, 1.0) * max).round() as u32;
+        unorm(r, 1023.0)
+            | (unorm(g, 1023.0) << 10)
+            | (unorm(b, 1023.0) << 20)
+            | (unorm(a, 3.0) << 30)
+    })
+}
+
+/// Decode packed DXGI_FORMAT_D24_UNORM_S8_UINT data to RGBAF32.
+///
+/// Depth is stored in red and normalized stencil is stored in green.
+pub fn rgbaf32_from_d24s8(width: u32, height: u32, data: &[u8]) -> Result<Vec<f32>, SurfaceError> {
+    rgbaf32_from_packed(width, height, data, |x| {
+        [
+            (x & 0xFFFFFF) as f32 / 16777215.0,
+            (x >> 24) as f32 / 255.0,
+            0.0,
+            1.0,
+        ]
+    })
+}
+
+/// Decode packed DXGI_FORMAT_D24_UNORM_S8_UINT data to RGBA8.
+///
+/// Depth is stored in red and stencil is stored in green.
+pub fn rgba8_from_d24s8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    // Round depth to the nearest value and preserve the exact stencil value.
+    let expected = validate_length(width, height, 4, data)?;
+    Ok(read_pixels::<u32, 1>(&data[..expected])
+        .flat_map(|[x]| {
+            let depth = ((x & 0xFFFFFF) * 255 + 8388607) / 16777215;
+            [depth as u8, (x >> 24) as u8, 0, 255]
+        })
+        .collect())
+}
+
+/// Encode the red and green channels of RGBAF32 data as depth and stencil
+/// to packed DXGI_FORMAT_D24_UNORM_S8_UINT.
+pub fn d24s8_from_rgbaf32(width: u32, height: u32, data: &[f32]) -> Result<Vec<u8>, SurfaceError> {
+    packed_from_rgbaf32(width, height, data, |[r, g, _, _]| {
+        let depth = (r.clamp(0.0, 1.0) * 16777215.0).round() as u32;
+        let stencil = (g.clamp(0.0, 1.0) * 255.0).round() as u32;
+        depth | (stencil << 24)
+    })
+}
+
+/// Decode DXGI_FORMAT_D32_FLOAT_S8X24_UINT data to RGBAF32.
+///
+/// Depth is stored in red and normalized stencil is stored in green.
+pub fn rgbaf32_from_d32fs8(width: u32, height: u32, data: &[u8]) -> Result<Vec<f32>, SurfaceError> {
+    let expected = validate_length(width, height, 8, data)?;
+    Ok(read_pixels::<u32, 2>(&data[..expected])
+        .flat_map(|[d, s]| [f32::from_bits(d), (s & 0xFF) as f32 / 255.0, 0.0, 1.0])
+        .collect())
+}
+
+/// Decode DXGI_FORMAT_D32_FLOAT_S8X24_UINT data to RGBA8.
+///
+/// Depth is stored in red and stencil is stored in green.
+pub fn rgba8_from_d32fs8(width: u32, height: u32, data: &[u8]) -> Result<Vec<u8>, SurfaceError> {
+    // Truncate depth to clamp to 0 to 255 like other floating point formats.
+    let expected = validate_length(width, height, 8, data)?;
+    Ok(read_pixels::<u32, 2>(&data[..expected])
+        .flat_map(|[d, s]| [(f32::from_bits(d) * 255.0) as u8, s as u8, 0, 255])
+        .collect())
+}
+
+/// Encode the red and green channels of RGBAF32 data as depth and stencil
+/// to DXGI_FORMAT_D32_FLOAT_S8X24_UINT.
+pub fn d32fs8_from_rgbaf32(width: u32, height: u32, data: &[f32]) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 16, bytemuck::cast_slice(data))?;
+    Ok(data[..expected / 4]
+        .chunks_exact(4)
+        .flat_map(|rgba| {
+            // The remaining 24 bits are unused.
+            let stencil = (rgba[1].clamp(0.0, 1.0) * 255.0).round() as u32;
+            [rgba[0].to_bits(), stencil]
+        })
+        .flat_map(u32::to_le_bytes)
+        .collect())
+}
+
+const RGB9E5_EXPONENT_BIAS: i32 = 15;
+const RGB9E5_MANTISSA_BITS: i32 = 9;
+
+fn rgbaf32_from_packed(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    unpack: impl Fn(u32) -> [f32; 4],
+) -> Result<Vec<f32>, SurfaceError> {
+    let expected = validate_length(width, height, 4, data)?;
+    Ok(read_pixels::<u32, 1>(&data[..expected])
+        .flat_map(|[x]| unpack(x))
+        .collect())
+}
+
+fn packed_from_rgbaf32(
+    width: u32,
+    height: u32,
+    data: &[f32],
+    pack: impl Fn([f32; 4]) -> u32,
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected = validate_length(width, height, 16, bytemuck::cast_slice(data))?;
+    Ok(data[..expected / 4]
+        .chunks_exact(4)
+        .flat_map(|rgba| pack([rgba[0], rgba[1], rgba[2], rgba[3]]).to_le_bytes())
+        .collect())
+}
+
+// Unsigned floats with 5 exponent bits have the same layout as f16 without the sign bit.
+fn f32_from_packed_float(x: u32, mantissa_bits: u32) -> f32 {
+    f16::from_bits((x << (10 - mantissa_bits)) as u16).to_f32()
+}
+
+fn packed_float_from_f32(f: f32, mantissa_bits: u32) -> u32 {
+    let infinity = 0x1F << mantissa_bits;
+    if f.is_nan() {
+        return infinity | 1 << (mantissa_bits - 1);
+    }
+    if f == f32::INFINITY {
+        return infinity;
+    }
+    if f <= 0.0 {
+        // Negative values including negative infinity are clamped to 0.0.
+        return 0;
+    }
+
+    // Clamp to the largest finite value instead of rounding to infinity.
+    let max = infinity - 1;
+    if f >= f32_from_packed_float(max, mantissa_bits) {
+        return max;
+    }
+
+    // Round to nearest even using the f32 bits to avoid rounding twice.
+    let bits = f.to_bits();
+    let exponent = ((bits >> 23) & 0xFF) as i32 - 127 + 15;
+    let mantissa = bits & 0x7FFFFF;
+    if exponent > 0 {
+        // A carry from the mantissa correctly increments the exponent.
+        round_shift(((exponent as u32) << 23) | mantissa, 23 - mantissa_bits)
+    } else {
+        // Denormals have no implied leading one and use the smallest exponent.
+        let shift = (24 - mantissa_bits as i32 - exponent) as u32;
+        round_shift(mantissa | 0x800000, shift)
+    }
+}
+
+fn round_shift(x: u32, shift: u32) -> u32 {
+    if shift >= 32 {
+        return 0;
+    }
+    let quotient = x >> shift;
+    let remainder = x & ((1 << shift) - 1);
+    let half = 1 << shift >> 1;
+    if remainder > half || (remainder == half && half != 0 && quotient & 1 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+fn read_pixels<T: bytemuck::Pod, const N: usize>(data: &[u8]) -> impl Iterator<Item = [T; N]> + '_ {
+    // The data may not be aligned for T.
+    let size = std::mem::size_of::<T>();
+    data.chunks_exact(N * size)
+        .map(move |p| std::array::from_fn(|i| bytemuck::pod_read_unaligned(&p[i * size..][..size])))
+}
+
+fn expand_rgba<T: Copy, const N: usize>(channels: [T; N], zero: T, one: T) -> [T; 4] {
+    match channels.as_slice() {
+        [r] => [*r, *r, *r, one],
+        [r, g] => [*r, *g, zero, one],
+        [r, g, b, a] => [*r, *g, *b, *a],
+        _ => unreachable!("uncompressed formats have 1, 2, or 4 channels"),
+    }
+}
+
+fn swap_red_blue(width: u32, height: u32, rgba: &mut [u8]) {
+    for i in 0..(width as usize * height as usize) {
+        // RGBA -> BGRA.
+        rgba.swap(i * 4, i * 4 + 2);
+    }
+}
+
+fn validate_length(
+    width: u32,
+    height: u32,
+
+    bytes_per_pixel: usize,
+    data: &[u8],
+) -> Result<usize, SurfaceError> {
+    let expected = expected_size(width, height, bytes_per_pixel).ok_or(
+        SurfaceError::PixelCountWouldOverflow {
+            width,
+            height,
+            depth: 1,
+        },
+    )?;
+
+    if data.len() < expected {
+        Err(SurfaceError::NotEnoughData {
+            expected,
+            actual: data.len(),
+        })
+    } else {
+        Ok(expected)
+    }
+}
+
+fn expected_size(width: u32, height: u32, bytes_per_pixel: usize) -> Option<usize> {
+    (width as usize)
+        .checked_mul(height as usize)?
+        .checked_mul(bytes_per_pixel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn r8_from_rgba8_valid() {
+        assert_eq!(vec![1], r8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap());
+    }
+
+    #[test]
+    fn r8_from_rgba8_invalid() {
+        let result = r8_from_rgba8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_r8_valid() {
+        assert_eq!(vec![64, 64, 64, 255], rgba8_from_r8(1, 1, &[64]).unwrap());
+    }
+
+    #[test]
+    fn rgba8_from_r8_invalid() {
+        let result = rgba8_from_r8(4, 4, &[64]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 16,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn bgra8_from_rgba8_valid() {
+        assert_eq!(
+            vec![3, 2, 1, 4],
+            bgra8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn bgra8_from_rgba8_invalid() {
+        let result = bgra8_from_rgba8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_bgra8_valid() {
+        assert_eq!(
+            vec![3, 2, 1, 4],
+            rgba8_from_bgra8(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_bgra8_invalid() {
+        let result = rgba8_from_bgra8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_rgbaf32_valid() {
+        assert_eq!(
+            vec![0, 63, 127, 255],
+            rgba8_from_rgbaf32(
+                1,
+                1,
+                bytemuck::cast_slice(&[0.0f32, 0.25f32, 0.5f32, 1.0f32])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgbaf32_invalid() {
+        let result = rgba8_from_rgbaf32(1, 1, &[0; 15]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 16,
+                actual: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn rgbaf32_from_rgba8_valid() {
+        assert_eq!(
+            bytemuck::cast_slice::<_, u8>(&[0.0f32, 0.2f32, 0.6f32, 1.0f32]),
+            rgbaf32_from_rgba8(1, 1, &[0, 51, 153, 255])
+                .unwrap()
+                .as_slice()
+        );
+    }
+
+    #[test]
+    fn rgbaf32_from_rgba8_invalid() {
+        let result = rgbaf32_from_rgba8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_rgbaf16_valid() {
+        assert_eq!(
+            vec![0, 63, 127, 255],
+            rgba8_from_rgbaf16(
+                1,
+                1,
+                bytemuck::cast_slice(&[
+                    f16::from_f32(0.0f32),
+                    f16::from_f32(0.25f32),
+                    f16::from_f32(0.5f32),
+                    f16::from_f32(1.0f32)
+                ])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgbaf16_invalid() {
+        let result = rgba8_from_rgbaf16(1, 1, &[0; 7]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 8,
+                actual: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn rgbaf16_from_rgba8_valid() {
+        assert_eq!(
+            bytemuck::cast_slice::<f16, u8>(&[
+                f16::from_f32(0.0f32),
+                f16::from_f32(0.2f32),
+                f16::from_f32(0.6f32),
+                f16::from_f32(1.0f32)
+            ]),
+            rgbaf16_from_rgba8(1, 1, &[0, 51, 153, 255])
+                .unwrap()
+                .as_slice()
+        );
+    }
+
+    #[test]
+    fn rgbaf16_from_rgba8_invalid() {
+        let result = rgbaf16_from_rgba8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_rgba8_valid() {
+        assert_eq!(
+            vec![1, 2, 3, 4],
+            rgba8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgba8_invalid() {
+        let result = rgba8_from_rgba8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgbaf32_from_rgbaf32_valid() {
+        assert_eq!(
+            vec![1.0, 2.0, 3.0, 4.0],
+            rgbaf32_from_rgbaf32(
+                1,
+                1,
+                bytemuck::cast_slice(&[1.0f32, 2.0f32, 3.0f32, 4.0f32])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbaf32_from_rgbaf32_invalid() {
+        let result = rgbaf32_from_rgbaf32(1, 1, &[0; 15]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 16,
+                actual: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn rgbaf32_from_rgbaf16_valid() {
+        assert_eq!(
+            vec![0.0, 0.25, 0.5, 1.0],
+            rgbaf32_from_rgbaf16(
+                1,
+                1,
+                bytemuck::cast_slice(&[
+                    f16::from_f32(0.0f32),
+                    f16::from_f32(0.25f32),
+                    f16::from_f32(0.5f32),
+                    f16::from_f32(1.0f32)
+                ])
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbaf32_from_rgbaf16_invalid() {
+        let result = rgbaf32_from_rgbaf16(1, 1, &[0; 7]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 8,
+                actual: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn bgra4_from_rgba8_valid() {
+        assert_eq!(
+            vec![0x30, 0xCF],
+            bgra4_from_rgba8(1, 1, &[255, 51, 0, 204]).unwrap()
+        );
+    }
+
+    #[test]
+    fn bgra4_from_rgba8_invalid() {
+        let result = bgra4_from_rgba8(1, 1, &[1, 2, 3]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_bgra4_valid() {
+        assert_eq!(
+            vec![255, 51, 0, 204],
+            rgba8_from_bgra4(1, 1, &[0x30, 0xCF]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_bgra4_invalid() {
+        let result = rgba8_from_bgra4(1, 1, &[1]);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn rgba8_from_channels_r16_valid() {
+        assert_eq!(
+            vec![128, 128, 128, 255],
+            rgba8_from_channels::<u16, 1>(1, 1, &0x8080u16.to_le_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_channels_rg16_snorm_valid() {
+        assert_eq!(
+            vec![0, 128, 0, 255, 255, 0, 0, 255],
+            rgba8_from_channels::<i16, 2>(
+                2,
+                1,
+                bytemuck::cast_slice(&[-32767i16, 0, 32767, -32768])
+            )
+            .unwrap()
+        );
     }
 
     #[test]
-    fn r8_from_rgba8_invalid() {
-        let result = r8_from_rgba8(1, 1, &[1, 2, 3]);
+    fn rgba8_from_channels_invalid() {
+        let result = rgba8_from_channels::<u16, 2>(1, 1, &[1, 2, 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
@@ -202,33 +1277,47 @@ mod tests {
     }
 
     #[test]
-    fn rgba8_from_r8_valid() {
-        assert_eq!(vec![64, 64, 64, 255], rgba8_from_r8(1, 1, &[64]).unwrap());
+    fn rgbaf32_from_channels_rg32_valid() {
+        assert_eq!(
+            vec![0.5, -2.0, 0.0, 1.0],
+            rgbaf32_from_channels::<f32, 2>(1, 1, bytemuck::cast_slice(&[0.5f32, -2.0f32]))
+                .unwrap()
+        );
     }
 
     #[test]
-    fn rgba8_from_r8_invalid() {
-        let result = rgba8_from_r8(4, 4, &[64]);
+    fn rgbaf32_from_channels_unaligned() {
+        // The data does not need to be aligned for the channel type.
+        let data = [0u8, 0x00, 0x3C];
+        assert_eq!(
+            vec![1.0, 1.0, 1.0, 1.0],
+            rgbaf32_from_channels::<f16, 1>(1, 1, &data[1..]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbaf32_from_channels_invalid() {
+        let result = rgbaf32_from_channels::<f32, 1>(2, 1, &[0; 7]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 16,
-                actual: 1
+                expected: 8,
+                actual: 7
             })
         ));
     }
 
     #[test]
-    fn bgra8_from_rgba8_valid() {
+    fn channels_from_rgba8_rg16_valid() {
         assert_eq!(
-            vec![3, 2, 1, 4],
-            bgra8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap()
+            bytemuck::cast_slice::<u16, u8>(&[257, 514]),
+            channels_from_rgba8::<u16, 2>(1, 1, &[1, 2, 3, 4]).unwrap()
         );
     }
 
     #[test]
-    fn bgra8_from_rgba8_invalid() {
-        let result = bgra8_from_rgba8(1, 1, &[1, 2, 3]);
+    fn channels_from_rgba8_invalid() {
+        let result = channels_from_rgba8::<u16, 1>(1, 1, &[1, 2, 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
@@ -239,93 +1328,131 @@ mod tests {
     }
 
     #[test]
-    fn rgba8_from_bgra8_valid() {
+    fn channels_from_rgbaf32_rgba16_snorm_valid() {
         assert_eq!(
-            vec![3, 2, 1, 4],
-            rgba8_from_bgra8(1, 1, &[1, 2, 3, 4]).unwrap()
+            bytemuck::cast_slice::<i16, u8>(&[-32767, 16384, 0, 32767]),
+            channels_from_rgbaf32::<i16, 4>(1, 1, &[-2.0, 0.5, 0.0, 1.0]).unwrap()
         );
     }
 
     #[test]
-    fn rgba8_from_bgra8_invalid() {
-        let result = rgba8_from_bgra8(1, 1, &[1, 2, 3]);
+    fn channels_from_rgbaf32_invalid() {
+        let result = channels_from_rgbaf32::<f16, 1>(1, 1, &[0.0; 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 4,
-                actual: 3
+                expected: 16,
+                actual: 12
             })
         ));
     }
 
     #[test]
-    fn rgba8_from_rgbaf32_valid() {
+    fn rgbaf32_from_rg11b10f_valid() {
+        let x = 0x3C0u32 | (0x400 << 11) | (0x1C0 << 22);
         assert_eq!(
-            vec![0, 63, 127, 255],
-            rgba8_from_rgbaf32(
-                1,
-                1,
-                bytemuck::cast_slice(&[0.0f32, 0.25f32, 0.5f32, 1.0f32])
-            )
-            .unwrap()
+            vec![1.0, 2.0, 0.5, 1.0],
+            rgbaf32_from_rg11b10f(1, 1, &x.to_le_bytes()).unwrap()
         );
     }
 
     #[test]
-    fn rgba8_from_rgbaf32_invalid() {
-        let result = rgba8_from_rgbaf32(1, 1, &[0; 15]);
+    fn rgbaf32_from_rg11b10f_invalid() {
+        let result = rgbaf32_from_rg11b10f(1, 1, &[0; 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 16,
-                actual: 15
+                expected: 4,
+                actual: 3
             })
         ));
     }
 
     #[test]
-    fn rgbaf32_from_rgba8_valid() {
+    fn rg11b10f_from_rgbaf32_clamp() {
+        // Negative values clamp to 0.0 and large values clamp to the max finite value.
+        let x = 0x7BFu32 << 11 | (0x1C0 << 22);
         assert_eq!(
-            bytemuck::cast_slice::<_, u8>(&[0.0f32, 0.2f32, 0.6f32, 1.0f32]),
-            rgbaf32_from_rgba8(1, 1, &[0, 51, 153, 255])
-                .unwrap()
-                .as_slice()
+            x.to_le_bytes().to_vec(),
+            rg11b10f_from_rgbaf32(1, 1, &[-1.0, 1.0e6, 0.5, 1.0]).unwrap()
         );
     }
 
     #[test]
-    fn rgbaf32_from_rgba8_invalid() {
-        let result = rgbaf32_from_rgba8(1, 1, &[1, 2, 3]);
+    fn rg11b10f_from_rgbaf32_rounding() {
+        // Ties round to the even mantissa.
+        let r = 1.0 + 2.0f32.powi(-7);
+        let g = 1.0 + 3.0 * 2.0f32.powi(-7);
+        // The smallest denormal value.
+        let b = 2.0f32.powi(-19);
+        let x = 0x3C0u32 | (0x3C2 << 11) | (1 << 22);
+        assert_eq!(
+            x.to_le_bytes().to_vec(),
+            rg11b10f_from_rgbaf32(1, 1, &[r, g, b, 1.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rg11b10f_from_rgbaf32_invalid() {
+        let result = rg11b10f_from_rgbaf32(1, 1, &[0.0; 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 4,
-                actual: 3
+                expected: 16,
+                actual: 12
             })
         ));
     }
 
     #[test]
-    fn rgba8_from_rgbaf16_valid() {
+    fn rgbaf32_from_rgb9e5_valid() {
+        let x = 256u32 | (128 << 9) | (16 << 27);
         assert_eq!(
-            vec![0, 63, 127, 255],
-            rgba8_from_rgbaf16(
-                1,
-                1,
-                bytemuck::cast_slice(&[
-                    f16::from_f32(0.0f32),
-                    f16::from_f32(0.25f32),
-                    f16::from_f32(0.5f32),
-                    f16::from_f32(1.0f32)
-                ])
-            )
-            .unwrap()
+            vec![1.0, 0.5, 0.0, 1.0],
+            rgbaf32_from_rgb9e5(1, 1, &x.to_le_bytes()).unwrap()
         );
     }
 
     #[test]
-    fn rgba8_from_rgbaf16_invalid() {
-        let result = rgba8_from_rgbaf16(1, 1, &[0; 7]);
+    fn rgb9e5_from_rgbaf32_valid() {
+        let x = 256u32 | (128 << 9) | (16 << 27);
+        assert_eq!(
+            x.to_le_bytes().to_vec(),
+            rgb9e5_from_rgbaf32(1, 1, &[1.0, 0.5, 0.0, 1.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgb9e5_from_rgbaf32_rounding() {
+        // Rounding the max channel to 512 increments the shared exponent.
+        let x = 256u32 | (16 << 27);
+        assert_eq!(
+            x.to_le_bytes().to_vec(),
+            rgb9e5_from_rgbaf32(1, 1, &[1.0 - 2.0f32.powi(-10), 0.0, 0.0, 1.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgb9e5_from_rgbaf32_clamp() {
+        let x = 511u32 | (31 << 27);
+        assert_eq!(
+            x.to_le_bytes().to_vec(),
+            rgb9e5_from_rgbaf32(1, 1, &[1.0e9, -1.0, f32::NAN, 1.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgb10a2_valid() {
+        let x = 1023u32 | (512 << 10) | (3 << 30);
+        assert_eq!(
+            vec![255, 128, 0, 255],
+            rgba8_from_rgb10a2(1, 1, &x.to_le_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgb10a2_invalid() {
+        let result = rgba8_from_rgb10a2(2, 1, &[0; 7]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
@@ -336,43 +1463,113 @@ mod tests {
     }
 
     #[test]
-    fn rgbaf16_from_rgba8_valid() {
+    fn rgbaf32_from_rgb10a2_valid() {
+        let x = 1023u32 | (1 << 30);
         assert_eq!(
-            bytemuck::cast_slice::<f16, u8>(&[
-                f16::from_f32(0.0f32),
-                f16::from_f32(0.2f32),
-                f16::from_f32(0.6f32),
-                f16::from_f32(1.0f32)
-            ]),
-            rgbaf16_from_rgba8(1, 1, &[0, 51, 153, 255])
-                .unwrap()
-                .as_slice()
+            vec![1.0, 0.0, 0.0, 1.0 / 3.0],
+            rgbaf32_from_rgb10a2(1, 1, &x.to_le_bytes()).unwrap()
         );
     }
 
     #[test]
-    fn rgbaf16_from_rgba8_invalid() {
-        let result = rgbaf16_from_rgba8(1, 1, &[1, 2, 3]);
+    fn rgb10a2_from_rgbaf32_clamp() {
+        let x = 1023u32 | (512 << 10) | (1 << 30);
+        assert_eq!(
+            x.to_le_bytes().to_vec(),
+            rgb10a2_from_rgbaf32(1, 1, &[2.0, 0.5, -1.0, 0.4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbaf32_from_d24s8_valid() {
+        let x = 0xFFFFFFu32 | (255 << 24);
+        assert_eq!(
+            vec![1.0, 1.0, 0.0, 1.0],
+            rgbaf32_from_d24s8(1, 1, &x.to_le_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_d24s8_valid() {
+        let x = 0x800000u32 | (3 << 24);
+        assert_eq!(
+            vec![128, 3, 0, 255],
+            rgba8_from_d24s8(1, 1, &x.to_le_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn d24s8_from_rgbaf32_clamp() {
+        let x = 0xFFFFFFu32 | (3 << 24);
+        assert_eq!(
+            x.to_le_bytes().to_vec(),
+            d24s8_from_rgbaf32(1, 1, &[2.0, 3.0 / 255.0, 0.0, 1.0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn d32fs8_round_trip() {
+        // The unused bits after the stencil value are zero.
+        let bytes = [0.25f32.to_bits(), 7].map(u32::to_le_bytes).concat();
+        let rgba = rgbaf32_from_d32fs8(1, 1, &bytes).unwrap();
+        assert_eq!(vec![0.25, 7.0 / 255.0, 0.0, 1.0], rgba);
+        assert_eq!(bytes, d32fs8_from_rgbaf32(1, 1, &rgba).unwrap());
+        assert_eq!(
+            vec![63, 7, 0, 255],
+            rgba8_from_d32fs8(1, 1, &bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_b5g6r5_valid() {
+        let x = (31u16 << 11) | (32 << 5);
+        assert_eq!(
+            vec![255, 130, 0, 255],
+            rgba8_from_b5g6r5(1, 1, &x.to_le_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_b5g6r5_invalid() {
+        let result = rgba8_from_b5g6r5(1, 1, &[0]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 4,
-                actual: 3
+                expected: 2,
+                actual: 1
             })
         ));
     }
 
     #[test]
-    fn rgba8_from_rgba8_valid() {
+    fn b5g6r5_from_rgba8_valid() {
         assert_eq!(
-            vec![1, 2, 3, 4],
-            rgba8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap()
+            vec![0x00, 0xFC],
+            b5g6r5_from_rgba8(1, 1, &[255, 130, 0, 255], false).unwrap()
         );
     }
 
     #[test]
-    fn rgba8_from_rgba8_invalid() {
-        let result = rgba8_from_rgba8(1, 1, &[1, 2, 3]);
+    fn b5g6r5_from_rgba8_dither() {
+        // Red is just under half of the first 5-bit step.
+        let data = [4, 0, 0, 255].repeat(16);
+        let red = |bytes: Vec<u8>| {
+            bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]) >> 11)
+                .sum::<u16>()
+        };
+
+        assert_eq!(0, red(b5g6r5_from_rgba8(4, 4, &data, false).unwrap()));
+
+        // Dithering should preserve the average value.
+        let sum = red(b5g6r5_from_rgba8(4, 4, &data, true).unwrap());
+        assert!((7..=9).contains(&sum), "{sum}");
+    }
+
+    #[test]
+    fn b5g6r5_from_rgba8_invalid() {
+        let result = b5g6r5_from_rgba8(1, 1, &[0; 3], true);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
@@ -383,96 +1580,183 @@ mod tests {
     }
 
     #[test]
-    fn rgbaf32_from_rgbaf32_valid() {
+    fn rgba8_from_bgr5a1_valid() {
+        let x = (31u16 << 10) | (16 << 5);
         assert_eq!(
-            vec![1.0, 2.0, 3.0, 4.0],
-            rgbaf32_from_rgbaf32(
+            vec![255, 132, 0, 0, 255, 132, 0, 255],
+            rgba8_from_bgr5a1(
+                2,
                 1,
-                1,
-                bytemuck::cast_slice(&[1.0f32, 2.0f32, 3.0f32, 4.0f32])
+                &[x.to_le_bytes(), (x | 0x8000).to_le_bytes()].concat()
             )
             .unwrap()
         );
     }
 
     #[test]
-    fn rgbaf32_from_rgbaf32_invalid() {
-        let result = rgbaf32_from_rgbaf32(1, 1, &[0; 15]);
+    fn rgba8_from_bgr5x1_valid() {
+        // The unused bit should not affect alpha.
+        let x = (31u16 << 10) | (16 << 5);
+        assert_eq!(
+            vec![255, 132, 0, 255],
+            rgba8_from_bgr5x1(1, 1, &x.to_le_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn bgr5a1_from_rgba8_valid() {
+        assert_eq!(
+            vec![0x00, 0x7E, 0x00, 0xFE],
+            bgr5a1_from_rgba8(2, 1, &[255, 132, 0, 127, 255, 132, 0, 128], false).unwrap()
+        );
+    }
+
+    #[test]
+    fn bgr5x1_from_rgba8_valid() {
+        assert_eq!(
+            vec![0x00, 0xFE],
+            bgr5x1_from_rgba8(1, 1, &[255, 132, 0, 0], false).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgb8_valid() {
+        assert_eq!(
+            vec![1, 2, 3, 255],
+            rgba8_from_rgb8(1, 1, &[1, 2, 3]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_rgb8_invalid() {
+        let result = rgba8_from_rgb8(2, 1, &[0; 5]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 16,
-                actual: 15
+                expected: 6,
+                actual: 5
             })
         ));
     }
 
     #[test]
-    fn rgbaf32_from_rgbaf16_valid() {
+    fn rgb8_from_rgba8_valid() {
+        assert_eq!(vec![1, 2, 3], rgb8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap());
+    }
+
+    #[test]
+    fn rgba8_from_bgr8_valid() {
         assert_eq!(
-            vec![0.0, 0.25, 0.5, 1.0],
-            rgbaf32_from_rgbaf16(
-                1,
-                1,
-                bytemuck::cast_slice(&[
-                    f16::from_f32(0.0f32),
-                    f16::from_f32(0.25f32),
-                    f16::from_f32(0.5f32),
-                    f16::from_f32(1.0f32)
-                ])
-            )
-            .unwrap()
+            vec![3, 2, 1, 255],
+            rgba8_from_bgr8(1, 1, &[1, 2, 3]).unwrap()
         );
     }
 
     #[test]
-    fn rgbaf32_from_rgbaf16_invalid() {
-        let result = rgbaf32_from_rgbaf16(1, 1, &[0; 7]);
+    fn bgr8_from_rgba8_valid() {
+        assert_eq!(vec![3, 2, 1], bgr8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap());
+    }
+
+    #[test]
+    fn rgba8_from_bgrx8_valid() {
+        assert_eq!(
+            vec![3, 2, 1, 255],
+            rgba8_from_bgrx8(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn bgrx8_from_rgba8_valid() {
+        assert_eq!(
+            vec![3, 2, 1, 255],
+            bgrx8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_la8_valid() {
+        assert_eq!(vec![1, 1, 1, 2], rgba8_from_la8(1, 1, &[1, 2]).unwrap());
+    }
+
+    #[test]
+    fn la8_from_rgba8_valid() {
+        assert_eq!(vec![1, 4], la8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap());
+    }
+
+    #[test]
+    fn rgba8_from_a8_valid() {
+        assert_eq!(vec![0, 0, 0, 1], rgba8_from_a8(1, 1, &[1]).unwrap());
+    }
+
+    #[test]
+    fn a8_from_rgba8_valid() {
+        assert_eq!(vec![4], a8_from_rgba8(1, 1, &[1, 2, 3, 4]).unwrap());
+    }
+
+    #[test]
+    fn a8_from_rgba8_invalid() {
+        let result = a8_from_rgba8(1, 1, &[0; 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 8,
-                actual: 7
+                expected: 4,
+                actual: 3
             })
         ));
     }
 
     #[test]
-    fn bgra4_from_rgba8_valid() {
+    fn rgbau32_from_channels_r8_sint_valid() {
         assert_eq!(
-            vec![0x30, 0xCF],
-            bgra4_from_rgba8(1, 1, &[255, 51, 0, 204]).unwrap()
+            vec![u32::MAX - 1, u32::MAX - 1, u32::MAX - 1, 1],
+            rgbau32_from_channels::<i8, 1>(1, 1, &[0xFE]).unwrap()
         );
     }
 
     #[test]
-    fn bgra4_from_rgba8_invalid() {
-        let result = bgra4_from_rgba8(1, 1, &[1, 2, 3]);
+    fn rgbau32_from_channels_rgba16_uint_valid() {
+        assert_eq!(
+            vec![1, 256, 65535, 0],
+            rgbau32_from_channels::<u16, 4>(1, 1, &[1, 0, 0, 1, 255, 255, 0, 0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn rgbau32_from_channels_invalid() {
+        let result = rgbau32_from_channels::<u32, 1>(2, 1, &[0; 7]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 4,
-                actual: 3
+                expected: 8,
+                actual: 7
             })
         ));
     }
 
     #[test]
-    fn rgba8_from_bgra4_valid() {
+    fn channels_from_rgbau32_r32_sint_valid() {
         assert_eq!(
-            vec![255, 51, 0, 204],
-            rgba8_from_bgra4(1, 1, &[0x30, 0xCF]).unwrap()
+            (-2i32).to_le_bytes().to_vec(),
+            channels_from_rgbau32::<i32, 1>(1, 1, &[-2i32 as u32, 0, 0, 1]).unwrap()
         );
     }
 
     #[test]
-    fn rgba8_from_bgra4_invalid() {
-        let result = rgba8_from_bgra4(1, 1, &[1]);
+    fn channels_from_rgbau32_clamp() {
+        assert_eq!(
+            vec![0xFF, 0xFF, 0xFF, 0x7F, 0x00, 0x80, 0x00, 0x00],
+            channels_from_rgbau32::<i16, 4>(1, 1, &[u32::MAX, 70000, -70000i32 as u32, 0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn channels_from_rgbau32_invalid() {
+        let result = channels_from_rgbau32::<u8, 4>(1, 1, &[0; 3]);
         assert!(matches!(
             result,
             Err(SurfaceError::NotEnoughData {
-                expected: 2,
-                actual: 1
+                expected: 16,
+                actual: 12
             })
         ));
     }
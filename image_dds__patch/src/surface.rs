@@ -1,6 +1,6 @@
 use crate::{
     calculate_offset, error::CreateImageError, max_mipmap_count, mip_dimension, mip_size,
-    ImageFormat, SurfaceError,
+    AlphaMode, ImageFormat, SurfaceError,
 };
 
 /// A surface with an image format known at runtime.
@@ -17,6 +17,9 @@ pub struct Surface<T> {
     pub depth: u32,
     /// The number of array layers in the surface.
     /// This should be `1` for most surfaces and `6` for cube maps.
+    ///
+    /// Cube maps store the faces for each cube in the order +X, -X, +Y, -Y, +Z, -Z.
+    /// Surfaces with more than 6 layers create a cube map array.
     pub layers: u32,
     /// The number of mipmaps in the surface.
     /// This should be `1` if the surface has only the base mip level.
@@ -24,6 +27,8 @@ pub struct Surface<T> {
     pub mipmaps: u32,
     /// The format of the bytes in [data](#structfield.data).
     pub image_format: ImageFormat,
+    /// How the alpha channel in [data](#structfield.data) should be interpreted.
+    pub alpha_mode: AlphaMode,
     /// The combined image data ordered by layer and then mipmap without additional padding.
     ///
     /// A surface with L layers and M mipmaps would have the following layout:
@@ -95,6 +100,17 @@ impl<T: AsRef<[u8]>> Surface<T> {
         // TODO: Return the mipmap and array offsets.
         Ok(())
     }
+
+    /// Check that the surface is 2D and has 6 [layers](#structfield.layers) for each cube.
+    pub(crate) fn validate_cube_map(&self) -> Result<(), SurfaceError> {
+        if self.layers == 0 || !self.layers.is_multiple_of(6) || self.depth > 1 {
+            return Err(SurfaceError::InvalidCubeMap {
+                layers: self.layers,
+                depth: self.depth,
+            });
+        }
+        Ok(())
+    }
 }
 
 /// An uncompressed [ImageFormat::Rgba8Unorm] surface with 4 bytes per pixel.
@@ -116,6 +132,8 @@ pub struct SurfaceRgba8<T> {
     /// This should be `1` if the surface has only the base mip level.
     /// All array layers are assumed to have the same number of mipmaps.
     pub mipmaps: u32,
+    /// How the alpha channel in [data](#structfield.data) should be interpreted.
+    pub alpha_mode: AlphaMode,
     /// The combined image data ordered by layer and then mipmap without additional padding.
     ///
     /// A surface with L layers and M mipmaps would have the following layout:
@@ -148,6 +166,7 @@ impl<T: AsRef<[u8]>> SurfaceRgba8<T> {
             layers: self.layers,
             mipmaps: self.mipmaps,
             image_format: ImageFormat::Rgba8Unorm,
+            alpha_mode: self.alpha_mode,
             data: self.data.as_ref(),
         }
         .validate()
@@ -164,6 +183,7 @@ impl<'a> SurfaceRgba8<&'a [u8]> {
             depth: 1,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: image.as_raw(),
         }
     }
@@ -178,6 +198,7 @@ impl<'a> SurfaceRgba8<&'a [u8]> {
             depth: 1,
             layers,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: image.as_raw(),
         }
     }
@@ -192,11 +213,48 @@ impl<'a> SurfaceRgba8<&'a [u8]> {
             depth,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: image.as_raw(),
         }
     }
 }
 
+impl SurfaceRgba8<Vec<u8>> {
+    /// Multiply RGB by alpha and set the alpha mode to [AlphaMode::Premultiplied].
+    ///
+    /// Surfaces using [AlphaMode::Premultiplied], [AlphaMode::Opaque], or [AlphaMode::Custom] are unchanged.
+    pub fn premultiply_alpha(&mut self) {
+        if can_premultiply(self.alpha_mode) {
+            for pixel in self.data.chunks_exact_mut(4) {
+                let alpha = pixel[3] as u32;
+                for c in &mut pixel[..3] {
+                    *c = ((*c as u32 * alpha + 127) / 255) as u8;
+                }
+            }
+            self.alpha_mode = AlphaMode::Premultiplied;
+        }
+    }
+
+    /// Divide RGB by alpha and set the alpha mode to [AlphaMode::Straight]
+    /// for surfaces using [AlphaMode::Premultiplied].
+    ///
+    /// Other surfaces are unchanged.
+    /// RGB is unchanged for pixels with an alpha of `0`.
+    pub fn unpremultiply_alpha(&mut self) {
+        if self.alpha_mode == AlphaMode::Premultiplied {
+            for pixel in self.data.chunks_exact_mut(4) {
+                let alpha = pixel[3] as u32;
+                for c in &mut pixel[..3] {
+                    if let Some(value) = (*c as u32 * 255 + alpha / 2).checked_div(alpha) {
+                        *c = value.min(255) as u8;
+                    }
+                }
+            }
+            self.alpha_mode = AlphaMode::Straight;
+        }
+    }
+}
+
 #[cfg(feature = "image")]
 impl SurfaceRgba8<Vec<u8>> {
     /// Create an image for all layers and depth slices for the given `mipmap`.
@@ -207,7 +265,8 @@ impl SurfaceRgba8<Vec<u8>> {
         // A single 2D image can only represent data from a single mip level across layers.
         let image_data: Vec<_> = (0..self.layers)
             .flat_map(|layer| {
-                (0..self.depth).flat_map(move |level| self.get(layer, level, mipmap).unwrap())
+                (0..mip_dimension(self.depth, mipmap))
+                    .flat_map(move |level| self.get(layer, level, mipmap).unwrap())
             })
             .copied()
             .collect();
@@ -275,6 +334,8 @@ pub struct SurfaceRgba32Float<T> {
     /// This should be `1` if the surface has only the base mip level.
     /// All array layers are assumed to have the same number of mipmaps.
     pub mipmaps: u32,
+    /// How the alpha channel in [data](#structfield.data) should be interpreted.
+    pub alpha_mode: AlphaMode,
     /// The combined `f32` image data ordered by layer and then mipmap without additional padding.
     ///
     /// A surface with L layers and M mipmaps would have the following layout:
@@ -309,6 +370,123 @@ impl<T: AsRef<[f32]>> SurfaceRgba32Float<T> {
             layers: self.layers,
             mipmaps: self.mipmaps,
             image_format: ImageFormat::Rgba32Float,
+            alpha_mode: self.alpha_mode,
+            data: bytemuck::cast_slice(self.data.as_ref()),
+        }
+        .validate()
+    }
+}
+
+impl SurfaceRgba32Float<Vec<f32>> {
+    /// Multiply RGB by alpha and set the alpha mode to [AlphaMode::Premultiplied].
+    ///
+    /// Surfaces using [AlphaMode::Premultiplied], [AlphaMode::Opaque], or [AlphaMode::Custom] are unchanged.
+    pub fn premultiply_alpha(&mut self) {
+        if can_premultiply(self.alpha_mode) {
+            for pixel in self.data.chunks_exact_mut(4) {
+                let alpha = pixel[3];
+                for c in &mut pixel[..3] {
+                    *c *= alpha;
+                }
+            }
+            self.alpha_mode = AlphaMode::Premultiplied;
+        }
+    }
+
+    /// Divide RGB by alpha and set the alpha mode to [AlphaMode::Straight]
+    /// for surfaces using [AlphaMode::Premultiplied].
+    ///
+    /// Other surfaces are unchanged.
+    /// RGB is unchanged for pixels with an alpha of `0.0`.
+    pub fn unpremultiply_alpha(&mut self) {
+        if self.alpha_mode == AlphaMode::Premultiplied {
+            for pixel in self.data.chunks_exact_mut(4) {
+                let alpha = pixel[3];
+                if alpha != 0.0 {
+                    for c in &mut pixel[..3] {
+                        *c /= alpha;
+                    }
+                }
+            }
+            self.alpha_mode = AlphaMode::Straight;
+        }
+    }
+
+    /// Replace RGB with the linearized depth from the red channel
+    /// to view depth formats like [ImageFormat::Depth32Float] as grayscale.
+    ///
+    /// Depth from a perspective projection becomes `0.0` at the `near` plane
+    /// and `1.0` at the `far` plane.
+    /// Reversed depth buffers that store `1.0` at the near plane should use `1.0 - depth` first.
+    /// Stencil values in the green channel are replaced, and alpha is unchanged.
+    pub fn linearize_depth(&mut self, near: f32, far: f32) {
+        for pixel in self.data.chunks_exact_mut(4) {
+            // Invert the projection that maps view space depth to the range 0.0 to 1.0.
+            // This simplifies (z - near) / (far - near) for z = near * far / (far - depth * (far - near)).
+            let depth = pixel[0];
+            let linear = near * depth / (far * (1.0 - depth) + near * depth);
+            pixel[..3].fill(linear);
+        }
+    }
+}
+
+/// An uncompressed [ImageFormat::Rgba32Uint] surface with 16 bytes per pixel.
+///
+/// Signed integer formats like [ImageFormat::R8Sint] are sign extended
+/// and stored as two's complement, so each value can be cast with `as i32`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurfaceRgba32Uint<T> {
+    /// The width of the surface in pixels.
+    pub width: u32,
+    /// The height of the surface in pixels.
+    pub height: u32,
+    /// The depth of the surface in pixels.
+    /// This should be `1` for 2D surfaces.
+    pub depth: u32,
+    /// The number of array layers in the surface.
+    /// This should be `1` for most surfaces and `6` for cube maps.
+    pub layers: u32,
+    /// The number of mipmaps in the surface.
+    /// This should be `1` if the surface has only the base mip level.
+    /// All array layers are assumed to have the same number of mipmaps.
+    pub mipmaps: u32,
+    /// How the alpha channel in [data](#structfield.data) should be interpreted.
+    pub alpha_mode: AlphaMode,
+    /// The combined `u32` image data ordered by layer and then mipmap without additional padding.
+    ///
+    /// A surface with L layers and M mipmaps would have the following layout:
+    /// Layer 0 Mip 0, Layer 0 Mip 1,  ..., Layer L-1 Mip M-1
+    pub data: T,
+}
+
+impl<T: AsRef<[u32]>> SurfaceRgba32Uint<T> {
+    /// Get the range of 2D image data corresponding to the specified `layer`, `depth_level`, and `mipmap`.
+    ///
+    /// The dimensions of the returned data should be calculated using [mip_dimension].
+    /// Returns [None] if the expected range is not fully contained within the buffer.
+    pub fn get(&self, layer: u32, depth_level: u32, mipmap: u32) -> Option<&[u32]> {
+        get_mipmap(
+            self.data.as_ref(),
+            (self.width, self.height, self.depth),
+            self.mipmaps,
+            ImageFormat::Rgba32Uint,
+            layer,
+            depth_level,
+            mipmap,
+        )
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), SurfaceError> {
+        Surface {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            layers: self.layers,
+            mipmaps: self.mipmaps,
+            image_format: ImageFormat::Rgba32Uint,
+            alpha_mode: self.alpha_mode,
             data: bytemuck::cast_slice(self.data.as_ref()),
         }
         .validate()
@@ -325,6 +503,7 @@ impl<'a> SurfaceRgba32Float<&'a [f32]> {
             depth: 1,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: image.as_raw(),
         }
     }
@@ -339,6 +518,7 @@ impl<'a> SurfaceRgba32Float<&'a [f32]> {
             depth: 1,
             layers,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: image.as_raw(),
         }
     }
@@ -353,6 +533,7 @@ impl<'a> SurfaceRgba32Float<&'a [f32]> {
             depth,
             layers: 1,
             mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
             data: image.as_raw(),
         }
     }
@@ -368,7 +549,8 @@ impl SurfaceRgba32Float<Vec<f32>> {
         // A single 2D image can only represent data from a single mip level across layers.
         let image_data: Vec<_> = (0..self.layers)
             .flat_map(|layer| {
-                (0..self.depth).flat_map(move |level| self.get(layer, level, mipmap).unwrap())
+                (0..mip_dimension(self.depth, mipmap))
+                    .flat_map(move |level| self.get(layer, level, mipmap).unwrap())
             })
             .copied()
             .collect();
@@ -415,6 +597,11 @@ impl SurfaceRgba32Float<Vec<f32>> {
     }
 }
 
+fn can_premultiply(alpha_mode: AlphaMode) -> bool {
+    // Premultiplying twice or premultiplying non transparency data would lose information.
+    matches!(alpha_mode, AlphaMode::Unknown | AlphaMode::Straight)
+}
+
 // TODO: Add tests for this.
 fn get_mipmap<T>(
     data: &[T],
@@ -430,6 +617,11 @@ fn get_mipmap<T>(
     let block_size_in_bytes = format.block_size_in_bytes();
     let block_dimensions = format.block_dimensions();
 
+    // Depth slices are only valid for the current mipmap.
+    if depth_level >= mip_dimension(depth, mipmap) {
+        return None;
+    }
+
     // TODO: Create an error for failed offset calculations?
     let offset_in_bytes = calculate_offset(
         layer,
@@ -460,3 +652,95 @@ fn get_mipmap<T>(
     let count = size_in_bytes / std::mem::size_of::<T>();
     data.get(start..start + count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linearize_depth_near_far_halfway() {
+        // Depth values cluster near 1.0 for most of the view distance.
+        let halfway = 100.0 / 99.9 * (1.0 - 0.1 / 50.05);
+        let mut surface = SurfaceRgba32Float {
+            width: 3,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![
+                0.0, 1.0, 0.0, 0.5, 1.0, 0.0, 0.0, 0.5, halfway, 0.0, 0.0, 0.5,
+            ],
+        };
+
+        surface.linearize_depth(0.1, 100.0);
+        assert_eq!(&[0.0, 0.0, 0.0, 0.5], &surface.data[..4]);
+        assert_eq!(&[1.0, 1.0, 1.0, 0.5], &surface.data[4..8]);
+        assert!(surface.data[8..11].iter().all(|d| (d - 0.5).abs() < 1e-4));
+        assert_eq!(0.5, surface.data[11]);
+    }
+
+    #[test]
+    fn premultiply_unpremultiply_rgba8() {
+        let mut surface = SurfaceRgba8 {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![255, 128, 0, 128, 200, 100, 50, 0],
+        };
+
+        surface.premultiply_alpha();
+        assert_eq!(AlphaMode::Premultiplied, surface.alpha_mode);
+        assert_eq!(vec![128, 64, 0, 128, 0, 0, 0, 0], surface.data);
+
+        // Premultiplying again should not darken the colors.
+        surface.premultiply_alpha();
+        assert_eq!(vec![128, 64, 0, 128, 0, 0, 0, 0], surface.data);
+
+        // Colors for transparent pixels can't be recovered.
+        surface.unpremultiply_alpha();
+        assert_eq!(AlphaMode::Straight, surface.alpha_mode);
+        assert_eq!(vec![255, 128, 0, 128, 0, 0, 0, 0], surface.data);
+    }
+
+    #[test]
+    fn premultiply_rgba8_custom() {
+        let mut surface = SurfaceRgba8 {
+            width: 1,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Custom,
+            data: vec![255, 255, 255, 0],
+        };
+
+        surface.premultiply_alpha();
+        assert_eq!(AlphaMode::Custom, surface.alpha_mode);
+        assert_eq!(vec![255, 255, 255, 0], surface.data);
+    }
+
+    #[test]
+    fn premultiply_unpremultiply_rgbaf32() {
+        let mut surface = SurfaceRgba32Float {
+            width: 2,
+            height: 1,
+            depth: 1,
+            layers: 1,
+            mipmaps: 1,
+            alpha_mode: AlphaMode::Straight,
+            data: vec![1.0, 0.5, 2.0, 0.5, 1.0, 1.0, 1.0, 0.0],
+        };
+
+        surface.premultiply_alpha();
+        assert_eq!(AlphaMode::Premultiplied, surface.alpha_mode);
+        assert_eq!(vec![0.5, 0.25, 1.0, 0.5, 0.0, 0.0, 0.0, 0.0], surface.data);
+
+        surface.unpremultiply_alpha();
+        assert_eq!(AlphaMode::Straight, surface.alpha_mode);
+        assert_eq!(vec![1.0, 0.5, 2.0, 0.5, 0.0, 0.0, 0.0, 0.0], surface.data);
+    }
+}
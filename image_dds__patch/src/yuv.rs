@@ -0,0 +1,338 @@
+// YUV formats store luma for every pixel but share chroma between 2 or 4 pixels.
+// Planar formats like NV12 store all luma samples before the interleaved chroma samples.
+use crate::{error::SurfaceError, mip_size, round_up, ImageFormat, YuvMatrix};
+
+const CHANNELS: usize = 4;
+
+/// Decode the YUV data in `data` to RGBA8 using `matrix`.
+pub fn rgba8_from_yuv(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[u8],
+    matrix: YuvMatrix,
+) -> Result<Vec<u8>, SurfaceError> {
+    // Converted values rarely land exactly on an 8-bit value, so round instead of truncating.
+    let rgba = rgbaf32_from_yuv(width, height, image_format, data, matrix)?;
+    Ok(rgba
+        .into_iter()
+        .map(|x| (x * 255.0).round() as u8)
+        .collect())
+}
+
+/// Decode the YUV data in `data` to RGBAF32 using `matrix`.
+///
+/// Pixels use the chroma of their block without interpolation.
+/// RGB values are clamped to the range `0.0` to `1.0`.
+pub fn rgbaf32_from_yuv(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[u8],
+    matrix: YuvMatrix,
+) -> Result<Vec<f32>, SurfaceError> {
+    let expected_size = yuv_size(width, height, image_format)?;
+    if data.len() < expected_size {
+        return Err(SurfaceError::NotEnoughData {
+            expected: expected_size,
+            actual: data.len(),
+        });
+    }
+
+    let planes = Planes::new(width, height, image_format);
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * CHANNELS);
+    for y in 0..height as usize {
+        for x in 0..width as usize {
+            let ycbcr = planes
+                .sample_indices(x, y)
+                .map(|i| read_sample(image_format, data, i));
+            rgba.extend(rgb_from_ycbcr(ycbcr, matrix));
+            rgba.push(1.0);
+        }
+    }
+    Ok(rgba)
+}
+
+/// Encode the RGB channels of RGBAF32 data to YUV using `matrix`.
+///
+/// Chroma is averaged over the pixels in each block.
+pub fn yuv_from_rgbaf32(
+    width: u32,
+    height: u32,
+    image_format: ImageFormat,
+    data: &[f32],
+    matrix: YuvMatrix,
+) -> Result<Vec<u8>, SurfaceError> {
+    let expected_size = width as usize * height as usize * CHANNELS;
+    if data.len() < expected_size {
+        return Err(SurfaceError::NotEnoughData {
+            expected: expected_size,
+            actual: data.len(),
+        });
+    }
+
+    let mut yuv = vec![0u8; yuv_size(width, height, image_format)?];
+
+    let planes = Planes::new(width, height, image_format);
+    let (block_width, block_height, _) = image_format.block_dimensions();
+    let (block_width, block_height) = (block_width as usize, block_height as usize);
+    for block_y in (0..planes.height).step_by(block_height) {
+        for block_x in (0..planes.width).step_by(block_width) {
+            let mut chroma = [0.0; 2];
+            for y in block_y..block_y + block_height {
+                for x in block_x..block_x + block_width {
+                    // Padding repeats the last row and column.
+                    let pixel_x = x.min(width as usize - 1);
+                    let pixel_y = y.min(height as usize - 1);
+                    let i = (pixel_y * width as usize + pixel_x) * CHANNELS;
+                    let rgb = [data[i], data[i + 1], data[i + 2]];
+                    let [luma, cb, cr] = ycbcr_from_rgb(rgb, matrix);
+
+                    write_sample(image_format, &mut yuv, planes.sample_indices(x, y)[0], luma);
+                    chroma[0] += cb;
+                    chroma[1] += cr;
+                }
+            }
+
+            // All pixels in a block share the same chroma samples.
+            let [_, cb_index, cr_index] = planes.sample_indices(block_x, block_y);
+            let count = (block_width * block_height) as f32;
+            write_sample(image_format, &mut yuv, cb_index, chroma[0] / count);
+            write_sample(image_format, &mut yuv, cr_index, chroma[1] / count);
+        }
+    }
+
+    Ok(yuv)
+}
+
+fn yuv_size(width: u32, height: u32, image_format: ImageFormat) -> Result<usize, SurfaceError> {
+    // Validate surface dimensions to check for potential overflow.
+    let (block_width, block_height, _) = image_format.block_dimensions();
+    mip_size(
+        width as usize,
+        height as usize,
+        1,
+        block_width as usize,
+        block_height as usize,
+        1,
+        image_format.block_size_in_bytes(),
+    )
+    .ok_or(SurfaceError::PixelCountWouldOverflow {
+        width,
+        height,
+        depth: 1,
+    })
+}
+
+// The layout of samples for dimensions padded to whole blocks.
+struct Planes {
+    image_format: ImageFormat,
+    width: usize,
+    height: usize,
+}
+
+impl Planes {
+    fn new(width: u32, height: u32, image_format: ImageFormat) -> Self {
+        let (block_width, block_height, _) = image_format.block_dimensions();
+        Self {
+            image_format,
+            width: round_up(width as usize, block_width as usize),
+            height: round_up(height as usize, block_height as usize),
+        }
+    }
+
+    // The sample indices of Y, U, and V for the pixel at (x, y).
+    fn sample_indices(&self, x: usize, y: usize) -> [usize; 3] {
+        match self.image_format {
+            ImageFormat::Yuy2 => {
+                let base = (y * self.width + x / 2 * 2) * 2;
+                [base + x % 2 * 2, base + 1, base + 3]
+            }
+            ImageFormat::Uyvy => {
+                let base = (y * self.width + x / 2 * 2) * 2;
+                [base + 1 + x % 2 * 2, base, base + 2]
+            }
+            ImageFormat::Nv12 | ImageFormat::P010 => {
+                let uv = self.width * self.height + y / 2 * self.width + x / 2 * 2;
+                [y * self.width + x, uv, uv + 1]
+            }
+            _ => unreachable!("{:?} is not a YUV format", self.image_format),
+        }
+    }
+}
+
+// Samples are scaled to the 8-bit range to use the same conversion for all formats.
+fn read_sample(image_format: ImageFormat, data: &[u8], index: usize) -> f32 {
+    if image_format == ImageFormat::P010 {
+        // 10-bit values are stored in the high bits of each 16-bit sample.
+        let value = u16::from_le_bytes([data[index * 2], data[index * 2 + 1]]);
+        (value >> 6) as f32 / 4.0
+    } else {
+        data[index] as f32
+    }
+}
+
+fn write_sample(image_format: ImageFormat, data: &mut [u8], index: usize, value: f32) {
+    if image_format == ImageFormat::P010 {
+        let value = ((value * 4.0).round().clamp(0.0, 1023.0) as u16) << 6;
+        data[index * 2..index * 2 + 2].copy_from_slice(&value.to_le_bytes());
+    } else {
+        data[index] = value.round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+// The weights of red and blue when calculating luma.
+fn coefficients(matrix: YuvMatrix) -> (f32, f32) {
+    match matrix {
+        YuvMatrix::Bt601 => (0.299, 0.114),
+        YuvMatrix::Bt709 => (0.2126, 0.0722),
+    }
+}
+
+fn rgb_from_ycbcr([luma, cb, cr]: [f32; 3], matrix: YuvMatrix) -> [f32; 3] {
+    let (kr, kb) = coefficients(matrix);
+    let kg = 1.0 - kr - kb;
+
+    // Remove the offset and scale of the limited range.
+    let y = (luma - 16.0) / 219.0;
+    let cb = (cb - 128.0) / 224.0;
+    let cr = (cr - 128.0) / 224.0;
+
+    let r = y + 2.0 * (1.0 - kr) * cr;
+    let b = y + 2.0 * (1.0 - kb) * cb;
+    let g = (y - kr * r - kb * b) / kg;
+    [r, g, b].map(|c| c.clamp(0.0, 1.0))
+}
+
+fn ycbcr_from_rgb(rgb: [f32; 3], matrix: YuvMatrix) -> [f32; 3] {
+    let (kr, kb) = coefficients(matrix);
+    let kg = 1.0 - kr - kb;
+
+    let [r, g, b] = rgb.map(|c| c.clamp(0.0, 1.0));
+    let y = kr * r + kg * g + kb * b;
+    let cb = (b - y) / (2.0 * (1.0 - kb));
+    let cr = (r - y) / (2.0 * (1.0 - kr));
+    [16.0 + 219.0 * y, 128.0 + 224.0 * cb, 128.0 + 224.0 * cr]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgba8_from_yuy2_black_white() {
+        assert_eq!(
+            vec![0, 0, 0, 255, 255, 255, 255, 255],
+            rgba8_from_yuv(
+                2,
+                1,
+                ImageFormat::Yuy2,
+                &[16, 128, 235, 128],
+                YuvMatrix::Bt601
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_uyvy_black_white() {
+        assert_eq!(
+            vec![0, 0, 0, 255, 255, 255, 255, 255],
+            rgba8_from_yuv(
+                2,
+                1,
+                ImageFormat::Uyvy,
+                &[128, 16, 128, 235],
+                YuvMatrix::Bt601
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn rgba8_from_nv12_red() {
+        // The Y plane is followed by a single UV pair.
+        let data = [81, 81, 81, 81, 90, 240];
+        assert_eq!(
+            vec![254, 0, 0, 255],
+            rgba8_from_yuv(2, 2, ImageFormat::Nv12, &data, YuvMatrix::Bt601).unwrap()[..4]
+        );
+    }
+
+    #[test]
+    fn rgba8_from_nv12_not_enough_data() {
+        let result = rgba8_from_yuv(4, 4, ImageFormat::Nv12, &[0u8; 16], YuvMatrix::Bt601);
+        assert!(matches!(
+            result,
+            Err(SurfaceError::NotEnoughData {
+                expected: 24,
+                actual: 16
+            })
+        ));
+    }
+
+    #[test]
+    fn nv12_from_rgbaf32_red() {
+        let data = [1.0, 0.0, 0.0, 1.0].repeat(4);
+        assert_eq!(
+            vec![81, 81, 81, 81, 90, 240],
+            yuv_from_rgbaf32(2, 2, ImageFormat::Nv12, &data, YuvMatrix::Bt601).unwrap()
+        );
+        assert_eq!(
+            vec![63, 63, 63, 63, 102, 240],
+            yuv_from_rgbaf32(2, 2, ImageFormat::Nv12, &data, YuvMatrix::Bt709).unwrap()
+        );
+    }
+
+    #[test]
+    fn nv12_from_rgbaf32_average_chroma() {
+        // Luma is stored per pixel, but chroma is shared by the 2x2 block.
+        let data = [
+            [1.0, 0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0, 1.0],
+        ]
+        .concat();
+        assert_eq!(
+            vec![81, 81, 235, 235, 109, 184],
+            yuv_from_rgbaf32(2, 2, ImageFormat::Nv12, &data, YuvMatrix::Bt601).unwrap()
+        );
+    }
+
+    #[test]
+    fn yuy2_from_rgbaf32_odd_width() {
+        // The last column is repeated to fill the block.
+        let data = [
+            [0.0, 0.0, 0.0, 1.0],
+            [1.0, 1.0, 1.0, 1.0],
+            [1.0, 1.0, 1.0, 1.0],
+        ]
+        .concat();
+        assert_eq!(
+            vec![16, 128, 235, 128, 235, 128, 235, 128],
+            yuv_from_rgbaf32(3, 1, ImageFormat::Yuy2, &data, YuvMatrix::Bt709).unwrap()
+        );
+    }
+
+    #[test]
+    fn p010_round_trip_white() {
+        let data = [1.0; 2 * 2 * 4];
+        let p010 = yuv_from_rgbaf32(2, 2, ImageFormat::P010, &data, YuvMatrix::Bt709).unwrap();
+
+        // 10-bit white is 940 for luma and 512 for chroma.
+        let samples: Vec<_> = p010
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(
+            vec![940 << 6, 940 << 6, 940 << 6, 940 << 6, 512 << 6, 512 << 6],
+            samples
+        );
+
+        assert_eq!(
+            data.to_vec(),
+            rgbaf32_from_yuv(2, 2, ImageFormat::P010, &p010, YuvMatrix::Bt709).unwrap()
+        );
+    }
+}